@@ -3,14 +3,18 @@
 from sealy.parameters import (BfvEncryptionParametersBuilder,
                               CkksEncryptionParametersBuilder)
 from sealy.sealy import (AsymmetricComponents, BFVEncoder, BFVEvaluator,
+                         BFVTensorEncoder, BFVTensorEvaluator,
                          Ciphertext, CiphertextTensor, CKKSEncoder,
                          CKKSEvaluator, CKKSTensorEncoder, CKKSTensorEvaluator,
-                         CoefficientModulus, Context, Decryptor, DegreeType,
-                         EncryptionParameters, Encryptor, GaloisKey,
-                         KeyGenerator, MemoryPool, Modulus, PlainModulus,
-                         Plaintext, PlaintextTensor, PolynomialArray,
-                         PublicKey, RelinearizationKey, SchemeType, SecretKey,
-                         SecurityLevel, TensorDecryptor, TensorEncryptor)
+                         CoefficientModulus, CompactCiphertext, Context,
+                         Decryptor, DegreeType, EncryptionParameters,
+                         Encryptor, GaloisKey, KeyGenerator, MemoryPool,
+                         Modulus, PlainModulus, Plaintext, PlaintextTensor,
+                         PolynomialArray, PublicKey, RelinearizationKey,
+                         SchemeType, SecretKey, SecurityLevel,
+                         SymmetricEncryptor, SymmetricTensorEncryptor,
+                         TensorDecryptor, TensorDiagnostics,
+                         TensorEncryptor)
 
 __all__ = [
     "BfvEncryptionParametersBuilder",
@@ -27,21 +31,27 @@ __all__ = [
     "Plaintext",
     "MemoryPool",
     "Ciphertext",
+    "CompactCiphertext",
     "PolynomialArray",
     "BFVEncoder",
     "Encryptor",
+    "SymmetricEncryptor",
     "AsymmetricComponents",
     "Decryptor",
     "BFVEvaluator",
     "CKKSEvaluator",
     "CKKSEncoder",
     "TensorEncryptor",
+    "SymmetricTensorEncryptor",
     "TensorDecryptor",
     "TensorDecryptor",
+    "TensorDiagnostics",
     "CiphertextTensor",
     "PlaintextTensor",
     "CKKSTensorEncoder",
     "CKKSTensorEvaluator",
+    "BFVTensorEncoder",
+    "BFVTensorEvaluator",
     "PublicKey",
     "SecretKey",
     "GaloisKey",
@@ -1,4 +1,4 @@
-from typing import List, Tuple
+from typing import List, Optional, Tuple
 
 class MemoryPool:
     """
@@ -659,6 +659,18 @@ class Ciphertext:
         """
         ...
 
+class CompactCiphertext:
+    """
+    A symmetric ciphertext that stores a seed in place of half its data.
+    It can only be serialized; load it back with `Ciphertext.from_bytes`.
+    """
+
+    def as_bytes(self) -> bytes:
+        """
+        Convert the compact ciphertext to a list of bytes.
+        """
+        ...
+
 from typing import List, Optional
 
 class PolynomialArray:
@@ -950,6 +962,48 @@ class Encryptor:
         """
         ...
 
+class SymmetricEncryptor:
+    """
+    Encrypts plaintext data into ciphertext with a secret key.
+    """
+
+    def __init__(self, ctx: "Context", secret_key: "SecretKey") -> None:
+        """
+        Initializes the encryptor.
+
+        Parameters:
+        ctx (Context): The context to use.
+        secret_key (SecretKey): The secret key to use.
+        """
+        ...
+
+    def encrypt_symmetric(self, plaintext: "Plaintext") -> "Ciphertext":
+        """
+        Encrypts the given plaintext.
+
+        Parameters:
+        plaintext (Plaintext): The plaintext to encrypt.
+
+        Returns:
+        Ciphertext: The encrypted ciphertext.
+        """
+        ...
+
+    def encrypt_symmetric_compact(
+        self, plaintext: "Plaintext"
+    ) -> "CompactCiphertext":
+        """
+        Encrypts the given plaintext into a ciphertext that serializes at
+        roughly half the size.
+
+        Parameters:
+        plaintext (Plaintext): The plaintext to encrypt.
+
+        Returns:
+        CompactCiphertext: The compact encrypted ciphertext.
+        """
+        ...
+
 class Decryptor:
     """
     Decrypts ciphertext data into plaintext.
@@ -1291,6 +1345,22 @@ class PlaintextTensor:
         """
         ...
 
+    @property
+    def shape(self) -> Optional[List[int]]:
+        """
+        The logical shape of the data, if known.
+        """
+        ...
+
+    def reshape(self, shape: List[int]) -> "PlaintextTensor":
+        """
+        Returns the same plaintexts in a new shape with the same number of values.
+
+        Parameters:
+        shape (List[int]): The new shape.
+        """
+        ...
+
 class CiphertextTensor:
     """
     Stores a batch of ciphertexts.
@@ -1305,12 +1375,44 @@ class CiphertextTensor:
         """
         ...
 
+    @property
+    def shape(self) -> Optional[List[int]]:
+        """
+        The logical shape of the data, if known.
+        """
+        ...
+
+    def reshape(self, shape: List[int]) -> "CiphertextTensor":
+        """
+        Returns the same ciphertexts in a new shape with the same number of values.
+
+        Parameters:
+        shape (List[int]): The new shape.
+        """
+        ...
+
     def to_bytes_chunk(self) -> List[bytes]:
         """
         Convert the ciphertexts to a list of bytes.
         """
         ...
 
+    def to_bytes(self) -> bytes:
+        """
+        Serialize the ciphertexts and their shape as a single stream.
+        """
+        ...
+
+    @classmethod
+    def from_bytes(cls, context: "Context", data: bytes) -> "CiphertextTensor":
+        """
+        Load the ciphertexts and their shape from a stream written by
+        `to_bytes` or `SymmetricTensorEncryptor.encrypt_symmetric_compact`.
+
+        :param data: The stream to load.
+        """
+        ...
+
     @classmethod
     def from_bytes_chunk(
         cls, context: "Context", data: List[bytes]
@@ -1349,6 +1451,50 @@ class TensorEncryptor:
         """
         ...
 
+class SymmetricTensorEncryptor:
+    """
+    Encrypts a tensor of plaintexts into a tensor of ciphertexts with a
+    secret key.
+    """
+
+    def __init__(self, ctx: "Context", secret_key: "SecretKey") -> None:
+        """
+        Initializes the batch encryptor.
+
+        Parameters:
+        ctx (Context): The context to use.
+        secret_key (SecretKey): The secret key to use.
+        """
+        ...
+
+    def encrypt_symmetric(
+        self, plaintexts: "PlaintextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Encrypts the given batch of plaintexts.
+
+        Parameters:
+        plaintexts (PlaintextTensor): The plaintexts to encrypt.
+
+        Returns:
+        CiphertextTensor: The encrypted ciphertexts.
+        """
+        ...
+
+    def encrypt_symmetric_compact(self, plaintexts: "PlaintextTensor") -> bytes:
+        """
+        Encrypts the given batch of plaintexts into compact ciphertexts and
+        serializes them, with their shape, as a single stream. Load the result
+        with `CiphertextTensor.from_bytes`.
+
+        Parameters:
+        plaintexts (PlaintextTensor): The plaintexts to encrypt.
+
+        Returns:
+        bytes: The serialized compact ciphertext tensor.
+        """
+        ...
+
 class TensorDecryptor:
     """
     Decrypts a batch of ciphertexts into a batch of plaintexts.
@@ -1376,6 +1522,91 @@ class TensorDecryptor:
         """
         ...
 
+    def invariant_noise_budget(
+        self, ciphertexts: "CiphertextTensor"
+    ) -> Tuple[List[int], int]:
+        """
+        Computes the invariant noise budget of every chunk. BFV only.
+
+        Parameters:
+        ciphertexts (CiphertextTensor): The ciphertexts to measure.
+
+        Returns:
+        Tuple[List[int], int]: The budget of every chunk in bits and the smallest one.
+        """
+        ...
+
+    def invariant_noise(
+        self, ciphertexts: "CiphertextTensor"
+    ) -> Tuple[List[float], float]:
+        """
+        Computes the invariant noise of every chunk. BFV only.
+
+        Parameters:
+        ciphertexts (CiphertextTensor): The ciphertexts to measure.
+
+        Returns:
+        Tuple[List[float], float]: The noise of every chunk and the largest one.
+        """
+        ...
+
+    def diagnose(self, ciphertexts: "CiphertextTensor") -> "TensorDiagnostics":
+        """
+        Describes the level, primes, polynomials and noise budget of every chunk.
+
+        Parameters:
+        ciphertexts (CiphertextTensor): The ciphertexts to describe.
+
+        Returns:
+        TensorDiagnostics: The report.
+        """
+        ...
+
+class TensorDiagnostics:
+    """
+    A report on the ciphertexts of a tensor, one entry per chunk.
+    """
+
+    @property
+    def chunk_count(self) -> int:
+        """The number of chunks."""
+        ...
+
+    @property
+    def levels(self) -> List[Optional[int]]:
+        """The chain index of every chunk; 0 is the last level."""
+        ...
+
+    @property
+    def coeff_modulus_sizes(self) -> List[int]:
+        """The number of primes in the coefficient modulus of every chunk."""
+        ...
+
+    @property
+    def poly_modulus_degrees(self) -> List[int]:
+        """The polynomial degree of every chunk."""
+        ...
+
+    @property
+    def polynomial_counts(self) -> List[int]:
+        """The number of polynomials in every chunk."""
+        ...
+
+    @property
+    def noise_budgets(self) -> List[Optional[int]]:
+        """The noise budget of every chunk in bits, or None under CKKS."""
+        ...
+
+    @property
+    def min_level(self) -> Optional[int]:
+        """The lowest level of any chunk."""
+        ...
+
+    @property
+    def min_noise_budget(self) -> Optional[int]:
+        """The smallest noise budget of any chunk."""
+        ...
+
 class CKKSTensorEncoder:
     """
     Encodes a batch of floating-point values into a batch of plaintexts.
@@ -1391,12 +1622,16 @@ class CKKSTensorEncoder:
         """
         ...
 
-    def encode_float(self, values: List[float]) -> "PlaintextTensor":
+    def encode_float(
+        self, values: List[float], shape: Optional[List[int]] = None
+    ) -> "PlaintextTensor":
         """
         Encodes the given batch of floating-point values.
 
         Parameters:
-        values (List[float]): The values to encode.
+        values (List[float]): The values to encode, in row-major order.
+        shape (Optional[List[int]]): The shape of the values. Defaults to a
+        flat shape.
 
         Returns:
         PlaintextTensor: The encoded plaintexts.
@@ -1411,7 +1646,7 @@ class CKKSTensorEncoder:
         plaintexts (PlaintextTensor): The plaintexts to decode.
 
         Returns:
-        List[float]: The decoded values.
+        List[float]: The decoded values in row-major order, without padding.
         """
         ...
 
@@ -1560,3 +1795,229 @@ class CKKSTensorEvaluator:
         CiphertextTensor: The relinearized ciphertexts.
         """
         ...
+
+class BFVTensorEncoder:
+    """
+    Encodes a batch of integer values into a batch of plaintexts.
+    """
+
+    def __init__(self, ctx: "Context") -> None:
+        """
+        Initializes the batch encoder.
+
+        Parameters:
+        ctx (Context): The context to use.
+        """
+        ...
+
+    def get_slot_count(self) -> int:
+        """
+        Returns the number of values each plaintext holds.
+        """
+        ...
+
+    def encode_int(
+        self, values: List[int], shape: Optional[List[int]] = None
+    ) -> "PlaintextTensor":
+        """
+        Encodes the given batch of integer values.
+
+        Parameters:
+        values (List[int]): The values to encode, in row-major order.
+        shape (Optional[List[int]]): The shape of the values. Defaults to a
+        flat shape.
+
+        Returns:
+        PlaintextTensor: The encoded plaintexts.
+        """
+        ...
+
+    def decode_int(self, plaintexts: "PlaintextTensor") -> List[int]:
+        """
+        Decodes the given batch of plaintexts.
+
+        Parameters:
+        plaintexts (PlaintextTensor): The plaintexts to decode.
+
+        Returns:
+        List[int]: The decoded values in row-major order, without padding.
+        """
+        ...
+
+class BFVTensorEvaluator:
+    """
+    Evaluates operations on BFV ciphertexts.
+    """
+
+    def __init__(self, ctx: "Context") -> None:
+        """
+        Initializes the evaluator.
+
+        Parameters:
+        ctx (Context): The context to use.
+        """
+        ...
+
+    def negate(self, a: "CiphertextTensor") -> "CiphertextTensor":
+        """
+        Negates the given batch of ciphertexts.
+
+        Parameters:
+        a (CiphertextTensor): The ciphertexts to negate.
+
+        Returns:
+        CiphertextTensor: The negated ciphertexts.
+        """
+        ...
+
+    def add(
+        self, a: "CiphertextTensor", b: "CiphertextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Adds two batches of ciphertexts.
+
+        Parameters:
+        a (CiphertextTensor): The first batch of ciphertexts.
+        b (CiphertextTensor): The second batch of ciphertexts.
+
+        Returns:
+        CiphertextTensor: The sum of the ciphertexts.
+        """
+        ...
+
+    def add_many(
+        self, ciphertexts: List["CiphertextTensor"]
+    ) -> "CiphertextTensor":
+        """
+        Adds multiple batches of ciphertexts.
+
+        Parameters:
+        ciphertexts (List[CiphertextTensor]): The batches of ciphertexts to add.
+
+        Returns:
+        CiphertextTensor: The sum of the ciphertexts.
+        """
+        ...
+
+    def multiply(
+        self, a: "CiphertextTensor", b: "CiphertextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Multiplies two batches of ciphertexts.
+
+        Parameters:
+        a (CiphertextTensor): The first batch of ciphertexts.
+        b (CiphertextTensor): The second batch of ciphertexts.
+
+        Returns:
+        CiphertextTensor: The product of the ciphertexts.
+        """
+        ...
+
+    def multiply_many(
+        self,
+        ciphertexts: List["CiphertextTensor"],
+        relin_key: "RelinearizationKey",
+    ) -> "CiphertextTensor":
+        """
+        Multiplies multiple batches of ciphertexts.
+
+        Parameters:
+        ciphertexts (List[CiphertextTensor]): The batches of ciphertexts to multiply.
+        relin_key (RelinearizationKey): The relinearization key to use.
+
+        Returns:
+        CiphertextTensor: The product of the ciphertexts.
+        """
+        ...
+
+    def multiply_plain(
+        self, a: "CiphertextTensor", b: "PlaintextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Multiplies a batch of ciphertexts by a plaintext.
+
+        Parameters:
+        a (CiphertextTensor): The batch of ciphertexts.
+        b (PlaintextTensor): The batch of plaintexts.
+
+        Returns:
+        CiphertextTensor: The product of the ciphertexts and plaintext.
+        """
+        ...
+
+    def sub(
+        self, a: "CiphertextTensor", b: "CiphertextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Subtracts two batches of ciphertexts.
+
+        Parameters:
+        a (CiphertextTensor): The first batch of ciphertexts.
+        b (CiphertextTensor): The second batch of ciphertexts.
+
+        Returns:
+        CiphertextTensor: The difference of the ciphertexts.
+        """
+        ...
+
+    def sub_plain(
+        self, a: "CiphertextTensor", b: "PlaintextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Subtracts a batch of plaintexts from a batch of ciphertexts.
+
+        Parameters:
+        a (CiphertextTensor): The batch of ciphertexts.
+        b (PlaintextTensor): The batch of plaintexts.
+
+        Returns:
+        CiphertextTensor: The difference of the ciphertexts and plaintext.
+        """
+        ...
+
+    def relinearize(
+        self, ciphertexts: "CiphertextTensor"
+    ) -> "CiphertextTensor":
+        """
+        Relinearizes the given batch of ciphertexts.
+
+        Parameters:
+        ciphertexts (CiphertextTensor): The batch of ciphertexts to relinearize.
+
+        Returns:
+        CiphertextTensor: The relinearized ciphertexts.
+        """
+        ...
+
+    def rotate_rows(
+        self, a: "CiphertextTensor", steps: int, galois_keys: "GaloisKey"
+    ) -> "CiphertextTensor":
+        """
+        Rotates the rows of every ciphertext in the batch cyclically.
+
+        Parameters:
+        a (CiphertextTensor): The batch of ciphertexts.
+        steps (int): The number of steps to rotate, left when positive.
+        galois_keys (GaloisKey): The Galois keys to use.
+
+        Returns:
+        CiphertextTensor: The rotated ciphertexts.
+        """
+        ...
+
+    def rotate_columns(
+        self, a: "CiphertextTensor", galois_keys: "GaloisKey"
+    ) -> "CiphertextTensor":
+        """
+        Swaps the two rows of every ciphertext in the batch.
+
+        Parameters:
+        a (CiphertextTensor): The batch of ciphertexts.
+        galois_keys (GaloisKey): The Galois keys to use.
+
+        Returns:
+        CiphertextTensor: The rotated ciphertexts.
+        """
+        ...
+
@@ -0,0 +1,185 @@
+import random
+from typing import List
+
+import pytest
+from sealy import (BfvEncryptionParametersBuilder, BFVTensorEncoder,
+                   BFVTensorEvaluator, CiphertextTensor, CoefficientModulus,
+                   Context, DegreeType, KeyGenerator, PlainModulus,
+                   SecurityLevel, TensorDecryptor, TensorEncryptor)
+
+
+def generate_random_counts(size):
+    return [random.randint(0, 100) for _ in range(size)]
+
+
+def sum_ciphertexts(
+    ctx: Context,
+    ciphertexts: List[CiphertextTensor],
+):
+    evaluator = BFVTensorEvaluator(ctx)
+
+    return evaluator.add_many(ciphertexts)
+
+
+def sum_plaintexts(plaintexts):
+    total = [0] * len(plaintexts[0])
+    for tensor in plaintexts:
+        for i, val in enumerate(tensor):
+            total[i] += val
+    return total
+
+
+@pytest.fixture
+def context() -> Context:
+    degree = DegreeType(8192)
+    security_level = SecurityLevel(128)
+    bit_sizes = [50, 30, 30, 50, 50]
+
+    expand_mod_chain = False
+    modulus_chain = CoefficientModulus.create(degree, bit_sizes)
+    encryption_parameters = (
+        BfvEncryptionParametersBuilder()
+        .with_poly_modulus_degree(degree)
+        .with_coefficient_modulus(modulus_chain)
+        .with_plain_modulus(PlainModulus.batching(degree, 20))
+        .build()
+    )
+
+    return Context(encryption_parameters, expand_mod_chain, security_level)
+
+
+@pytest.fixture
+def key_generator(context: Context):
+    return KeyGenerator(context)
+
+
+@pytest.fixture
+def encoder(context: Context):
+    return BFVTensorEncoder(context)
+
+
+@pytest.fixture
+def encryptor(context: Context, key_generator: KeyGenerator):
+    public_key = key_generator.create_public_key()
+    return TensorEncryptor(context, public_key)
+
+
+@pytest.fixture
+def decryptor(context: Context, key_generator: KeyGenerator):
+    private_key = key_generator.secret_key()
+    return TensorDecryptor(context, private_key)
+
+
+def test_sum_ciphertexts(
+    context: Context,
+    encoder: BFVTensorEncoder,
+    encryptor: TensorEncryptor,
+    decryptor: TensorDecryptor,
+):
+    client_1_counts = generate_random_counts(11000)
+    client_2_counts = generate_random_counts(11000)
+    client_3_counts = generate_random_counts(11000)
+
+    client_1_encrypted_counts = encryptor.encrypt(
+        encoder.encode_int(client_1_counts)
+    )
+    client_2_encrypted_counts = encryptor.encrypt(
+        encoder.encode_int(client_2_counts)
+    )
+    client_3_encrypted_counts = encryptor.encrypt(
+        encoder.encode_int(client_3_counts)
+    )
+
+    sum_truth = sum_plaintexts(
+        [client_1_counts, client_2_counts, client_3_counts]
+    )
+
+    total = sum_ciphertexts(
+        context,
+        [
+            client_1_encrypted_counts,
+            client_2_encrypted_counts,
+            client_3_encrypted_counts,
+        ],
+    )
+
+    sum_plain = encoder.decode_int(decryptor.decrypt(total))
+
+    assert sum_plain == sum_truth
+
+
+def test_multiply_plain(
+    context: Context,
+    encoder: BFVTensorEncoder,
+    encryptor: TensorEncryptor,
+    decryptor: TensorDecryptor,
+):
+    evaluator = BFVTensorEvaluator(context)
+
+    counts = generate_random_counts(11000)
+    weights = generate_random_counts(11000)
+
+    product = evaluator.multiply_plain(
+        encryptor.encrypt(encoder.encode_int(counts)),
+        encoder.encode_int(weights),
+    )
+
+    decoded = encoder.decode_int(decryptor.decrypt(product))
+
+    assert decoded == [c * w for c, w in zip(counts, weights)]
+
+
+def test_rotate_rows(
+    context: Context,
+    key_generator: KeyGenerator,
+    encoder: BFVTensorEncoder,
+    encryptor: TensorEncryptor,
+    decryptor: TensorDecryptor,
+):
+    evaluator = BFVTensorEvaluator(context)
+    galois_keys = key_generator.create_galois_key()
+
+    counts = generate_random_counts(11000)
+    row_size = encoder.get_slot_count() // 2
+
+    encrypted = encryptor.encrypt(encoder.encode_int(counts))
+    rotated = evaluator.rotate_rows(encrypted, 1, galois_keys)
+    decoded = encoder.decode_int(decryptor.decrypt(rotated))
+
+    assert decoded[0] == counts[1]
+    assert decoded[row_size - 1] == counts[0]
+
+    swapped = evaluator.rotate_columns(encrypted, galois_keys)
+    decoded = encoder.decode_int(decryptor.decrypt(swapped))
+
+    assert decoded[0] == counts[row_size]
+    assert decoded[row_size] == counts[0]
+
+
+def test_noise_budget_and_diagnose(
+    context: Context,
+    encoder: BFVTensorEncoder,
+    encryptor: TensorEncryptor,
+    decryptor: TensorDecryptor,
+):
+    evaluator = BFVTensorEvaluator(context)
+
+    counts = generate_random_counts(11000)
+    encrypted = encryptor.encrypt(encoder.encode_int(counts))
+
+    budgets, minimum = decryptor.invariant_noise_budget(encrypted)
+    assert len(budgets) == 2
+    assert minimum == min(budgets)
+
+    noise, maximum = decryptor.invariant_noise(encrypted)
+    assert len(noise) == 2
+    assert maximum == max(noise)
+
+    squared = evaluator.multiply(encrypted, encrypted)
+    report = decryptor.diagnose(squared)
+
+    assert report.chunk_count == 2
+    assert report.polynomial_counts == [3, 3]
+    assert report.poly_modulus_degrees == [8192, 8192]
+    assert report.min_noise_budget < minimum
+    assert str(report).startswith("2 chunks")
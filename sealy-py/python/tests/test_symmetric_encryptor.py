@@ -0,0 +1,50 @@
+import random
+
+import pytest
+from sealy import (CiphertextTensor, CkksEncryptionParametersBuilder,
+                   CKKSTensorEncoder, CoefficientModulus, Context, DegreeType,
+                   KeyGenerator, SecurityLevel, SymmetricTensorEncryptor,
+                   TensorDecryptor)
+
+
+@pytest.fixture
+def context() -> Context:
+    degree = DegreeType(8192)
+    security_level = SecurityLevel(128)
+    bit_sizes = [60, 40, 40, 60]
+
+    expand_mod_chain = False
+    modulus_chain = CoefficientModulus.create(degree, bit_sizes)
+    encryption_parameters = (
+        CkksEncryptionParametersBuilder()
+        .with_poly_modulus_degree(degree)
+        .with_coefficient_modulus(modulus_chain)
+        .build()
+    )
+
+    return Context(encryption_parameters, expand_mod_chain, security_level)
+
+
+def test_compact_ciphertexts_are_smaller_and_decrypt(context: Context):
+    key_generator = KeyGenerator(context)
+    secret_key = key_generator.secret_key()
+
+    encoder = CKKSTensorEncoder(context, 2**40)
+    encryptor = SymmetricTensorEncryptor(context, secret_key)
+    decryptor = TensorDecryptor(context, secret_key)
+
+    data = [random.uniform(0.0, 1.0) for _ in range(5000)]
+    encoded = encoder.encode_float(data)
+
+    full = encryptor.encrypt_symmetric(encoded).to_bytes()
+    compact = encryptor.encrypt_symmetric_compact(encoded)
+
+    assert len(compact) * 3 < len(full) * 2
+
+    loaded = CiphertextTensor.from_bytes(context, compact)
+    assert loaded.shape == [5000]
+
+    decoded = encoder.decode_float(decryptor.decrypt(loaded))
+
+    for expected, actual in zip(data, decoded):
+        assert abs(expected - actual) < 1e-6
@@ -123,3 +123,26 @@ def test_average_ciphertexts(
 
     for t, p in zip(avg_truth, avg_plain):
         assert abs(t - p) < 1e-6
+
+
+def test_shape_survives_encryption(
+    encoder: CKKSTensorEncoder,
+    encryptor: TensorEncryptor,
+    decryptor: TensorDecryptor,
+):
+    data = generate_random_tensor(2 * 3000)
+
+    encoded = encoder.encode_float(data, [2, 3000])
+    encrypted = encryptor.encrypt(encoded)
+
+    assert encrypted.shape == [2, 3000]
+    assert encrypted.reshape([3000, 2]).shape == [3000, 2]
+
+    with pytest.raises(ValueError):
+        encrypted.reshape([7])
+
+    decoded = encoder.decode_float(decryptor.decrypt(encrypted))
+
+    assert len(decoded) == len(data)
+    for t, p in zip(data, decoded):
+        assert abs(t - p) < 1e-6
@@ -2,6 +2,7 @@ use pyo3::prelude::*;
 use sealy::{FromBytes, ToBytes};
 
 use crate::context::PyContext;
+
 /// Class to store a ciphertext element.
 #[derive(Debug, Clone)]
 #[pyclass(module = "sealy", name = "Ciphertext")]
@@ -54,13 +55,23 @@ impl PyCiphertext {
 	}
 
 	/// Returns the number of polynomials in this ciphertext.
-	pub fn get_num_polynomials(&self) -> u64 {
-		self.inner.num_polynomials()
+	pub fn get_num_polynomials(&self) -> PyResult<u64> {
+		self.inner.num_polynomials().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get number of polynomials: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns the number of components in the coefficient modulus.
-	pub fn get_coeff_modulus_size(&self) -> u64 {
-		self.inner.coeff_modulus_size()
+	pub fn get_coeff_modulus_size(&self) -> PyResult<u64> {
+		self.inner.coeff_modulus_size().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get coefficient modulus size: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns the coefficient in the form the ciphertext is currently in (NTT
@@ -84,8 +95,13 @@ impl PyCiphertext {
 	}
 
 	/// Returns whether the ciphertext is in NTT form.
-	pub fn is_ntt_form(&self) -> bool {
-		self.inner.is_ntt_form()
+	pub fn is_ntt_form(&self) -> PyResult<bool> {
+		self.inner.is_ntt_form().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to check NTT form: {:?}",
+				e
+			))
+		})
 	}
 
 	fn __eq__(
@@ -95,3 +111,26 @@ impl PyCiphertext {
 		self.inner == other.inner
 	}
 }
+
+/// A symmetric-key ciphertext that stores a seed instead of its second
+/// polynomial. It can only be serialized; load the bytes with
+/// `Ciphertext.from_bytes` to get a usable ciphertext.
+#[derive(Debug)]
+#[pyclass(module = "sealy", name = "CompactCiphertext")]
+pub struct PyCompactCiphertext {
+	pub(crate) inner: sealy::CompactCiphertext,
+}
+
+#[pymethods]
+impl PyCompactCiphertext {
+	/// Returns the ciphertext as a byte array.
+	pub fn as_bytes(&self) -> PyResult<Vec<u8>> {
+		let data = self.inner.as_bytes().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get compact ciphertext as bytes: {:?}",
+				e
+			))
+		})?;
+		Ok(data)
+	}
+}
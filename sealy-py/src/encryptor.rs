@@ -1,7 +1,10 @@
 use pyo3::prelude::*;
 
 use crate::{
-	ciphertext::PyCiphertext, context::PyContext, keys::PyPublicKey, plaintext::PyPlaintext,
+	ciphertext::{PyCiphertext, PyCompactCiphertext},
+	context::PyContext,
+	keys::{PyPublicKey, PySecretKey},
+	plaintext::PyPlaintext,
 	poly_array::PyPolynomialArray,
 };
 
@@ -121,3 +124,73 @@ impl PyEncryptor {
 		))
 	}
 }
+
+/// Encrypts Plaintext objects into Ciphertext objects with a secret key.
+#[pyclass(module = "sealy", name = "SymmetricEncryptor")]
+pub struct PySymmetricEncryptor {
+	pub(crate) inner: sealy::Encryptor<sealy::Sym>,
+}
+
+#[pymethods]
+impl PySymmetricEncryptor {
+	/// Creates an Encryptor instance initialized with the specified sealy::Context
+	/// and secret key.
+	///
+	/// * `ctx` - The sealy::Context
+	/// * `secret_key` - The secret key
+	#[new]
+	pub fn new(
+		ctx: &PyContext,
+		secret_key: &PySecretKey,
+	) -> PyResult<Self> {
+		let encryptor =
+			sealy::Encryptor::with_secret_key(&ctx.inner, &secret_key.inner).map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to create encryptor with secret key: {:?}",
+					e
+				))
+			})?;
+		Ok(Self {
+			inner: encryptor,
+		})
+	}
+
+	/// Encrypts a plaintext with the secret key and returns the ciphertext.
+	pub fn encrypt_symmetric(
+		&self,
+		plaintext: &PyPlaintext,
+	) -> PyResult<PyCiphertext> {
+		let ciphertext = self
+			.inner
+			.encrypt_symmetric(&plaintext.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to encrypt plaintext: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertext {
+			inner: ciphertext,
+		})
+	}
+
+	/// Encrypts a plaintext with the secret key and returns a compact
+	/// ciphertext that serializes at roughly half the size.
+	pub fn encrypt_symmetric_compact(
+		&self,
+		plaintext: &PyPlaintext,
+	) -> PyResult<PyCompactCiphertext> {
+		let ciphertext = self
+			.inner
+			.encrypt_symmetric_compact(&plaintext.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to encrypt plaintext: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCompactCiphertext {
+			inner: ciphertext,
+		})
+	}
+}
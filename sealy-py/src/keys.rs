@@ -285,19 +285,29 @@ impl PyKeyGenerator {
 	}
 
 	/// Returns a copy of the secret key.
-	pub fn secret_key(&self) -> PySecretKey {
-		let sk = self.inner.secret_key();
-		PySecretKey {
+	pub fn secret_key(&self) -> PyResult<PySecretKey> {
+		let sk = self.inner.secret_key().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get secret key: {:?}",
+				e
+			))
+		})?;
+		Ok(PySecretKey {
 			inner: sk,
-		}
+		})
 	}
 
 	/// Generates and returns a new public key.
-	pub fn create_public_key(&self) -> PyPublicKey {
-		let pk = self.inner.create_public_key();
-		PyPublicKey {
+	pub fn create_public_key(&self) -> PyResult<PyPublicKey> {
+		let pk = self.inner.create_public_key().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to create public key: {:?}",
+				e
+			))
+		})?;
+		Ok(PyPublicKey {
 			inner: pk,
-		}
+		})
 	}
 
 	/// Creates relinearization keys
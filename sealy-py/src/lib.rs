@@ -13,11 +13,11 @@ mod plaintext;
 mod poly_array;
 mod tensor;
 
-use crate::ciphertext::PyCiphertext;
+use crate::ciphertext::{PyCiphertext, PyCompactCiphertext};
 use crate::context::PyContext;
 use crate::decryptor::PyDecryptor;
 use crate::encoder::{PyBFVEncoder, PyCKKSEncoder};
-use crate::encryptor::{PyAsymmetricComponents, PyEncryptor};
+use crate::encryptor::{PyAsymmetricComponents, PyEncryptor, PySymmetricEncryptor};
 use crate::evaluator::{PyBFVEvaluator, PyCKKSEvaluator};
 use crate::keys::{PyGaloisKey, PyKeyGenerator, PyPublicKey, PyRelinearizationKey, PySecretKey};
 use crate::memory::PyMemoryPool;
@@ -28,8 +28,9 @@ use crate::parameters::{
 use crate::plaintext::PyPlaintext;
 use crate::poly_array::PyPolynomialArray;
 use crate::tensor::{
-	PyCKKSTensorEncoder, PyCKKSTensorEvaluator, PyCiphertextTensor, PyPlaintextTensor,
-	PyTensorDecryptor, PyTensorEncryptor,
+	PyBFVTensorEncoder, PyBFVTensorEvaluator, PyCKKSTensorEncoder, PyCKKSTensorEvaluator,
+	PyCiphertextTensor, PyPlaintextTensor, PySymmetricTensorEncryptor, PyTensorDecryptor,
+	PyTensorDiagnostics, PyTensorEncryptor,
 };
 
 /// A Python module implemented in Rust. The name of this function must match
@@ -53,11 +54,13 @@ fn sealy(m: &Bound<'_, PyModule>) -> PyResult<()> {
 	m.add_class::<PyMemoryPool>()?;
 	m.add_class::<PyPlaintext>()?;
 	m.add_class::<PyCiphertext>()?;
+	m.add_class::<PyCompactCiphertext>()?;
 	m.add_class::<PyPolynomialArray>()?;
 	m.add_class::<PyBFVEncoder>()?;
 	m.add_class::<PyCKKSEncoder>()?;
 	m.add_class::<PyAsymmetricComponents>()?;
 	m.add_class::<PyEncryptor>()?;
+	m.add_class::<PySymmetricEncryptor>()?;
 	m.add_class::<PyDecryptor>()?;
 	m.add_class::<PyBFVEvaluator>()?;
 	m.add_class::<PyCKKSEvaluator>()?;
@@ -67,8 +70,12 @@ fn sealy(m: &Bound<'_, PyModule>) -> PyResult<()> {
 	m.add_class::<PyCiphertextTensor>()?;
 	m.add_class::<PyCKKSTensorEncoder>()?;
 	m.add_class::<PyCKKSTensorEvaluator>()?;
+	m.add_class::<PyBFVTensorEncoder>()?;
+	m.add_class::<PyBFVTensorEvaluator>()?;
 	m.add_class::<PyTensorEncryptor>()?;
+	m.add_class::<PySymmetricTensorEncryptor>()?;
 	m.add_class::<PyTensorDecryptor>()?;
+	m.add_class::<PyTensorDiagnostics>()?;
 
 	Ok(())
 }
@@ -10,10 +10,15 @@ pub struct PySchemeType {
 #[pymethods]
 impl PySchemeType {
 	#[new]
-	pub fn new(val: u8) -> Self {
-		Self {
-			inner: sealy::SchemeType::from_u8(val),
-		}
+	pub fn new(val: u8) -> PyResult<Self> {
+		Ok(Self {
+			inner: sealy::SchemeType::from_u8(val).map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+					"Invalid scheme type: {:?}",
+					e
+				))
+			})?,
+		})
 	}
 
 	#[staticmethod]
@@ -77,34 +82,61 @@ impl PyEncryptionParameters {
 		sealy::EncryptionParameters::block_size()
 	}
 
-	pub fn get_poly_modulus_degree(&self) -> u64 {
-		self.inner.get_poly_modulus_degree()
+	pub fn get_poly_modulus_degree(&self) -> PyResult<u64> {
+		self.inner.get_poly_modulus_degree().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get polynomial modulus degree: {:?}",
+				e
+			))
+		})
 	}
 
-	pub fn get_scheme(&self) -> PySchemeType {
-		PySchemeType {
-			inner: self.inner.get_scheme(),
-		}
+	pub fn get_scheme(&self) -> PyResult<PySchemeType> {
+		Ok(PySchemeType {
+			inner: self.inner.get_scheme().map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to get scheme: {:?}",
+					e
+				))
+			})?,
+		})
 	}
 
-	pub fn get_plain_modulus(&self) -> PyModulus {
-		PyModulus {
-			inner: self.inner.get_plain_modulus(),
-		}
+	pub fn get_plain_modulus(&self) -> PyResult<PyModulus> {
+		Ok(PyModulus {
+			inner: self.inner.get_plain_modulus().map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to get plain modulus: {:?}",
+					e
+				))
+			})?,
+		})
 	}
 
-	pub fn get_coefficient_modulus(&self) -> Vec<PyModulus> {
-		self.inner
+	pub fn get_coefficient_modulus(&self) -> PyResult<Vec<PyModulus>> {
+		Ok(self
+			.inner
 			.get_coefficient_modulus()
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to get coefficient modulus: {:?}",
+					e
+				))
+			})?
 			.into_iter()
 			.map(|m| PyModulus {
 				inner: m,
 			})
-			.collect()
+			.collect())
 	}
 
-	pub fn get_parms_id(&self) -> u64 {
-		self.inner.get_parms_id()
+	pub fn get_parms_id(&self) -> PyResult<u64> {
+		self.inner.get_parms_id().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get parms id: {:?}",
+				e
+			))
+		})
 	}
 
 	pub fn set_coefficient_modulus(
@@ -168,14 +200,14 @@ impl PyEncryptionParameters {
 	}
 
 	fn __getnewargs__(&self) -> PyResult<(PySchemeType,)> {
-		Ok((self.get_scheme(),))
+		Ok((self.get_scheme()?,))
 	}
 
 	pub fn __setstate__(
 		&mut self,
 		state: Vec<u8>,
 	) -> PyResult<()> {
-		self.inner = sealy::EncryptionParameters::from_bytes(&self.get_scheme().inner, &state)
+		self.inner = sealy::EncryptionParameters::from_bytes(&self.get_scheme()?.inner, &state)
 			.map_err(|e| {
 				PyErr::new::<pyo3::exceptions::PyException, _>(format!(
 					"Error deserializing EncryptionParameters: {}",
@@ -253,8 +285,14 @@ impl PyCoefficientModulus {
 	pub fn max_bit_count(
 		degree: PyDegreeType,
 		security_level: PySecurityLevel,
-	) -> u32 {
+	) -> PyResult<u32> {
 		sealy::CoefficientModulusFactory::max_bit_count(degree.inner.into(), security_level.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to get max bit count: {:?}",
+					e
+				))
+			})
 	}
 }
 
@@ -309,8 +347,13 @@ impl PyModulus {
 		})
 	}
 
-	pub fn get_value(&self) -> u64 {
-		self.inner.value()
+	pub fn get_value(&self) -> PyResult<u64> {
+		self.inner.value().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get modulus value: {:?}",
+				e
+			))
+		})
 	}
 
 	fn __str__(&self) -> String {
@@ -91,8 +91,13 @@ impl PyPlaintext {
 	pub fn get_coefficient(
 		&self,
 		index: usize,
-	) -> u64 {
-		self.inner.get_coefficient(index)
+	) -> PyResult<u64> {
+		self.inner.get_coefficient(index).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get coefficient: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Sets the coefficient at the given location. Coefficients are ordered
@@ -102,26 +107,46 @@ impl PyPlaintext {
 		&mut self,
 		index: usize,
 		value: u64,
-	) {
-		self.inner.set_coefficient(index, value);
+	) -> PyResult<()> {
+		self.inner.set_coefficient(index, value).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to set coefficient: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Sets the number of coefficients this plaintext can hold.
 	pub fn resize(
 		&mut self,
 		count: usize,
-	) {
-		self.inner.resize(count);
+	) -> PyResult<()> {
+		self.inner.resize(count).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to resize plaintext: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns the number of coefficients this plaintext can hold.
-	pub fn size(&self) -> usize {
-		self.inner.len()
+	pub fn size(&self) -> PyResult<usize> {
+		self.inner.len().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get plaintext size: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns whether the plaintext is in NTT form.
-	pub fn is_ntt_form(&self) -> bool {
-		self.inner.is_ntt_form()
+	pub fn is_ntt_form(&self) -> PyResult<bool> {
+		self.inner.is_ntt_form().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to check NTT form: {:?}",
+				e
+			))
+		})
 	}
 
 	fn __len__(&self) -> usize {
@@ -89,30 +89,55 @@ impl PyPolynomialArray {
 	/// Has the array data been loaded? When an array is created, it initially
 	/// has no data. Once data is loaded this is true. Additionally data can only
 	/// be loaded once.
-	pub fn is_reserved(&self) -> bool {
-		self.inner.is_reserved()
+	pub fn is_reserved(&self) -> PyResult<bool> {
+		self.inner.is_reserved().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to check if polynomial array is reserved: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Is the array in RNS form (true).
-	pub fn is_rns(&self) -> bool {
-		self.inner.is_rns()
+	pub fn is_rns(&self) -> PyResult<bool> {
+		self.inner.is_rns().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to check RNS form: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Is the array in RNS form (true).
-	pub fn is_multiprecision(&self) -> bool {
-		self.inner.is_multiprecision()
+	pub fn is_multiprecision(&self) -> PyResult<bool> {
+		self.inner.is_multiprecision().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to check multiprecision form: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Converts the polynomial array into the RNS format regardless of its
 	/// current format.
-	pub fn to_rns(&self) {
-		self.inner.to_rns();
+	pub fn to_rns(&self) -> PyResult<()> {
+		self.inner.to_rns().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to convert to RNS form: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Converts the polynomial array into the multiprecision format regardless
 	/// of its current format.
-	pub fn to_multiprecision(&self) {
-		self.inner.to_multiprecision();
+	pub fn to_multiprecision(&self) -> PyResult<()> {
+		self.inner.to_multiprecision().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to convert to multiprecision form: {:?}",
+				e
+			))
+		})
 	}
 
 	/// This will be in coefficient order; all the limbs with a given coefficient
@@ -157,18 +182,33 @@ impl PyPolynomialArray {
 	}
 
 	/// Returns the number of polynomials stored in the `PolynomialArray`.
-	pub fn get_num_polynomials(&self) -> u64 {
-		self.inner.num_polynomials()
+	pub fn get_num_polynomials(&self) -> PyResult<u64> {
+		self.inner.num_polynomials().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get number of polynomials: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns the number of coefficients in each polynomial in the `PolynomialArray`.
-	pub fn get_poly_modulus_degree(&self) -> u64 {
-		self.inner.poly_modulus_degree()
+	pub fn get_poly_modulus_degree(&self) -> PyResult<u64> {
+		self.inner.poly_modulus_degree().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get polynomial modulus degree: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Returns how many moduli are in the coefficient modulus set.
-	pub fn get_coeff_modulus_size(&self) -> u64 {
-		self.inner.coeff_modulus_size()
+	pub fn get_coeff_modulus_size(&self) -> PyResult<u64> {
+		self.inner.coeff_modulus_size().map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get coefficient modulus size: {:?}",
+				e
+			))
+		})
 	}
 
 	/// Reduces the polynomial array by dropping the last modulus in the modulus
@@ -1,7 +1,7 @@
 use crate::{
 	ciphertext::PyCiphertext,
 	context::PyContext,
-	keys::{PyPublicKey, PyRelinearizationKey, PySecretKey},
+	keys::{PyGaloisKey, PyPublicKey, PyRelinearizationKey, PySecretKey},
 	plaintext::PyPlaintext,
 	PyCKKSEvaluator,
 };
@@ -19,11 +19,33 @@ impl PyPlaintextTensor {
 	/// Creates a new batch array.
 	#[new]
 	fn new(ndarr: Vec<PyPlaintext>) -> PyResult<Self> {
-		let batch = sealy::Tensor(ndarr.iter().map(|x| x.inner.clone()).collect());
+		let batch = sealy::Tensor::new(ndarr.iter().map(|x| x.inner.clone()).collect());
 		Ok(Self {
 			inner: batch,
 		})
 	}
+
+	/// Returns the logical shape of the data, if known.
+	#[getter]
+	fn shape(&self) -> Option<Vec<usize>> {
+		self.inner.shape().map(|shape| shape.dims().to_vec())
+	}
+
+	/// Returns a plaintext tensor with the same data in a new shape.
+	fn reshape(
+		&self,
+		shape: Vec<usize>,
+	) -> PyResult<Self> {
+		let inner = self.inner.clone().reshape(shape).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+				"Failed to reshape plaintext batch: {:?}",
+				e
+			))
+		})?;
+		Ok(Self {
+			inner,
+		})
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -37,12 +59,34 @@ impl PyCiphertextTensor {
 	/// Creates a new batch array.
 	#[new]
 	fn new(ndarr: Vec<PyCiphertext>) -> PyResult<Self> {
-		let batch = sealy::Tensor(ndarr.iter().map(|x| x.inner.clone()).collect());
+		let batch = sealy::Tensor::new(ndarr.iter().map(|x| x.inner.clone()).collect());
 		Ok(Self {
 			inner: batch,
 		})
 	}
 
+	/// Returns the logical shape of the data, if known.
+	#[getter]
+	fn shape(&self) -> Option<Vec<usize>> {
+		self.inner.shape().map(|shape| shape.dims().to_vec())
+	}
+
+	/// Returns a ciphertext tensor with the same data in a new shape.
+	fn reshape(
+		&self,
+		shape: Vec<usize>,
+	) -> PyResult<Self> {
+		let inner = self.inner.clone().reshape(shape).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+				"Failed to reshape ciphertext batch: {:?}",
+				e
+			))
+		})?;
+		Ok(Self {
+			inner,
+		})
+	}
+
 	/// Converts the batch array to a list of byte arrays.
 	pub fn to_bytes_chunk(&self) -> PyResult<Vec<Vec<u8>>> {
 		let bytes = self.inner.to_chunk().map_err(|e| {
@@ -54,6 +98,37 @@ impl PyCiphertextTensor {
 		Ok(bytes)
 	}
 
+	/// Serializes the tensor and its shape as a single stream, in the format
+	/// of `Tensor::save_to`.
+	pub fn to_bytes(&self) -> PyResult<Vec<u8>> {
+		let mut bytes = vec![];
+		self.inner.save_to(&mut bytes).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to get ciphertext batch as bytes: {:?}",
+				e
+			))
+		})?;
+		Ok(bytes)
+	}
+
+	/// Loads a ciphertext tensor, and its shape, from a stream written by
+	/// `to_bytes` or `SymmetricTensorEncryptor.encrypt_symmetric_compact`.
+	#[staticmethod]
+	pub fn from_bytes(
+		ctx: &PyContext,
+		bytes: Vec<u8>,
+	) -> PyResult<Self> {
+		let batch = sealy::Tensor::load_from(&ctx.inner, &mut bytes.as_slice()).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to create ciphertext batch from bytes: {:?}",
+				e
+			))
+		})?;
+		Ok(Self {
+			inner: batch,
+		})
+	}
+
 	/// Creates a new ciphertext batch array from a list of byte arrays.
 	#[staticmethod]
 	pub fn from_bytes_chunk(
@@ -117,6 +192,72 @@ impl PyTensorEncryptor {
 	}
 }
 
+/// Encryptor that can encrypt multiple messages at once with a secret key.
+#[pyclass(module = "sealy", name = "SymmetricTensorEncryptor")]
+pub struct PySymmetricTensorEncryptor {
+	inner: sealy::TensorEncryptor<sealy::Sym>,
+}
+
+#[pymethods]
+impl PySymmetricTensorEncryptor {
+	/// Creates a new TensorEncryptor instance with a secret key.
+	#[new]
+	fn new(
+		ctx: &PyContext,
+		sk: &PySecretKey,
+	) -> PyResult<Self> {
+		let inner =
+			sealy::TensorEncryptor::with_secret_key(&ctx.inner, &sk.inner).map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to create TensorEncryptor: {:?}",
+					e
+				))
+			})?;
+		Ok(Self {
+			inner,
+		})
+	}
+
+	/// Encrypts a plaintext with the secret key and returns the ciphertext.
+	pub fn encrypt_symmetric(
+		&self,
+		plaintext: PyPlaintextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let ciphertext = self
+			.inner
+			.encrypt_symmetric(&plaintext.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to encrypt batch: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertextTensor {
+			inner: ciphertext,
+		})
+	}
+
+	/// Encrypts a plaintext with the secret key and returns the compact
+	/// ciphertexts already serialized as a single stream with their shape, in
+	/// the format of `CiphertextTensor.to_bytes`.
+	pub fn encrypt_symmetric_compact(
+		&self,
+		plaintext: PyPlaintextTensor,
+	) -> PyResult<Vec<u8>> {
+		let mut bytes = vec![];
+		self.inner
+			.encrypt_symmetric_compact(&plaintext.inner)
+			.and_then(|ciphertext| ciphertext.save_to(&mut bytes))
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to encrypt batch: {:?}",
+					e
+				))
+			})?;
+		Ok(bytes)
+	}
+}
+
 /// Decrypts batches of ciphertexts.
 #[pyclass(module = "sealy", name = "TensorDecryptor")]
 pub struct PyTensorDecryptor {
@@ -157,6 +298,130 @@ impl PyTensorDecryptor {
 			inner: plaintext,
 		})
 	}
+
+	/// Returns the noise budget of every chunk and the smallest one.
+	pub fn invariant_noise_budget(
+		&self,
+		ciphertext_batch: &PyCiphertextTensor,
+	) -> PyResult<(Vec<u32>, u32)> {
+		self.inner
+			.invariant_noise_budget(&ciphertext_batch.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to compute noise budget: {:?}",
+					e
+				))
+			})
+	}
+
+	/// Returns the invariant noise of every chunk and the largest one.
+	pub fn invariant_noise(
+		&self,
+		ciphertext_batch: &PyCiphertextTensor,
+	) -> PyResult<(Vec<f64>, f64)> {
+		self.inner
+			.invariant_noise(&ciphertext_batch.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to compute noise: {:?}",
+					e
+				))
+			})
+	}
+
+	/// Describes the level, primes, polynomials and noise budget of every chunk.
+	pub fn diagnose(
+		&self,
+		ciphertext_batch: &PyCiphertextTensor,
+	) -> PyResult<PyTensorDiagnostics> {
+		let inner = self.inner.diagnose(&ciphertext_batch.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to diagnose batch: {:?}",
+				e
+			))
+		})?;
+		Ok(PyTensorDiagnostics {
+			inner,
+		})
+	}
+}
+
+/// A report on the ciphertexts of a tensor.
+#[derive(Debug, Clone)]
+#[pyclass(module = "sealy", name = "TensorDiagnostics")]
+pub struct PyTensorDiagnostics {
+	inner: sealy::TensorDiagnostics,
+}
+
+#[pymethods]
+impl PyTensorDiagnostics {
+	/// Returns the number of chunks.
+	#[getter]
+	fn chunk_count(&self) -> usize {
+		self.inner.chunk_count()
+	}
+
+	/// Returns the chain index of every chunk, or None if unknown.
+	#[getter]
+	fn levels(&self) -> Vec<Option<u64>> {
+		self.inner.chunks.iter().map(|c| c.level).collect()
+	}
+
+	/// Returns the number of primes in the coefficient modulus of every chunk.
+	#[getter]
+	fn coeff_modulus_sizes(&self) -> Vec<u64> {
+		self.inner
+			.chunks
+			.iter()
+			.map(|c| c.coeff_modulus_size)
+			.collect()
+	}
+
+	/// Returns the polynomial degree of every chunk.
+	#[getter]
+	fn poly_modulus_degrees(&self) -> Vec<u64> {
+		self.inner
+			.chunks
+			.iter()
+			.map(|c| c.poly_modulus_degree)
+			.collect()
+	}
+
+	/// Returns the number of polynomials in every chunk.
+	#[getter]
+	fn polynomial_counts(&self) -> Vec<u64> {
+		self.inner
+			.chunks
+			.iter()
+			.map(|c| c.num_polynomials)
+			.collect()
+	}
+
+	/// Returns the noise budget of every chunk, or None under CKKS.
+	#[getter]
+	fn noise_budgets(&self) -> Vec<Option<u32>> {
+		self.inner.chunks.iter().map(|c| c.noise_budget).collect()
+	}
+
+	/// Returns the lowest level of any chunk.
+	#[getter]
+	fn min_level(&self) -> Option<u64> {
+		self.inner.min_level()
+	}
+
+	/// Returns the smallest noise budget of any chunk.
+	#[getter]
+	fn min_noise_budget(&self) -> Option<u32> {
+		self.inner.min_noise_budget()
+	}
+
+	fn __str__(&self) -> String {
+		self.inner.to_string()
+	}
+
+	fn __repr__(&self) -> String {
+		format!("TensorDiagnostics({})", self.inner)
+	}
 }
 
 /// An encoder that encodes data in batches.
@@ -193,15 +458,19 @@ impl PyCKKSTensorEncoder {
 	/// Encodes the given data into a plaintext.
 	///
 	/// # Arguments
-	/// * `data` - The data to encode.
+	/// * `data` - The data to encode, in row-major order.
+	/// * `shape` - The shape of the data. Defaults to a flat shape.
 	///
 	/// # Returns
 	/// The encoded plaintext.
+	#[pyo3(signature = (data, shape=None))]
 	fn encode_float(
 		&self,
 		data: Vec<f64>,
+		shape: Option<Vec<usize>>,
 	) -> PyResult<PyPlaintextTensor> {
-		let batch = self.inner.encode_f64(&data).map_err(|e| {
+		let shape = shape.unwrap_or_else(|| vec![data.len()]);
+		let batch = self.inner.encode_f64_shaped(&data, shape).map_err(|e| {
 			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
 				"Failed to encode batch: {:?}",
 				e
@@ -435,3 +704,326 @@ impl PyCKKSTensorEvaluator {
 		})
 	}
 }
+
+/// An encoder that encodes integer data in batches.
+#[pyclass(module = "sealy", name = "BFVTensorEncoder")]
+pub struct PyBFVTensorEncoder {
+	inner: sealy::TensorEncoder<sealy::BFVEncoder>,
+}
+
+#[pymethods]
+impl PyBFVTensorEncoder {
+	/// Creates a new TensorEncoder.
+	#[new]
+	fn new(ctx: &PyContext) -> PyResult<Self> {
+		let encoder = sealy::BFVEncoder::new(&ctx.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to create BFVEncoder: {:?}",
+				e
+			))
+		})?;
+		let inner = sealy::TensorEncoder::new(encoder);
+		Ok(Self {
+			inner,
+		})
+	}
+
+	/// Returns the number of slots in this encoder produces.
+	fn get_slot_count(&self) -> usize {
+		self.inner.get_slot_count()
+	}
+
+	/// Encodes the given data into a plaintext.
+	///
+	/// # Arguments
+	/// * `data` - The data to encode, in row-major order.
+	/// * `shape` - The shape of the data. Defaults to a flat shape.
+	///
+	/// # Returns
+	/// The encoded plaintext.
+	#[pyo3(signature = (data, shape=None))]
+	fn encode_int(
+		&self,
+		data: Vec<i64>,
+		shape: Option<Vec<usize>>,
+	) -> PyResult<PyPlaintextTensor> {
+		let shape = shape.unwrap_or_else(|| vec![data.len()]);
+		let batch = self.inner.encode_i64_shaped(&data, shape).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to encode batch: {:?}",
+				e
+			))
+		})?;
+		Ok(PyPlaintextTensor {
+			inner: batch,
+		})
+	}
+
+	/// Decodes the given plaintext into data.
+	///
+	/// # Arguments
+	/// * `batch` - The encoded data.
+	///
+	/// # Returns
+	/// The decoded data.
+	fn decode_int(
+		&self,
+		batch: PyPlaintextTensor,
+	) -> PyResult<Vec<i64>> {
+		let data = self.inner.decode_i64(&batch.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to decode batch: {:?}",
+				e
+			))
+		})?;
+
+		Ok(data)
+	}
+}
+
+// An evaluator that evaluates batches of data.
+#[pyclass(module = "sealy", name = "BFVTensorEvaluator")]
+pub struct PyBFVTensorEvaluator {
+	inner: sealy::TensorEvaluator<sealy::BFVEvaluator>,
+}
+
+#[pymethods]
+impl PyBFVTensorEvaluator {
+	/// Creates a new TensorEvaluator.
+	#[new]
+	fn new(ctx: &PyContext) -> PyResult<Self> {
+		let inner = sealy::TensorEvaluator::bfv(&ctx.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to create BFVTensorEvaluator: {:?}",
+				e
+			))
+		})?;
+
+		Ok(Self {
+			inner,
+		})
+	}
+
+	/// Negates a batch of ciphertexts.
+	pub fn negate(
+		&self,
+		a: &PyCiphertextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let negated = self.inner.negate(&a.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to negate batch: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: negated,
+		})
+	}
+
+	/// Adds two ciphertexts.
+	pub fn add(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyCiphertextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let sum = self.inner.add(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to add ciphertexts: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: sum,
+		})
+	}
+
+	/// Adds many ciphertexts.
+	pub fn add_many(
+		&self,
+		a: Vec<PyCiphertextTensor>,
+	) -> PyResult<PyCiphertextTensor> {
+		let mut ciphertexts = Vec::new();
+		for c in a {
+			ciphertexts.push(c.inner);
+		}
+		let sum = self.inner.add_many(&ciphertexts).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to add many ciphertexts: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: sum,
+		})
+	}
+
+	/// Multiplies two ciphertexts.
+	pub fn multiply(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyCiphertextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let product = self.inner.multiply(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to multiply ciphertexts: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: product,
+		})
+	}
+
+	/// Multiplies many ciphertexts.
+	pub fn multiply_many(
+		&self,
+		a: Vec<PyCiphertextTensor>,
+		relin_keys: &PyRelinearizationKey,
+	) -> PyResult<PyCiphertextTensor> {
+		let mut ciphertexts = Vec::new();
+		for c in a {
+			ciphertexts.push(c.inner);
+		}
+		let product = self
+			.inner
+			.multiply_many(&ciphertexts, &relin_keys.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to multiply many ciphertexts: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertextTensor {
+			inner: product,
+		})
+	}
+
+	/// Subtracts two ciphertexts.
+	pub fn sub(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyCiphertextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let difference = self.inner.sub(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to subtract ciphertexts: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: difference,
+		})
+	}
+
+	/// Adds a ciphertext and a plaintext.
+	pub fn add_plain(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyPlaintextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let sum = self.inner.add_plain(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to add plaintext to ciphertext: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: sum,
+		})
+	}
+
+	/// Subtracts a plaintext from a ciphertext.
+	pub fn sub_plain(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyPlaintextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let difference = self.inner.sub_plain(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to subtract plaintext from ciphertext: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: difference,
+		})
+	}
+
+	/// Multiplies a ciphertext by a plaintext.
+	pub fn multiply_plain(
+		&self,
+		a: &PyCiphertextTensor,
+		b: &PyPlaintextTensor,
+	) -> PyResult<PyCiphertextTensor> {
+		let product = self.inner.multiply_plain(&a.inner, &b.inner).map_err(|e| {
+			PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+				"Failed to multiply ciphertext by plaintext: {:?}",
+				e
+			))
+		})?;
+		Ok(PyCiphertextTensor {
+			inner: product,
+		})
+	}
+
+	pub fn relinearize(
+		&self,
+		a: &PyCiphertextTensor,
+		relin_keys: &PyRelinearizationKey,
+	) -> PyResult<PyCiphertextTensor> {
+		let relinearized = self
+			.inner
+			.relinearize(&a.inner, &relin_keys.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to relinearize ciphertext: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertextTensor {
+			inner: relinearized,
+		})
+	}
+
+	/// Rotates the rows of every ciphertext in the batch cyclically by the
+	/// given number of steps.
+	pub fn rotate_rows(
+		&self,
+		a: &PyCiphertextTensor,
+		steps: i32,
+		galois_keys: &PyGaloisKey,
+	) -> PyResult<PyCiphertextTensor> {
+		let rotated = self
+			.inner
+			.rotate_rows(&a.inner, steps, &galois_keys.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to rotate rows: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertextTensor {
+			inner: rotated,
+		})
+	}
+
+	/// Swaps the two rows of every ciphertext in the batch.
+	pub fn rotate_columns(
+		&self,
+		a: &PyCiphertextTensor,
+		galois_keys: &PyGaloisKey,
+	) -> PyResult<PyCiphertextTensor> {
+		let rotated = self
+			.inner
+			.rotate_columns(&a.inner, &galois_keys.inner)
+			.map_err(|e| {
+				PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+					"Failed to rotate columns: {:?}",
+					e
+				))
+			})?;
+		Ok(PyCiphertextTensor {
+			inner: rotated,
+		})
+	}
+}
@@ -78,8 +78,10 @@ fn run_benchmark(
 	let encoder = CKKSEncoder::new(&ctx, scale).expect("Failed to create encoder");
 	let batch_encoder = TensorEncoder::new(encoder);
 
-	let public_key = key_gen.create_public_key();
-	let private_key = key_gen.secret_key();
+	let public_key = key_gen
+		.create_public_key()
+		.expect("Failed to create public key");
+	let private_key = key_gen.secret_key().expect("Failed to create secret key");
 
 	println!("Encoding clients gradients...");
 	let mut plaintexts = Vec::with_capacity(num_clients);
@@ -142,6 +142,7 @@ fn main() {
 		.allowlist_function("KeyGenerator_.*")
 		.allowlist_function("KSwitchKeys_.*")
 		.allowlist_function("MemoryManager_.*")
+		.allowlist_function("MMProf_.*")
 		.allowlist_function("MemoryPoolHandle_.*")
 		.allowlist_function("Modulus_.*")
 		.allowlist_function("Plaintext_.*")
@@ -68,8 +68,8 @@ fn main() -> Result<(), Error> {
 	let scale = 2.0f64.powi(40);
 	let encoder = CKKSEncoder::new(&ctx, scale)?;
 
-	let public_key = key_gen.create_public_key();
-	let private_key = key_gen.secret_key();
+	let public_key = key_gen.create_public_key()?;
+	let private_key = key_gen.secret_key()?;
 
 	let client_1_gradients = generate_random_tensor(10);
 	let client_2_gradients = generate_random_tensor(10);
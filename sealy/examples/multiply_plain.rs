@@ -17,8 +17,8 @@ fn main() -> anyhow::Result<()> {
 
 	let encoder = BFVEncoder::new(&ctx)?;
 
-	let public_key = gen.create_public_key();
-	let secret_key = gen.secret_key();
+	let public_key = gen.create_public_key()?;
+	let secret_key = gen.secret_key()?;
 
 	let encryptor = Encryptor::with_public_key(&ctx, &public_key)?;
 	let decryptor = Decryptor::new(&ctx, &secret_key)?;
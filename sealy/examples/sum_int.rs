@@ -22,8 +22,8 @@ fn main() -> anyhow::Result<()> {
 	let key_gen = KeyGenerator::new(&ctx)?;
 	let encoder = BFVEncoder::new(&ctx)?;
 
-	let public_key = key_gen.create_public_key();
-	let private_key = key_gen.secret_key();
+	let public_key = key_gen.create_public_key()?;
+	let private_key = key_gen.secret_key()?;
 
 	let encryptor = Encryptor::with_public_and_secret_key(&ctx, &public_key, &private_key)?;
 	let decryptor = Decryptor::new(&ctx, &private_key)?;
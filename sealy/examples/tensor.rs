@@ -62,8 +62,8 @@ fn main() -> Result<(), Error> {
 
 	let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40))?);
 
-	let public_key = key_gen.create_public_key();
-	let private_key = key_gen.secret_key();
+	let public_key = key_gen.create_public_key()?;
+	let private_key = key_gen.secret_key()?;
 
 	let encryptor = TensorEncryptor::with_public_and_secret_key(&ctx, &public_key, &private_key)?;
 	let decryptor = TensorDecryptor::new(&ctx, &private_key)?;
@@ -3,9 +3,22 @@ use std::fmt::Debug;
 use std::ptr::null_mut;
 use std::sync::atomic::{AtomicPtr, Ordering};
 
-use crate::{bindgen, serialization::CompressionType, Context, FromBytes, ToBytes};
+use crate::{
+	bindgen,
+	serialization::{load_checked, CompressionType},
+	Context, EncryptionParameters, FromBytes, ToBytes,
+};
 use crate::{error::*, try_seal};
 
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+
+/// The smallest number of polynomials in a valid ciphertext.
+pub(crate) const CIPHERTEXT_SIZE_MIN: u64 = 2;
+
+/// The largest number of polynomials in a valid ciphertext.
+pub(crate) const CIPHERTEXT_SIZE_MAX: u64 = 16;
+
 /// Class to store a ciphertext element. The data for a ciphertext consists
 /// of two or more polynomials, which are in Microsoft SEAL stored in a CRT
 /// form with respect to the factors of the coefficient modulus. This data
@@ -49,22 +62,21 @@ impl Ciphertext {
 	}
 
 	/// Returns the number of polynomials in this ciphertext.
-	pub fn num_polynomials(&self) -> u64 {
+	pub fn num_polynomials(&self) -> Result<u64> {
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe { bindgen::Ciphertext_Size(self.get_handle(), &mut size) }).unwrap();
+		try_seal!(unsafe { bindgen::Ciphertext_Size(self.get_handle(), &mut size) })?;
 
-		size
+		Ok(size)
 	}
 
 	/// Returns the number of components in the coefficient modulus.
-	pub fn coeff_modulus_size(&self) -> u64 {
+	pub fn coeff_modulus_size(&self) -> Result<u64> {
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe { bindgen::Ciphertext_CoeffModulusSize(self.get_handle(), &mut size) })
-			.unwrap();
+		try_seal!(unsafe { bindgen::Ciphertext_CoeffModulusSize(self.get_handle(), &mut size) })?;
 
-		size
+		Ok(size)
 	}
 
 	/// Returns the value at a specific point in the coefficient array. This is
@@ -91,7 +103,7 @@ impl Ciphertext {
 		poly_index: usize,
 		coeff_index: usize,
 	) -> Result<Vec<u64>> {
-		let size = self.coeff_modulus_size();
+		let size = self.coeff_modulus_size()?;
 		let mut data: Vec<u64> = Vec::with_capacity(size as usize);
 
 		try_seal!(unsafe {
@@ -110,14 +122,96 @@ impl Ciphertext {
 		Ok(data.clone())
 	}
 
+	/// Returns the parms_id of the encryption parameters this ciphertext is
+	/// valid for.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+		try_seal!(unsafe {
+			bindgen::Ciphertext_ParmsId(self.get_handle(), parms_id.as_mut_ptr())
+		})?;
+
+		Ok(parms_id)
+	}
+
+	/// Returns the degree of the polynomials in this ciphertext.
+	pub fn poly_modulus_degree(&self) -> Result<u64> {
+		let mut degree: u64 = 0;
+
+		try_seal!(unsafe {
+			bindgen::Ciphertext_PolyModulusDegree(self.get_handle(), &mut degree)
+		})?;
+
+		Ok(degree)
+	}
+
+	/// Returns the scale of the ciphertext. Only meaningful for CKKS; BFV
+	/// ciphertexts have a scale of 1.
+	pub fn scale(&self) -> Result<f64> {
+		let mut scale = 0.0;
+
+		try_seal!(unsafe { bindgen::Ciphertext_Scale(self.get_handle(), &mut scale) })?;
+
+		Ok(scale)
+	}
+
+	/// Overrides the scale of the ciphertext without touching its data, so
+	/// every value it decrypts to is multiplied by the old scale over the new
+	/// one.
+	pub(crate) fn set_scale(
+		&self,
+		scale: f64,
+	) -> Result<()> {
+		try_seal!(unsafe { bindgen::Ciphertext_SetScale(self.get_handle(), scale) })?;
+
+		Ok(())
+	}
+
+	/// Checks whether the ciphertext data and metadata are valid for the given
+	/// context. This is a full check of every coefficient and can be slow for
+	/// large ciphertexts.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe {
+			bindgen::ValCheck_Ciphertext_IsValidFor(
+				self.get_handle(),
+				context.get_handle(),
+				&mut result,
+			)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Checks whether the ciphertext metadata (parms_id, sizes and number of
+	/// polynomials) matches a data level of the given context. This is cheap
+	/// but does not look at the coefficients.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let level = match context.get_chain_level(&self.parms_id()?)? {
+			Some(level) => level,
+			None => return Ok(false),
+		};
+
+		Ok(level.chain_index <= context.get_first_chain_index()?
+			&& level.poly_modulus_degree == self.poly_modulus_degree()?
+			&& level.coeff_modulus_size == self.coeff_modulus_size()?
+			&& (CIPHERTEXT_SIZE_MIN..=CIPHERTEXT_SIZE_MAX).contains(&self.num_polynomials()?))
+	}
+
 	/// Returns whether the ciphertext is in NTT form.
-	pub fn is_ntt_form(&self) -> bool {
+	pub fn is_ntt_form(&self) -> Result<bool> {
 		let mut result = false;
 
-		try_seal!(unsafe { bindgen::Ciphertext_IsNTTForm(self.get_handle(), &mut result) })
-			.expect("Fatal error in Plaintext::is_ntt_form().");
+		try_seal!(unsafe { bindgen::Ciphertext_IsNTTForm(self.get_handle(), &mut result) })?;
 
-		result
+		Ok(result)
 	}
 }
 
@@ -162,16 +256,28 @@ impl PartialEq for Ciphertext {
 
 impl ToBytes for Ciphertext {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::Ciphertext_SaveSize(
-				self.get_handle(),
-				CompressionType::ZStd as u8,
-				&mut num_bytes,
-			)
+			bindgen::Ciphertext_SaveSize(self.get_handle(), compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -181,8 +287,8 @@ impl ToBytes for Ciphertext {
 			bindgen::Ciphertext_Save(
 				self.get_handle(),
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -193,22 +299,50 @@ impl ToBytes for Ciphertext {
 	}
 }
 
+impl Serialize for Ciphertext {
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let data = self
+			.as_bytes()
+			.map_err(|e| S::Error::custom(format!("Failed to get ciphertext bytes: {}", e)))?;
+
+		serializer.serialize_bytes(&data)
+	}
+}
+
 impl FromBytes for Ciphertext {
 	type State = Context;
 	fn from_bytes(
 		context: &Context,
 		bytes: &[u8],
 	) -> Result<Self> {
-		let ciphertext = Self::new()?;
-		let mut bytes_read = 0i64;
+		// SAFETY: the object is checked for the context before it is returned.
+		let ciphertext = unsafe { Self::from_bytes_unchecked(context, bytes)? };
 
-		try_seal!(unsafe {
-			bindgen::Ciphertext_Load(
+		if !ciphertext.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
+
+		Ok(ciphertext)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let ciphertext = Self::new()?;
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::Ciphertext_UnsafeLoad(
 				ciphertext.get_handle(),
 				context.get_handle(),
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut bytes_read,
+				data,
+				len,
+				bytes_read,
 			)
 		})?;
 
@@ -216,10 +350,53 @@ impl FromBytes for Ciphertext {
 	}
 }
 
+/// A symmetric-key ciphertext whose second polynomial is replaced by the
+/// random number seed used to generate it. This form isn't directly usable,
+/// but serializes in roughly half the size of a regular ciphertext. Load the
+/// bytes with [`Ciphertext::from_bytes`] to expand it back into a full
+/// ciphertext.
+pub struct CompactCiphertext(Ciphertext);
+
+impl CompactCiphertext {
+	pub(crate) fn new(ciphertext: Ciphertext) -> Self {
+		Self(ciphertext)
+	}
+}
+
+impl Debug for CompactCiphertext {
+	fn fmt(
+		&self,
+		f: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result {
+		f.debug_tuple("CompactCiphertext").field(&self.0).finish()
+	}
+}
+
+impl ToBytes for CompactCiphertext {
+	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
+		self.0.save_size(compression)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		self.0.as_bytes_with(compression)
+	}
+}
+
 impl Drop for Ciphertext {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Ciphertext_Destroy(self.get_handle()) })
-			.expect("Internal error in Ciphertext::drop");
+		unsafe {
+			bindgen::Ciphertext_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -175,6 +175,112 @@ impl Context {
 		Ok(bit_count)
 	}
 
+	/// Describes the level of the modulus switching chain identified by the
+	/// given parms_id, or returns `None` if the parms_id is not part of this
+	/// context.
+	pub(crate) fn get_chain_level(
+		&self,
+		parms_id: &[u64],
+	) -> Result<Option<ChainLevel>> {
+		let mut context_data: *mut c_void = null_mut();
+
+		try_seal!(unsafe {
+			let mut parms_id = parms_id.to_vec();
+			let parms_id_ptr = parms_id.as_mut_ptr();
+			bindgen::SEALContext_GetContextData(self.get_handle(), parms_id_ptr, &mut context_data)
+		})?;
+
+		if context_data.is_null() {
+			return Ok(None);
+		}
+
+		let mut chain_index: u64 = 0;
+		let mut parms: *mut c_void = null_mut();
+
+		try_seal!(unsafe { bindgen::ContextData_ChainIndex(context_data, &mut chain_index) })?;
+		try_seal!(unsafe { bindgen::ContextData_Parms(context_data, &mut parms) })?;
+
+		let parms = EncryptionParameters {
+			handle: parms,
+		};
+
+		Ok(Some(ChainLevel {
+			chain_index,
+			poly_modulus_degree: parms.get_poly_modulus_degree()?,
+			coeff_modulus_size: parms.get_coefficient_modulus()?.len() as u64,
+		}))
+	}
+
+	/// Returns the chain index of the first (highest) data level. Levels above
+	/// it are reserved for keys.
+	pub(crate) fn get_first_chain_index(&self) -> Result<u64> {
+		let mut chain_index: u64 = 0;
+
+		try_seal!(unsafe {
+			let context_data = self.get_first_context_data()?;
+			bindgen::ContextData_ChainIndex(context_data, &mut chain_index)
+		})?;
+
+		Ok(chain_index)
+	}
+
+	/// Returns the values of the primes in the coefficient modulus of the first
+	/// (highest) data level. Each lower level drops the last of them.
+	pub(crate) fn get_first_coeff_modulus(&self) -> Result<Vec<u64>> {
+		let mut parms: *mut c_void = null_mut();
+
+		try_seal!(unsafe {
+			let context_data = self.get_first_context_data()?;
+			bindgen::ContextData_Parms(context_data, &mut parms)
+		})?;
+
+		let parms = EncryptionParameters {
+			handle: parms,
+		};
+
+		parms
+			.get_coefficient_modulus()?
+			.iter()
+			.map(|modulus| modulus.value())
+			.collect()
+	}
+
+	/// Returns the parms_id and encryption parameters of every data level in
+	/// the modulus switching chain, from the first (highest) level down to the
+	/// last.
+	pub(crate) fn get_data_levels(&self) -> Result<Vec<DataLevel>> {
+		let mut levels = vec![];
+		let mut context_data = unsafe { self.get_first_context_data()? };
+
+		while !context_data.is_null() {
+			let mut parms: *mut c_void = null_mut();
+			let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+			try_seal!(unsafe { bindgen::ContextData_Parms(context_data, &mut parms) })?;
+
+			let parms = EncryptionParameters {
+				handle: parms,
+			};
+
+			try_seal!(unsafe {
+				bindgen::EncParams_GetParmsId(parms.handle, parms_id.as_mut_ptr())
+			})?;
+
+			levels.push(DataLevel {
+				parms_id,
+				parms,
+			});
+
+			let mut next: *mut c_void = null_mut();
+
+			try_seal!(unsafe { bindgen::ContextData_NextContextData(context_data, &mut next) })?;
+
+			context_data = next;
+		}
+
+		Ok(levels)
+	}
+
 	/// Returns the ContextData given a parms_id.
 	#[allow(unused)]
 	unsafe fn get_context_data(
@@ -197,7 +303,6 @@ impl Context {
 	}
 
 	/// Returns the first ContextData in the modulus switching chain.
-	#[allow(unused)]
 	unsafe fn get_first_context_data(&self) -> Result<*mut c_void> {
 		let mut context_data: *mut c_void = null_mut();
 
@@ -229,10 +334,30 @@ impl Context {
 	}
 }
 
+/// The shape of the data at one level of the modulus switching chain.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct ChainLevel {
+	/// The position in the chain. The last (lowest) level has index 0.
+	pub(crate) chain_index: u64,
+	/// The degree of the polynomial modulus.
+	pub(crate) poly_modulus_degree: u64,
+	/// The number of primes left in the coefficient modulus.
+	pub(crate) coeff_modulus_size: u64,
+}
+
+/// One data level of the modulus switching chain.
+pub(crate) struct DataLevel {
+	/// The parms_id identifying the level.
+	pub(crate) parms_id: Vec<u64>,
+	/// The encryption parameters at the level.
+	pub(crate) parms: EncryptionParameters,
+}
+
 impl Drop for Context {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::SEALContext_Destroy(self.get_handle()) })
-			.expect("Internal error in Context::drop().");
+		unsafe {
+			bindgen::SEALContext_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -272,9 +397,16 @@ mod tests {
 
 		let expected_params = ctx.get_encryption_parameters().unwrap();
 
-		assert_eq!(expected_params.get_poly_modulus_degree(), 1024);
-		assert_eq!(expected_params.get_scheme(), SchemeType::Bfv);
-		assert_eq!(expected_params.get_plain_modulus().value(), 1234);
-		assert_eq!(expected_params.get_coefficient_modulus().len(), 5);
+		assert_eq!(expected_params.get_poly_modulus_degree().unwrap(), 1024);
+		assert_eq!(expected_params.get_scheme().unwrap(), SchemeType::Bfv);
+		assert_eq!(
+			expected_params
+				.get_plain_modulus()
+				.unwrap()
+				.value()
+				.unwrap(),
+			1234
+		);
+		assert_eq!(expected_params.get_coefficient_modulus().unwrap().len(), 5);
 	}
 }
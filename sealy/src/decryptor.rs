@@ -4,7 +4,11 @@ use std::{
 	sync::atomic::{AtomicPtr, Ordering},
 };
 
-use crate::{bindgen, error::Result, try_seal, Ciphertext, Context, Plaintext, SecretKey};
+use crate::{
+	bindgen,
+	error::{Error, Result},
+	try_seal, CKKSEncoder, Ciphertext, Context, Plaintext, SecretKey,
+};
 
 /// Decrypts Ciphertext objects into Plaintext objects. Constructing a Decryptor requires
 /// a SEALContext with valid encryption parameters, and the secret key. The Decryptor is
@@ -33,9 +37,12 @@ impl Decryptor {
 	) -> Result<Self> {
 		let mut handle = null_mut();
 
-		try_seal!(unsafe {
-			bindgen::Decryptor_Create(ctx.get_handle(), secret_key.get_handle(), &mut handle)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Decryptor_Create(ctx.get_handle(), secret_key.get_handle(), &mut handle)
+			},
+			"Decryptor::new"
+		)?;
 
 		Ok(Self {
 			handle: AtomicPtr::new(handle),
@@ -56,13 +63,16 @@ impl Decryptor {
 	) -> Result<Plaintext> {
 		let plaintext = Plaintext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Decryptor_Decrypt(
-				self.get_handle(),
-				ciphertext.get_handle(),
-				plaintext.get_handle(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Decryptor_Decrypt(
+					self.get_handle(),
+					ciphertext.get_handle(),
+					plaintext.get_handle(),
+				)
+			},
+			"Decryptor::decrypt"
+		)?;
 
 		Ok(plaintext)
 	}
@@ -90,13 +100,16 @@ impl Decryptor {
 	) -> Result<u32> {
 		let mut noise: i32 = 0;
 
-		try_seal!(unsafe {
-			bindgen::Decryptor_InvariantNoiseBudget(
-				self.get_handle(),
-				ciphertext.get_handle(),
-				&mut noise,
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Decryptor_InvariantNoiseBudget(
+					self.get_handle(),
+					ciphertext.get_handle(),
+					&mut noise,
+				)
+			},
+			"Decryptor::invariant_noise_budget"
+		)?;
 
 		Ok(noise as u32)
 	}
@@ -118,22 +131,98 @@ impl Decryptor {
 	) -> Result<f64> {
 		let mut noise: f64 = 0f64;
 
-		try_seal!(unsafe {
-			bindgen::Decryptor_InvariantNoise(
-				self.get_handle(),
-				ciphertext.get_handle(),
-				&mut noise,
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Decryptor_InvariantNoise(
+					self.get_handle(),
+					ciphertext.get_handle(),
+					&mut noise,
+				)
+			},
+			"Decryptor::invariant_noise"
+		)?;
 
 		Ok(noise)
 	}
+	/// Measures how far the CKKS ciphertext decrypts from the values it is
+	/// expected to hold. CKKS has no noise budget; its noise instead shows up
+	/// as error in the decoded values, which this reports.
+	///
+	/// Only the first `expected.len()` slots are compared.
+	///
+	/// * `encoder` - The encoder to decode the decrypted plaintext with.
+	/// * `ciphertext` - The ciphertext to measure.
+	/// * `expected` - The exact values the ciphertext should hold.
+	pub fn measure_precision(
+		&self,
+		encoder: &CKKSEncoder,
+		ciphertext: &Ciphertext,
+		expected: &[f64],
+	) -> Result<Precision> {
+		let actual = encoder.decode_f64(&self.decrypt(ciphertext)?)?;
+
+		Precision::between(expected, &actual)
+	}
+}
+
+/// The error of decrypted CKKS values against the values they approximate.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Precision {
+	/// The largest absolute error of any slot.
+	pub max_error: f64,
+	/// The mean absolute error.
+	pub mean_error: f64,
+	/// The root mean square error.
+	pub rms_error: f64,
+	/// The bits of precision left after the binary point in the worst slot,
+	/// i.e. -log2(max_error). Infinite if the values are exact.
+	pub bits: f64,
+}
+
+impl Precision {
+	/// Compares `actual` against `expected` slot by slot. Slots of `actual`
+	/// beyond `expected.len()` are ignored. Returns
+	/// [`Error::InvalidArgument`] if `expected` is empty or longer than
+	/// `actual`.
+	///
+	/// * `expected` - The exact values.
+	/// * `actual` - The approximated values, e.g. decoded from a ciphertext.
+	pub fn between(
+		expected: &[f64],
+		actual: &[f64],
+	) -> Result<Self> {
+		if expected.is_empty() || expected.len() > actual.len() {
+			return Err(Error::InvalidArgument);
+		}
+
+		let mut max_error = 0f64;
+		let mut sum = 0f64;
+		let mut sum_squares = 0f64;
+
+		for (e, a) in expected.iter().zip(actual) {
+			let error = (e - a).abs();
+
+			max_error = max_error.max(error);
+			sum += error;
+			sum_squares += error * error;
+		}
+
+		let count = expected.len() as f64;
+
+		Ok(Self {
+			max_error,
+			mean_error: sum / count,
+			rms_error: (sum_squares / count).sqrt(),
+			bits: -max_error.log2(),
+		})
+	}
 }
 
 impl Drop for Decryptor {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Decryptor_Destroy(self.get_handle()) })
-			.expect("Internal error Decryptor::drop().");
+		unsafe {
+			bindgen::Decryptor_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -141,7 +230,7 @@ impl Drop for Decryptor {
 mod tests {
 	use crate::*;
 
-	use super::Decryptor;
+	use super::{Decryptor, Precision};
 
 	fn mk_ctx<F>(enc_modifier: F) -> Context
 	where
@@ -172,7 +261,7 @@ mod tests {
 		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let secret_key = gen.secret_key();
+		let secret_key = gen.secret_key().unwrap();
 		let decryptor = Decryptor::new(&ctx, &secret_key);
 
 		std::mem::drop(decryptor);
@@ -195,8 +284,8 @@ mod tests {
 
 		let plaintext = encoder.encode_u64(&data).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -232,8 +321,8 @@ mod tests {
 
 		let plaintext = encoder.encode_i64(&data).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -269,8 +358,8 @@ mod tests {
 
 		let plaintext = encoder.encode_u64(&data).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -292,6 +381,61 @@ mod tests {
 		assert_eq!(data, data_2);
 	}
 
+	#[test]
+	fn precision_compares_the_expected_slots() {
+		let precision = Precision::between(&[1.0, 2.0, -3.0], &[1.5, 2.0, -3.25, 100.0]).unwrap();
+
+		assert_eq!(precision.max_error, 0.5);
+		assert_eq!(precision.mean_error, 0.25);
+		assert_eq!(precision.rms_error, (0.3125f64 / 3.0).sqrt());
+		assert_eq!(precision.bits, 1.0);
+
+		assert!(Precision::between(&[1.0, 2.0], &[1.0]).is_err());
+		assert!(Precision::between(&[], &[1.0]).is_err());
+		assert_eq!(
+			Precision::between(&[1.0], &[1.0]).unwrap().bits,
+			f64::INFINITY
+		);
+	}
+
+	#[test]
+	fn can_measure_ckks_precision() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let encoder = CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap();
+
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+
+		let encryptor =
+			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
+		let decryptor = Decryptor::new(&ctx, &secret_key).unwrap();
+
+		let data: Vec<f64> = (0..encoder.get_slot_count())
+			.map(|i| i as f64 / 100.0)
+			.collect();
+		let ciphertext = encryptor
+			.encrypt(&encoder.encode_f64(&data).unwrap())
+			.unwrap();
+
+		let precision = decryptor
+			.measure_precision(&encoder, &ciphertext, &data)
+			.unwrap();
+
+		assert!(precision.max_error < 1e-4);
+		assert!(precision.bits > 13.0);
+		assert!(precision.mean_error <= precision.rms_error);
+		assert!(precision.rms_error <= precision.max_error);
+	}
+
 	#[cfg(feature = "deterministic")]
 	mod deterministic {
 		use std::collections::hash_map::DefaultHasher;
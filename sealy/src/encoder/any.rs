@@ -0,0 +1,159 @@
+use crate::error::{Error, Result};
+use crate::{AnyPlaintext, BFVEncoder, CKKSEncoder, Context, Plaintext, SchemeType};
+
+/// A [`BFVEncoder`] or [`CKKSEncoder`], picked from the scheme of a context
+/// at runtime.
+///
+/// Plaintexts are tagged with the scheme they were encoded under. Encoding
+/// or decoding a type the scheme has no encoding for, such as `i64` under
+/// CKKS or `f64` under BFV, fails with [`Error::InvalidOperation`], and a
+/// plaintext of the other scheme with [`Error::ContextMismatch`], both
+/// wrapped with the operation's name.
+#[derive(Debug)]
+pub enum AnyEncoder {
+	/// Batches integers modulo the plain modulus.
+	Bfv(BFVEncoder),
+	/// Encodes real numbers at a scale.
+	Ckks(CKKSEncoder),
+}
+
+impl AnyEncoder {
+	/// Creates the encoder for the scheme of the given context. Fails with
+	/// [`Error::InvalidOperation`] if the context has no scheme.
+	///
+	/// * `ctx` - The context.
+	/// * `scale` - The scale CKKS values are encoded at. Unused under BFV.
+	pub fn new(
+		ctx: &Context,
+		scale: f64,
+	) -> Result<Self> {
+		match ctx.get_encryption_parameters()?.get_scheme()? {
+			SchemeType::Bfv => Ok(Self::Bfv(BFVEncoder::new(ctx)?)),
+			SchemeType::Ckks => Ok(Self::Ckks(CKKSEncoder::new(ctx, scale)?)),
+			SchemeType::None => Err(Error::InvalidOperation.in_operation("AnyEncoder::new")),
+		}
+	}
+
+	/// Returns the scheme of the encoder.
+	pub fn scheme(&self) -> SchemeType {
+		match self {
+			Self::Bfv(_) => SchemeType::Bfv,
+			Self::Ckks(_) => SchemeType::Ckks,
+		}
+	}
+
+	/// Returns the number of values a plaintext holds.
+	pub fn get_slot_count(&self) -> usize {
+		match self {
+			Self::Bfv(e) => e.get_slot_count(),
+			Self::Ckks(e) => e.get_slot_count(),
+		}
+	}
+
+	/// Encodes real numbers. CKKS only.
+	///
+	/// * `data` - The values to encode.
+	pub fn encode_f64(
+		&self,
+		data: &[f64],
+	) -> Result<AnyPlaintext> {
+		let encoder = self.ckks("AnyEncoder::encode_f64")?;
+
+		Ok(AnyPlaintext::Ckks(encoder.encode_f64(data)?))
+	}
+
+	/// Decodes real numbers. CKKS only.
+	///
+	/// * `plaintext` - The plaintext to decode.
+	pub fn decode_f64(
+		&self,
+		plaintext: &AnyPlaintext,
+	) -> Result<Vec<f64>> {
+		let operation = "AnyEncoder::decode_f64";
+		let encoder = self.ckks(operation)?;
+
+		encoder.decode_f64(self.plain(operation, plaintext)?)
+	}
+
+	/// Encodes signed integers. BFV only.
+	///
+	/// * `data` - The values to encode.
+	pub fn encode_i64(
+		&self,
+		data: &[i64],
+	) -> Result<AnyPlaintext> {
+		let encoder = self.bfv("AnyEncoder::encode_i64")?;
+
+		Ok(AnyPlaintext::Bfv(encoder.encode_i64(data)?))
+	}
+
+	/// Decodes signed integers. BFV only.
+	///
+	/// * `plaintext` - The plaintext to decode.
+	pub fn decode_i64(
+		&self,
+		plaintext: &AnyPlaintext,
+	) -> Result<Vec<i64>> {
+		let operation = "AnyEncoder::decode_i64";
+		let encoder = self.bfv(operation)?;
+
+		encoder.decode_i64(self.plain(operation, plaintext)?)
+	}
+
+	/// Encodes integers modulo the plain modulus. BFV only.
+	///
+	/// * `data` - The values to encode.
+	pub fn encode_u64(
+		&self,
+		data: &[u64],
+	) -> Result<AnyPlaintext> {
+		let encoder = self.bfv("AnyEncoder::encode_u64")?;
+
+		Ok(AnyPlaintext::Bfv(encoder.encode_u64(data)?))
+	}
+
+	/// Decodes integers modulo the plain modulus. BFV only.
+	///
+	/// * `plaintext` - The plaintext to decode.
+	pub fn decode_u64(
+		&self,
+		plaintext: &AnyPlaintext,
+	) -> Result<Vec<u64>> {
+		let operation = "AnyEncoder::decode_u64";
+		let encoder = self.bfv(operation)?;
+
+		encoder.decode_u64(self.plain(operation, plaintext)?)
+	}
+
+	fn bfv(
+		&self,
+		operation: &'static str,
+	) -> Result<&BFVEncoder> {
+		match self {
+			Self::Bfv(e) => Ok(e),
+			Self::Ckks(_) => Err(Error::InvalidOperation.in_operation(operation)),
+		}
+	}
+
+	fn ckks(
+		&self,
+		operation: &'static str,
+	) -> Result<&CKKSEncoder> {
+		match self {
+			Self::Ckks(e) => Ok(e),
+			Self::Bfv(_) => Err(Error::InvalidOperation.in_operation(operation)),
+		}
+	}
+
+	fn plain<'a>(
+		&self,
+		operation: &'static str,
+		plaintext: &'a AnyPlaintext,
+	) -> Result<&'a Plaintext> {
+		if plaintext.scheme() != self.scheme() {
+			return Err(Error::ContextMismatch.in_operation(operation));
+		}
+
+		Ok(plaintext.plaintext())
+	}
+}
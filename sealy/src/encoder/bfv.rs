@@ -6,6 +6,7 @@ use std::sync::atomic::Ordering;
 
 use crate::bindgen;
 use crate::error::*;
+use crate::memory::{self, MemoryPool};
 use crate::try_seal;
 use crate::{Context, Plaintext};
 
@@ -48,6 +49,8 @@ use crate::{Context, Plaintext};
 /// flags ParametersSet and EnableBatching set to true.
 pub struct BFVEncoder {
 	handle: AtomicPtr<c_void>,
+	pool: Option<MemoryPool>,
+	slot_count: usize,
 }
 
 impl BFVEncoder {
@@ -61,9 +64,29 @@ impl BFVEncoder {
 
 		try_seal!(unsafe { bindgen::BatchEncoder_Create(ctx.get_handle(), &mut handle) })?;
 
-		Ok(Self {
+		let mut encoder = Self {
 			handle: AtomicPtr::new(handle),
-		})
+			pool: None,
+			slot_count: 0,
+		};
+
+		let mut count: u64 = 0;
+
+		try_seal!(unsafe { bindgen::BatchEncoder_GetSlotCount(encoder.get_handle(), &mut count) })?;
+
+		encoder.slot_count = count as usize;
+
+		Ok(encoder)
+	}
+
+	/// Allocates from the given pool while decoding instead of the pool of
+	/// the current [`crate::MemoryProfile`].
+	pub fn with_memory_pool(
+		mut self,
+		pool: MemoryPool,
+	) -> Self {
+		self.pool = Some(pool);
+		self
 	}
 
 	/// Get the handle to the underlying SEAL object.
@@ -71,14 +94,14 @@ impl BFVEncoder {
 		self.handle.load(Ordering::SeqCst)
 	}
 
+	/// Get the handle of the pool to allocate from.
+	unsafe fn pool_handle(&self) -> *mut c_void {
+		memory::pool_handle(self.pool.as_ref())
+	}
+
 	/// Returns the number of "Batched" slots in this encoder produces.
 	pub fn get_slot_count(&self) -> usize {
-		let mut count: u64 = 0;
-
-		try_seal!(unsafe { bindgen::BatchEncoder_GetSlotCount(self.get_handle(), &mut count) })
-			.expect("Internal error in BVTEncoder::get_slot_count().");
-
-		count as usize
+		self.slot_count
 	}
 
 	/// Creates a plaintext from a given matrix. This function "batches" a given matrix
@@ -100,14 +123,17 @@ impl BFVEncoder {
 
 		// I pinky promise SEAL won't mutate data, the C bindings just aren't
 		// const correct.
-		try_seal!(unsafe {
-			bindgen::BatchEncoder_Encode1(
-				self.get_handle(),
-				data.len() as u64,
-				data.as_ptr() as *mut u64,
-				plaintext.get_handle(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::BatchEncoder_Encode1(
+					self.get_handle(),
+					data.len() as u64,
+					data.as_ptr() as *mut u64,
+					plaintext.get_handle(),
+				)
+			},
+			"BFVEncoder::encode_u64"
+		)?;
 
 		Ok(plaintext)
 	}
@@ -126,26 +152,27 @@ impl BFVEncoder {
 		&self,
 		plaintext: &Plaintext,
 	) -> Result<Vec<u64>> {
-		let mut data = Vec::with_capacity(self.get_slot_count());
+		// SEAL writes exactly one value per slot of the encoder's parameters,
+		// and rejects plaintexts of other parameters before writing anything.
+		let mut data = vec![0; self.slot_count];
 		let data_ptr = data.as_mut_ptr();
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe {
-			bindgen::BatchEncoder_Decode1(
-				self.get_handle(),
-				plaintext.get_handle(),
-				&mut size,
-				data_ptr,
-				null_mut(),
-			)
-		})?;
-
-		if data.capacity() < size as usize {
-			panic!("Allocation overflow BVTEncoder::decode_unsigned");
-		}
-
-		unsafe {
-			data.set_len(size as usize);
+		try_seal!(
+			unsafe {
+				bindgen::BatchEncoder_Decode1(
+					self.get_handle(),
+					plaintext.get_handle(),
+					&mut size,
+					data_ptr,
+					self.pool_handle(),
+				)
+			},
+			"BFVEncoder::decode_u64"
+		)?;
+
+		if size as usize != data.len() {
+			return Err(Error::InvalidArgument.in_operation("BFVEncoder::decode_u64"));
 		}
 
 		Ok(data)
@@ -170,14 +197,17 @@ impl BFVEncoder {
 
 		// We pinky promise SEAL won't mutate data, the C bindings just aren't
 		// const correct.
-		try_seal!(unsafe {
-			bindgen::BatchEncoder_Encode2(
-				self.get_handle(),
-				data.len() as u64,
-				data.as_ptr() as *mut i64,
-				plaintext.get_handle(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::BatchEncoder_Encode2(
+					self.get_handle(),
+					data.len() as u64,
+					data.as_ptr() as *mut i64,
+					plaintext.get_handle(),
+				)
+			},
+			"BFVEncoder::encode_i64"
+		)?;
 
 		Ok(plaintext)
 	}
@@ -196,26 +226,27 @@ impl BFVEncoder {
 		&self,
 		plaintext: &Plaintext,
 	) -> Result<Vec<i64>> {
-		let mut data = Vec::with_capacity(self.get_slot_count());
+		// SEAL writes exactly one value per slot of the encoder's parameters,
+		// and rejects plaintexts of other parameters before writing anything.
+		let mut data = vec![0; self.slot_count];
 		let data_ptr = data.as_mut_ptr();
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe {
-			bindgen::BatchEncoder_Decode2(
-				self.get_handle(),
-				plaintext.get_handle(),
-				&mut size,
-				data_ptr,
-				null_mut(),
-			)
-		})?;
-
-		if data.capacity() < size as usize {
-			panic!("Allocation overflow BVTEncoder::decode_unsigned");
-		}
-
-		unsafe {
-			data.set_len(size as usize);
+		try_seal!(
+			unsafe {
+				bindgen::BatchEncoder_Decode2(
+					self.get_handle(),
+					plaintext.get_handle(),
+					&mut size,
+					data_ptr,
+					self.pool_handle(),
+				)
+			},
+			"BFVEncoder::decode_i64"
+		)?;
+
+		if size as usize != data.len() {
+			return Err(Error::InvalidArgument.in_operation("BFVEncoder::decode_i64"));
 		}
 
 		Ok(data)
@@ -250,8 +281,9 @@ impl BFVEncoder {
 
 impl Drop for BFVEncoder {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::BatchEncoder_Destroy(self.get_handle()) })
-			.expect("Internal error in BFVEncoder::drop.");
+		unsafe {
+			bindgen::BatchEncoder_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -3,8 +3,9 @@ use std::fmt::Debug;
 use std::ptr::null_mut;
 use std::sync::atomic::{AtomicPtr, Ordering};
 
-use crate::error::Result;
-use crate::{bindgen, try_seal, Context, MemoryPool, Plaintext};
+use crate::error::{Error, Result};
+use crate::memory::{self, MemoryPool};
+use crate::{bindgen, try_seal, Context, Plaintext};
 
 /// To create CKKS plaintexts we need a special encoder: there is no other way
 /// to create them. The BatchEncoder cannot be used with the
@@ -16,6 +17,9 @@ pub struct CKKSEncoder {
 	handle: AtomicPtr<c_void>,
 	parms_id: Vec<u64>,
 	scale: f64,
+	coeff_modulus: Vec<u64>,
+	pool: Option<MemoryPool>,
+	slot_count: usize,
 }
 
 impl CKKSEncoder {
@@ -32,14 +36,36 @@ impl CKKSEncoder {
 
 		// TODO: Investigate how to properly set the parms_id in the ckks encoding.
 		let parms_id = ctx.get_first_parms_id()?;
+		let coeff_modulus = ctx.get_first_coeff_modulus()?;
 
 		try_seal!(unsafe { bindgen::CKKSEncoder_Create(ctx.get_handle(), &mut handle) })?;
 
-		Ok(Self {
+		let mut encoder = Self {
 			handle: AtomicPtr::new(handle),
 			parms_id,
 			scale,
-		})
+			coeff_modulus,
+			pool: None,
+			slot_count: 0,
+		};
+
+		let mut count: u64 = 0;
+
+		try_seal!(unsafe { bindgen::CKKSEncoder_SlotCount(encoder.get_handle(), &mut count) })?;
+
+		encoder.slot_count = count as usize;
+
+		Ok(encoder)
+	}
+
+	/// Allocates from the given pool while encoding and decoding instead of
+	/// the pool of the current [`crate::MemoryProfile`].
+	pub fn with_memory_pool(
+		mut self,
+		pool: MemoryPool,
+	) -> Self {
+		self.pool = Some(pool);
+		self
 	}
 
 	/// Get the handle to the underlying SEAL object.
@@ -47,14 +73,14 @@ impl CKKSEncoder {
 		self.handle.load(Ordering::SeqCst)
 	}
 
+	/// Get the handle of the pool to allocate from.
+	unsafe fn pool_handle(&self) -> *mut c_void {
+		memory::pool_handle(self.pool.as_ref())
+	}
+
 	/// Returns the number of  slots in this encoder produces.
 	pub fn get_slot_count(&self) -> usize {
-		let mut count: u64 = 0;
-
-		try_seal!(unsafe { bindgen::CKKSEncoder_SlotCount(self.get_handle(), &mut count) })
-			.expect("Internal error in BVTEncoder::get_slot_count().");
-
-		count as usize
+		self.slot_count
 	}
 
 	/// Creates a plaintext from a given matrix of f64 data.
@@ -78,29 +104,58 @@ impl CKKSEncoder {
 		&self,
 		data: &[f64],
 	) -> Result<Plaintext> {
-		let mem = MemoryPool::new()?;
+		self.encode_f64_at(data, &self.parms_id, self.scale)
+	}
 
+	/// Creates a plaintext from the given f64 data at the level identified
+	/// by `parms_id` and with the given scale, e.g. to match a ciphertext
+	/// that has been rescaled or switched down the modulus chain.
+	///
+	///  * `data` - The values to encode
+	///  * `parms_id` - The parms_id of the level to encode at
+	///  * `scale` - The scaling factor
+	pub fn encode_f64_at(
+		&self,
+		data: &[f64],
+		parms_id: &[u64],
+		scale: f64,
+	) -> Result<Plaintext> {
 		let plaintext = Plaintext::new()?;
 
 		// I pinky promise SEAL won't mutate data, the C bindings just aren't
 		// const correct.
-		try_seal!(unsafe {
-			let mut parms_id = self.parms_id.clone();
-			let parms_id_ptr = parms_id.as_mut_ptr();
-			bindgen::CKKSEncoder_Encode1(
-				self.get_handle(),
-				data.len() as u64,
-				data.as_ptr() as *mut f64,
-				parms_id_ptr,
-				self.scale,
-				plaintext.get_handle(),
-				mem.get_handle(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				let mut parms_id = parms_id.to_vec();
+				let parms_id_ptr = parms_id.as_mut_ptr();
+				bindgen::CKKSEncoder_Encode1(
+					self.get_handle(),
+					data.len() as u64,
+					data.as_ptr() as *mut f64,
+					parms_id_ptr,
+					scale,
+					plaintext.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"CKKSEncoder::encode_f64_at"
+		)?;
 
 		Ok(plaintext)
 	}
 
+	/// Returns the prime that rescaling a ciphertext with
+	/// `coeff_modulus_size` primes divides it by, i.e. the last prime of its
+	/// level, or `None` if no data level has that many primes.
+	pub(crate) fn rescale_prime(
+		&self,
+		coeff_modulus_size: u64,
+	) -> Option<u64> {
+		let index = (coeff_modulus_size as usize).checked_sub(1)?;
+
+		self.coeff_modulus.get(index).copied()
+	}
+
 	/// Inverse of encode. This function decodes a given plaintext into
 	/// a list of f64 elements.
 	///
@@ -109,28 +164,29 @@ impl CKKSEncoder {
 		&self,
 		plaintext: &Plaintext,
 	) -> Result<Vec<f64>> {
-		let mut data = Vec::with_capacity(self.get_slot_count());
+		// SEAL writes exactly one value per slot of the encoder's parameters,
+		// and rejects plaintexts of other parameters before writing anything.
+		let mut data = vec![0.0; self.slot_count];
 		let data_ptr = data.as_mut_ptr();
 		let mut size: u64 = 0;
 
 		// I pinky promise SEAL won't mutate data, the C bindings just aren't
 		// const correct.
-		try_seal!(unsafe {
-			bindgen::CKKSEncoder_Decode1(
-				self.get_handle(),
-				plaintext.get_handle(),
-				&mut size,
-				data_ptr,
-				null_mut(),
-			)
-		})?;
-
-		if data.capacity() < size as usize {
-			panic!("Allocation overflow BVTEncoder::decode_unsigned");
-		}
-
-		unsafe {
-			data.set_len(size as usize);
+		try_seal!(
+			unsafe {
+				bindgen::CKKSEncoder_Decode1(
+					self.get_handle(),
+					plaintext.get_handle(),
+					&mut size,
+					data_ptr,
+					self.pool_handle(),
+				)
+			},
+			"CKKSEncoder::decode_f64"
+		)?;
+
+		if size as usize != data.len() {
+			return Err(Error::InvalidArgument.in_operation("CKKSEncoder::decode_f64"));
 		}
 
 		Ok(data)
@@ -146,6 +202,7 @@ impl Debug for CKKSEncoder {
 			.field("handle", &self.handle)
 			.field("parms_id", &self.parms_id)
 			.field("scale", &self.scale)
+			.field("coeff_modulus", &self.coeff_modulus)
 			.finish()
 	}
 }
@@ -1,3 +1,6 @@
+/// Encoder for a scheme chosen at runtime.
+pub mod any;
+
 /// BFV encoder.
 pub mod bfv;
 
@@ -7,11 +7,12 @@ use std::sync::atomic::Ordering;
 use crate::bindgen;
 use crate::component_marker;
 use crate::error::*;
+use crate::memory::{self, MemoryPool};
 use crate::poly_array::PolynomialArray;
 use crate::try_seal;
 use crate::{
-	Asym, AsymmetricComponents, Ciphertext, Context, Plaintext, PublicKey, SecretKey, Sym, SymAsym,
-	SymmetricComponents,
+	Asym, AsymmetricComponents, Ciphertext, CompactCiphertext, Context, Plaintext, PublicKey,
+	SecretKey, Sym, SymAsym, SymmetricComponents,
 };
 
 /// Encrypts Plaintext objects into Ciphertext objects.
@@ -40,6 +41,7 @@ use crate::{
 /// NTT form, and will throw an exception if this is not the case.
 pub struct Encryptor<T = ()> {
 	handle: AtomicPtr<c_void>,
+	pool: Option<MemoryPool>,
 	_marker: PhantomData<T>,
 }
 
@@ -57,6 +59,21 @@ impl<T> Encryptor<T> {
 	pub(crate) unsafe fn get_handle(&self) -> *mut c_void {
 		self.handle.load(Ordering::SeqCst)
 	}
+
+	/// Allocates from the given pool during encryption instead of the pool of
+	/// the current [`crate::MemoryProfile`].
+	pub fn with_memory_pool(
+		mut self,
+		pool: MemoryPool,
+	) -> Self {
+		self.pool = Some(pool);
+		self
+	}
+
+	/// Returns the handle of the pool to allocate from.
+	unsafe fn pool_handle(&self) -> *mut c_void {
+		memory::pool_handle(self.pool.as_ref())
+	}
 }
 
 impl Encryptor {
@@ -73,17 +90,21 @@ impl Encryptor {
 	) -> Result<Encryptor<SymAsym>> {
 		let mut handle: *mut c_void = null_mut();
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_Create(
-				ctx.get_handle(),
-				public_key.get_handle(),
-				secret_key.get_handle(),
-				&mut handle,
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_Create(
+					ctx.get_handle(),
+					public_key.get_handle(),
+					secret_key.get_handle(),
+					&mut handle,
+				)
+			},
+			"Encryptor::with_public_and_secret_key"
+		)?;
 
 		Ok(Encryptor {
 			handle: AtomicPtr::new(handle),
+			pool: None,
 			_marker: PhantomData,
 		})
 	}
@@ -96,17 +117,21 @@ impl Encryptor {
 	) -> Result<AsymmetricEncryptor> {
 		let mut handle: *mut c_void = null_mut();
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_Create(
-				ctx.get_handle(),
-				public_key.get_handle(),
-				null_mut(),
-				&mut handle,
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_Create(
+					ctx.get_handle(),
+					public_key.get_handle(),
+					null_mut(),
+					&mut handle,
+				)
+			},
+			"Encryptor::with_public_key"
+		)?;
 
 		Ok(Encryptor {
 			handle: AtomicPtr::new(handle),
+			pool: None,
 			_marker: PhantomData,
 		})
 	}
@@ -119,17 +144,21 @@ impl Encryptor {
 	) -> Result<SymmetricEncryptor> {
 		let mut handle: *mut c_void = null_mut();
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_Create(
-				ctx.get_handle(),
-				null_mut(),
-				secret_key.get_handle(),
-				&mut handle,
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_Create(
+					ctx.get_handle(),
+					null_mut(),
+					secret_key.get_handle(),
+					&mut handle,
+				)
+			},
+			"Encryptor::with_secret_key"
+		)?;
 
 		Ok(Encryptor {
 			handle: AtomicPtr::new(handle),
+			pool: None,
 			_marker: PhantomData,
 		})
 	}
@@ -186,14 +215,17 @@ impl<T: component_marker::Asym> Encryptor<T> {
 		// the regular encrypt function, we skip that allocation.
 		let ciphertext = Ciphertext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_Encrypt(
-				self.get_handle(),
-				plaintext.get_handle(),
-				ciphertext.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_Encrypt(
+					self.get_handle(),
+					plaintext.get_handle(),
+					ciphertext.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt"
+		)?;
 
 		Ok(ciphertext)
 	}
@@ -218,18 +250,21 @@ impl<T: component_marker::Asym> Encryptor<T> {
 		let e_destination = PolynomialArray::new()?;
 		let r_destination = Plaintext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptReturnComponents(
-				self.get_handle(),
-				plaintext.get_handle(),
-				true,
-				ciphertext.get_handle(),
-				u_destination.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptReturnComponents(
+					self.get_handle(),
+					plaintext.get_handle(),
+					true,
+					ciphertext.get_handle(),
+					u_destination.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_return_components"
+		)?;
 
 		Ok((
 			ciphertext,
@@ -264,19 +299,22 @@ impl<T: component_marker::Asym> Encryptor<T> {
 		let r_destination = Plaintext::new()?;
 
 		// We do not need the components so we do not export them.
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptReturnComponentsSetSeed(
-				self.get_handle(),
-				plaintext.get_handle(),
-				false,
-				ciphertext.get_handle(),
-				u_destination.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				seed.as_ptr() as *mut c_void,
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptReturnComponentsSetSeed(
+					self.get_handle(),
+					plaintext.get_handle(),
+					false,
+					ciphertext.get_handle(),
+					u_destination.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					seed.as_ptr() as *mut c_void,
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_deterministic"
+		)?;
 
 		Ok(ciphertext)
 	}
@@ -309,19 +347,22 @@ impl<T: component_marker::Asym> Encryptor<T> {
 		let r_destination = Plaintext::new()?;
 
 		// We do not need the components so we do not export them.
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptReturnComponentsSetSeed(
-				self.get_handle(),
-				plaintext.get_handle(),
-				true,
-				ciphertext.get_handle(),
-				u_destination.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				seed.as_ptr() as *mut c_void,
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptReturnComponentsSetSeed(
+					self.get_handle(),
+					plaintext.get_handle(),
+					true,
+					ciphertext.get_handle(),
+					u_destination.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					seed.as_ptr() as *mut c_void,
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_return_components_deterministic"
+		)?;
 
 		Ok((
 			ciphertext,
@@ -344,21 +385,51 @@ impl<T: component_marker::Sym> Encryptor<T> {
 	pub fn encrypt_symmetric(
 		&self,
 		plaintext: &Plaintext,
+	) -> Result<Ciphertext> {
+		self.encrypt_symmetric_internal(plaintext, false)
+	}
+
+	/// Encrypts a plaintext with the secret key and returns a compact
+	/// ciphertext.
+	///
+	/// Half of the ciphertext data is pseudo-randomly generated from a seed to
+	/// reduce the object size. The resulting serializable object cannot be used
+	/// directly and is meant to be serialized for the size reduction to have an
+	/// impact. Deserializing it with [`crate::FromBytes`] yields a regular
+	/// [`Ciphertext`].
+	///
+	/// * `plainext` - The plaintext to encrypt.
+	pub fn encrypt_symmetric_compact(
+		&self,
+		plaintext: &Plaintext,
+	) -> Result<CompactCiphertext> {
+		Ok(CompactCiphertext::new(
+			self.encrypt_symmetric_internal(plaintext, true)?,
+		))
+	}
+
+	fn encrypt_symmetric_internal(
+		&self,
+		plaintext: &Plaintext,
+		save_seed: bool,
 	) -> Result<Ciphertext> {
 		// We don't call the encrypt_return_components because the return
 		// components are allocated on the SEAL global memory pool. By calling
 		// the regular encrypt function, we skip that allocation.
 		let ciphertext = Ciphertext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptSymmetric(
-				self.get_handle(),
-				plaintext.get_handle(),
-				false,
-				ciphertext.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptSymmetric(
+					self.get_handle(),
+					plaintext.get_handle(),
+					save_seed,
+					ciphertext.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_symmetric"
+		)?;
 
 		Ok(ciphertext)
 	}
@@ -389,17 +460,20 @@ impl<T: component_marker::Sym> Encryptor<T> {
 		let r_destination = Plaintext::new()?;
 
 		// We do not need the components so we do not export them.
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptSymmetricReturnComponentsSetSeed(
-				self.get_handle(),
-				plaintext.get_handle(),
-				ciphertext.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				seed.as_ptr() as *mut c_void,
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptSymmetricReturnComponentsSetSeed(
+					self.get_handle(),
+					plaintext.get_handle(),
+					ciphertext.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					seed.as_ptr() as *mut c_void,
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_symmetric_deterministic"
+		)?;
 
 		Ok(ciphertext)
 	}
@@ -423,16 +497,19 @@ impl<T: component_marker::Sym> Encryptor<T> {
 		let e_destination = PolynomialArray::new()?;
 		let r_destination = Plaintext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptSymmetricReturnComponents(
-				self.get_handle(),
-				plaintext.get_handle(),
-				ciphertext.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptSymmetricReturnComponents(
+					self.get_handle(),
+					plaintext.get_handle(),
+					ciphertext.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_symmetric_return_components"
+		)?;
 
 		Ok((
 			ciphertext,
@@ -467,17 +544,20 @@ impl<T: component_marker::Sym> Encryptor<T> {
 		let r_destination = Plaintext::new()?;
 
 		// We do not need the components so we do not export them.
-		try_seal!(unsafe {
-			bindgen::Encryptor_EncryptSymmetricReturnComponentsSetSeed(
-				self.get_handle(),
-				plaintext.get_handle(),
-				ciphertext.get_handle(),
-				e_destination.get_handle(),
-				r_destination.get_handle(),
-				seed.as_ptr() as *mut c_void,
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Encryptor_EncryptSymmetricReturnComponentsSetSeed(
+					self.get_handle(),
+					plaintext.get_handle(),
+					ciphertext.get_handle(),
+					e_destination.get_handle(),
+					r_destination.get_handle(),
+					seed.as_ptr() as *mut c_void,
+					self.pool_handle(),
+				)
+			},
+			"Encryptor::encrypt_symmetric_return_components_deterministic"
+		)?;
 
 		Ok((
 			ciphertext,
@@ -488,8 +568,9 @@ impl<T: component_marker::Sym> Encryptor<T> {
 
 impl<T> Drop for Encryptor<T> {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Encryptor_Destroy(self.get_handle()) })
-			.expect("Internal error in Enryptor::drop");
+		unsafe {
+			bindgen::Encryptor_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -517,7 +598,7 @@ mod tests {
 		let ctx = mk_ctx(|b| b);
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let public_key = gen.create_public_key();
+		let public_key = gen.create_public_key().unwrap();
 
 		let encryptor = Encryptor::with_public_key(&ctx, &public_key).unwrap();
 
@@ -530,7 +611,7 @@ mod tests {
 
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let secret_key = gen.secret_key();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor = Encryptor::with_secret_key(&ctx, &secret_key).unwrap();
 
@@ -543,12 +624,36 @@ mod tests {
 
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
 
 		std::mem::drop(encryptor);
 	}
+
+	#[test]
+	fn compact_symmetric_ciphertext_is_smaller_and_decrypts() {
+		let ctx = mk_ctx(|b| b);
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let secret_key = gen.secret_key().unwrap();
+		let encryptor = Encryptor::with_secret_key(&ctx, &secret_key).unwrap();
+		let decryptor = Decryptor::new(&ctx, &secret_key).unwrap();
+
+		let plaintext = Plaintext::from_hex_string("12x^2 + 34").unwrap();
+
+		let full = encryptor.encrypt_symmetric(&plaintext).unwrap();
+		let compact = encryptor.encrypt_symmetric_compact(&plaintext).unwrap();
+
+		let full_bytes = full.as_bytes().unwrap();
+		let compact_bytes = compact.as_bytes().unwrap();
+
+		assert!(compact_bytes.len() * 3 < full_bytes.len() * 2);
+
+		let expanded = Ciphertext::from_bytes(&ctx, &compact_bytes).unwrap();
+
+		assert_eq!(decryptor.decrypt(&expanded).unwrap(), plaintext);
+	}
 }
@@ -1,7 +1,10 @@
 use std::os::raw::c_long;
+use std::sync::Arc;
 
 use static_assertions::const_assert;
 
+use crate::SchemeType;
+
 use crate::bindgen::{
 	COR_E_INVALIDOPERATION, COR_E_IO, E_INVALIDARG, E_OUTOFMEMORY, E_POINTER, E_UNEXPECTED,
 };
@@ -29,6 +32,12 @@ pub enum Error {
 	#[error("Internal error {0}")]
 	InternalError(c_long),
 
+	/// SEAL refused an operation that is not allowed in the current state,
+	/// e.g. producing a transparent ciphertext or using a scheme-specific
+	/// operation under the other scheme.
+	#[error("Invalid operation")]
+	InvalidOperation,
+
 	/// An unknown error occurred in SEAL.
 	#[error("Unknown {0}")]
 	Unknown(c_long),
@@ -56,6 +65,388 @@ pub enum Error {
 	/// Float encoder not set.
 	#[error("Float encoder not set")]
 	FloatEncoderNotSet,
+
+	/// An object was produced under a different context than the one used to load it.
+	#[error("The data was produced under a different context")]
+	ContextMismatch,
+
+	/// An object failed validation against the context it was used with.
+	#[error("The data is not valid for the given context")]
+	InvalidForContext,
+
+	/// Reading from or writing to a stream failed.
+	#[error("I/O error: {0}")]
+	IoError(#[source] IoError),
+
+	/// Serialized input was rejected before or while loading it.
+	#[error("Malformed input: {0}")]
+	MalformedInput(Box<MalformedInput>),
+
+	/// The operands of a tensor operation have incompatible shapes.
+	#[error("Shape mismatch: {0}")]
+	ShapeMismatch(Box<ShapeMismatch>),
+
+	/// The operands of a CKKS operation have different scales.
+	#[error("Scale mismatch: {0}")]
+	ScaleMismatch(Box<ScaleMismatch>),
+
+	/// The operands of an operation are at different levels of the modulus
+	/// switching chain.
+	#[error("Parms mismatch: {0}")]
+	ParmsMismatch(Box<ParmsMismatch>),
+
+	/// A key was generated for different encryption parameters.
+	#[error("Key mismatch: {0}")]
+	KeyMismatch(Box<KeyMismatch>),
+
+	/// An encrypted result differs from its plaintext reference.
+	#[error("Result mismatch: {0}")]
+	ResultMismatch(Box<ResultMismatch>),
+
+	/// A circuit could not be compiled or run.
+	#[error("Circuit error: {0}")]
+	Circuit(Box<CircuitError>),
+
+	/// A SEAL operation failed; wraps the error with the operation's name.
+	#[error("{0}")]
+	Operation(Box<OperationError>),
+}
+
+impl Error {
+	/// Attaches the name of the failing operation to the error. Errors that
+	/// already name their operation are returned unchanged.
+	pub fn in_operation(
+		self,
+		operation: &'static str,
+	) -> Self {
+		match self {
+			Error::Operation(_)
+			| Error::ScaleMismatch(_)
+			| Error::ParmsMismatch(_)
+			| Error::KeyMismatch(_) => self,
+			error => Error::Operation(Box::new(OperationError {
+				operation,
+				error,
+			})),
+		}
+	}
+
+	/// Returns the name of the failing operation, if known.
+	pub fn operation(&self) -> Option<&'static str> {
+		match self {
+			Error::Operation(e) => Some(e.operation),
+			Error::ScaleMismatch(e) => Some(e.operation),
+			Error::ParmsMismatch(e) => Some(e.operation),
+			Error::KeyMismatch(e) => Some(e.operation),
+			_ => None,
+		}
+	}
+
+	/// Returns the error without the operation context, so callers can match
+	/// on the underlying variant.
+	pub fn kind(&self) -> &Error {
+		match self {
+			Error::Operation(e) => e.error.kind(),
+			error => error,
+		}
+	}
+}
+
+/// An error together with the name of the operation that raised it.
+///
+/// SEAL's C API reports failures as bare error codes, so the operation name
+/// is the only context available for failures that could not be diagnosed
+/// further.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{operation} failed: {error}")]
+pub struct OperationError {
+	/// The name of the failing operation, e.g. `Evaluator::add`.
+	pub operation: &'static str,
+	/// The underlying error.
+	pub error: Error,
+}
+
+/// The scales of two CKKS operands that SEAL refused to combine.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{operation}: left {left}, right {right}")]
+pub struct ScaleMismatch {
+	/// The name of the failing operation.
+	pub operation: &'static str,
+	/// The scale of the left operand.
+	pub left: f64,
+	/// The scale of the right operand.
+	pub right: f64,
+}
+
+impl PartialEq for ScaleMismatch {
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool {
+		self.operation == other.operation
+			&& self.left.to_bits() == other.left.to_bits()
+			&& self.right.to_bits() == other.right.to_bits()
+	}
+}
+
+impl Eq for ScaleMismatch {}
+
+/// The parms_ids of two operands that SEAL refused to combine.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{operation}: left {left:x?}, right {right:x?}")]
+pub struct ParmsMismatch {
+	/// The name of the failing operation.
+	pub operation: &'static str,
+	/// The parms_id of the left operand.
+	pub left: Vec<u64>,
+	/// The parms_id of the right operand.
+	pub right: Vec<u64>,
+}
+
+/// A key whose parms_id is not the key parms_id of the context in use.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{operation}: {key} were generated for parms_id {actual:x?}, expected {expected:x?}")]
+pub struct KeyMismatch {
+	/// The name of the failing operation.
+	pub operation: &'static str,
+	/// The kind of key, e.g. `relinearization keys`.
+	pub key: &'static str,
+	/// The key parms_id of the context.
+	pub expected: Vec<u64>,
+	/// The parms_id of the key.
+	pub actual: Vec<u64>,
+}
+
+/// The shapes of two tensor operands that could not be combined.
+///
+/// Each side is the logical shape of the tensor when known, otherwise its
+/// number of chunks.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("left {left:?}, right {right:?}")]
+pub struct ShapeMismatch {
+	/// The shape of the left operand.
+	pub left: Vec<usize>,
+	/// The shape of the right operand.
+	pub right: Vec<usize>,
+}
+
+/// The first slot where an encrypted result differs from its plaintext
+/// reference by more than the tolerance, see [`crate::Differential`]. BFV
+/// values are converted to `f64`.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("slot {slot}: expected {expected}, got {actual}, tolerance {tolerance}")]
+pub struct ResultMismatch {
+	/// The index of the slot.
+	pub slot: usize,
+	/// The value computed by [`crate::PlainEvaluator`].
+	pub expected: f64,
+	/// The decrypted value.
+	pub actual: f64,
+	/// The largest difference allowed.
+	pub tolerance: f64,
+}
+
+impl PartialEq for ResultMismatch {
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool {
+		self.slot == other.slot
+			&& self.expected.to_bits() == other.expected.to_bits()
+			&& self.actual.to_bits() == other.actual.to_bits()
+			&& self.tolerance.to_bits() == other.tolerance.to_bits()
+	}
+}
+
+impl Eq for ResultMismatch {}
+
+/// A failed read or write. Displays as its [`std::io::ErrorKind`]; the
+/// underlying [`std::io::Error`] is its source.
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<std::io::Error>);
+
+impl IoError {
+	/// Returns the kind of the underlying error.
+	pub fn kind(&self) -> std::io::ErrorKind {
+		self.0.kind()
+	}
+
+	/// Returns the underlying error.
+	pub fn get_ref(&self) -> &std::io::Error {
+		&self.0
+	}
+}
+
+impl std::fmt::Display for IoError {
+	fn fmt(
+		&self,
+		f: &mut std::fmt::Formatter<'_>,
+	) -> std::fmt::Result {
+		self.kind().fmt(f)
+	}
+}
+
+impl std::error::Error for IoError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(self.get_ref())
+	}
+}
+
+impl PartialEq for IoError {
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool {
+		self.kind() == other.kind() && self.0.to_string() == other.0.to_string()
+	}
+}
+
+impl Eq for IoError {}
+
+/// Describes why serialized input was rejected by a loader.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MalformedInput {
+	/// The input is shorter than the SEAL header.
+	#[error("expected at least {expected} bytes, got {actual}")]
+	Truncated {
+		/// The minimum number of bytes required.
+		expected: u64,
+		/// The number of bytes available.
+		actual: u64,
+	},
+
+	/// The header does not start with SEAL's magic number.
+	#[error("invalid magic number {0:#06x}")]
+	InvalidMagic(u16),
+
+	/// The header declares a header size other than SEAL's.
+	#[error("invalid header size {0}")]
+	InvalidHeaderSize(u8),
+
+	/// The data was written by an unsupported SEAL version.
+	#[error("unsupported version {major}.{minor}")]
+	UnsupportedVersion {
+		/// The major version in the header.
+		major: u8,
+		/// The minor version in the header.
+		minor: u8,
+	},
+
+	/// The header names an unknown compression mode.
+	#[error("unsupported compression mode {0}")]
+	UnsupportedCompression(u8),
+
+	/// The size declared in the header does not match the input.
+	#[error("declared size {declared} does not match the {actual} bytes given")]
+	SizeMismatch {
+		/// The size declared in the header.
+		declared: u64,
+		/// The size of the input.
+		actual: u64,
+	},
+
+	/// The size declared in the header exceeds the configured maximum.
+	#[error("declared size {declared} exceeds the limit of {limit} bytes")]
+	SizeLimitExceeded {
+		/// The size declared in the header.
+		declared: u64,
+		/// The configured limit, see [`crate::set_max_load_size`].
+		limit: u64,
+	},
+
+	/// SEAL consumed a different number of bytes than the input holds.
+	#[error("{read} of {len} bytes were consumed")]
+	TrailingBytes {
+		/// The number of bytes SEAL read.
+		read: u64,
+		/// The size of the input.
+		len: u64,
+	},
+
+	/// SEAL rejected the payload after the header passed validation.
+	#[error("the payload is corrupt")]
+	Corrupt,
+
+	/// A recorded shape has more elements, or larger strides, than fit in
+	/// memory.
+	#[error("the shape is too large")]
+	ShapeOverflow,
+
+	/// A recorded shape has more elements than its chunks have slots.
+	#[error("the shape has {elements} elements but the chunks hold only {capacity}")]
+	ShapeExceedsData {
+		/// The number of elements of the shape.
+		elements: u64,
+		/// The number of slots in the chunks.
+		capacity: u64,
+	},
+}
+
+impl From<MalformedInput> for Error {
+	fn from(err: MalformedInput) -> Self {
+		Error::MalformedInput(Box::new(err))
+	}
+}
+
+/// Describes why a [`crate::Circuit`] could not be compiled or run.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CircuitError {
+	/// A wire was created by another circuit.
+	#[error("wire {0} does not belong to this circuit")]
+	UnknownWire(usize),
+
+	/// Two inputs, constants or outputs share a name.
+	#[error("the name {0:?} is used more than once")]
+	DuplicateName(String),
+
+	/// No ciphertext was given for an input.
+	#[error("no value was given for input {0:?}")]
+	MissingInput(String),
+
+	/// No plaintext was given for a constant.
+	#[error("no value was given for constant {0:?}")]
+	MissingConstant(String),
+
+	/// The circuit needs keys that were not given.
+	#[error("the circuit needs {0}")]
+	MissingKey(&'static str),
+
+	/// The wrong number of inputs or constants was given.
+	#[error("expected {expected} {what}, got {actual}")]
+	CountMismatch {
+		/// What was counted.
+		what: &'static str,
+		/// The number the circuit declares.
+		expected: usize,
+		/// The number given.
+		actual: usize,
+	},
+
+	/// The circuit needs more resources than allowed.
+	#[error("{limit} is {value}, over the limit of {max}")]
+	LimitExceeded {
+		/// The name of the limit.
+		limit: &'static str,
+		/// The amount the circuit needs.
+		value: usize,
+		/// The amount allowed.
+		max: usize,
+	},
+
+	/// The scheme has no such operation.
+	#[error("{operation} is not supported under {scheme:?}")]
+	Unsupported {
+		/// The name of the operation.
+		operation: &'static str,
+		/// The scheme the circuit was compiled for.
+		scheme: SchemeType,
+	},
+}
+
+impl From<CircuitError> for Error {
+	fn from(err: CircuitError) -> Self {
+		Error::Circuit(Box::new(err))
+	}
 }
 
 const_assert!(std::mem::size_of::<Error>() <= 16);
@@ -67,27 +458,45 @@ impl From<c_long> for Error {
 			E_INVALIDARG => Error::InvalidArgument,
 			E_OUTOFMEMORY => Error::OutOfMemory,
 			E_UNEXPECTED => Error::Unexpected,
-			COR_E_IO => Error::InternalError(err),
-			COR_E_INVALIDOPERATION => Error::InternalError(err),
+			COR_E_IO => std::io::Error::new(
+				std::io::ErrorKind::Other,
+				"SEAL failed to read or write a stream",
+			)
+			.into(),
+			COR_E_INVALIDOPERATION => Error::InvalidOperation,
 			_ => Error::Unknown(err),
 		}
 	}
 }
 
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Error::IoError(IoError(Arc::new(err)))
+	}
+}
+
 /// The result type for SEAL operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// A macro that receives a c_long error code and returns a [`Result`] error.
 /// If the c_long corresponds to E_OK, it returns `Ok(())`, otherwise it returns
-/// `Err(Error::from(err))`.
+/// `Err(Error::from(err))`. The expression is evaluated exactly once.
+///
+/// With a second argument, the error is wrapped with that operation name, see
+/// [`Error::in_operation`].
 #[macro_export]
 macro_rules! try_seal {
-	($err:expr) => {
-		if $err == $crate::bindgen::S_OK {
+	($err:expr) => {{
+		let err = $err;
+
+		if err == $crate::bindgen::S_OK {
 			Ok(())
 		} else {
-			Err($crate::Error::from($err))
+			Err($crate::Error::from(err))
 		}
+	}};
+	($err:expr, $operation:expr) => {
+		$crate::try_seal!($err).map_err(|e: $crate::Error| e.in_operation($operation))
 	};
 }
 
@@ -95,3 +504,29 @@ macro_rules! try_seal {
 pub fn convert_seal_error(err: c_long) -> Result<()> {
 	try_seal!(err)
 }
+
+#[cfg(test)]
+mod tests {
+	use std::error::Error as _;
+
+	use crate::*;
+
+	#[test]
+	fn io_errors_keep_their_kind_and_source() {
+		let error = Error::from(std::io::Error::new(
+			std::io::ErrorKind::UnexpectedEof,
+			"stream ended",
+		));
+
+		match &error {
+			Error::IoError(e) => assert_eq!(e.kind(), std::io::ErrorKind::UnexpectedEof),
+			e => panic!("expected an I/O error, got {:?}", e),
+		}
+
+		let source = error.source().and_then(|e| e.source()).unwrap();
+		let io = source.downcast_ref::<std::io::Error>().unwrap();
+
+		assert_eq!(io.to_string(), "stream ended");
+		assert_eq!(error.clone(), error);
+	}
+}
@@ -0,0 +1,712 @@
+use crate::error::*;
+use crate::{
+	BFVEvaluator, CKKSEvaluator, Ciphertext, Context, Evaluator, GaloisKey, Plaintext,
+	RelinearizationKey, SchemeType,
+};
+
+/// A plaintext tagged with the scheme it was encoded under, as produced by
+/// [`crate::AnyEncoder`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyPlaintext {
+	/// A BFV plaintext.
+	Bfv(Plaintext),
+	/// A CKKS plaintext.
+	Ckks(Plaintext),
+}
+
+impl AnyPlaintext {
+	/// Tags a plaintext with the scheme it was encoded under. Fails with
+	/// [`Error::InvalidArgument`] for [`SchemeType::None`].
+	pub fn new(
+		scheme: SchemeType,
+		plaintext: Plaintext,
+	) -> Result<Self> {
+		match scheme {
+			SchemeType::Bfv => Ok(Self::Bfv(plaintext)),
+			SchemeType::Ckks => Ok(Self::Ckks(plaintext)),
+			SchemeType::None => Err(Error::InvalidArgument),
+		}
+	}
+
+	/// Returns the scheme the plaintext was encoded under.
+	pub fn scheme(&self) -> SchemeType {
+		match self {
+			Self::Bfv(_) => SchemeType::Bfv,
+			Self::Ckks(_) => SchemeType::Ckks,
+		}
+	}
+
+	/// Returns the plaintext, e.g. to encrypt it.
+	pub fn plaintext(&self) -> &Plaintext {
+		match self {
+			Self::Bfv(p) | Self::Ckks(p) => p,
+		}
+	}
+
+	/// Consumes the value, returning the plaintext.
+	pub fn into_plaintext(self) -> Plaintext {
+		match self {
+			Self::Bfv(p) | Self::Ckks(p) => p,
+		}
+	}
+}
+
+/// A ciphertext tagged with the scheme it was encrypted under, consumed and
+/// produced by [`DynEvaluator`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnyCiphertext {
+	/// A BFV ciphertext.
+	Bfv(Ciphertext),
+	/// A CKKS ciphertext.
+	Ckks(Ciphertext),
+}
+
+impl AnyCiphertext {
+	/// Tags a ciphertext with the scheme it was encrypted under. Fails with
+	/// [`Error::InvalidArgument`] for [`SchemeType::None`].
+	pub fn new(
+		scheme: SchemeType,
+		ciphertext: Ciphertext,
+	) -> Result<Self> {
+		match scheme {
+			SchemeType::Bfv => Ok(Self::Bfv(ciphertext)),
+			SchemeType::Ckks => Ok(Self::Ckks(ciphertext)),
+			SchemeType::None => Err(Error::InvalidArgument),
+		}
+	}
+
+	/// Returns the scheme the ciphertext was encrypted under.
+	pub fn scheme(&self) -> SchemeType {
+		match self {
+			Self::Bfv(_) => SchemeType::Bfv,
+			Self::Ckks(_) => SchemeType::Ckks,
+		}
+	}
+
+	/// Returns the ciphertext, e.g. to decrypt or serialize it.
+	pub fn ciphertext(&self) -> &Ciphertext {
+		match self {
+			Self::Bfv(c) | Self::Ckks(c) => c,
+		}
+	}
+
+	/// Consumes the value, returning the ciphertext.
+	pub fn into_ciphertext(self) -> Ciphertext {
+		match self {
+			Self::Bfv(c) | Self::Ckks(c) => c,
+		}
+	}
+}
+
+/// An evaluator whose scheme is chosen at runtime, usable as
+/// `Box<dyn DynEvaluator>`.
+///
+/// Unlike [`Evaluator`], the trait has no associated types: values are
+/// [`AnyCiphertext`] and [`AnyPlaintext`], tagged with their scheme. Operands
+/// of another scheme fail with [`Error::ContextMismatch`], and operations the
+/// scheme lacks, such as rescaling under BFV or row rotations under CKKS,
+/// with [`Error::InvalidOperation`], both wrapped with the operation's name.
+/// See [`AnyEvaluator`].
+pub trait DynEvaluator: Send + Sync {
+	/// Returns the scheme of the evaluator.
+	fn scheme(&self) -> SchemeType;
+
+	/// Negates `a`.
+	fn negate(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Adds `a` and `b`.
+	fn add(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Adds all the ciphertexts in `a`.
+	fn add_many(
+		&self,
+		a: &[AnyCiphertext],
+	) -> Result<AnyCiphertext>;
+
+	/// Subtracts `b` from `a`.
+	fn sub(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Multiplies `a` and `b`.
+	fn multiply(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Multiplies all the ciphertexts in `a`, relinearizing after each
+	/// product. BFV only.
+	fn multiply_many(
+		&self,
+		a: &[AnyCiphertext],
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Squares `a`.
+	fn square(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Raises `a` to `exponent`, relinearizing after each product. BFV only.
+	fn exponentiate(
+		&self,
+		a: &AnyCiphertext,
+		exponent: u64,
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Adds a plaintext to `a`.
+	fn add_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext>;
+
+	/// Subtracts a plaintext from `a`.
+	fn sub_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext>;
+
+	/// Multiplies `a` by a plaintext.
+	fn multiply_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext>;
+
+	/// Relinearizes `a` down to 2 polynomials.
+	fn relinearize(
+		&self,
+		a: &AnyCiphertext,
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Switches `a` to the next level of the modulus chain.
+	fn mod_switch_to_next(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Switches an NTT form plaintext to the next level of the modulus
+	/// chain. Fails under BFV, whose plaintexts are not in NTT form.
+	fn mod_switch_to_next_plaintext(
+		&self,
+		a: &AnyPlaintext,
+	) -> Result<AnyPlaintext>;
+
+	/// Rescales `a` to the next level. CKKS only.
+	fn rescale_to_next(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext>;
+
+	/// Rotates the rows of the BFV matrix by `steps`. BFV only.
+	fn rotate_rows(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Swaps the rows of the BFV matrix. BFV only.
+	fn rotate_columns(
+		&self,
+		a: &AnyCiphertext,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Rotates the CKKS slots by `steps`. CKKS only.
+	fn rotate_vector(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext>;
+
+	/// Rotates the slots of `a` by `steps`: the rows under BFV, the whole
+	/// vector under CKKS.
+	fn rotate_slots(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext>;
+}
+
+/// A [`BFVEvaluator`] or [`CKKSEvaluator`], picked from the scheme of a
+/// context at runtime.
+pub enum AnyEvaluator {
+	/// Evaluates BFV ciphertexts.
+	Bfv(BFVEvaluator),
+	/// Evaluates CKKS ciphertexts.
+	Ckks(CKKSEvaluator),
+}
+
+impl AnyEvaluator {
+	/// Creates the evaluator for the scheme of the given context. Fails with
+	/// [`Error::InvalidOperation`] if the context has no scheme.
+	///  * `ctx` - The context.
+	pub fn new(ctx: &Context) -> Result<Self> {
+		match ctx.get_encryption_parameters()?.get_scheme()? {
+			SchemeType::Bfv => Ok(Self::Bfv(BFVEvaluator::new(ctx)?)),
+			SchemeType::Ckks => Ok(Self::Ckks(CKKSEvaluator::new(ctx)?)),
+			SchemeType::None => Err(Error::InvalidOperation.in_operation("AnyEvaluator::new")),
+		}
+	}
+
+	fn evaluator(&self) -> &dyn Evaluator<Plaintext = Plaintext, Ciphertext = Ciphertext> {
+		match self {
+			Self::Bfv(e) => e,
+			Self::Ckks(e) => e,
+		}
+	}
+
+	fn wrap(
+		&self,
+		c: Ciphertext,
+	) -> AnyCiphertext {
+		match self {
+			Self::Bfv(_) => AnyCiphertext::Bfv(c),
+			Self::Ckks(_) => AnyCiphertext::Ckks(c),
+		}
+	}
+
+	fn cipher<'a>(
+		&self,
+		operation: &'static str,
+		a: &'a AnyCiphertext,
+	) -> Result<&'a Ciphertext> {
+		if a.scheme() != self.scheme() {
+			return Err(Error::ContextMismatch.in_operation(operation));
+		}
+
+		Ok(a.ciphertext())
+	}
+
+	fn ciphers(
+		&self,
+		operation: &'static str,
+		a: &[AnyCiphertext],
+	) -> Result<Vec<Ciphertext>> {
+		a.iter()
+			.map(|c| self.cipher(operation, c).cloned())
+			.collect()
+	}
+
+	fn plain<'a>(
+		&self,
+		operation: &'static str,
+		a: &'a AnyPlaintext,
+	) -> Result<&'a Plaintext> {
+		if a.scheme() != self.scheme() {
+			return Err(Error::ContextMismatch.in_operation(operation));
+		}
+
+		Ok(a.plaintext())
+	}
+
+	fn bfv(
+		&self,
+		operation: &'static str,
+	) -> Result<&BFVEvaluator> {
+		match self {
+			Self::Bfv(e) => Ok(e),
+			Self::Ckks(_) => Err(Error::InvalidOperation.in_operation(operation)),
+		}
+	}
+
+	fn ckks(
+		&self,
+		operation: &'static str,
+	) -> Result<&CKKSEvaluator> {
+		match self {
+			Self::Ckks(e) => Ok(e),
+			Self::Bfv(_) => Err(Error::InvalidOperation.in_operation(operation)),
+		}
+	}
+}
+
+impl DynEvaluator for AnyEvaluator {
+	fn scheme(&self) -> SchemeType {
+		match self {
+			Self::Bfv(_) => SchemeType::Bfv,
+			Self::Ckks(_) => SchemeType::Ckks,
+		}
+	}
+
+	fn negate(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let a = self.cipher("DynEvaluator::negate", a)?;
+
+		Ok(self.wrap(self.evaluator().negate(a)?))
+	}
+
+	fn add(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::add";
+		let (a, b) = (self.cipher(operation, a)?, self.cipher(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().add(a, b)?))
+	}
+
+	fn add_many(
+		&self,
+		a: &[AnyCiphertext],
+	) -> Result<AnyCiphertext> {
+		let a = self.ciphers("DynEvaluator::add_many", a)?;
+
+		Ok(self.wrap(self.evaluator().add_many(&a)?))
+	}
+
+	fn sub(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::sub";
+		let (a, b) = (self.cipher(operation, a)?, self.cipher(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().sub(a, b)?))
+	}
+
+	fn multiply(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::multiply";
+		let (a, b) = (self.cipher(operation, a)?, self.cipher(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().multiply(a, b)?))
+	}
+
+	fn multiply_many(
+		&self,
+		a: &[AnyCiphertext],
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::multiply_many";
+		let evaluator = self.bfv(operation)?;
+		let a = self.ciphers(operation, a)?;
+
+		Ok(self.wrap(evaluator.multiply_many(&a, relin_keys)?))
+	}
+
+	fn square(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let a = self.cipher("DynEvaluator::square", a)?;
+
+		Ok(self.wrap(self.evaluator().square(a)?))
+	}
+
+	fn exponentiate(
+		&self,
+		a: &AnyCiphertext,
+		exponent: u64,
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::exponentiate";
+		let evaluator = self.bfv(operation)?;
+		let a = self.cipher(operation, a)?;
+
+		Ok(self.wrap(evaluator.exponentiate(a, exponent, relin_keys)?))
+	}
+
+	fn add_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::add_plain";
+		let (a, b) = (self.cipher(operation, a)?, self.plain(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().add_plain(a, b)?))
+	}
+
+	fn sub_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::sub_plain";
+		let (a, b) = (self.cipher(operation, a)?, self.plain(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().sub_plain(a, b)?))
+	}
+
+	fn multiply_plain(
+		&self,
+		a: &AnyCiphertext,
+		b: &AnyPlaintext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::multiply_plain";
+		let (a, b) = (self.cipher(operation, a)?, self.plain(operation, b)?);
+
+		Ok(self.wrap(self.evaluator().multiply_plain(a, b)?))
+	}
+
+	fn relinearize(
+		&self,
+		a: &AnyCiphertext,
+		relin_keys: &RelinearizationKey,
+	) -> Result<AnyCiphertext> {
+		let a = self.cipher("DynEvaluator::relinearize", a)?;
+
+		Ok(self.wrap(self.evaluator().relinearize(a, relin_keys)?))
+	}
+
+	fn mod_switch_to_next(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let a = self.cipher("DynEvaluator::mod_switch_to_next", a)?;
+
+		Ok(self.wrap(self.evaluator().mod_switch_to_next(a)?))
+	}
+
+	fn mod_switch_to_next_plaintext(
+		&self,
+		a: &AnyPlaintext,
+	) -> Result<AnyPlaintext> {
+		let operation = "DynEvaluator::mod_switch_to_next_plaintext";
+		let evaluator = self.ckks(operation)?;
+		let a = self.plain(operation, a)?;
+
+		Ok(AnyPlaintext::Ckks(
+			evaluator.mod_switch_to_next_plaintext(a)?,
+		))
+	}
+
+	fn rescale_to_next(
+		&self,
+		a: &AnyCiphertext,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::rescale_to_next";
+		let evaluator = self.ckks(operation)?;
+		let a = self.cipher(operation, a)?;
+
+		Ok(self.wrap(evaluator.rescale_to_next(a)?))
+	}
+
+	fn rotate_rows(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::rotate_rows";
+		let evaluator = self.bfv(operation)?;
+		let a = self.cipher(operation, a)?;
+
+		Ok(self.wrap(evaluator.rotate_rows(a, steps, galois_keys)?))
+	}
+
+	fn rotate_columns(
+		&self,
+		a: &AnyCiphertext,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::rotate_columns";
+		let evaluator = self.bfv(operation)?;
+		let a = self.cipher(operation, a)?;
+
+		Ok(self.wrap(evaluator.rotate_columns(a, galois_keys)?))
+	}
+
+	fn rotate_vector(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext> {
+		let operation = "DynEvaluator::rotate_vector";
+		let evaluator = self.ckks(operation)?;
+		let a = self.cipher(operation, a)?;
+
+		Ok(self.wrap(evaluator.rotate_vector(a, steps, galois_keys)?))
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &AnyCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext> {
+		match self {
+			Self::Bfv(_) => self.rotate_rows(a, steps, galois_keys),
+			Self::Ckks(_) => self.rotate_vector(a, steps, galois_keys),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn bfv_context() -> Context {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	fn ckks_context() -> Context {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	/// (2x)², rotated by one slot, written once for both schemes.
+	fn compute(
+		evaluator: &dyn DynEvaluator,
+		x: &AnyCiphertext,
+		relin_keys: &RelinearizationKey,
+		galois_keys: &GaloisKey,
+	) -> Result<AnyCiphertext> {
+		let doubled = evaluator.add(x, x)?;
+		let squared = evaluator.relinearize(&evaluator.square(&doubled)?, relin_keys)?;
+
+		evaluator.rotate_slots(&squared, 1, galois_keys)
+	}
+
+	#[test]
+	fn can_evaluate_scheme_chosen_at_runtime() {
+		for ctx in [bfv_context(), ckks_context()] {
+			let gen = KeyGenerator::new(&ctx).unwrap();
+			let relin_keys = gen.create_relinearization_keys().unwrap();
+			let galois_keys = gen.create_galois_keys().unwrap();
+
+			let encoder = AnyEncoder::new(&ctx, 2.0f64.powi(40)).unwrap();
+			let encryptor =
+				Encryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+			let decryptor = Decryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+			let evaluator: Box<dyn DynEvaluator> = Box::new(AnyEvaluator::new(&ctx).unwrap());
+
+			assert_eq!(evaluator.scheme(), encoder.scheme());
+
+			let x = (0..encoder.get_slot_count())
+				.map(|i| (i % 9) as i64 - 4)
+				.collect::<Vec<_>>();
+			let expected = (0..x.len())
+				.map(|i| {
+					// BFV rotates each half of the slots on its own.
+					let row = match encoder.scheme() {
+						SchemeType::Bfv => x.len() / 2,
+						_ => x.len(),
+					};
+					let j = i / row * row + (i + 1) % row;
+
+					4 * x[j] * x[j]
+				})
+				.collect::<Vec<_>>();
+
+			let plain = match encoder.scheme() {
+				SchemeType::Bfv => encoder.encode_i64(&x).unwrap(),
+				_ => {
+					let x = x.iter().map(|v| *v as f64).collect::<Vec<_>>();
+					encoder.encode_f64(&x).unwrap()
+				}
+			};
+
+			let x_enc = AnyCiphertext::new(
+				plain.scheme(),
+				encryptor.encrypt(plain.plaintext()).unwrap(),
+			)
+			.unwrap();
+
+			let y_enc = compute(evaluator.as_ref(), &x_enc, &relin_keys, &galois_keys).unwrap();
+			let y = AnyPlaintext::new(
+				y_enc.scheme(),
+				decryptor.decrypt(y_enc.ciphertext()).unwrap(),
+			)
+			.unwrap();
+
+			match encoder.scheme() {
+				SchemeType::Bfv => assert_eq!(encoder.decode_i64(&y).unwrap(), expected),
+				_ => {
+					for (actual, expected) in encoder.decode_f64(&y).unwrap().iter().zip(&expected)
+					{
+						assert!((actual - *expected as f64).abs() < 1e-3);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn unsupported_operations_are_rejected() {
+		let bfv_ctx = bfv_context();
+		let ckks_ctx = ckks_context();
+
+		let bfv = AnyEvaluator::new(&bfv_ctx).unwrap();
+		let ckks = AnyEvaluator::new(&ckks_ctx).unwrap();
+
+		let bfv_encoder = AnyEncoder::new(&bfv_ctx, 1.0).unwrap();
+		let ckks_encoder = AnyEncoder::new(&ckks_ctx, 2.0f64.powi(40)).unwrap();
+
+		let gen = KeyGenerator::new(&bfv_ctx).unwrap();
+		let encryptor =
+			Encryptor::with_public_key(&bfv_ctx, &gen.create_public_key().unwrap()).unwrap();
+
+		let plain = bfv_encoder.encode_u64(&[1, 2, 3]).unwrap();
+		let x = AnyCiphertext::new(
+			SchemeType::Bfv,
+			encryptor.encrypt(plain.plaintext()).unwrap(),
+		)
+		.unwrap();
+
+		let err = bfv.rescale_to_next(&x).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidOperation);
+		assert_eq!(err.operation(), Some("DynEvaluator::rescale_to_next"));
+
+		let err = bfv.mod_switch_to_next_plaintext(&plain).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidOperation);
+
+		// A BFV ciphertext passed to the CKKS evaluator.
+		let err = ckks.negate(&x).unwrap_err();
+		assert_eq!(*err.kind(), Error::ContextMismatch);
+		assert_eq!(err.operation(), Some("DynEvaluator::negate"));
+
+		let err = ckks_encoder.encode_i64(&[1]).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidOperation);
+
+		let err = bfv_encoder.encode_f64(&[1.0]).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidOperation);
+
+		let ckks_plain = ckks_encoder.encode_f64(&[1.0]).unwrap();
+		let err = bfv.add_plain(&x, &ckks_plain).unwrap_err();
+		assert_eq!(*err.kind(), Error::ContextMismatch);
+	}
+}
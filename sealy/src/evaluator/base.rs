@@ -5,6 +5,7 @@ use std::sync::atomic::Ordering;
 
 use crate::bindgen;
 use crate::error::*;
+use crate::memory::{self, MemoryPool};
 use crate::try_seal;
 use crate::{Ciphertext, Context, Plaintext, RelinearizationKey};
 
@@ -55,6 +56,92 @@ use crate::{Ciphertext, Context, Plaintext, RelinearizationKey};
 /// Ideally, unless these two functions are called, all other functions should "just work".
 pub struct EvaluatorBase {
 	handle: AtomicPtr<c_void>,
+	pool: Option<MemoryPool>,
+	key_parms_id: Vec<u64>,
+}
+
+/// The metadata SEAL compares between the operands of an operation.
+pub(crate) struct Operand {
+	parms_id: Vec<u64>,
+	scale: f64,
+}
+
+impl Operand {
+	pub(crate) fn ciphertext(a: &Ciphertext) -> Result<Self> {
+		Ok(Self {
+			parms_id: a.parms_id()?,
+			scale: a.scale()?,
+		})
+	}
+
+	pub(crate) fn plaintext(a: &Plaintext) -> Result<Self> {
+		Ok(Self {
+			parms_id: a.parms_id()?,
+			scale: a.scale()?,
+		})
+	}
+}
+
+/// Mirrors SEAL's `are_close`, which it uses to compare scales.
+pub(crate) fn are_close(
+	a: f64,
+	b: f64,
+) -> bool {
+	(a - b).abs() < f64::EPSILON * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Returns the mismatch between `a` and `b` that SEAL rejects, if any.
+fn mismatch(
+	operation: &'static str,
+	a: &Operand,
+	b: &Operand,
+	same_scale: bool,
+) -> Option<Error> {
+	// Plaintexts outside NTT form have an all-zero parms_id and fit any level.
+	let b_unset = b.parms_id.iter().all(|&x| x == 0);
+
+	if !b_unset && a.parms_id != b.parms_id {
+		return Some(Error::ParmsMismatch(Box::new(ParmsMismatch {
+			operation,
+			left: a.parms_id.clone(),
+			right: b.parms_id.clone(),
+		})));
+	}
+
+	if same_scale && !are_close(a.scale, b.scale) {
+		return Some(Error::ScaleMismatch(Box::new(ScaleMismatch {
+			operation,
+			left: a.scale,
+			right: b.scale,
+		})));
+	}
+
+	None
+}
+
+/// Explains why SEAL rejected the operands of `operation`.
+///
+/// SEAL's C API only reports an error code, so after an invalid argument
+/// error the operands are read back and compared to the first one to tell
+/// parms and scale mismatches apart from other failures. `same_scale` is set
+/// for the operations that require equal scales, e.g. additions.
+pub(crate) fn operand_error(
+	err: Error,
+	operation: &'static str,
+	operands: &[Result<Operand>],
+	same_scale: bool,
+) -> Error {
+	if *err.kind() == Error::InvalidArgument {
+		if let Some(Ok(first)) = operands.first() {
+			for other in operands[1..].iter().flatten() {
+				if let Some(mismatch) = mismatch(operation, first, other, same_scale) {
+					return mismatch;
+				}
+			}
+		}
+	}
+
+	err.in_operation(operation)
 }
 
 impl EvaluatorBase {
@@ -67,6 +154,8 @@ impl EvaluatorBase {
 
 		Ok(Self {
 			handle: AtomicPtr::new(handle),
+			pool: None,
+			key_parms_id: ctx.get_key_parms_id()?,
 		})
 	}
 
@@ -75,14 +164,55 @@ impl EvaluatorBase {
 		self.handle.load(Ordering::SeqCst)
 	}
 
+	/// Allocates from the given pool instead of the pool of the current
+	/// memory manager profile.
+	pub(crate) fn with_memory_pool(
+		mut self,
+		pool: MemoryPool,
+	) -> Self {
+		self.pool = Some(pool);
+		self
+	}
+
+	/// Gets the handle of the pool to allocate from.
+	pub(crate) unsafe fn pool_handle(&self) -> *mut c_void {
+		memory::pool_handle(self.pool.as_ref())
+	}
+
+	/// Explains why SEAL rejected a key passed to `operation`: keys must be
+	/// generated for the key parms_id of the evaluator's context.
+	pub(crate) fn key_error(
+		&self,
+		err: Error,
+		operation: &'static str,
+		key: &'static str,
+		parms_id: Result<Vec<u64>>,
+	) -> Error {
+		if *err.kind() == Error::InvalidArgument {
+			if let Ok(actual) = parms_id {
+				if actual != self.key_parms_id {
+					return Error::KeyMismatch(Box::new(KeyMismatch {
+						operation,
+						key,
+						expected: self.key_parms_id.clone(),
+						actual,
+					}));
+				}
+			}
+		}
+
+		err.in_operation(operation)
+	}
+
 	/// Negates a ciphertext and stores the result inplace.
 	pub(crate) fn negate_inplace(
 		&self,
 		a: &Ciphertext,
 	) -> Result<()> {
-		try_seal!(unsafe {
-			bindgen::Evaluator_Negate(self.get_handle(), a.get_handle(), a.get_handle())
-		})?;
+		try_seal!(
+			unsafe { bindgen::Evaluator_Negate(self.get_handle(), a.get_handle(), a.get_handle()) },
+			"Evaluator::negate_inplace"
+		)?;
 
 		Ok(())
 	}
@@ -93,9 +223,12 @@ impl EvaluatorBase {
 	) -> Result<Ciphertext> {
 		let out = Ciphertext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Evaluator_Negate(self.get_handle(), a.get_handle(), out.get_handle())
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_Negate(self.get_handle(), a.get_handle(), out.get_handle())
+			},
+			"Evaluator::negate"
+		)?;
 
 		Ok(out)
 	}
@@ -112,6 +245,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				a.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::add_inplace",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				true,
+			)
 		})?;
 
 		Ok(())
@@ -131,6 +272,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				c.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::add",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				true,
+			)
 		})?;
 
 		Ok(c)
@@ -155,6 +304,14 @@ impl EvaluatorBase {
 				a_ptr.as_mut_ptr(),
 				c.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::add_many",
+				&a.iter().map(Operand::ciphertext).collect::<Vec<_>>(),
+				true,
+			)
 		})?;
 
 		Ok(c)
@@ -182,9 +339,22 @@ impl EvaluatorBase {
 				a_ptr.as_mut_ptr(),
 				relin_keys.get_handle(),
 				c.get_handle(),
-				null_mut(),
+				self.pool_handle(),
 				// mem.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				self.key_error(
+					e,
+					"Evaluator::multiply_many",
+					"relinearization keys",
+					relin_keys.parms_id(),
+				),
+				"Evaluator::multiply_many",
+				&a.iter().map(Operand::ciphertext).collect::<Vec<_>>(),
+				false,
+			)
 		})?;
 
 		Ok(c)
@@ -202,6 +372,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				a.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::sub_inplace",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				true,
+			)
 		})?;
 
 		Ok(())
@@ -221,6 +399,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				c.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::sub",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				true,
+			)
 		})?;
 
 		Ok(c)
@@ -237,7 +423,15 @@ impl EvaluatorBase {
 				a.get_handle(),
 				b.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::multiply_inplace",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				false,
 			)
 		})?;
 
@@ -257,7 +451,15 @@ impl EvaluatorBase {
 				a.get_handle(),
 				b.get_handle(),
 				c.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::multiply",
+				&[Operand::ciphertext(a), Operand::ciphertext(b)],
+				false,
 			)
 		})?;
 
@@ -268,14 +470,17 @@ impl EvaluatorBase {
 		&self,
 		a: &Ciphertext,
 	) -> Result<()> {
-		try_seal!(unsafe {
-			bindgen::Evaluator_Square(
-				self.get_handle(),
-				a.get_handle(),
-				a.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_Square(
+					self.get_handle(),
+					a.get_handle(),
+					a.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::square_inplace"
+		)?;
 
 		Ok(())
 	}
@@ -286,14 +491,17 @@ impl EvaluatorBase {
 	) -> Result<Ciphertext> {
 		let c = Ciphertext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Evaluator_Square(
-				self.get_handle(),
-				a.get_handle(),
-				c.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_Square(
+					self.get_handle(),
+					a.get_handle(),
+					c.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::square"
+		)?;
 
 		Ok(c)
 	}
@@ -304,14 +512,17 @@ impl EvaluatorBase {
 	) -> Result<Ciphertext> {
 		let c = Ciphertext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Evaluator_ModSwitchToNext1(
-				self.get_handle(),
-				a.get_handle(),
-				c.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_ModSwitchToNext1(
+					self.get_handle(),
+					a.get_handle(),
+					c.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::mod_switch_to_next"
+		)?;
 
 		Ok(c)
 	}
@@ -320,14 +531,17 @@ impl EvaluatorBase {
 		&self,
 		a: &Ciphertext,
 	) -> Result<()> {
-		try_seal!(unsafe {
-			bindgen::Evaluator_ModSwitchToNext1(
-				self.get_handle(),
-				a.get_handle(),
-				a.get_handle(),
-				null_mut(),
-			)
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_ModSwitchToNext1(
+					self.get_handle(),
+					a.get_handle(),
+					a.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::mod_switch_to_next_inplace"
+		)?;
 
 		Ok(())
 	}
@@ -338,9 +552,16 @@ impl EvaluatorBase {
 	) -> Result<Plaintext> {
 		let p = Plaintext::new()?;
 
-		try_seal!(unsafe {
-			bindgen::Evaluator_ModSwitchToNext2(self.get_handle(), a.get_handle(), p.get_handle())
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_ModSwitchToNext2(
+					self.get_handle(),
+					a.get_handle(),
+					p.get_handle(),
+				)
+			},
+			"Evaluator::mod_switch_to_next_plaintext"
+		)?;
 
 		Ok(p)
 	}
@@ -349,9 +570,16 @@ impl EvaluatorBase {
 		&self,
 		a: &Plaintext,
 	) -> Result<()> {
-		try_seal!(unsafe {
-			bindgen::Evaluator_ModSwitchToNext2(self.get_handle(), a.get_handle(), a.get_handle())
-		})?;
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_ModSwitchToNext2(
+					self.get_handle(),
+					a.get_handle(),
+					a.get_handle(),
+				)
+			},
+			"Evaluator::mod_switch_to_next_inplace_plaintext"
+		)?;
 
 		Ok(())
 	}
@@ -371,7 +599,15 @@ impl EvaluatorBase {
 				exponent,
 				relin_keys.get_handle(),
 				c.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::exponentiate",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -391,7 +627,15 @@ impl EvaluatorBase {
 				exponent,
 				relin_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::exponentiate_inplace",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -412,6 +656,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				c.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::add_plain",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				true,
+			)
 		})?;
 
 		Ok(c)
@@ -429,6 +681,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				a.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::add_plain_inplace",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				true,
+			)
 		})?;
 
 		Ok(())
@@ -448,6 +708,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				c.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::sub_plain",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				true,
+			)
 		})?;
 
 		Ok(c)
@@ -465,6 +733,14 @@ impl EvaluatorBase {
 				b.get_handle(),
 				a.get_handle(),
 			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::sub_plain_inplace",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				true,
+			)
 		})?;
 
 		Ok(())
@@ -483,7 +759,15 @@ impl EvaluatorBase {
 				a.get_handle(),
 				b.get_handle(),
 				c.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::multiply_plain",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				false,
 			)
 		})?;
 
@@ -501,7 +785,15 @@ impl EvaluatorBase {
 				a.get_handle(),
 				b.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			operand_error(
+				e,
+				"Evaluator::multiply_plain_inplace",
+				&[Operand::ciphertext(a), Operand::plaintext(b)],
+				false,
 			)
 		})?;
 
@@ -513,7 +805,8 @@ impl EvaluatorBase {
 
 impl Drop for EvaluatorBase {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Evaluator_Destroy(self.get_handle()) })
-			.expect("Internal error in Evaluator::drop()");
+		unsafe {
+			bindgen::Evaluator_Destroy(self.get_handle());
+		}
 	}
 }
@@ -1,9 +1,7 @@
-use std::ptr::null_mut;
-
 use crate::evaluator::base::EvaluatorBase;
 use crate::{
-	bindgen, try_seal, Ciphertext, Context, Evaluator, GaloisKey, Plaintext, RelinearizationKey,
-	Result,
+	bindgen, try_seal, Ciphertext, Context, Evaluator, GaloisKey, MemoryPool, Plaintext,
+	RelinearizationKey, Result,
 };
 
 /// An evaluator that contains additional operations specific to the BFV scheme.
@@ -23,6 +21,15 @@ impl BFVEvaluator {
 	pub fn new(ctx: &Context) -> Result<BFVEvaluator> {
 		Ok(BFVEvaluator(EvaluatorBase::new(ctx)?))
 	}
+
+	/// Allocates from the given pool during evaluation instead of the pool of
+	/// the current [`crate::MemoryProfile`].
+	pub fn with_memory_pool(
+		self,
+		pool: MemoryPool,
+	) -> Self {
+		Self(self.0.with_memory_pool(pool))
+	}
 }
 
 impl Evaluator for BFVEvaluator {
@@ -225,7 +232,15 @@ impl Evaluator for BFVEvaluator {
 				a.get_handle(),
 				relin_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::relinearize_inplace",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -245,7 +260,15 @@ impl Evaluator for BFVEvaluator {
 				a.get_handle(),
 				relin_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::relinearize",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -267,7 +290,15 @@ impl Evaluator for BFVEvaluator {
 				steps,
 				galois_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_rows",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -287,7 +318,15 @@ impl Evaluator for BFVEvaluator {
 				steps,
 				galois_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_rows_inplace",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -307,7 +346,15 @@ impl Evaluator for BFVEvaluator {
 				a.get_handle(),
 				galois_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_columns",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -325,7 +372,15 @@ impl Evaluator for BFVEvaluator {
 				a.get_handle(),
 				galois_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_columns_inplace",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -356,8 +411,8 @@ mod tests {
 
 		let encoder = BFVEncoder::new(&ctx).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -637,6 +692,30 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn can_multiply_with_custom_pools() {
+		run_bfv_test(|decryptor, encoder, encryptor, evaluator, _| {
+			let pool = MemoryPool::new().unwrap();
+			let evaluator = evaluator.with_memory_pool(pool.clone());
+			let encryptor = encryptor.with_memory_pool(pool.clone());
+			let encoder = encoder.with_memory_pool(pool.clone());
+
+			let a = make_vec(&encoder);
+			let a_c = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+
+			let c_c = evaluator.multiply(&a_c, &a_c).unwrap();
+			let c = encoder
+				.decode_i64(&decryptor.decrypt(&c_c).unwrap())
+				.unwrap();
+
+			for i in 0..a.len() {
+				assert_eq!(c[i], a[i] * a[i]);
+			}
+
+			assert!(pool.pool_allocated_byte_count().unwrap() > 0);
+		});
+	}
+
 	#[test]
 	fn can_multiply_inplace() {
 		run_bfv_test(|decryptor, encoder, encryptor, evaluator, _| {
@@ -1054,4 +1133,47 @@ mod tests {
 			assert_eq!(a[4097], c[1]);
 		});
 	}
+
+	#[test]
+	fn relinearize_reports_keys_from_another_context() {
+		run_bfv_test(|_, encoder, encryptor, evaluator, keygen| {
+			let params = BFVEncryptionParametersBuilder::new()
+				.set_poly_modulus_degree(DegreeType::D8192)
+				.set_coefficient_modulus(
+					CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+				)
+				.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 32).unwrap())
+				.build()
+				.unwrap();
+
+			let other_ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+			let other_keys = KeyGenerator::new(&other_ctx)
+				.unwrap()
+				.create_relinearization_keys()
+				.unwrap();
+
+			let a_c = encryptor
+				.encrypt(&encoder.encode_i64(&make_vec(&encoder)).unwrap())
+				.unwrap();
+			let a_c = evaluator.square(&a_c).unwrap();
+
+			let err = evaluator.relinearize(&a_c, &other_keys).unwrap_err();
+
+			match err {
+				Error::KeyMismatch(mismatch) => {
+					assert_eq!(mismatch.operation, "Evaluator::relinearize");
+					assert_eq!(mismatch.actual, other_keys.parms_id().unwrap());
+					assert_eq!(
+						mismatch.expected,
+						keygen
+							.create_relinearization_keys()
+							.unwrap()
+							.parms_id()
+							.unwrap()
+					);
+				}
+				err => panic!("unexpected error {err:?}"),
+			}
+		});
+	}
 }
@@ -1,9 +1,7 @@
-use std::ptr::null_mut;
-
 use crate::evaluator::base::EvaluatorBase;
 use crate::{
-	bindgen, try_seal, Ciphertext, Context, Evaluator, GaloisKey, Plaintext, RelinearizationKey,
-	Result,
+	bindgen, try_seal, Ciphertext, Context, Evaluator, GaloisKey, MemoryPool, Plaintext,
+	RelinearizationKey, Result,
 };
 
 /// An evaluator that contains additional operations specific to the CKKS scheme.
@@ -23,6 +21,130 @@ impl CKKSEvaluator {
 	pub fn new(ctx: &Context) -> Result<CKKSEvaluator> {
 		Ok(CKKSEvaluator(EvaluatorBase::new(ctx)?))
 	}
+
+	/// Allocates from the given pool during evaluation instead of the pool of
+	/// the current [`crate::MemoryProfile`].
+	pub fn with_memory_pool(
+		self,
+		pool: MemoryPool,
+	) -> Self {
+		Self(self.0.with_memory_pool(pool))
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`: slot `i` of the result
+	/// holds slot `i + steps` of `a`. Negative steps rotate to the right.
+	///
+	/// Unlike BFV, the CKKS slots form a single vector, so this is the
+	/// rotation to use instead of [`Evaluator::rotate_rows`].
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by.
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let out = Ciphertext::new()?;
+
+		try_seal!(unsafe {
+			bindgen::Evaluator_RotateVector(
+				self.get_handle(),
+				a.get_handle(),
+				steps,
+				galois_keys.get_handle(),
+				out.get_handle(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_vector",
+				"Galois keys",
+				galois_keys.parms_id(),
+			)
+		})?;
+
+		Ok(out)
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`, in place.
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by.
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector_inplace(
+		&self,
+		a: &mut Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<()> {
+		try_seal!(unsafe {
+			bindgen::Evaluator_RotateVector(
+				self.get_handle(),
+				a.get_handle(),
+				steps,
+				galois_keys.get_handle(),
+				a.get_handle(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_vector_inplace",
+				"Galois keys",
+				galois_keys.parms_id(),
+			)
+		})?;
+
+		Ok(())
+	}
+
+	/// Divides `a` by the last prime of its coefficient modulus and switches
+	/// it to the next level, dividing its scale by that prime too.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		let out = Ciphertext::new()?;
+
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_RescaleToNext(
+					self.get_handle(),
+					a.get_handle(),
+					out.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::rescale_to_next"
+		)?;
+
+		Ok(out)
+	}
+
+	/// Rescales `a` to the next level, in place.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next_inplace(
+		&self,
+		a: &mut Ciphertext,
+	) -> Result<()> {
+		try_seal!(
+			unsafe {
+				bindgen::Evaluator_RescaleToNext(
+					self.get_handle(),
+					a.get_handle(),
+					a.get_handle(),
+					self.pool_handle(),
+				)
+			},
+			"Evaluator::rescale_to_next_inplace"
+		)?;
+
+		Ok(())
+	}
 }
 
 impl Evaluator for CKKSEvaluator {
@@ -225,7 +347,15 @@ impl Evaluator for CKKSEvaluator {
 				a.get_handle(),
 				relin_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::relinearize_inplace",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -245,7 +375,15 @@ impl Evaluator for CKKSEvaluator {
 				a.get_handle(),
 				relin_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::relinearize",
+				"relinearization keys",
+				relin_keys.parms_id(),
 			)
 		})?;
 
@@ -267,7 +405,15 @@ impl Evaluator for CKKSEvaluator {
 				steps,
 				galois_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_rows",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -287,7 +433,15 @@ impl Evaluator for CKKSEvaluator {
 				steps,
 				galois_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_rows_inplace",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -307,7 +461,15 @@ impl Evaluator for CKKSEvaluator {
 				a.get_handle(),
 				galois_keys.get_handle(),
 				out.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_columns",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -325,7 +487,15 @@ impl Evaluator for CKKSEvaluator {
 				a.get_handle(),
 				galois_keys.get_handle(),
 				a.get_handle(),
-				null_mut(),
+				self.pool_handle(),
+			)
+		})
+		.map_err(|e| {
+			self.key_error(
+				e,
+				"Evaluator::rotate_columns_inplace",
+				"Galois keys",
+				galois_keys.parms_id(),
 			)
 		})?;
 
@@ -363,8 +533,8 @@ mod tests {
 		let scale = 2.0f64.powi(40);
 		let encoder = CKKSEncoder::new(&ctx, scale).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -1069,4 +1239,119 @@ mod tests {
 			float_assert_eq(a[4097], c[1]);
 		});
 	}
+
+	#[test]
+	fn can_rotate_vector() {
+		run_ckks_test(|decryptor, encoder, encryptor, evaluator, keygen| {
+			let galois_keys = keygen.create_galois_keys().unwrap();
+
+			let a = make_vec(&encoder);
+			let a_p = encoder.encode_f64(&a).unwrap();
+			let mut a_c = encryptor.encrypt(&a_p).unwrap();
+
+			let c_c = evaluator.rotate_vector(&a_c, 1, &galois_keys).unwrap();
+			let c = encoder
+				.decode_f64(&decryptor.decrypt(&c_c).unwrap())
+				.unwrap();
+
+			// The slots form a single vector of N/2 elements.
+			float_assert_eq(a[1], c[0]);
+			float_assert_eq(a[2048], c[2047]);
+			float_assert_eq(a[0], c[4095]);
+
+			evaluator
+				.rotate_vector_inplace(&mut a_c, -1, &galois_keys)
+				.unwrap();
+			let c = encoder
+				.decode_f64(&decryptor.decrypt(&a_c).unwrap())
+				.unwrap();
+
+			float_assert_eq(a[0], c[1]);
+			float_assert_eq(a[4095], c[0]);
+		});
+	}
+
+	#[test]
+	fn can_rescale_to_next() {
+		run_ckks_test(|decryptor, encoder, encryptor, evaluator, _| {
+			let a = make_small_vec(&encoder);
+			let a_p = encoder.encode_f64(&a).unwrap();
+			let a_c = encryptor.encrypt(&a_p).unwrap();
+
+			let mut c_c = evaluator.multiply_plain(&a_c, &a_p).unwrap();
+			let size = c_c.coeff_modulus_size().unwrap();
+
+			evaluator.rescale_to_next_inplace(&mut c_c).unwrap();
+			assert_eq!(c_c.coeff_modulus_size().unwrap(), size - 1);
+
+			let c = encoder
+				.decode_f64(&decryptor.decrypt(&c_c).unwrap())
+				.unwrap();
+
+			for i in 0..a.len() {
+				assert!((c[i] - a[i] * a[i]).abs() < 0.01);
+			}
+		});
+	}
+
+	#[test]
+	fn add_reports_scale_mismatch() {
+		run_ckks_test(|_, encoder, encryptor, evaluator, _| {
+			let a_p = encoder.encode_f64(&make_small_vec(&encoder)).unwrap();
+			let a_c = encryptor.encrypt(&a_p).unwrap();
+			let b_c = evaluator.multiply_plain(&a_c, &a_p).unwrap();
+
+			let err = evaluator.add(&a_c, &b_c).unwrap_err();
+
+			assert_eq!(err.operation(), Some("Evaluator::add"));
+			assert_eq!(
+				err,
+				Error::ScaleMismatch(Box::new(ScaleMismatch {
+					operation: "Evaluator::add",
+					left: a_c.scale().unwrap(),
+					right: b_c.scale().unwrap(),
+				}))
+			);
+		});
+	}
+
+	#[test]
+	fn add_reports_parms_mismatch() {
+		run_ckks_test(|_, encoder, encryptor, evaluator, _| {
+			let a_p = encoder.encode_f64(&make_small_vec(&encoder)).unwrap();
+			let a_c = encryptor.encrypt(&a_p).unwrap();
+			let b_c = evaluator.mod_switch_to_next(&a_c).unwrap();
+
+			let err = evaluator.sub(&a_c, &b_c).unwrap_err();
+
+			assert_eq!(
+				err,
+				Error::ParmsMismatch(Box::new(ParmsMismatch {
+					operation: "Evaluator::sub",
+					left: a_c.parms_id().unwrap(),
+					right: b_c.parms_id().unwrap(),
+				}))
+			);
+		});
+	}
+
+	#[test]
+	fn rescale_names_the_failing_operation() {
+		run_ckks_test(|_, encoder, encryptor, evaluator, _| {
+			let a_p = encoder.encode_f64(&make_small_vec(&encoder)).unwrap();
+			let a_c = encryptor.encrypt(&a_p).unwrap();
+
+			while a_c.coeff_modulus_size().unwrap() > 1 {
+				evaluator.mod_switch_to_next_inplace(&a_c).unwrap();
+			}
+
+			let err = evaluator.rescale_to_next(&a_c).unwrap_err();
+
+			assert_eq!(err.operation(), Some("Evaluator::rescale_to_next"));
+			assert_eq!(err.kind(), &Error::InvalidArgument);
+			assert!(err
+				.to_string()
+				.starts_with("Evaluator::rescale_to_next failed"));
+		});
+	}
 }
@@ -0,0 +1,129 @@
+//! Pieces shared by [`super::noise::NoiseEstimator`],
+//! [`super::precision::PrecisionEstimator`] and
+//! [`super::plain::PlainEvaluator`], which model SEAL without encrypting.
+
+use std::collections::VecDeque;
+
+use crate::error::*;
+use crate::Context;
+
+/// The standard deviation of the error distribution SEAL samples from.
+const NOISE_STANDARD_DEVIATION: f64 = 3.2;
+
+/// SEAL clips sampled errors at six standard deviations.
+pub(super) const NOISE_MAX_DEVIATION: f64 = 6.0 * NOISE_STANDARD_DEVIATION;
+
+/// Returns log2(2^a + 2^b) without leaving the log domain.
+pub(super) fn log_add(
+	a: f64,
+	b: f64,
+) -> f64 {
+	let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+
+	if lo == f64::NEG_INFINITY {
+		hi
+	} else {
+		hi + (1.0 + (lo - hi).exp2()).log2()
+	}
+}
+
+/// Multiplies `items` together pairwise, as SEAL's `multiply_many` does:
+/// each product joins the back of the queue, keeping the depth logarithmic.
+/// Fails with [`Error::InvalidArgument`] if there are no items.
+pub(super) fn multiply_pairwise<S>(
+	operation: &'static str,
+	items: impl IntoIterator<Item = S>,
+	mut multiply: impl FnMut(S, S) -> Result<S>,
+) -> Result<S> {
+	let mut queue: VecDeque<S> = items.into_iter().collect();
+
+	while let Some(x) = queue.pop_front() {
+		match queue.pop_front() {
+			Some(y) => queue.push_back(multiply(x, y)?),
+			None => return Ok(x),
+		}
+	}
+
+	Err(Error::InvalidArgument.in_operation(operation))
+}
+
+/// Multiplies `count` copies of `item` together, as SEAL's `exponentiate`
+/// does through `multiply_many`, with the same products as
+/// [`multiply_pairwise`] but without the copies: runs of equal values queue
+/// as one value and its count, so O(log count) products are computed.
+/// Fails with [`Error::InvalidArgument`] if `count` is zero.
+pub(super) fn multiply_copies<S: Clone>(
+	operation: &'static str,
+	item: S,
+	count: u64,
+	mut multiply: impl FnMut(S, S) -> Result<S>,
+) -> Result<S> {
+	let mut queue: VecDeque<(S, u64)> = VecDeque::new();
+
+	if count > 0 {
+		queue.push_back((item, count));
+	}
+
+	while let Some((x, n)) = queue.pop_front() {
+		// Pair the run up; an odd one out pairs with whatever follows it.
+		if n > 1 {
+			let square = multiply(x.clone(), x.clone())?;
+
+			if n % 2 == 1 {
+				queue.push_front((x, 1));
+			}
+
+			queue.push_back((square, n / 2));
+			continue;
+		}
+
+		match queue.pop_front() {
+			Some((y, m)) => {
+				if m > 1 {
+					queue.push_front((y.clone(), m - 1));
+				}
+
+				queue.push_back((multiply(x, y)?, 1));
+			}
+			None => return Ok(x),
+		}
+	}
+
+	Err(Error::InvalidArgument.in_operation(operation))
+}
+
+/// One level of the modulus switching chain as seen by an estimator.
+#[derive(Debug, Clone)]
+pub(super) struct Level {
+	pub(super) parms_id: Vec<u64>,
+	/// log2 of the product of the primes in the coefficient modulus.
+	pub(super) log_q: f64,
+	/// log2 of the last prime, which rescaling divides by.
+	pub(super) log_last_prime: f64,
+	/// The number of primes in the coefficient modulus.
+	pub(super) coeff_modulus_size: usize,
+}
+
+impl Level {
+	/// Returns the data levels of the context, first to last.
+	pub(super) fn chain(ctx: &Context) -> Result<Vec<Self>> {
+		ctx.get_data_levels()?
+			.into_iter()
+			.map(|level| {
+				let primes = level
+					.parms
+					.get_coefficient_modulus()?
+					.iter()
+					.map(|m| m.value().map(|v| (v as f64).log2()))
+					.collect::<Result<Vec<f64>>>()?;
+
+				Ok(Level {
+					parms_id: level.parms_id,
+					log_q: primes.iter().sum(),
+					log_last_prime: primes.last().copied().unwrap_or(0.0),
+					coeff_modulus_size: primes.len(),
+				})
+			})
+			.collect()
+	}
+}
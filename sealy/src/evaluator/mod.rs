@@ -1,9 +1,14 @@
 use crate::error::*;
 use crate::{GaloisKey, RelinearizationKey};
 
+pub mod any;
 pub mod base;
 pub mod bfv;
 pub mod ckks;
+mod estimate;
+pub mod noise;
+pub mod plain;
+pub mod precision;
 
 /// An interface for an evaluator.
 pub trait Evaluator {
@@ -0,0 +1,863 @@
+use std::cell::Cell;
+
+use crate::error::*;
+use crate::evaluator::estimate::{
+	log_add, multiply_copies, multiply_pairwise, Level, NOISE_MAX_DEVIATION,
+};
+use crate::{Context, Evaluator, GaloisKey, Plaintext, RelinearizationKey, SchemeType};
+
+/// Slack, in bits, added to the noise of a fresh encryption.
+const FRESH_SLACK_BITS: f64 = 3.0;
+
+/// Slack, in bits, added to the noise of a ciphertext-ciphertext product.
+const MULTIPLY_SLACK_BITS: f64 = 4.0;
+
+/// The estimated state of a ciphertext.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct State {
+	/// log2 of the infinity norm of the invariant noise.
+	log_noise: f64,
+	/// The number of polynomials in the ciphertext.
+	size: usize,
+	/// The position in the estimator's chain; 0 is the first data level.
+	level: usize,
+}
+
+/// The estimated noise of a BFV ciphertext, produced and consumed by
+/// [`NoiseEstimator`].
+#[derive(Debug, Clone)]
+pub struct NoiseCiphertext {
+	state: Cell<State>,
+}
+
+impl NoiseCiphertext {
+	fn new(state: State) -> Self {
+		Self {
+			state: Cell::new(state),
+		}
+	}
+
+	/// Returns the predicted invariant noise budget in bits, comparable to
+	/// [`crate::Decryptor::invariant_noise_budget`]. A budget of 0 means the
+	/// ciphertext is not expected to decrypt correctly.
+	pub fn noise_budget(&self) -> u32 {
+		let budget = -1.0 - self.state.get().log_noise;
+
+		budget.floor().clamp(0.0, u32::MAX as f64) as u32
+	}
+
+	/// Returns the number of polynomials the ciphertext would have.
+	pub fn size(&self) -> usize {
+		self.state.get().size
+	}
+
+	/// Returns how many times the ciphertext was switched down the modulus
+	/// chain since it was encrypted.
+	pub fn level(&self) -> usize {
+		self.state.get().level
+	}
+}
+
+/// The size of a BFV plaintext as it matters for noise growth, produced by
+/// [`NoiseEstimator::plaintext`] or [`NoiseEstimator::dense_plaintext`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NoisePlaintext {
+	/// log2 of the 1-norm of the plaintext polynomial, with coefficients
+	/// taken in the centered representation modulo the plain modulus.
+	log_norm: f64,
+}
+
+/// Predicts the noise budget BFV operations consume without running them.
+///
+/// The estimator implements [`Evaluator`] over [`NoiseCiphertext`] and
+/// [`NoisePlaintext`], so a circuit written against the trait can be run on it
+/// first to learn whether the real ciphertexts would still decrypt. Noise is
+/// tracked with heuristic worst-case bounds on the invariant noise, padded so
+/// the predicted budget stays at or below what
+/// [`crate::Decryptor::invariant_noise_budget`] would report.
+///
+/// The estimator also rejects what SEAL would reject: operands at different
+/// levels, switching past the last level and rotating ciphertexts that are not
+/// relinearized. Keys passed to it are not inspected.
+pub struct NoiseEstimator {
+	levels: Vec<Level>,
+	log_degree: f64,
+	plain_modulus: u64,
+	log_plain_modulus: f64,
+}
+
+impl NoiseEstimator {
+	/// Creates a NoiseEstimator for the BFV parameters of the given context.
+	/// Returns [`Error::InvalidOperation`] for other schemes.
+	///  * `ctx` - The context.
+	pub fn new(ctx: &Context) -> Result<Self> {
+		let parms = ctx.get_encryption_parameters()?;
+
+		if parms.get_scheme()? != SchemeType::Bfv {
+			return Err(Error::InvalidOperation.in_operation("NoiseEstimator::new"));
+		}
+
+		let plain_modulus = parms.get_plain_modulus()?.value()?;
+
+		let levels = Level::chain(ctx)?;
+
+		Ok(Self {
+			levels,
+			log_degree: (parms.get_poly_modulus_degree()? as f64).log2(),
+			plain_modulus,
+			log_plain_modulus: (plain_modulus as f64).log2(),
+		})
+	}
+
+	/// Returns a freshly encrypted ciphertext at the first data level, as
+	/// produced by encrypting with the public key.
+	pub fn encrypt(&self) -> NoiseCiphertext {
+		// A fresh ciphertext carries e * u + e1 + e2 * s, whose coefficients
+		// are sums of about n errors each.
+		let log_error = FRESH_SLACK_BITS + NOISE_MAX_DEVIATION.log2() + self.log_degree / 2.0;
+
+		NoiseCiphertext::new(State {
+			log_noise: self.log_plain_modulus - self.levels[0].log_q + log_error,
+			size: 2,
+			level: 0,
+		})
+	}
+
+	/// Describes the given plaintext by the size of its coefficients.
+	///  * `plaintext` - The plaintext a circuit will use.
+	pub fn plaintext(
+		&self,
+		plaintext: &Plaintext,
+	) -> Result<NoisePlaintext> {
+		let mut norm = 0.0;
+
+		for i in 0..plaintext.len()? {
+			let c = plaintext.get_coefficient(i)?;
+
+			norm += c.min(self.plain_modulus - c) as f64;
+		}
+
+		Ok(NoisePlaintext {
+			log_norm: norm.log2(),
+		})
+	}
+
+	/// Returns a plaintext no plaintext can exceed: every coefficient as large
+	/// as the plain modulus allows. Use it when the plaintexts of a circuit are
+	/// not known in advance, e.g. batched client data.
+	pub fn dense_plaintext(&self) -> NoisePlaintext {
+		NoisePlaintext {
+			log_norm: self.log_degree + self.log_plain_modulus - 1.0,
+		}
+	}
+
+	/// Returns the level a ciphertext in the given state is at.
+	fn level(
+		&self,
+		state: &State,
+	) -> &Level {
+		&self.levels[state.level]
+	}
+
+	fn same_level(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<()> {
+		if a.level != b.level {
+			return Err(Error::ParmsMismatch(Box::new(ParmsMismatch {
+				operation,
+				left: self.level(a).parms_id.clone(),
+				right: self.level(b).parms_id.clone(),
+			})));
+		}
+
+		Ok(())
+	}
+
+	fn add_states(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		self.same_level(operation, a, b)?;
+
+		Ok(State {
+			log_noise: log_add(a.log_noise, b.log_noise),
+			size: a.size.max(b.size),
+			level: a.level,
+		})
+	}
+
+	fn multiply_states(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		self.same_level(operation, a, b)?;
+
+		// v = t * n * (v_a * |c_b| + v_b * |c_a|) plus rounding, where every
+		// polynomial beyond the second adds about a bit to |c|.
+		let growth = self.log_plain_modulus + self.log_degree + MULTIPLY_SLACK_BITS;
+		let noise = log_add(
+			a.log_noise + (b.size - 2) as f64,
+			b.log_noise + (a.size - 2) as f64,
+		);
+		let rounding = self.log_plain_modulus - self.level(a).log_q + self.log_degree;
+
+		Ok(State {
+			log_noise: log_add(growth + noise, rounding),
+			size: a.size + b.size - 1,
+			level: a.level,
+		})
+	}
+
+	/// Adds the noise of switching the key of a size 2 ciphertext, as done by
+	/// relinearization and rotations.
+	fn key_switch_state(
+		&self,
+		state: &State,
+	) -> State {
+		let level = self.level(state);
+		let log_error =
+			self.log_degree + (NOISE_MAX_DEVIATION * level.coeff_modulus_size as f64 + 1.0).log2();
+
+		State {
+			log_noise: log_add(
+				state.log_noise,
+				self.log_plain_modulus - level.log_q + log_error,
+			),
+			size: 2,
+			level: state.level,
+		}
+	}
+
+	fn relinearize_state(
+		&self,
+		state: &State,
+	) -> State {
+		if state.size <= 2 {
+			return *state;
+		}
+
+		// Each extra polynomial is folded in by its own key switch.
+		(2..state.size).fold(
+			State {
+				size: 2,
+				..*state
+			},
+			|state, _| self.key_switch_state(&state),
+		)
+	}
+
+	fn rotate_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		if state.size != 2 {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		Ok(self.key_switch_state(state))
+	}
+
+	fn mod_switch_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		let level = state.level + 1;
+
+		if level >= self.levels.len() {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		// Dropping a prime keeps the invariant noise but rounds every
+		// coefficient, adding up to (1 + |s|_1) / 2 in units of q' / t.
+		let rounding = self.log_plain_modulus - self.levels[level].log_q + self.log_degree;
+
+		Ok(State {
+			log_noise: log_add(state.log_noise, rounding),
+			size: state.size,
+			level,
+		})
+	}
+
+	fn add_plain_state(
+		&self,
+		state: &State,
+	) -> State {
+		// Scaling the plaintext by q / t rounds each coefficient by at most 1/2.
+		let rounding = self.log_plain_modulus - self.level(state).log_q - 1.0;
+
+		State {
+			log_noise: log_add(state.log_noise, rounding),
+			..*state
+		}
+	}
+
+	fn multiply_plain_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+		b: &NoisePlaintext,
+	) -> Result<State> {
+		// SEAL refuses to produce the transparent product with zero.
+		if b.log_norm == f64::NEG_INFINITY {
+			return Err(Error::InvalidOperation.in_operation(operation));
+		}
+
+		Ok(State {
+			log_noise: state.log_noise + b.log_norm,
+			..*state
+		})
+	}
+
+	fn multiply_many_state(
+		&self,
+		operation: &'static str,
+		a: &[State],
+	) -> Result<State> {
+		multiply_pairwise(operation, a.iter().copied(), |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+	}
+
+	/// Multiplies and relinearizes, one step of `multiply_many`.
+	fn multiply_relinearize(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		let product = self.multiply_states(operation, a, b)?;
+
+		Ok(self.relinearize_state(&product))
+	}
+}
+
+impl Evaluator for NoiseEstimator {
+	type Plaintext = NoisePlaintext;
+	type Ciphertext = NoiseCiphertext;
+
+	fn negate_inplace(
+		&self,
+		_a: &mut NoiseCiphertext,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	fn negate(
+		&self,
+		a: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		Ok(a.clone())
+	}
+
+	fn add_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"NoiseEstimator::add_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn add(
+		&self,
+		a: &NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		self.add_states("NoiseEstimator::add", &a.state.get(), &b.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn add_many(
+		&self,
+		a: &[NoiseCiphertext],
+	) -> Result<NoiseCiphertext> {
+		let (first, rest) = a
+			.split_first()
+			.ok_or_else(|| Error::InvalidArgument.in_operation("NoiseEstimator::add_many"))?;
+
+		rest.iter()
+			.try_fold(first.state.get(), |sum, c| {
+				self.add_states("NoiseEstimator::add_many", &sum, &c.state.get())
+			})
+			.map(NoiseCiphertext::new)
+	}
+
+	fn multiply_many(
+		&self,
+		a: &[NoiseCiphertext],
+		_relin_keys: &RelinearizationKey,
+	) -> Result<NoiseCiphertext> {
+		let states: Vec<State> = a.iter().map(|c| c.state.get()).collect();
+
+		self.multiply_many_state("NoiseEstimator::multiply_many", &states)
+			.map(NoiseCiphertext::new)
+	}
+
+	fn sub_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"NoiseEstimator::sub_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn sub(
+		&self,
+		a: &NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		self.add_states("NoiseEstimator::sub", &a.state.get(), &b.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn multiply_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<()> {
+		let state = self.multiply_states(
+			"NoiseEstimator::multiply_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn multiply(
+		&self,
+		a: &NoiseCiphertext,
+		b: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		self.multiply_states("NoiseEstimator::multiply", &a.state.get(), &b.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn square_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+	) -> Result<()> {
+		let state = a.state.get();
+		let state = self.multiply_states("NoiseEstimator::square_inplace", &state, &state)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn square(
+		&self,
+		a: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		let state = a.state.get();
+
+		self.multiply_states("NoiseEstimator::square", &state, &state)
+			.map(NoiseCiphertext::new)
+	}
+
+	fn mod_switch_to_next(
+		&self,
+		a: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		self.mod_switch_state("NoiseEstimator::mod_switch_to_next", &a.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn mod_switch_to_next_inplace(
+		&self,
+		a: &NoiseCiphertext,
+	) -> Result<()> {
+		let state =
+			self.mod_switch_state("NoiseEstimator::mod_switch_to_next_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn mod_switch_to_next_plaintext(
+		&self,
+		_a: &NoisePlaintext,
+	) -> Result<NoisePlaintext> {
+		// BFV plaintexts are not in NTT form, which SEAL requires here.
+		Err(Error::InvalidArgument.in_operation("NoiseEstimator::mod_switch_to_next_plaintext"))
+	}
+
+	fn mod_switch_to_next_inplace_plaintext(
+		&self,
+		_a: &NoisePlaintext,
+	) -> Result<()> {
+		Err(Error::InvalidArgument
+			.in_operation("NoiseEstimator::mod_switch_to_next_inplace_plaintext"))
+	}
+
+	fn exponentiate(
+		&self,
+		a: &NoiseCiphertext,
+		exponent: u64,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<NoiseCiphertext> {
+		let operation = "NoiseEstimator::exponentiate";
+
+		multiply_copies(operation, a.state.get(), exponent, |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+		.map(NoiseCiphertext::new)
+	}
+
+	fn exponentiate_inplace(
+		&self,
+		a: &NoiseCiphertext,
+		exponent: u64,
+		relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		let result = self.exponentiate(a, exponent, relin_keys)?;
+		a.state.set(result.state.get());
+		Ok(())
+	}
+
+	fn add_plain(
+		&self,
+		a: &NoiseCiphertext,
+		_b: &NoisePlaintext,
+	) -> Result<NoiseCiphertext> {
+		Ok(NoiseCiphertext::new(self.add_plain_state(&a.state.get())))
+	}
+
+	fn add_plain_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		_b: &NoisePlaintext,
+	) -> Result<()> {
+		a.state.set(self.add_plain_state(&a.state.get()));
+		Ok(())
+	}
+
+	fn sub_plain(
+		&self,
+		a: &NoiseCiphertext,
+		_b: &NoisePlaintext,
+	) -> Result<NoiseCiphertext> {
+		Ok(NoiseCiphertext::new(self.add_plain_state(&a.state.get())))
+	}
+
+	fn sub_plain_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		_b: &NoisePlaintext,
+	) -> Result<()> {
+		a.state.set(self.add_plain_state(&a.state.get()));
+		Ok(())
+	}
+
+	fn multiply_plain(
+		&self,
+		a: &NoiseCiphertext,
+		b: &NoisePlaintext,
+	) -> Result<NoiseCiphertext> {
+		self.multiply_plain_state("NoiseEstimator::multiply_plain", &a.state.get(), b)
+			.map(NoiseCiphertext::new)
+	}
+
+	fn multiply_plain_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		b: &NoisePlaintext,
+	) -> Result<()> {
+		let state =
+			self.multiply_plain_state("NoiseEstimator::multiply_plain_inplace", &a.state.get(), b)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn relinearize_inplace(
+		&self,
+		a: &mut NoiseCiphertext,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		a.state.set(self.relinearize_state(&a.state.get()));
+		Ok(())
+	}
+
+	fn relinearize(
+		&self,
+		a: &NoiseCiphertext,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<NoiseCiphertext> {
+		Ok(NoiseCiphertext::new(self.relinearize_state(&a.state.get())))
+	}
+
+	fn rotate_rows(
+		&self,
+		a: &NoiseCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<NoiseCiphertext> {
+		self.rotate_state("NoiseEstimator::rotate_rows", &a.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn rotate_rows_inplace(
+		&self,
+		a: &NoiseCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state = self.rotate_state("NoiseEstimator::rotate_rows_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn rotate_columns(
+		&self,
+		a: &NoiseCiphertext,
+		_galois_keys: &GaloisKey,
+	) -> Result<NoiseCiphertext> {
+		self.rotate_state("NoiseEstimator::rotate_columns", &a.state.get())
+			.map(NoiseCiphertext::new)
+	}
+
+	fn rotate_columns_inplace(
+		&self,
+		a: &NoiseCiphertext,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state = self.rotate_state("NoiseEstimator::rotate_columns_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	fn run_noise_test<F>(test: F)
+	where
+		F: FnOnce(
+			NoiseEstimator,
+			Decryptor,
+			BFVEncoder,
+			Encryptor<SymAsym>,
+			BFVEvaluator,
+			KeyGenerator,
+		),
+	{
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 32).unwrap())
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = BFVEncoder::new(&ctx).unwrap();
+
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+
+		let encryptor =
+			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
+		let decryptor = Decryptor::new(&ctx, &secret_key).unwrap();
+		let evaluator = BFVEvaluator::new(&ctx).unwrap();
+		let estimator = NoiseEstimator::new(&ctx).unwrap();
+
+		test(estimator, decryptor, encoder, encryptor, evaluator, gen);
+	}
+
+	fn make_vec(encoder: &BFVEncoder) -> Vec<i64> {
+		(0..encoder.get_slot_count())
+			.map(|i| 16i64 - i as i64 % 32i64)
+			.collect()
+	}
+
+	#[test]
+	fn fresh_estimate_is_conservative() {
+		run_noise_test(|estimator, decryptor, encoder, encryptor, _, _| {
+			let a = encryptor
+				.encrypt(&encoder.encode_i64(&make_vec(&encoder)).unwrap())
+				.unwrap();
+			let a_e = estimator.encrypt();
+
+			let actual = decryptor.invariant_noise_budget(&a).unwrap();
+
+			assert!(a_e.noise_budget() <= actual);
+			assert!(actual - a_e.noise_budget() <= 10);
+			assert_eq!(a_e.size(), 2);
+		});
+	}
+
+	#[test]
+	fn circuit_estimate_is_conservative() {
+		run_noise_test(
+			|estimator, decryptor, encoder, encryptor, evaluator, keygen| {
+				let relin_keys = keygen.create_relinearization_keys().unwrap();
+				let galois_keys = keygen.create_galois_keys().unwrap();
+
+				let p = encoder.encode_i64(&make_vec(&encoder)).unwrap();
+				let p_e = estimator.plaintext(&p).unwrap();
+
+				let a = encryptor.encrypt(&p).unwrap();
+				let a_e = estimator.encrypt();
+
+				let check = |c: &Ciphertext, c_e: &NoiseCiphertext| {
+					let actual = decryptor.invariant_noise_budget(c).unwrap();
+					assert!(
+						c_e.noise_budget() <= actual,
+						"{} > {}",
+						c_e.noise_budget(),
+						actual
+					);
+				};
+
+				let b = evaluator.square(&a).unwrap();
+				let b_e = estimator.square(&a_e).unwrap();
+				check(&b, &b_e);
+				assert_eq!(b_e.size(), 3);
+
+				let b = evaluator.relinearize(&b, &relin_keys).unwrap();
+				let b_e = estimator.relinearize(&b_e, &relin_keys).unwrap();
+				check(&b, &b_e);
+				assert_eq!(b_e.size(), 2);
+
+				let c = evaluator.rotate_rows(&b, 1, &galois_keys).unwrap();
+				let c_e = estimator.rotate_rows(&b_e, 1, &galois_keys).unwrap();
+				check(&c, &c_e);
+
+				let d = evaluator.multiply_plain(&c, &p).unwrap();
+				let d_e = estimator.multiply_plain(&c_e, &p_e).unwrap();
+				check(&d, &d_e);
+
+				let e = evaluator.add(&d, &b).unwrap();
+				let e_e = estimator.add(&d_e, &b_e).unwrap();
+				check(&e, &e_e);
+
+				let f = evaluator.mod_switch_to_next(&e).unwrap();
+				let f_e = estimator.mod_switch_to_next(&e_e).unwrap();
+				check(&f, &f_e);
+				assert_eq!(f_e.level(), 1);
+			},
+		);
+	}
+
+	#[test]
+	fn predicts_exhausting_the_budget() {
+		run_noise_test(
+			|estimator, decryptor, encoder, encryptor, evaluator, keygen| {
+				let relin_keys = keygen.create_relinearization_keys().unwrap();
+
+				let mut a = encryptor
+					.encrypt(&encoder.encode_i64(&make_vec(&encoder)).unwrap())
+					.unwrap();
+				let mut a_e = estimator.encrypt();
+
+				let mut depth = 0;
+
+				while a_e.noise_budget() > 0 {
+					evaluator.square_inplace(&mut a).unwrap();
+					evaluator.relinearize_inplace(&mut a, &relin_keys).unwrap();
+
+					estimator.square_inplace(&mut a_e).unwrap();
+					estimator
+						.relinearize_inplace(&mut a_e, &relin_keys)
+						.unwrap();
+
+					depth += 1;
+				}
+
+				assert!(depth > 0);
+				assert!(depth <= 4);
+				assert!(decryptor.invariant_noise_budget(&a).unwrap() >= a_e.noise_budget());
+			},
+		);
+	}
+
+	#[test]
+	fn exponentiates_without_copying_the_operand() {
+		run_noise_test(|estimator, _, _, _, _, keygen| {
+			let relin_keys = keygen.create_relinearization_keys().unwrap();
+			let a = estimator.encrypt();
+
+			let power = estimator.exponentiate(&a, 5, &relin_keys).unwrap();
+			let many = estimator
+				.multiply_many(&vec![a.clone(); 5], &relin_keys)
+				.unwrap();
+
+			assert_eq!(power.noise_budget(), many.noise_budget());
+			assert_eq!(power.size(), many.size());
+
+			// One copy per unit of the exponent would never fit in memory.
+			let huge = estimator.exponentiate(&a, u64::MAX, &relin_keys).unwrap();
+
+			assert_eq!(huge.noise_budget(), 0);
+			assert_eq!(huge.size(), 2);
+
+			let err = estimator.exponentiate(&a, 0, &relin_keys).unwrap_err();
+
+			assert_eq!(*err.kind(), Error::InvalidArgument);
+			assert_eq!(err.operation(), Some("NoiseEstimator::exponentiate"));
+		});
+	}
+
+	#[test]
+	fn rejects_operands_at_different_levels() {
+		run_noise_test(|estimator, _, _, _, _, _| {
+			let a = estimator.encrypt();
+			let b = estimator.mod_switch_to_next(&a).unwrap();
+
+			let err = estimator.add(&a, &b).unwrap_err();
+
+			assert!(matches!(err, Error::ParmsMismatch(_)));
+			assert_eq!(err.operation(), Some("NoiseEstimator::add"));
+		});
+	}
+
+	#[test]
+	fn rejects_switching_past_the_last_level() {
+		run_noise_test(|estimator, _, _, _, _, _| {
+			let a = estimator.encrypt();
+
+			// [50, 30, 30, 50] leaves four data levels.
+			for _ in 0..3 {
+				estimator.mod_switch_to_next_inplace(&a).unwrap();
+			}
+
+			let err = estimator.mod_switch_to_next(&a).unwrap_err();
+
+			assert_eq!(*err.kind(), Error::InvalidArgument);
+		});
+	}
+
+	#[test]
+	fn rejects_rotating_unrelinearized_ciphertexts() {
+		run_noise_test(|estimator, _, _, _, _, keygen| {
+			let galois_keys = keygen.create_galois_keys().unwrap();
+
+			let a = estimator.square(&estimator.encrypt()).unwrap();
+
+			assert!(estimator.rotate_rows(&a, 1, &galois_keys).is_err());
+			assert!(estimator.rotate_columns(&a, &galois_keys).is_err());
+		});
+	}
+}
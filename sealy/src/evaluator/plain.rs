@@ -0,0 +1,1586 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::error::*;
+use crate::evaluator::base::are_close;
+use crate::evaluator::estimate::{multiply_copies, multiply_pairwise};
+use crate::{
+	BFVEncoder, BFVEvaluator, CKKSEncoder, CKKSEvaluator, CircuitEvaluator, CircuitKeys, Context,
+	Decryptor, Encryptor, Evaluator, GaloisKey, Precision, PublicKey, RelinearizationKey,
+	SchemeType, SecretKey,
+};
+
+mod sealed {
+	use crate::SchemeType;
+
+	/// Slot arithmetic of a scheme. `modulus` is the plain modulus under BFV
+	/// and unused under CKKS.
+	pub trait Slot: Copy + Default + std::fmt::Debug {
+		const SCHEME: SchemeType;
+
+		fn is_valid(
+			self,
+			modulus: u64,
+		) -> bool;
+
+		fn add(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self;
+
+		fn sub(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self;
+
+		fn mul(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self;
+
+		fn neg(
+			self,
+			modulus: u64,
+		) -> Self;
+
+		fn to_f64(self) -> f64;
+	}
+
+	impl Slot for f64 {
+		const SCHEME: SchemeType = SchemeType::Ckks;
+
+		fn is_valid(
+			self,
+			_modulus: u64,
+		) -> bool {
+			true
+		}
+
+		fn add(
+			self,
+			other: Self,
+			_modulus: u64,
+		) -> Self {
+			self + other
+		}
+
+		fn sub(
+			self,
+			other: Self,
+			_modulus: u64,
+		) -> Self {
+			self - other
+		}
+
+		fn mul(
+			self,
+			other: Self,
+			_modulus: u64,
+		) -> Self {
+			self * other
+		}
+
+		fn neg(
+			self,
+			_modulus: u64,
+		) -> Self {
+			-self
+		}
+
+		fn to_f64(self) -> f64 {
+			self
+		}
+	}
+
+	impl Slot for u64 {
+		const SCHEME: SchemeType = SchemeType::Bfv;
+
+		fn is_valid(
+			self,
+			modulus: u64,
+		) -> bool {
+			self < modulus
+		}
+
+		fn add(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self {
+			((self as u128 + other as u128) % modulus as u128) as u64
+		}
+
+		fn sub(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self {
+			((self as u128 + modulus as u128 - other as u128) % modulus as u128) as u64
+		}
+
+		fn mul(
+			self,
+			other: Self,
+			modulus: u64,
+		) -> Self {
+			((self as u128 * other as u128) % modulus as u128) as u64
+		}
+
+		fn neg(
+			self,
+			modulus: u64,
+		) -> Self {
+			(modulus - self) % modulus
+		}
+
+		fn to_f64(self) -> f64 {
+			self as f64
+		}
+	}
+}
+
+/// The type of a slot [`PlainEvaluator`] computes on: `f64` under CKKS and
+/// `u64` modulo the plain modulus under BFV.
+pub trait PlainSlot: sealed::Slot {}
+
+impl PlainSlot for f64 {}
+impl PlainSlot for u64 {}
+
+/// The cleartext state of a value, as SEAL would hold it encrypted.
+#[derive(Debug, Clone)]
+struct State<T> {
+	/// One value per slot.
+	values: Vec<T>,
+	/// The number of polynomials; 1 for plaintexts.
+	size: usize,
+	/// The position in the evaluator's chain; 0 is the first data level.
+	level: usize,
+	/// The CKKS scale; always 1 under BFV.
+	scale: f64,
+}
+
+/// The cleartext slots of a ciphertext, produced and consumed by
+/// [`PlainEvaluator`].
+#[derive(Debug, Clone)]
+pub struct PlainCiphertext<T> {
+	state: RefCell<State<T>>,
+}
+
+impl<T: PlainSlot> PlainCiphertext<T> {
+	fn new(state: State<T>) -> Self {
+		Self {
+			state: RefCell::new(state),
+		}
+	}
+
+	/// Returns the values of all the slots, as decrypting and decoding the
+	/// ciphertext would.
+	pub fn values(&self) -> Vec<T> {
+		self.state.borrow().values.clone()
+	}
+
+	/// Returns the number of polynomials the ciphertext would have.
+	pub fn size(&self) -> usize {
+		self.state.borrow().size
+	}
+
+	/// Returns how many times the ciphertext was rescaled or switched down
+	/// the modulus chain since it was encrypted.
+	pub fn level(&self) -> usize {
+		self.state.borrow().level
+	}
+
+	/// Returns the scale the ciphertext would have; 1 under BFV.
+	pub fn scale(&self) -> f64 {
+		self.state.borrow().scale
+	}
+}
+
+/// The cleartext slots of a plaintext, produced by
+/// [`PlainEvaluator::plaintext`].
+#[derive(Debug, Clone)]
+pub struct PlainPlaintext<T> {
+	state: RefCell<State<T>>,
+}
+
+impl<T: PlainSlot> PlainPlaintext<T> {
+	/// Returns the values of all the slots.
+	pub fn values(&self) -> Vec<T> {
+		self.state.borrow().values.clone()
+	}
+
+	/// Returns the scale the plaintext would have; 1 under BFV.
+	pub fn scale(&self) -> f64 {
+		self.state.borrow().scale
+	}
+}
+
+/// Computes on cleartext slots with the semantics of [`BFVEvaluator`] or
+/// [`CKKSEvaluator`], for testing code written against [`Evaluator`] without
+/// the cost of encryption.
+///
+/// Values are vectors of one `f64` per CKKS slot or one `u64` per BFV slot,
+/// and BFV arithmetic wraps around the plain modulus. Rotations move slots as
+/// SEAL does: the rows of the 2-by-(N/2) BFV matrix, or the whole CKKS
+/// vector with [`PlainEvaluator::rotate_vector`]. The evaluator also rejects
+/// what SEAL would: operands at different levels or, under CKKS, at
+/// different scales, levels past the end of the modulus chain, rotations of
+/// ciphertexts that were not relinearized and operations the scheme lacks.
+/// CKKS scales follow SEAL's: products multiply them and rescaling divides
+/// them by the last prime of the level. Values are exact: compare against
+/// encrypted results within a tolerance, e.g. with [`Differential`]. Keys
+/// passed to it are not inspected.
+pub struct PlainEvaluator<T> {
+	levels: Vec<Vec<u64>>,
+	/// The last prime of each level, which rescaling divides the scale by.
+	last_primes: Vec<f64>,
+	/// The scale values are encrypted and encoded at.
+	scale: f64,
+	slot_count: usize,
+	plain_modulus: u64,
+	_slot: PhantomData<T>,
+}
+
+impl PlainEvaluator<f64> {
+	/// Creates a PlainEvaluator for the CKKS parameters of the given context.
+	/// Returns [`Error::InvalidOperation`] for other schemes.
+	///  * `ctx` - The context.
+	///  * `scale` - The scale values are encrypted and encoded at.
+	pub fn ckks(
+		ctx: &Context,
+		scale: f64,
+	) -> Result<Self> {
+		Self::new(ctx, scale, "PlainEvaluator::ckks")
+	}
+
+	/// Rescales `a` to the next level, as
+	/// [`CKKSEvaluator::rescale_to_next`] does. The values are unchanged and
+	/// the scale is divided by the last prime of the current level.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next(
+		&self,
+		a: &PlainCiphertext<f64>,
+	) -> Result<PlainCiphertext<f64>> {
+		self.rescale_state("PlainEvaluator::rescale_to_next", &a.state.borrow())
+			.map(PlainCiphertext::new)
+	}
+
+	/// Rescales `a` to the next level, in place.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next_inplace(
+		&self,
+		a: &mut PlainCiphertext<f64>,
+	) -> Result<()> {
+		let state =
+			self.rescale_state("PlainEvaluator::rescale_to_next_inplace", &a.state.borrow())?;
+		a.state = RefCell::new(state);
+		Ok(())
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`, as
+	/// [`CKKSEvaluator::rotate_vector`] does.
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by (positive left, negative right).
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector(
+		&self,
+		a: &PlainCiphertext<f64>,
+		steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<PlainCiphertext<f64>> {
+		let state = a.state.borrow();
+
+		self.rotate_state(
+			"PlainEvaluator::rotate_vector",
+			&state,
+			steps,
+			self.slot_count,
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`, in place.
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by (positive left, negative right).
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector_inplace(
+		&self,
+		a: &mut PlainCiphertext<f64>,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let result = self.rotate_vector(a, steps, galois_keys)?;
+		a.state = result.state;
+		Ok(())
+	}
+
+	/// Returns `a` with its scale overridden by `scale`, which multiplies
+	/// what it decrypts to by the old scale over the new one.
+	pub(crate) fn set_scale(
+		&self,
+		a: &PlainCiphertext<f64>,
+		scale: f64,
+	) -> PlainCiphertext<f64> {
+		let state = a.state.borrow();
+		let ratio = state.scale / scale;
+
+		PlainCiphertext::new(State {
+			values: state.values.iter().map(|x| x * ratio).collect(),
+			scale,
+			..*state
+		})
+	}
+
+	/// Returns `a` multiplied by ones encoded at its level and at the ratio
+	/// of `scale` to its scale, which brings it to `scale`. The encoder
+	/// rounds the ones to an integer multiple of one over the ratio.
+	pub(crate) fn raise_scale(
+		&self,
+		a: &PlainCiphertext<f64>,
+		scale: f64,
+	) -> PlainCiphertext<f64> {
+		let state = a.state.borrow();
+		let ratio = scale / state.scale;
+		let one = ratio.round() / ratio;
+
+		PlainCiphertext::new(State {
+			values: state.values.iter().map(|x| x * one).collect(),
+			scale,
+			..*state
+		})
+	}
+
+	/// Rescales `a` to the next level and divides its scale by the last
+	/// prime of its current level.
+	fn rescale_state(
+		&self,
+		operation: &'static str,
+		a: &State<f64>,
+	) -> Result<State<f64>> {
+		let state = self.next_level(operation, a)?;
+
+		Ok(State {
+			scale: a.scale / self.last_primes[a.level],
+			..state
+		})
+	}
+}
+
+impl PlainEvaluator<u64> {
+	/// Creates a PlainEvaluator for the BFV parameters of the given context.
+	/// Returns [`Error::InvalidOperation`] for other schemes.
+	///  * `ctx` - The context. Its plain modulus must allow batching.
+	pub fn bfv(ctx: &Context) -> Result<Self> {
+		Self::new(ctx, 1.0, "PlainEvaluator::bfv")
+	}
+}
+
+impl<T: PlainSlot> PlainEvaluator<T> {
+	fn new(
+		ctx: &Context,
+		scale: f64,
+		operation: &'static str,
+	) -> Result<Self> {
+		let parms = ctx.get_encryption_parameters()?;
+
+		if parms.get_scheme()? != T::SCHEME {
+			return Err(Error::InvalidOperation.in_operation(operation));
+		}
+
+		let degree = parms.get_poly_modulus_degree()? as usize;
+
+		let (slot_count, plain_modulus) = match T::SCHEME {
+			SchemeType::Bfv => (degree, parms.get_plain_modulus()?.value()?),
+			_ => (degree / 2, 0),
+		};
+
+		let mut levels = vec![];
+		let mut last_primes = vec![];
+
+		for level in ctx.get_data_levels()? {
+			let last_prime = match level.parms.get_coefficient_modulus()?.last() {
+				Some(prime) => prime.value()? as f64,
+				None => return Err(Error::InvalidArgument.in_operation(operation)),
+			};
+
+			levels.push(level.parms_id);
+			last_primes.push(last_prime);
+		}
+
+		Ok(Self {
+			levels,
+			last_primes,
+			scale,
+			slot_count,
+			plain_modulus,
+			_slot: PhantomData,
+		})
+	}
+
+	/// Returns the number of slots of a value.
+	pub fn slot_count(&self) -> usize {
+		self.slot_count
+	}
+
+	/// Returns a freshly encrypted ciphertext at the first data level holding
+	/// `values`, with the remaining slots set to zero. Fails with
+	/// [`Error::InvalidArgument`] where the encoder would: if there are more
+	/// values than slots or, under BFV, a value is not below the plain
+	/// modulus.
+	///  * `values` - The values of the slots.
+	pub fn encrypt(
+		&self,
+		values: &[T],
+	) -> Result<PlainCiphertext<T>> {
+		let state = self.encode("PlainEvaluator::encrypt", values)?;
+
+		Ok(PlainCiphertext::new(State {
+			size: 2,
+			..state
+		}))
+	}
+
+	/// Returns a plaintext at the first data level holding `values`, as
+	/// [`PlainEvaluator::encrypt`] does.
+	///  * `values` - The values of the slots.
+	pub fn plaintext(
+		&self,
+		values: &[T],
+	) -> Result<PlainPlaintext<T>> {
+		Ok(PlainPlaintext {
+			state: RefCell::new(self.encode("PlainEvaluator::plaintext", values)?),
+		})
+	}
+
+	fn encode(
+		&self,
+		operation: &'static str,
+		values: &[T],
+	) -> Result<State<T>> {
+		if values.len() > self.slot_count || !values.iter().all(|v| v.is_valid(self.plain_modulus))
+		{
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		let mut values = values.to_vec();
+		values.resize(self.slot_count, T::default());
+
+		Ok(State {
+			values,
+			size: 1,
+			level: 0,
+			scale: self.scale,
+		})
+	}
+
+	fn same_level(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+	) -> Result<()> {
+		if a.level != b.level {
+			return Err(Error::ParmsMismatch(Box::new(ParmsMismatch {
+				operation,
+				left: self.levels[a.level].clone(),
+				right: self.levels[b.level].clone(),
+			})));
+		}
+
+		Ok(())
+	}
+
+	/// Rejects operands whose scales SEAL would consider different. BFV
+	/// scales are always 1, so this only fails under CKKS.
+	fn same_scale(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+	) -> Result<()> {
+		if !are_close(a.scale, b.scale) {
+			return Err(Error::ScaleMismatch(Box::new(ScaleMismatch {
+				operation,
+				left: a.scale,
+				right: b.scale,
+			})));
+		}
+
+		Ok(())
+	}
+
+	fn zip(
+		&self,
+		a: &State<T>,
+		b: &State<T>,
+		f: fn(T, T, u64) -> T,
+	) -> Vec<T> {
+		a.values
+			.iter()
+			.zip(&b.values)
+			.map(|(x, y)| f(*x, *y, self.plain_modulus))
+			.collect()
+	}
+
+	fn add_states(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+		f: fn(T, T, u64) -> T,
+	) -> Result<State<T>> {
+		self.same_level(operation, a, b)?;
+		self.same_scale(operation, a, b)?;
+
+		Ok(State {
+			values: self.zip(a, b, f),
+			size: a.size.max(b.size),
+			..*a
+		})
+	}
+
+	fn multiply_states(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+	) -> Result<State<T>> {
+		self.same_level(operation, a, b)?;
+
+		Ok(State {
+			values: self.zip(a, b, T::mul),
+			size: a.size + b.size - 1,
+			level: a.level,
+			scale: a.scale * b.scale,
+		})
+	}
+
+	/// Adds or subtracts a plaintext. BFV plaintexts are not at any level, so
+	/// only CKKS plaintexts must match the ciphertext's.
+	fn plain_states(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+		f: fn(T, T, u64) -> T,
+	) -> Result<State<T>> {
+		if T::SCHEME == SchemeType::Ckks {
+			self.same_level(operation, a, b)?;
+		}
+
+		self.same_scale(operation, a, b)?;
+
+		Ok(State {
+			values: self.zip(a, b, f),
+			..*a
+		})
+	}
+
+	/// Multiplies by a plaintext, which multiplies the scales.
+	fn plain_product(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+	) -> Result<State<T>> {
+		if T::SCHEME == SchemeType::Ckks {
+			self.same_level(operation, a, b)?;
+		}
+
+		Ok(State {
+			values: self.zip(a, b, T::mul),
+			scale: a.scale * b.scale,
+			..*a
+		})
+	}
+
+	fn negate_state(
+		&self,
+		a: &State<T>,
+	) -> State<T> {
+		State {
+			values: a.values.iter().map(|x| x.neg(self.plain_modulus)).collect(),
+			..*a
+		}
+	}
+
+	fn relinearize_state(
+		&self,
+		a: &State<T>,
+	) -> State<T> {
+		State {
+			values: a.values.clone(),
+			size: 2,
+			..*a
+		}
+	}
+
+	/// Rotates each run of `width` slots of `a` left by `steps`.
+	fn rotate_state(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		steps: i32,
+		width: usize,
+	) -> Result<State<T>> {
+		if a.size != 2 {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		let shift = (steps as i64).rem_euclid(width as i64) as usize;
+		let mut values = a.values.clone();
+
+		for run in values.chunks_mut(width) {
+			run.rotate_left(shift);
+		}
+
+		Ok(State {
+			values,
+			..*a
+		})
+	}
+
+	fn rotate_rows_state(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		steps: i32,
+	) -> Result<State<T>> {
+		self.bfv_only(operation)?;
+		self.rotate_state(operation, a, steps, self.slot_count / 2)
+	}
+
+	fn rotate_columns_state(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+	) -> Result<State<T>> {
+		self.bfv_only(operation)?;
+		self.rotate_state(operation, a, (self.slot_count / 2) as i32, self.slot_count)
+	}
+
+	fn next_level(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+	) -> Result<State<T>> {
+		let level = a.level + 1;
+
+		if level >= self.levels.len() {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		Ok(State {
+			values: a.values.clone(),
+			level,
+			..*a
+		})
+	}
+
+	fn multiply_many_state(
+		&self,
+		operation: &'static str,
+		a: Vec<State<T>>,
+	) -> Result<State<T>> {
+		self.bfv_only(operation)?;
+
+		multiply_pairwise(operation, a, |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+	}
+
+	/// Multiplies and relinearizes, one step of `multiply_many`.
+	fn multiply_relinearize(
+		&self,
+		operation: &'static str,
+		a: &State<T>,
+		b: &State<T>,
+	) -> Result<State<T>> {
+		let product = self.multiply_states(operation, a, b)?;
+
+		Ok(self.relinearize_state(&product))
+	}
+
+	/// SEAL has no row rotations, products of many ciphertexts or powers
+	/// under CKKS.
+	fn bfv_only(
+		&self,
+		operation: &'static str,
+	) -> Result<()> {
+		if T::SCHEME != SchemeType::Bfv {
+			return Err(Error::InvalidOperation.in_operation(operation));
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: PlainSlot> Evaluator for PlainEvaluator<T> {
+	type Plaintext = PlainPlaintext<T>;
+	type Ciphertext = PlainCiphertext<T>;
+
+	fn negate_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.negate_state(a.state.get_mut());
+		a.state = RefCell::new(state);
+		Ok(())
+	}
+
+	fn negate(
+		&self,
+		a: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		Ok(PlainCiphertext::new(self.negate_state(&a.state.borrow())))
+	}
+
+	fn add_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.add(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn add(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.add_states(
+			"PlainEvaluator::add",
+			&a.state.borrow(),
+			&b.state.borrow(),
+			T::add,
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn add_many(
+		&self,
+		a: &[PlainCiphertext<T>],
+	) -> Result<PlainCiphertext<T>> {
+		let (first, rest) = a
+			.split_first()
+			.ok_or_else(|| Error::InvalidArgument.in_operation("PlainEvaluator::add_many"))?;
+
+		rest.iter()
+			.try_fold(first.state.borrow().clone(), |sum, c| {
+				self.add_states("PlainEvaluator::add_many", &sum, &c.state.borrow(), T::add)
+			})
+			.map(PlainCiphertext::new)
+	}
+
+	fn multiply_many(
+		&self,
+		a: &[PlainCiphertext<T>],
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PlainCiphertext<T>> {
+		let states = a.iter().map(|c| c.state.borrow().clone()).collect();
+
+		self.multiply_many_state("PlainEvaluator::multiply_many", states)
+			.map(PlainCiphertext::new)
+	}
+
+	fn sub_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.sub(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn sub(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.add_states(
+			"PlainEvaluator::sub",
+			&a.state.borrow(),
+			&b.state.borrow(),
+			T::sub,
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn multiply_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.multiply(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn multiply(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.multiply_states(
+			"PlainEvaluator::multiply",
+			&a.state.borrow(),
+			&b.state.borrow(),
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn square_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.square(a)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn square(
+		&self,
+		a: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		let state = a.state.borrow();
+
+		self.multiply_states("PlainEvaluator::square", &state, &state)
+			.map(PlainCiphertext::new)
+	}
+
+	fn mod_switch_to_next(
+		&self,
+		a: &PlainCiphertext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.next_level("PlainEvaluator::mod_switch_to_next", &a.state.borrow())
+			.map(PlainCiphertext::new)
+	}
+
+	fn mod_switch_to_next_inplace(
+		&self,
+		a: &PlainCiphertext<T>,
+	) -> Result<()> {
+		let state = self.next_level(
+			"PlainEvaluator::mod_switch_to_next_inplace",
+			&a.state.borrow(),
+		)?;
+		a.state.replace(state);
+		Ok(())
+	}
+
+	fn mod_switch_to_next_plaintext(
+		&self,
+		a: &PlainPlaintext<T>,
+	) -> Result<PlainPlaintext<T>> {
+		let operation = "PlainEvaluator::mod_switch_to_next_plaintext";
+
+		// BFV plaintexts are not in NTT form, which SEAL requires here.
+		if T::SCHEME == SchemeType::Bfv {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		Ok(PlainPlaintext {
+			state: RefCell::new(self.next_level(operation, &a.state.borrow())?),
+		})
+	}
+
+	fn mod_switch_to_next_inplace_plaintext(
+		&self,
+		a: &PlainPlaintext<T>,
+	) -> Result<()> {
+		let operation = "PlainEvaluator::mod_switch_to_next_inplace_plaintext";
+
+		if T::SCHEME == SchemeType::Bfv {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		let state = self.next_level(operation, &a.state.borrow())?;
+		a.state.replace(state);
+		Ok(())
+	}
+
+	fn exponentiate(
+		&self,
+		a: &PlainCiphertext<T>,
+		exponent: u64,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PlainCiphertext<T>> {
+		let operation = "PlainEvaluator::exponentiate";
+
+		if exponent == 0 {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		self.bfv_only(operation)?;
+
+		multiply_copies(operation, a.state.borrow().clone(), exponent, |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+		.map(PlainCiphertext::new)
+	}
+
+	fn exponentiate_inplace(
+		&self,
+		a: &PlainCiphertext<T>,
+		exponent: u64,
+		relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		let result = self.exponentiate(a, exponent, relin_keys)?;
+		a.state.replace(result.state.into_inner());
+		Ok(())
+	}
+
+	fn add_plain(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.plain_states(
+			"PlainEvaluator::add_plain",
+			&a.state.borrow(),
+			&b.state.borrow(),
+			T::add,
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn add_plain_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<()> {
+		let state = self.add_plain(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn sub_plain(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.plain_states(
+			"PlainEvaluator::sub_plain",
+			&a.state.borrow(),
+			&b.state.borrow(),
+			T::sub,
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn sub_plain_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<()> {
+		let state = self.sub_plain(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn multiply_plain(
+		&self,
+		a: &PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<PlainCiphertext<T>> {
+		self.plain_product(
+			"PlainEvaluator::multiply_plain",
+			&a.state.borrow(),
+			&b.state.borrow(),
+		)
+		.map(PlainCiphertext::new)
+	}
+
+	fn multiply_plain_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		b: &PlainPlaintext<T>,
+	) -> Result<()> {
+		let state = self.multiply_plain(a, b)?.state;
+		a.state = state;
+		Ok(())
+	}
+
+	fn relinearize_inplace(
+		&self,
+		a: &mut PlainCiphertext<T>,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		let state = self.relinearize_state(a.state.get_mut());
+		a.state = RefCell::new(state);
+		Ok(())
+	}
+
+	fn relinearize(
+		&self,
+		a: &PlainCiphertext<T>,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PlainCiphertext<T>> {
+		Ok(PlainCiphertext::new(
+			self.relinearize_state(&a.state.borrow()),
+		))
+	}
+
+	fn rotate_rows(
+		&self,
+		a: &PlainCiphertext<T>,
+		steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<PlainCiphertext<T>> {
+		self.rotate_rows_state("PlainEvaluator::rotate_rows", &a.state.borrow(), steps)
+			.map(PlainCiphertext::new)
+	}
+
+	fn rotate_rows_inplace(
+		&self,
+		a: &PlainCiphertext<T>,
+		steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state = self.rotate_rows_state(
+			"PlainEvaluator::rotate_rows_inplace",
+			&a.state.borrow(),
+			steps,
+		)?;
+		a.state.replace(state);
+		Ok(())
+	}
+
+	fn rotate_columns(
+		&self,
+		a: &PlainCiphertext<T>,
+		_galois_keys: &GaloisKey,
+	) -> Result<PlainCiphertext<T>> {
+		self.rotate_columns_state("PlainEvaluator::rotate_columns", &a.state.borrow())
+			.map(PlainCiphertext::new)
+	}
+
+	fn rotate_columns_inplace(
+		&self,
+		a: &PlainCiphertext<T>,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state =
+			self.rotate_columns_state("PlainEvaluator::rotate_columns_inplace", &a.state.borrow())?;
+		a.state.replace(state);
+		Ok(())
+	}
+}
+
+/// Code written once against [`CircuitEvaluator`], so [`Differential`] can
+/// run it both encrypted and on [`PlainEvaluator`].
+pub trait Computation {
+	/// Computes a ciphertext from the inputs.
+	///  * `evaluator` - The evaluator to compute with.
+	///  * `keys` - The evaluation keys given to [`Differential::new`].
+	///  * `inputs` - The encrypted inputs.
+	///  * `constants` - The plaintext constants.
+	fn compute<E>(
+		&self,
+		evaluator: &E,
+		keys: &CircuitKeys,
+		inputs: &[E::Ciphertext],
+		constants: &[E::Plaintext],
+	) -> Result<E::Ciphertext>
+	where
+		E: CircuitEvaluator;
+}
+
+/// Runs a [`Computation`] on encrypted values and on [`PlainEvaluator`], and
+/// checks that the decrypted result matches the plaintext reference.
+///
+/// The reference runs first, so code the evaluator rejects fails without
+/// touching a ciphertext. Every slot is compared, including the zero slots
+/// past the given values. Mismatches are reported as
+/// [`Error::ResultMismatch`].
+pub struct Differential<'a> {
+	context: &'a Context,
+	public_key: &'a PublicKey,
+	secret_key: &'a SecretKey,
+	keys: CircuitKeys<'a>,
+}
+
+impl<'a> Differential<'a> {
+	/// Creates a Differential for the given context and keys.
+	///  * `context` - The context to encrypt under.
+	///  * `public_key` - The key to encrypt the inputs with.
+	///  * `secret_key` - The key to decrypt the result with.
+	///  * `keys` - The evaluation keys passed to the computation.
+	pub fn new(
+		context: &'a Context,
+		public_key: &'a PublicKey,
+		secret_key: &'a SecretKey,
+		keys: CircuitKeys<'a>,
+	) -> Self {
+		Self {
+			context,
+			public_key,
+			secret_key,
+			keys,
+		}
+	}
+
+	/// Runs `computation` under CKKS and returns the precision of the
+	/// decrypted result against the reference.
+	///  * `computation` - The code to run.
+	///  * `scale` - The scale to encode the inputs and constants at.
+	///  * `inputs` - The values of the inputs, encrypted at the first level.
+	///  * `constants` - The values of the constants, encoded at the first level.
+	///  * `tolerance` - The largest absolute error allowed in any slot.
+	pub fn ckks<C: Computation>(
+		&self,
+		computation: &C,
+		scale: f64,
+		inputs: &[&[f64]],
+		constants: &[&[f64]],
+		tolerance: f64,
+	) -> Result<Precision> {
+		let reference = PlainEvaluator::ckks(self.context, scale)?;
+		let expected = self.reference(computation, &reference, inputs, constants)?;
+
+		let encoder = CKKSEncoder::new(self.context, scale)?;
+		let encryptor = Encryptor::with_public_key(self.context, self.public_key)?;
+		let decryptor = Decryptor::new(self.context, self.secret_key)?;
+		let evaluator = CKKSEvaluator::new(self.context)?;
+
+		let inputs = inputs
+			.iter()
+			.map(|values| encryptor.encrypt(&encoder.encode_f64(values)?))
+			.collect::<Result<Vec<_>>>()?;
+		let constants = constants
+			.iter()
+			.map(|values| encoder.encode_f64(values))
+			.collect::<Result<Vec<_>>>()?;
+
+		let result = computation.compute(&evaluator, &self.keys, &inputs, &constants)?;
+		let actual = encoder.decode_f64(&decryptor.decrypt(&result)?)?;
+
+		compare(&expected, &actual, tolerance)?;
+
+		Precision::between(&expected, &actual)
+	}
+
+	/// Runs `computation` under BFV and returns the decrypted result, which
+	/// must equal the reference exactly.
+	///  * `computation` - The code to run.
+	///  * `inputs` - The values of the inputs, modulo the plain modulus.
+	///  * `constants` - The values of the constants, modulo the plain modulus.
+	pub fn bfv<C: Computation>(
+		&self,
+		computation: &C,
+		inputs: &[&[u64]],
+		constants: &[&[u64]],
+	) -> Result<Vec<u64>> {
+		let reference = PlainEvaluator::bfv(self.context)?;
+		let expected = self.reference(computation, &reference, inputs, constants)?;
+
+		let encoder = BFVEncoder::new(self.context)?;
+		let encryptor = Encryptor::with_public_key(self.context, self.public_key)?;
+		let decryptor = Decryptor::new(self.context, self.secret_key)?;
+		let evaluator = BFVEvaluator::new(self.context)?;
+
+		let inputs = inputs
+			.iter()
+			.map(|values| encryptor.encrypt(&encoder.encode_u64(values)?))
+			.collect::<Result<Vec<_>>>()?;
+		let constants = constants
+			.iter()
+			.map(|values| encoder.encode_u64(values))
+			.collect::<Result<Vec<_>>>()?;
+
+		let result = computation.compute(&evaluator, &self.keys, &inputs, &constants)?;
+		let actual = encoder.decode_u64(&decryptor.decrypt(&result)?)?;
+
+		compare(&expected, &actual, 0.0)?;
+
+		Ok(actual)
+	}
+
+	fn reference<C, T>(
+		&self,
+		computation: &C,
+		evaluator: &PlainEvaluator<T>,
+		inputs: &[&[T]],
+		constants: &[&[T]],
+	) -> Result<Vec<T>>
+	where
+		C: Computation,
+		T: PlainSlot,
+		PlainEvaluator<T>:
+			CircuitEvaluator<Ciphertext = PlainCiphertext<T>, Plaintext = PlainPlaintext<T>>,
+	{
+		let inputs = inputs
+			.iter()
+			.map(|values| evaluator.encrypt(values))
+			.collect::<Result<Vec<_>>>()?;
+		let constants = constants
+			.iter()
+			.map(|values| evaluator.plaintext(values))
+			.collect::<Result<Vec<_>>>()?;
+
+		let result = computation.compute(evaluator, &self.keys, &inputs, &constants)?;
+
+		Ok(result.values())
+	}
+}
+
+/// Fails with [`Error::ResultMismatch`] at the first slot where `actual`
+/// is further than `tolerance` from `expected`.
+fn compare<T: PlainSlot>(
+	expected: &[T],
+	actual: &[T],
+	tolerance: f64,
+) -> Result<()> {
+	if expected.len() != actual.len() {
+		return Err(Error::ShapeMismatch(Box::new(ShapeMismatch {
+			left: vec![expected.len()],
+			right: vec![actual.len()],
+		})));
+	}
+
+	let mismatch = expected
+		.iter()
+		.zip(actual)
+		.map(|(e, a)| (e.to_f64(), a.to_f64()))
+		.enumerate()
+		.find(|(_, (e, a))| {
+			let error = (e - a).abs();
+
+			error > tolerance || error.is_nan()
+		});
+
+	match mismatch {
+		Some((slot, (expected, actual))) => Err(Error::ResultMismatch(Box::new(ResultMismatch {
+			slot,
+			expected,
+			actual,
+			tolerance,
+		}))),
+		None => Ok(()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn bfv_context() -> Context {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	fn ckks_context() -> Context {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	/// ((x + y) * x) rotated by one slot, times a constant.
+	struct Polynomial;
+
+	impl Computation for Polynomial {
+		fn compute<E>(
+			&self,
+			evaluator: &E,
+			keys: &CircuitKeys,
+			inputs: &[E::Ciphertext],
+			constants: &[E::Plaintext],
+		) -> Result<E::Ciphertext>
+		where
+			E: CircuitEvaluator,
+		{
+			let relin_keys = keys.relinearization.ok_or(Error::InvalidArgument)?;
+			let galois_keys = keys.galois.ok_or(Error::InvalidArgument)?;
+
+			let sum = evaluator.add(&inputs[0], &inputs[1])?;
+			let product = evaluator.multiply(&sum, &inputs[0])?;
+			let product = evaluator.relinearize(&product, relin_keys)?;
+			let rotated = evaluator.rotate_slots(&product, 1, galois_keys)?;
+
+			evaluator.multiply_plain(&rotated, &constants[0])
+		}
+	}
+
+	#[test]
+	fn plain_bfv_wraps_and_rotates() {
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let evaluator = PlainEvaluator::bfv(&ctx).unwrap();
+		let t = ctx
+			.get_encryption_parameters()
+			.unwrap()
+			.get_plain_modulus()
+			.unwrap()
+			.value()
+			.unwrap();
+
+		assert_eq!(evaluator.slot_count(), 8192);
+		assert!(evaluator.encrypt(&[t]).is_err());
+
+		let a = evaluator.encrypt(&[1, 2, t - 1]).unwrap();
+		let b = evaluator.encrypt(&[t - 1, 3]).unwrap();
+
+		let sum = evaluator.add(&a, &b).unwrap().values();
+		assert_eq!(&sum[..3], &[0, 5, t - 1]);
+
+		let neg = evaluator.negate(&a).unwrap().values();
+		assert_eq!(&neg[..4], &[t - 1, t - 2, 1, 0]);
+
+		let product = evaluator.multiply(&a, &b).unwrap();
+		assert_eq!(product.size(), 3);
+		assert_eq!(&product.values()[..3], &[t - 1, 6, 0]);
+
+		// Rotations need a relinearized ciphertext and move each row.
+		assert!(evaluator.rotate_rows(&product, 1, &galois_keys).is_err());
+
+		let rotated = evaluator
+			.rotate_rows(
+				&evaluator.relinearize(&product, &relin_keys).unwrap(),
+				1,
+				&galois_keys,
+			)
+			.unwrap()
+			.values();
+		assert_eq!(&rotated[..2], &[6, 0]);
+		assert_eq!(rotated[4095], t - 1);
+
+		let cubed = evaluator
+			.multiply_many(&[a.clone(), a.clone(), a.clone()], &relin_keys)
+			.unwrap();
+		assert_eq!(&cubed.values()[..2], &[1, 8]);
+
+		let cubed = evaluator.exponentiate(&a, 3, &relin_keys).unwrap();
+		assert_eq!(&cubed.values()[..2], &[1, 8]);
+
+		// One copy of the operand per unit of the exponent would never fit in
+		// memory.
+		let power = evaluator.exponentiate(&a, u64::MAX, &relin_keys).unwrap();
+		let expected = (0..64).fold(1u128, |acc, _| acc * acc % t as u128 * 2 % t as u128);
+		assert_eq!(power.size(), 2);
+		assert_eq!(&power.values()[..2], &[1, expected as u64]);
+
+		let err = evaluator.multiply_many(&[], &relin_keys).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidArgument);
+		assert_eq!(err.operation(), Some("PlainEvaluator::multiply_many"));
+
+		let swapped = evaluator.rotate_columns(&a, &galois_keys).unwrap().values();
+		assert_eq!(&swapped[4096..4099], &[1, 2, t - 1]);
+
+		let lower = evaluator.mod_switch_to_next(&a).unwrap();
+		assert!(matches!(
+			evaluator.add(&lower, &a),
+			Err(Error::ParmsMismatch(_))
+		));
+
+		let p = evaluator.plaintext(&[5]).unwrap();
+		assert_eq!(evaluator.add_plain(&lower, &p).unwrap().values()[0], 6);
+	}
+
+	#[test]
+	fn plain_ckks_rejects_what_seal_rejects() {
+		let ctx = ckks_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let scale = 2.0f64.powi(40);
+		let evaluator = PlainEvaluator::ckks(&ctx, scale).unwrap();
+		assert!(PlainEvaluator::bfv(&ctx).is_err());
+		assert_eq!(evaluator.slot_count(), 4096);
+
+		let a = evaluator.encrypt(&[1.5, -2.0]).unwrap();
+		assert_eq!(a.scale(), scale);
+
+		let squared = evaluator.square(&a).unwrap();
+		assert_eq!(squared.scale(), scale * scale);
+
+		let squared = evaluator.relinearize(&squared, &relin_keys).unwrap();
+		let squared = evaluator.rescale_to_next(&squared).unwrap();
+
+		assert_eq!(&squared.values()[..2], &[2.25, 4.0]);
+		assert_eq!(squared.level(), 1);
+
+		// The primes are close to the scale, but not equal to it.
+		assert!(squared.scale() != scale && (squared.scale() / scale - 1.0).abs() < 1e-3);
+
+		let lower = evaluator.mod_switch_to_next(&a).unwrap();
+		assert!(matches!(
+			evaluator.add(&lower, &squared),
+			Err(Error::ScaleMismatch(_))
+		));
+
+		let rotated = evaluator
+			.rotate_vector(&squared, -1, &galois_keys)
+			.unwrap()
+			.values();
+		assert_eq!(&rotated[..3], &[0.0, 2.25, 4.0]);
+
+		let err = evaluator.rotate_rows(&a, 1, &galois_keys).unwrap_err();
+		assert_eq!(*err.kind(), Error::InvalidOperation);
+		assert_eq!(err.operation(), Some("PlainEvaluator::rotate_rows"));
+
+		assert!(matches!(
+			evaluator.add(&a, &squared),
+			Err(Error::ParmsMismatch(_))
+		));
+
+		let p = evaluator.plaintext(&[1.0]).unwrap();
+		assert!(matches!(
+			evaluator.add_plain(&squared, &p),
+			Err(Error::ParmsMismatch(_))
+		));
+
+		let last = evaluator.rescale_to_next(&squared).unwrap();
+		assert!(evaluator.rescale_to_next(&last).is_err());
+	}
+
+	/// x squared and rescaled, plus x switched to the same level: the levels
+	/// match, the scales do not.
+	struct MismatchedSum;
+
+	impl Computation for MismatchedSum {
+		fn compute<E>(
+			&self,
+			evaluator: &E,
+			keys: &CircuitKeys,
+			inputs: &[E::Ciphertext],
+			_constants: &[E::Plaintext],
+		) -> Result<E::Ciphertext>
+		where
+			E: CircuitEvaluator,
+		{
+			let relin_keys = keys.relinearization.ok_or(Error::InvalidArgument)?;
+
+			let squared = evaluator.square(&inputs[0])?;
+			let squared = evaluator.relinearize(&squared, relin_keys)?;
+			let squared = evaluator.rescale_to_next(&squared)?;
+			let lower = evaluator.mod_switch_to_next(&inputs[0])?;
+
+			evaluator.add(&squared, &lower)
+		}
+	}
+
+	#[test]
+	fn both_backends_reject_scale_mismatch() {
+		let ctx = ckks_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			..Default::default()
+		};
+		let scale = 2.0f64.powi(40);
+		let x = [1.0, 2.0, 3.0];
+
+		let plain = PlainEvaluator::ckks(&ctx, scale).unwrap();
+		let inputs = [plain.encrypt(&x).unwrap()];
+		let plain_err = MismatchedSum
+			.compute(&plain, &keys, &inputs, &[])
+			.unwrap_err();
+
+		let encoder = CKKSEncoder::new(&ctx, scale).unwrap();
+		let encryptor = Encryptor::with_public_key(&ctx, &public_key).unwrap();
+		let evaluator = CKKSEvaluator::new(&ctx).unwrap();
+		let inputs = [encryptor.encrypt(&encoder.encode_f64(&x).unwrap()).unwrap()];
+		let seal_err = MismatchedSum
+			.compute(&evaluator, &keys, &inputs, &[])
+			.unwrap_err();
+
+		// SEAL divides and multiplies scales the same way, so both backends
+		// see the same two scales.
+		match (plain_err, seal_err) {
+			(Error::ScaleMismatch(plain), Error::ScaleMismatch(seal)) => {
+				assert_eq!((plain.left, plain.right), (seal.left, seal.right));
+			}
+			errors => panic!("expected two scale mismatches, got {:?}", errors),
+		}
+
+		let differential = Differential::new(&ctx, &public_key, &secret_key, keys);
+		assert!(matches!(
+			differential.ckks(&MismatchedSum, scale, &[&x], &[], 1e-3),
+			Err(Error::ScaleMismatch(_))
+		));
+	}
+
+	#[test]
+	fn differential_runs_agree() {
+		let ctx = ckks_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+		let differential = Differential::new(&ctx, &public_key, &secret_key, keys);
+
+		let x = (0..4096)
+			.map(|i| (i % 16) as f64 / 4.0 - 2.0)
+			.collect::<Vec<_>>();
+		let y = (0..4096).map(|i| (i % 7) as f64 / 2.0).collect::<Vec<_>>();
+		let c = vec![0.5; 4096];
+
+		let precision = differential
+			.ckks(&Polynomial, 2.0f64.powi(40), &[&x, &y], &[&c], 1e-3)
+			.unwrap();
+		assert!(precision.bits > 10.0);
+
+		// Encoding is not exact, so nothing is within a zero tolerance.
+		assert!(matches!(
+			differential.ckks(&Polynomial, 2.0f64.powi(40), &[&x, &y], &[&c], 0.0),
+			Err(Error::ResultMismatch(_))
+		));
+
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+		let differential = Differential::new(&ctx, &public_key, &secret_key, keys);
+
+		// Large enough values to wrap around the plain modulus.
+		let x = (0..8192).map(|i| i * 97 % 1000).collect::<Vec<u64>>();
+		let y = (0..8192).map(|i| i * 31 % 5000).collect::<Vec<u64>>();
+		let c = vec![3; 8192];
+
+		let result = differential.bfv(&Polynomial, &[&x, &y], &[&c]).unwrap();
+		assert_eq!(result.len(), 8192);
+	}
+}
@@ -0,0 +1,1071 @@
+use std::cell::{Cell, RefCell};
+
+use crate::error::*;
+use crate::evaluator::base::are_close;
+use crate::evaluator::estimate::{
+	log_add, multiply_copies, multiply_pairwise, Level, NOISE_MAX_DEVIATION,
+};
+use crate::{Context, Evaluator, GaloisKey, RelinearizationKey, SchemeType};
+
+/// Slack, in bits, added to the error of encryption and key switching.
+const SLACK_BITS: f64 = 3.0;
+
+/// The estimated state of a CKKS ciphertext or plaintext.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct State {
+	/// log2 of the largest absolute error of a slot.
+	log_error: f64,
+	/// log2 of the largest absolute value of a slot.
+	log_magnitude: f64,
+	/// log2 of the scale.
+	log_scale: f64,
+	/// The number of polynomials; 1 for plaintexts.
+	size: usize,
+	/// The position in the estimator's chain; 0 is the first data level.
+	level: usize,
+}
+
+impl State {
+	fn precision_bits(&self) -> f64 {
+		-self.log_error
+	}
+}
+
+/// The estimated error of a CKKS ciphertext, produced and consumed by
+/// [`PrecisionEstimator`].
+#[derive(Debug, Clone)]
+pub struct PrecisionCiphertext {
+	state: Cell<State>,
+}
+
+impl PrecisionCiphertext {
+	fn new(state: State) -> Self {
+		Self {
+			state: Cell::new(state),
+		}
+	}
+
+	/// Returns the predicted bits of precision after the binary point, to be
+	/// compared with [`crate::Precision::bits`].
+	pub fn precision_bits(&self) -> f64 {
+		self.state.get().precision_bits()
+	}
+
+	/// Returns the scale the ciphertext would have.
+	pub fn scale(&self) -> f64 {
+		self.state.get().log_scale.exp2()
+	}
+
+	/// Returns the number of polynomials the ciphertext would have.
+	pub fn size(&self) -> usize {
+		self.state.get().size
+	}
+
+	/// Returns how many times the ciphertext was rescaled or switched down
+	/// the modulus chain since it was encrypted.
+	pub fn level(&self) -> usize {
+		self.state.get().level
+	}
+}
+
+/// The estimated error of an encoded CKKS plaintext, produced by
+/// [`PrecisionEstimator::plaintext`] or [`PrecisionEstimator::plaintext_for`].
+#[derive(Debug, Clone)]
+pub struct PrecisionPlaintext {
+	state: Cell<State>,
+}
+
+impl PrecisionPlaintext {
+	/// Returns the scale the plaintext would have.
+	pub fn scale(&self) -> f64 {
+		self.state.get().log_scale.exp2()
+	}
+}
+
+/// The precision left after one operation, as recorded by
+/// [`PrecisionEstimator::history`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PrecisionStep {
+	/// The name of the operation.
+	pub operation: &'static str,
+	/// The predicted bits of precision of the result.
+	pub bits: f64,
+	/// The bits of precision the operation lost relative to its least
+	/// precise input.
+	pub loss: f64,
+}
+
+/// Predicts the error CKKS operations introduce without running them.
+///
+/// The estimator implements [`Evaluator`] over [`PrecisionCiphertext`] and
+/// [`PrecisionPlaintext`], plus [`PrecisionEstimator::rescale_to_next`], so a
+/// circuit written against the trait can be run on it to see how many bits of
+/// precision its result keeps for a given scale and coefficient modulus. Error
+/// is tracked in the decoded slots with heuristic bounds, padded so the
+/// prediction stays at or below what [`crate::Decryptor::measure_precision`]
+/// reports. Each operation is recorded in [`PrecisionEstimator::history`].
+///
+/// As SEAL does, the estimator rejects operands at different levels or
+/// scales and scales that outgrow the coefficient modulus. Keys passed to it
+/// are not inspected.
+pub struct PrecisionEstimator {
+	levels: Vec<Level>,
+	log_degree: f64,
+	log_scale: f64,
+	history: RefCell<Vec<PrecisionStep>>,
+}
+
+impl PrecisionEstimator {
+	/// Creates a PrecisionEstimator for the CKKS parameters of the given
+	/// context, encoding at the given scale as [`crate::CKKSEncoder`] does.
+	/// Returns [`Error::InvalidOperation`] for other schemes.
+	///  * `ctx` - The context.
+	///  * `scale` - The scale values are encoded at.
+	pub fn new(
+		ctx: &Context,
+		scale: f64,
+	) -> Result<Self> {
+		let parms = ctx.get_encryption_parameters()?;
+
+		if parms.get_scheme()? != SchemeType::Ckks {
+			return Err(Error::InvalidOperation.in_operation("PrecisionEstimator::new"));
+		}
+
+		let levels = Level::chain(ctx)?;
+
+		Ok(Self {
+			levels,
+			log_degree: (parms.get_poly_modulus_degree()? as f64).log2(),
+			log_scale: scale.log2(),
+			history: RefCell::new(vec![]),
+		})
+	}
+
+	/// Returns the operations run so far with the precision each one left.
+	pub fn history(&self) -> Vec<PrecisionStep> {
+		self.history.borrow().clone()
+	}
+
+	/// Forgets the recorded operations.
+	pub fn clear_history(&self) {
+		self.history.borrow_mut().clear();
+	}
+
+	/// Returns a freshly encrypted ciphertext at the first data level, as
+	/// produced by encoding and encrypting values with the public key.
+	///  * `magnitude` - The largest absolute value the ciphertext will hold.
+	pub fn encrypt(
+		&self,
+		magnitude: f64,
+	) -> PrecisionCiphertext {
+		// A fresh ciphertext carries e * u + e1 + e2 * s, whose coefficients
+		// are sums of about n errors each; decoding sums n coefficients again.
+		let log_error = SLACK_BITS + NOISE_MAX_DEVIATION.log2() + self.log_degree;
+		let plaintext = self.encode(magnitude, 0, self.log_scale);
+
+		PrecisionCiphertext::new(State {
+			log_error: log_add(plaintext.log_error, log_error - self.log_scale),
+			size: 2,
+			..plaintext
+		})
+	}
+
+	/// Returns a plaintext encoded at the first data level and the default
+	/// scale.
+	///  * `magnitude` - The largest absolute value the plaintext will hold.
+	pub fn plaintext(
+		&self,
+		magnitude: f64,
+	) -> PrecisionPlaintext {
+		PrecisionPlaintext {
+			state: Cell::new(self.encode(magnitude, 0, self.log_scale)),
+		}
+	}
+
+	/// Returns a plaintext encoded at the level and scale of `ciphertext`, as
+	/// [`crate::CKKSEncoder::encode_f64_at`] would encode it to match.
+	///  * `magnitude` - The largest absolute value the plaintext will hold.
+	///  * `ciphertext` - The ciphertext the plaintext will be combined with.
+	pub fn plaintext_for(
+		&self,
+		magnitude: f64,
+		ciphertext: &PrecisionCiphertext,
+	) -> PrecisionPlaintext {
+		let state = ciphertext.state.get();
+
+		PrecisionPlaintext {
+			state: Cell::new(self.encode(magnitude, state.level, state.log_scale)),
+		}
+	}
+
+	/// Rescales `a` to the next level, dividing its scale by the last prime
+	/// of its level.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		let state = self.rescale_state("PrecisionEstimator::rescale_to_next", &a.state.get())?;
+
+		Ok(PrecisionCiphertext::new(state))
+	}
+
+	/// Rescales `a` to the next level, in place.
+	///  * `a` - The ciphertext to rescale.
+	pub fn rescale_to_next_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+	) -> Result<()> {
+		let state = self.rescale_state(
+			"PrecisionEstimator::rescale_to_next_inplace",
+			&a.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	/// Returns `a` with its scale overridden by `scale`, which multiplies
+	/// what it decrypts to by the old scale over the new one. The change is
+	/// counted as error, since the value was meant to stay the same.
+	pub(crate) fn set_scale(
+		&self,
+		a: &PrecisionCiphertext,
+		scale: f64,
+	) -> Result<PrecisionCiphertext> {
+		let state = a.state.get();
+		let log_scale = scale.log2();
+		let log_ratio = state.log_scale - log_scale;
+		let log_shift = state.log_magnitude + (log_ratio.exp2() - 1.0).abs().log2();
+
+		let result = State {
+			log_error: log_add(state.log_error + log_ratio, log_shift),
+			log_magnitude: state.log_magnitude + log_ratio,
+			log_scale,
+			..state
+		};
+
+		self.finish("PrecisionEstimator::set_scale", &[&state], result)
+			.map(PrecisionCiphertext::new)
+	}
+
+	/// Returns `a` multiplied by ones encoded at its level and at the ratio
+	/// of `scale` to its scale, which brings it to `scale`.
+	pub(crate) fn raise_scale(
+		&self,
+		a: &PrecisionCiphertext,
+		scale: f64,
+	) -> Result<PrecisionCiphertext> {
+		let state = a.state.get();
+		let log_scale = scale.log2();
+		let log_ratio = log_scale - state.log_scale;
+
+		// Ones encode to a single coefficient, rounded by at most 1/2.
+		let ones = State {
+			log_error: -1.0 - log_ratio,
+			log_magnitude: 0.0,
+			log_scale: log_ratio,
+			size: 1,
+			level: state.level,
+		};
+		let product = self.multiply_states("PrecisionEstimator::raise_scale", &state, &ones)?;
+
+		Ok(PrecisionCiphertext::new(State {
+			log_scale,
+			..product
+		}))
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`, as
+	/// [`crate::CKKSEvaluator::rotate_vector`] does.
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by.
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector(
+		&self,
+		a: &PrecisionCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<PrecisionCiphertext> {
+		self.rotate_state("PrecisionEstimator::rotate_vector", &a.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	/// Rotates the slots of `a` cyclically by `steps`, in place.
+	///  * `a` - The ciphertext to rotate.
+	///  * `steps` - The number of slots to rotate by.
+	///  * `galois_keys` - The Galois keys.
+	pub fn rotate_vector_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state =
+			self.rotate_state("PrecisionEstimator::rotate_vector_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	/// Returns the state of values encoded at the given level and scale.
+	fn encode(
+		&self,
+		magnitude: f64,
+		level: usize,
+		log_scale: f64,
+	) -> State {
+		// Rounding each coefficient by at most 1/2 moves a slot by at most n/2.
+		State {
+			log_error: self.log_degree - 1.0 - log_scale,
+			log_magnitude: magnitude.abs().log2(),
+			log_scale,
+			size: 1,
+			level,
+		}
+	}
+
+	/// Records the operation and checks the result against the modulus of
+	/// its level.
+	fn finish(
+		&self,
+		operation: &'static str,
+		inputs: &[&State],
+		state: State,
+	) -> Result<State> {
+		let level = &self.levels[state.level];
+
+		if state.log_scale >= level.log_q {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		let mut state = state;
+
+		// Values that outgrow the modulus wrap around and keep no precision.
+		if state.log_magnitude + state.log_scale >= level.log_q - 1.0 {
+			state.log_error = state.log_error.max(state.log_magnitude);
+		}
+
+		let before = inputs
+			.iter()
+			.map(|s| s.precision_bits())
+			.fold(f64::INFINITY, f64::min);
+
+		self.history.borrow_mut().push(PrecisionStep {
+			operation,
+			bits: state.precision_bits(),
+			loss: before - state.precision_bits(),
+		});
+
+		Ok(state)
+	}
+
+	fn same_level(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<()> {
+		if a.level != b.level {
+			return Err(Error::ParmsMismatch(Box::new(ParmsMismatch {
+				operation,
+				left: self.levels[a.level].parms_id.clone(),
+				right: self.levels[b.level].parms_id.clone(),
+			})));
+		}
+
+		Ok(())
+	}
+
+	fn add_states(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		self.same_level(operation, a, b)?;
+
+		if !are_close(a.log_scale.exp2(), b.log_scale.exp2()) {
+			return Err(Error::ScaleMismatch(Box::new(ScaleMismatch {
+				operation,
+				left: a.log_scale.exp2(),
+				right: b.log_scale.exp2(),
+			})));
+		}
+
+		let state = State {
+			log_error: log_add(a.log_error, b.log_error),
+			log_magnitude: log_add(a.log_magnitude, b.log_magnitude),
+			log_scale: a.log_scale,
+			size: a.size.max(b.size),
+			level: a.level,
+		};
+
+		self.finish(operation, &[a, b], state)
+	}
+
+	fn multiply_states(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		self.same_level(operation, a, b)?;
+
+		// (x + e_x)(y + e_y) = xy + x e_y + y e_x + e_x e_y, slot by slot.
+		let log_error = log_add(
+			log_add(a.log_magnitude + b.log_error, b.log_magnitude + a.log_error),
+			a.log_error + b.log_error,
+		);
+
+		let state = State {
+			log_error,
+			log_magnitude: a.log_magnitude + b.log_magnitude,
+			log_scale: a.log_scale + b.log_scale,
+			size: a.size + b.size - 1,
+			level: a.level,
+		};
+
+		self.finish(operation, &[a, b], state)
+	}
+
+	/// Adds the error of switching the key of a size 2 ciphertext, as done by
+	/// relinearization and rotations.
+	fn key_switch_state(
+		&self,
+		state: &State,
+	) -> State {
+		let level = &self.levels[state.level];
+		let log_error = SLACK_BITS
+			+ NOISE_MAX_DEVIATION.log2()
+			+ self.log_degree
+			+ (level.coeff_modulus_size as f64).log2() / 2.0;
+
+		State {
+			log_error: log_add(state.log_error, log_error - state.log_scale),
+			size: 2,
+			..*state
+		}
+	}
+
+	fn relinearize_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		// Each extra polynomial is folded in by its own key switch.
+		let relinearized = (2..state.size).fold(*state, |s, _| self.key_switch_state(&s));
+
+		self.finish(operation, &[state], relinearized)
+	}
+
+	fn rotate_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		if state.size != 2 {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		self.finish(operation, &[state], self.key_switch_state(state))
+	}
+
+	fn next_level(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<usize> {
+		let level = state.level + 1;
+
+		if level >= self.levels.len() {
+			return Err(Error::InvalidArgument.in_operation(operation));
+		}
+
+		Ok(level)
+	}
+
+	fn mod_switch_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		// Dropping a prime without dividing by it is exact.
+		let level = self.next_level(operation, state)?;
+
+		self.finish(
+			operation,
+			&[state],
+			State {
+				level,
+				..*state
+			},
+		)
+	}
+
+	fn rescale_state(
+		&self,
+		operation: &'static str,
+		state: &State,
+	) -> Result<State> {
+		let level = self.next_level(operation, state)?;
+		let log_scale = state.log_scale - self.levels[state.level].log_last_prime;
+
+		// Dividing rounds each coefficient, adding about (1 + |s|) / 2 to it.
+		let log_rounding = 2.0 + self.log_degree - log_scale;
+
+		self.finish(
+			operation,
+			&[state],
+			State {
+				log_error: log_add(state.log_error, log_rounding),
+				log_scale,
+				level,
+				..*state
+			},
+		)
+	}
+
+	fn multiply_many_state(
+		&self,
+		operation: &'static str,
+		a: &[State],
+	) -> Result<State> {
+		multiply_pairwise(operation, a.iter().copied(), |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+	}
+
+	/// Multiplies and relinearizes, one step of `multiply_many`.
+	fn multiply_relinearize(
+		&self,
+		operation: &'static str,
+		a: &State,
+		b: &State,
+	) -> Result<State> {
+		let product = self.multiply_states(operation, a, b)?;
+
+		self.relinearize_state(operation, &product)
+	}
+}
+
+impl Evaluator for PrecisionEstimator {
+	type Plaintext = PrecisionPlaintext;
+	type Ciphertext = PrecisionCiphertext;
+
+	fn negate_inplace(
+		&self,
+		_a: &mut PrecisionCiphertext,
+	) -> Result<()> {
+		Ok(())
+	}
+
+	fn negate(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		Ok(a.clone())
+	}
+
+	fn add_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"PrecisionEstimator::add_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn add(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		self.add_states("PrecisionEstimator::add", &a.state.get(), &b.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn add_many(
+		&self,
+		a: &[PrecisionCiphertext],
+	) -> Result<PrecisionCiphertext> {
+		let (first, rest) = a
+			.split_first()
+			.ok_or_else(|| Error::InvalidArgument.in_operation("PrecisionEstimator::add_many"))?;
+
+		rest.iter()
+			.try_fold(first.state.get(), |sum, c| {
+				self.add_states("PrecisionEstimator::add_many", &sum, &c.state.get())
+			})
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn multiply_many(
+		&self,
+		a: &[PrecisionCiphertext],
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PrecisionCiphertext> {
+		let states: Vec<State> = a.iter().map(|c| c.state.get()).collect();
+
+		self.multiply_many_state("PrecisionEstimator::multiply_many", &states)
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn sub_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"PrecisionEstimator::sub_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn sub(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		self.add_states("PrecisionEstimator::sub", &a.state.get(), &b.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn multiply_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<()> {
+		let state = self.multiply_states(
+			"PrecisionEstimator::multiply_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn multiply(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		self.multiply_states(
+			"PrecisionEstimator::multiply",
+			&a.state.get(),
+			&b.state.get(),
+		)
+		.map(PrecisionCiphertext::new)
+	}
+
+	fn square_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+	) -> Result<()> {
+		let state = a.state.get();
+		let state = self.multiply_states("PrecisionEstimator::square_inplace", &state, &state)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn square(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		let state = a.state.get();
+
+		self.multiply_states("PrecisionEstimator::square", &state, &state)
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn mod_switch_to_next(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		self.mod_switch_state("PrecisionEstimator::mod_switch_to_next", &a.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn mod_switch_to_next_inplace(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<()> {
+		let state = self.mod_switch_state(
+			"PrecisionEstimator::mod_switch_to_next_inplace",
+			&a.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn mod_switch_to_next_plaintext(
+		&self,
+		a: &PrecisionPlaintext,
+	) -> Result<PrecisionPlaintext> {
+		let state = self.mod_switch_state(
+			"PrecisionEstimator::mod_switch_to_next_plaintext",
+			&a.state.get(),
+		)?;
+
+		Ok(PrecisionPlaintext {
+			state: Cell::new(state),
+		})
+	}
+
+	fn mod_switch_to_next_inplace_plaintext(
+		&self,
+		a: &PrecisionPlaintext,
+	) -> Result<()> {
+		let state = self.mod_switch_state(
+			"PrecisionEstimator::mod_switch_to_next_inplace_plaintext",
+			&a.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn exponentiate(
+		&self,
+		a: &PrecisionCiphertext,
+		exponent: u64,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PrecisionCiphertext> {
+		let operation = "PrecisionEstimator::exponentiate";
+
+		multiply_copies(operation, a.state.get(), exponent, |x, y| {
+			self.multiply_relinearize(operation, &x, &y)
+		})
+		.map(PrecisionCiphertext::new)
+	}
+
+	fn exponentiate_inplace(
+		&self,
+		a: &PrecisionCiphertext,
+		exponent: u64,
+		relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		let result = self.exponentiate(a, exponent, relin_keys)?;
+		a.state.set(result.state.get());
+		Ok(())
+	}
+
+	fn add_plain(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<PrecisionCiphertext> {
+		self.add_states(
+			"PrecisionEstimator::add_plain",
+			&a.state.get(),
+			&b.state.get(),
+		)
+		.map(PrecisionCiphertext::new)
+	}
+
+	fn add_plain_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"PrecisionEstimator::add_plain_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn sub_plain(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<PrecisionCiphertext> {
+		self.add_states(
+			"PrecisionEstimator::sub_plain",
+			&a.state.get(),
+			&b.state.get(),
+		)
+		.map(PrecisionCiphertext::new)
+	}
+
+	fn sub_plain_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<()> {
+		let state = self.add_states(
+			"PrecisionEstimator::sub_plain_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn multiply_plain(
+		&self,
+		a: &PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<PrecisionCiphertext> {
+		self.multiply_states(
+			"PrecisionEstimator::multiply_plain",
+			&a.state.get(),
+			&b.state.get(),
+		)
+		.map(PrecisionCiphertext::new)
+	}
+
+	fn multiply_plain_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		b: &PrecisionPlaintext,
+	) -> Result<()> {
+		let state = self.multiply_states(
+			"PrecisionEstimator::multiply_plain_inplace",
+			&a.state.get(),
+			&b.state.get(),
+		)?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn relinearize_inplace(
+		&self,
+		a: &mut PrecisionCiphertext,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<()> {
+		let state =
+			self.relinearize_state("PrecisionEstimator::relinearize_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn relinearize(
+		&self,
+		a: &PrecisionCiphertext,
+		_relin_keys: &RelinearizationKey,
+	) -> Result<PrecisionCiphertext> {
+		self.relinearize_state("PrecisionEstimator::relinearize", &a.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn rotate_rows(
+		&self,
+		a: &PrecisionCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<PrecisionCiphertext> {
+		self.rotate_state("PrecisionEstimator::rotate_rows", &a.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn rotate_rows_inplace(
+		&self,
+		a: &PrecisionCiphertext,
+		_steps: i32,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state = self.rotate_state("PrecisionEstimator::rotate_rows_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+
+	fn rotate_columns(
+		&self,
+		a: &PrecisionCiphertext,
+		_galois_keys: &GaloisKey,
+	) -> Result<PrecisionCiphertext> {
+		self.rotate_state("PrecisionEstimator::rotate_columns", &a.state.get())
+			.map(PrecisionCiphertext::new)
+	}
+
+	fn rotate_columns_inplace(
+		&self,
+		a: &PrecisionCiphertext,
+		_galois_keys: &GaloisKey,
+	) -> Result<()> {
+		let state =
+			self.rotate_state("PrecisionEstimator::rotate_columns_inplace", &a.state.get())?;
+		a.state.set(state);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::*;
+
+	fn run_precision_test<F>(test: F)
+	where
+		F: FnOnce(
+			PrecisionEstimator,
+			Decryptor,
+			CKKSEncoder,
+			Encryptor<SymAsym>,
+			CKKSEvaluator,
+			KeyGenerator,
+		),
+	{
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let scale = 2.0f64.powi(40);
+		let encoder = CKKSEncoder::new(&ctx, scale).unwrap();
+
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+
+		let encryptor =
+			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
+		let decryptor = Decryptor::new(&ctx, &secret_key).unwrap();
+		let evaluator = CKKSEvaluator::new(&ctx).unwrap();
+		let estimator = PrecisionEstimator::new(&ctx, scale).unwrap();
+
+		test(estimator, decryptor, encoder, encryptor, evaluator, gen);
+	}
+
+	fn make_vec(encoder: &CKKSEncoder) -> Vec<f64> {
+		(0..encoder.get_slot_count())
+			.map(|i| (i % 64) as f64 / 8.0 - 4.0)
+			.collect()
+	}
+
+	#[test]
+	fn circuit_estimate_is_conservative() {
+		run_precision_test(
+			|estimator, decryptor, encoder, encryptor, evaluator, keygen| {
+				let relin_keys = keygen.create_relinearization_keys().unwrap();
+				let galois_keys = keygen.create_galois_keys().unwrap();
+
+				let a = make_vec(&encoder);
+				let a_c = encryptor.encrypt(&encoder.encode_f64(&a).unwrap()).unwrap();
+				let a_e = estimator.encrypt(4.0);
+
+				let check = |c: &Ciphertext, c_e: &PrecisionCiphertext, expected: &[f64]| {
+					let actual = decryptor.measure_precision(&encoder, c, expected).unwrap();
+					assert!(
+						c_e.precision_bits() <= actual.bits,
+						"{} > {}",
+						c_e.precision_bits(),
+						actual.bits
+					);
+				};
+
+				check(&a_c, &a_e, &a);
+
+				let squared: Vec<f64> = a.iter().map(|x| x * x).collect();
+				let b_c = evaluator.square(&a_c).unwrap();
+				let b_c = evaluator.relinearize(&b_c, &relin_keys).unwrap();
+				let b_c = evaluator.rescale_to_next(&b_c).unwrap();
+				let b_e = estimator.square(&a_e).unwrap();
+				let b_e = estimator.relinearize(&b_e, &relin_keys).unwrap();
+				let b_e = estimator.rescale_to_next(&b_e).unwrap();
+				check(&b_c, &b_e, &squared);
+				assert!((b_c.scale().unwrap() / b_e.scale() - 1.0).abs() < 1e-9);
+
+				let rotated: Vec<f64> = (0..squared.len())
+					.map(|i| squared[(i + 1) % squared.len()])
+					.collect();
+				let c_c = evaluator.rotate_vector(&b_c, 1, &galois_keys).unwrap();
+				let c_e = estimator.rotate_vector(&b_e, 1, &galois_keys).unwrap();
+				check(&c_c, &c_e, &rotated);
+
+				let summed: Vec<f64> = rotated.iter().zip(&squared).map(|(x, y)| x + y).collect();
+				let d_c = evaluator.add(&c_c, &b_c).unwrap();
+				let d_e = estimator.add(&c_e, &b_e).unwrap();
+				check(&d_c, &d_e, &summed);
+
+				let history = estimator.history();
+				assert_eq!(history.len(), 5);
+				assert_eq!(history[0].operation, "PrecisionEstimator::square");
+				assert!(history.iter().all(|step| step.bits.is_finite()));
+			},
+		);
+	}
+
+	#[test]
+	fn rejects_mismatched_scales() {
+		run_precision_test(|estimator, _, _, _, _, _| {
+			let a = estimator.encrypt(1.0);
+			let b = estimator.square(&a).unwrap();
+			let b = estimator.mod_switch_to_next(&b).unwrap();
+			let c = estimator.mod_switch_to_next(&a).unwrap();
+
+			let err = estimator.add(&b, &c).unwrap_err();
+
+			assert!(matches!(err, Error::ScaleMismatch(_)));
+		});
+	}
+
+	#[test]
+	fn rejects_scales_beyond_the_modulus() {
+		run_precision_test(|estimator, _, _, _, _, _| {
+			let a = estimator.encrypt(1.0);
+			let a = estimator.mod_switch_to_next(&a).unwrap();
+			let a = estimator.mod_switch_to_next(&a).unwrap();
+
+			// The last level keeps a single 60 bit prime.
+			let err = estimator.square(&a).unwrap_err();
+
+			assert_eq!(*err.kind(), Error::InvalidArgument);
+			assert_eq!(err.operation(), Some("PrecisionEstimator::square"));
+		});
+	}
+
+	#[test]
+	fn exponentiates_without_copying_the_operand() {
+		run_precision_test(|estimator, _, _, _, _, keygen| {
+			let relin_keys = keygen.create_relinearization_keys().unwrap();
+			let a = estimator.encrypt(1.0);
+
+			let power = estimator.exponentiate(&a, 3, &relin_keys).unwrap();
+			let many = estimator
+				.multiply_many(&vec![a.clone(); 3], &relin_keys)
+				.unwrap();
+
+			assert_eq!(power.precision_bits(), many.precision_bits());
+			assert_eq!(power.scale(), many.scale());
+
+			// One copy per unit of the exponent would never fit in memory;
+			// the scale outgrows the modulus after a few squarings instead.
+			let err = estimator
+				.exponentiate(&a, u64::MAX, &relin_keys)
+				.unwrap_err();
+
+			assert_eq!(*err.kind(), Error::InvalidArgument);
+			assert_eq!(err.operation(), Some("PrecisionEstimator::exponentiate"));
+		});
+	}
+
+	#[test]
+	fn rejects_non_ckks_contexts() {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus_u64(1234)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+
+		assert!(PrecisionEstimator::new(&ctx, 2.0f64.powi(40)).is_err());
+	}
+}
@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+
+use super::program::CircuitProgram;
+use super::{CircuitEvaluator, CircuitKeys, CompiledCircuit, Op};
+use crate::error::CircuitError;
+use crate::{
+	Ciphertext, Context, MaybeSync, Plaintext, Result, SchemeType, Tensor, TensorEvaluator,
+};
+
+/// The most resources a [`CircuitInterpreter`] lets a program use.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CircuitLimits {
+	/// The most steps, counted before and after compilation.
+	pub max_steps: usize,
+	/// The largest multiplicative depth.
+	pub max_depth: usize,
+	/// The most rotations, of rows or columns.
+	pub max_rotations: usize,
+	/// The most chunks in any input or constant.
+	pub max_chunks: usize,
+}
+
+impl Default for CircuitLimits {
+	fn default() -> Self {
+		Self {
+			max_steps: 1 << 12,
+			max_depth: 16,
+			max_rotations: 256,
+			max_chunks: 1 << 10,
+		}
+	}
+}
+
+/// Runs circuit programs received from untrusted clients on tensors of
+/// ciphertexts.
+///
+/// A program is compiled for the scheme of the interpreter's context and
+/// rejected with [`CircuitError::LimitExceeded`] before anything runs if it
+/// needs more than the [`CircuitLimits`] allow, or more levels than the
+/// context's modulus chain has. Depth, rotations and levels are bounded
+/// from the program as sent, before it is compiled, and compiling stops as
+/// soon as it emits too many steps.
+pub struct CircuitInterpreter {
+	scheme: SchemeType,
+	levels: usize,
+	limits: CircuitLimits,
+}
+
+impl CircuitInterpreter {
+	/// Creates an interpreter for programs run under the given context.
+	///
+	/// * `ctx` - The context of the ciphertexts the programs run on.
+	/// * `limits` - The most resources a program may use.
+	pub fn new(
+		ctx: &Context,
+		limits: CircuitLimits,
+	) -> Result<Self> {
+		Ok(Self {
+			scheme: ctx.get_encryption_parameters()?.get_scheme()?,
+			levels: ctx.get_data_levels()?.len().saturating_sub(1),
+			limits,
+		})
+	}
+
+	/// Returns the limits programs are held to.
+	pub fn limits(&self) -> &CircuitLimits {
+		&self.limits
+	}
+
+	/// Checks a program against the limits and compiles it, without running
+	/// it.
+	pub fn check(
+		&self,
+		program: &CircuitProgram,
+	) -> Result<CompiledCircuit> {
+		let circuit = program.circuit();
+
+		check_limit("steps", circuit.ops.len(), self.limits.max_steps)?;
+
+		for constant in program.constants() {
+			check_limit("chunks", constant.len(), self.limits.max_chunks)?;
+		}
+
+		// Compiling only drops rotations and never adds depth, so the program
+		// as sent bounds both, along with the levels, before any compiling.
+		let (depth, levels) = circuit.measure(self.scheme);
+		let rotations = circuit
+			.ops
+			.iter()
+			.filter(|op| matches!(op, Op::Rotate(..) | Op::RotateColumns(_)))
+			.count();
+
+		check_limit("depth", depth, self.limits.max_depth)?;
+		check_limit("rotations", rotations, self.limits.max_rotations)?;
+		check_limit("levels", levels, self.levels)?;
+
+		circuit.compile_within(self.scheme, self.limits.max_steps)
+	}
+
+	/// Runs a program and returns its outputs by name.
+	///
+	/// * `evaluator` - The evaluator to run the operations with.
+	/// * `program` - The program to run.
+	/// * `keys` - The evaluation keys sent by the client.
+	/// * `inputs` - The encrypted inputs, in the order they were declared.
+	pub fn run<E>(
+		&self,
+		evaluator: &TensorEvaluator<E>,
+		program: &CircuitProgram,
+		keys: &CircuitKeys,
+		inputs: &[Tensor<Ciphertext>],
+	) -> Result<BTreeMap<String, Tensor<Ciphertext>>>
+	where
+		E: CircuitEvaluator<Plaintext = Plaintext, Ciphertext = Ciphertext> + MaybeSync,
+	{
+		let compiled = self.check(program)?;
+
+		for input in inputs {
+			check_limit("chunks", input.len(), self.limits.max_chunks)?;
+		}
+
+		let input_names = compiled.inputs();
+
+		if inputs.len() != input_names.len() {
+			return Err(CircuitError::CountMismatch {
+				what: "inputs",
+				expected: input_names.len(),
+				actual: inputs.len(),
+			}
+			.into());
+		}
+
+		let inputs = input_names.into_iter().zip(inputs).collect::<Vec<_>>();
+		let constants = compiled
+			.constants()
+			.into_iter()
+			.zip(program.constants())
+			.collect::<Vec<_>>();
+
+		compiled.run(evaluator, keys, &inputs, &constants)
+	}
+}
+
+fn check_limit(
+	limit: &'static str,
+	value: usize,
+	max: usize,
+) -> Result<()> {
+	if value > max {
+		return Err(CircuitError::LimitExceeded {
+			limit,
+			value,
+			max,
+		}
+		.into());
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn bfv_context() -> Context {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	#[test]
+	fn can_run_program_sent_by_client() {
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+
+		// The client: (a + b) * w, rotated by one slot.
+		let mut circuit = Circuit::new();
+		let a = circuit.input("a");
+		let b = circuit.input("b");
+		let w = circuit.constant("w");
+		let sum = circuit.add(a, b);
+		let weighted = circuit.multiply_plain(sum, w);
+		let rotated = circuit.rotate(weighted, 1);
+		circuit.output("y", rotated);
+
+		let a = (0..10_000).map(|x| x % 7).collect::<Vec<i64>>();
+		let b = (0..10_000).map(|x| x % 5).collect::<Vec<i64>>();
+		let w = vec![3; 8192];
+
+		let program = CircuitProgram::new(circuit, vec![encoder.encode_i64(&w).unwrap()]).unwrap();
+		let bytes = program.as_bytes().unwrap();
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_enc = encryptor.encrypt(&encoder.encode_i64(&b).unwrap()).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		// The server.
+		let program = CircuitProgram::from_bytes(&ctx, &bytes).unwrap();
+		let interpreter = CircuitInterpreter::new(&ctx, CircuitLimits::default()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+
+		let keys = CircuitKeys {
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+
+		let outputs = interpreter
+			.run(&evaluator, &program, &keys, &[a_enc, b_enc])
+			.unwrap();
+
+		let y = encoder
+			.decode_i64(&decryptor.decrypt(&outputs["y"]).unwrap())
+			.unwrap();
+
+		for (i, y) in y.iter().take(10_000).enumerate() {
+			let j = i / 4096 * 4096 + (i + 1) % 4096;
+			let expected = if j < 10_000 { (a[j] + b[j]) * 3 } else { 0 };
+			assert_eq!(*y, expected);
+		}
+	}
+
+	#[test]
+	fn program_limits_are_enforced() {
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let mut circuit = Circuit::new();
+		let mut x = circuit.input("x");
+
+		for _ in 0..4 {
+			x = circuit.square(x);
+		}
+
+		circuit.output("y", x);
+
+		let program = CircuitProgram::new(circuit, vec![]).unwrap();
+		let limits = CircuitLimits {
+			max_depth: 3,
+			..Default::default()
+		};
+		let interpreter = CircuitInterpreter::new(&ctx, limits).unwrap();
+
+		assert_eq!(
+			interpreter.check(&program).err(),
+			Some(Error::from(CircuitError::LimitExceeded {
+				limit: "depth",
+				value: 4,
+				max: 3,
+			}))
+		);
+
+		// Each squaring compiles to two steps.
+		let limits = CircuitLimits {
+			max_steps: 6,
+			..Default::default()
+		};
+		let interpreter = CircuitInterpreter::new(&ctx, limits).unwrap();
+
+		assert_eq!(
+			interpreter.check(&program).err(),
+			Some(Error::from(CircuitError::LimitExceeded {
+				limit: "compiled steps",
+				value: 7,
+				max: 6,
+			}))
+		);
+
+		let interpreter = CircuitInterpreter::new(&ctx, CircuitLimits::default()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			..Default::default()
+		};
+
+		assert_eq!(
+			interpreter.run(&evaluator, &program, &keys, &[]).err(),
+			Some(Error::from(CircuitError::CountMismatch {
+				what: "inputs",
+				expected: 1,
+				actual: 0,
+			}))
+		);
+	}
+
+	#[test]
+	fn deep_programs_are_rejected_before_compiling() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+
+		// Deep and wide: a long chain of squarings, each also summed with the
+		// input. Column rotations have no CKKS counterpart, so compiling
+		// would fail with Unsupported rather than a limit.
+		let mut circuit = Circuit::new();
+		let x = circuit.input("x");
+		let mut y = circuit.rotate_columns(x);
+
+		for _ in 0..1000 {
+			y = circuit.square(y);
+			y = circuit.add(y, x);
+		}
+
+		circuit.output("y", y);
+
+		let program = CircuitProgram::new(circuit, vec![]).unwrap();
+		let interpreter = CircuitInterpreter::new(&ctx, CircuitLimits::default()).unwrap();
+
+		assert_eq!(
+			interpreter.check(&program).err(),
+			Some(Error::from(CircuitError::LimitExceeded {
+				limit: "depth",
+				value: 1000,
+				max: 16,
+			}))
+		);
+
+		let limits = CircuitLimits {
+			max_depth: usize::MAX,
+			..Default::default()
+		};
+		let interpreter = CircuitInterpreter::new(&ctx, limits).unwrap();
+
+		assert_eq!(
+			interpreter.check(&program).err(),
+			Some(Error::from(CircuitError::LimitExceeded {
+				limit: "levels",
+				value: 1000,
+				max: 2,
+			}))
+		);
+	}
+
+	#[test]
+	fn malformed_programs_are_rejected() {
+		let ctx = bfv_context();
+
+		let mut circuit = Circuit::new();
+		let x = circuit.input("x");
+		let c = circuit.constant("c");
+		let y = circuit.add_plain(x, c);
+		circuit.output("y", y);
+
+		assert_eq!(
+			CircuitProgram::new(circuit.clone(), vec![]).err(),
+			Some(Error::from(CircuitError::CountMismatch {
+				what: "constants",
+				expected: 1,
+				actual: 0,
+			}))
+		);
+
+		let encoder = BFVEncoder::new(&ctx).unwrap();
+		let constant = Tensor::new(vec![encoder.encode_i64(&[1, 2, 3]).unwrap()]);
+		let bytes = CircuitProgram::new(circuit, vec![constant])
+			.unwrap()
+			.as_bytes()
+			.unwrap();
+
+		let loaded = CircuitProgram::from_bytes(&ctx, &bytes).unwrap();
+		assert_eq!(loaded.constants().len(), 1);
+
+		for len in [0, 5, 20, bytes.len() - 1] {
+			assert!(CircuitProgram::from_bytes(&ctx, &bytes[..len]).is_err());
+		}
+
+		// Point the output at the constant.
+		let mut tampered = bytes.clone();
+		let output = tampered
+			.windows(5)
+			.position(|window| window == [b'y', 2, 0, 0, 0])
+			.unwrap();
+		tampered[output + 1] = 1;
+
+		assert_eq!(
+			CircuitProgram::from_bytes(&ctx, &tampered).err(),
+			Some(Error::from(CircuitError::UnknownWire(1)))
+		);
+
+		let mut tampered = bytes;
+		tampered[4] = 2;
+
+		assert!(matches!(
+			CircuitProgram::from_bytes(&ctx, &tampered),
+			Err(Error::SerializationError(_))
+		));
+	}
+}
@@ -0,0 +1,1962 @@
+//! Computations over encrypted values, declared up front and run later.
+//!
+//! A [`Circuit`] records the inputs, plaintext constants and operations of a
+//! computation without running any of them. [`Circuit::compile`] then
+//! prepares it for a scheme: it relinearizes after every multiplication,
+//! rescales under CKKS, and switches operands down so that both sides of an
+//! operation are at the same level. The resulting [`CompiledCircuit`] reports
+//! its depth and the keys it needs, and runs on any [`CircuitEvaluator`].
+//!
+//! Under CKKS the compiler also follows the scale of every step, assuming
+//! inputs and constants are encoded at one scale Δ. Where the two sides of
+//! an addition or subtraction reach it at different scales, as when the
+//! product of two values at Δ, rescaled to Δ²/q, meets a value at Δ, it
+//! brings the lower side up to the higher one when the circuit runs: if the
+//! two are within a relative 2^-16 of each other by overriding the scale,
+//! which changes the value by as much, and otherwise by multiplying with ones
+//! encoded at the ratio of the scales, which the encoder rounds to an
+//! integer. A plaintext constant cannot be raised, so adding one to a
+//! ciphertext at a much larger scale still fails with
+//! [`Error::ScaleMismatch`].
+
+pub mod interpreter;
+pub mod program;
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::error::CircuitError;
+use crate::evaluator::base::are_close;
+use crate::{
+	BFVEvaluator, CKKSEncoder, CKKSEvaluator, Ciphertext, Error, Evaluator, GaloisKey,
+	NoiseCiphertext, NoiseEstimator, NoisePlaintext, PlainCiphertext, PlainEvaluator,
+	PlainPlaintext, Plaintext, PrecisionCiphertext, PrecisionEstimator, PrecisionPlaintext,
+	RelinearizationKey, Result, SchemeType,
+};
+
+/// The largest relative difference between two CKKS scales that a circuit
+/// resolves by overriding one of them.
+const SCALE_TOLERANCE: f64 = 1.0 / (1 << 16) as f64;
+
+/// The id of the next circuit created.
+static NEXT_CIRCUIT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An encrypted value of a [`Circuit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Wire {
+	circuit: u64,
+	index: usize,
+}
+
+/// A plaintext constant of a [`Circuit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PlainWire {
+	circuit: u64,
+	index: usize,
+}
+
+/// One step of a circuit. Operands are indices of earlier steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Op {
+	Input(String),
+	Constant(String),
+	Negate(usize),
+	Add(usize, usize),
+	Sub(usize, usize),
+	Multiply(usize, usize),
+	Square(usize),
+	AddPlain(usize, usize),
+	SubPlain(usize, usize),
+	MultiplyPlain(usize, usize),
+	Rotate(usize, i32),
+	RotateColumns(usize),
+	Relinearize(usize),
+	Rescale(usize),
+	ModSwitch(usize),
+	ModSwitchPlain(usize),
+	/// Brings ciphertext `a` up to the scale of ciphertext `b`. Only added
+	/// by the compiler.
+	MatchScale(usize, usize),
+	/// Brings ciphertext `a` up to the scale of plaintext `p`. Only added by
+	/// the compiler.
+	MatchPlainScale(usize, usize),
+}
+
+impl Op {
+	/// Returns the steps this one reads, ciphertexts first.
+	fn operands(&self) -> Vec<usize> {
+		match *self {
+			Op::Input(_) | Op::Constant(_) => vec![],
+			Op::Negate(a)
+			| Op::Square(a)
+			| Op::Rotate(a, _)
+			| Op::RotateColumns(a)
+			| Op::Relinearize(a)
+			| Op::Rescale(a)
+			| Op::ModSwitch(a)
+			| Op::ModSwitchPlain(a) => vec![a],
+			Op::Add(a, b)
+			| Op::Sub(a, b)
+			| Op::Multiply(a, b)
+			| Op::AddPlain(a, b)
+			| Op::SubPlain(a, b)
+			| Op::MultiplyPlain(a, b)
+			| Op::MatchScale(a, b)
+			| Op::MatchPlainScale(a, b) => vec![a, b],
+		}
+	}
+
+	/// Returns the same step reading `f(i)` instead of every operand `i`.
+	fn map_operands(
+		&self,
+		f: impl Fn(usize) -> usize,
+	) -> Op {
+		match *self {
+			Op::Input(ref name) => Op::Input(name.clone()),
+			Op::Constant(ref name) => Op::Constant(name.clone()),
+			Op::Negate(a) => Op::Negate(f(a)),
+			Op::Add(a, b) => Op::Add(f(a), f(b)),
+			Op::Sub(a, b) => Op::Sub(f(a), f(b)),
+			Op::Multiply(a, b) => Op::Multiply(f(a), f(b)),
+			Op::Square(a) => Op::Square(f(a)),
+			Op::AddPlain(a, p) => Op::AddPlain(f(a), f(p)),
+			Op::SubPlain(a, p) => Op::SubPlain(f(a), f(p)),
+			Op::MultiplyPlain(a, p) => Op::MultiplyPlain(f(a), f(p)),
+			Op::Rotate(a, steps) => Op::Rotate(f(a), steps),
+			Op::RotateColumns(a) => Op::RotateColumns(f(a)),
+			Op::Relinearize(a) => Op::Relinearize(f(a)),
+			Op::Rescale(a) => Op::Rescale(f(a)),
+			Op::ModSwitch(a) => Op::ModSwitch(f(a)),
+			Op::ModSwitchPlain(a) => Op::ModSwitchPlain(f(a)),
+			Op::MatchScale(a, b) => Op::MatchScale(f(a), f(b)),
+			Op::MatchPlainScale(a, p) => Op::MatchPlainScale(f(a), f(p)),
+		}
+	}
+
+	/// Returns whether the step produces a plaintext.
+	fn is_plain(&self) -> bool {
+		matches!(self, Op::Constant(_) | Op::ModSwitchPlain(_))
+	}
+
+	/// Returns whether operand `i` of the step is a plaintext.
+	fn operand_is_plain(
+		&self,
+		i: usize,
+	) -> bool {
+		match self {
+			Op::AddPlain(..)
+			| Op::SubPlain(..)
+			| Op::MultiplyPlain(..)
+			| Op::MatchPlainScale(..) => i == 1,
+			Op::ModSwitchPlain(_) => true,
+			_ => false,
+		}
+	}
+}
+
+/// What the compiler knows about the value of a step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Info {
+	/// How many levels below the first the value is.
+	level: usize,
+	/// The number of ciphertext multiplications leading to the value.
+	depth: usize,
+}
+
+impl Info {
+	/// Derives the info of `op` from the info of its operands.
+	fn of(
+		op: &Op,
+		infos: &[Info],
+	) -> Info {
+		let fresh = Info {
+			level: 0,
+			depth: 0,
+		};
+
+		let joined = |a: usize, b: usize| Info {
+			level: infos[a].level.max(infos[b].level),
+			depth: infos[a].depth.max(infos[b].depth),
+		};
+
+		match *op {
+			Op::Input(_) | Op::Constant(_) => fresh,
+			Op::Negate(a)
+			| Op::Rotate(a, _)
+			| Op::RotateColumns(a)
+			| Op::Relinearize(a)
+			| Op::AddPlain(a, _)
+			| Op::SubPlain(a, _)
+			| Op::MultiplyPlain(a, _)
+			| Op::MatchScale(a, _)
+			| Op::MatchPlainScale(a, _) => infos[a],
+			Op::Add(a, b) | Op::Sub(a, b) => joined(a, b),
+			Op::Multiply(a, b) => Info {
+				depth: joined(a, b).depth + 1,
+				..joined(a, b)
+			},
+			Op::Square(a) => Info {
+				depth: infos[a].depth + 1,
+				..infos[a]
+			},
+			Op::Rescale(a) | Op::ModSwitch(a) | Op::ModSwitchPlain(a) => Info {
+				level: infos[a].level + 1,
+				..infos[a]
+			},
+		}
+	}
+}
+
+/// A factor of the CKKS scale of a step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Factor {
+	/// The scale inputs and constants are encoded at.
+	Encoding,
+	/// The last prime of a level, which rescaling divides by.
+	Prime(usize),
+	/// The scale the operands of an aligned step were brought to, which is
+	/// only known when the circuit runs.
+	Aligned(usize),
+}
+
+/// What the compiler knows about the CKKS scale of a step: a product of
+/// powers of factors. Steps with equal scales have the same scale when the
+/// circuit runs; different scales may still turn out close.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Scale(BTreeMap<Factor, i32>);
+
+impl Scale {
+	fn of(factor: Factor) -> Scale {
+		Scale(BTreeMap::from([(factor, 1)]))
+	}
+
+	/// Returns this scale times `other` to the power `power`.
+	fn times(
+		&self,
+		other: &Scale,
+		power: i32,
+	) -> Scale {
+		let mut factors = self.0.clone();
+
+		for (factor, exponent) in &other.0 {
+			let entry = factors.entry(*factor).or_insert(0);
+			*entry += exponent * power;
+
+			if *entry == 0 {
+				factors.remove(factor);
+			}
+		}
+
+		Scale(factors)
+	}
+}
+
+/// A computation over encrypted values, recorded to be compiled and run
+/// later.
+///
+/// Every method that adds an operation returns a handle to its result, to be
+/// passed to later operations. Handles remember the circuit they came from,
+/// and a clone of a circuit accepts the handles of the original. Nothing is
+/// checked until [`Circuit::compile`].
+#[derive(Debug, Clone)]
+pub struct Circuit {
+	/// Tags the handles of this circuit; not part of its value.
+	id: u64,
+	ops: Vec<Op>,
+	outputs: Vec<(String, usize)>,
+	/// The first handle given to this circuit that came from another one.
+	foreign: Option<usize>,
+}
+
+impl Default for Circuit {
+	fn default() -> Self {
+		Self {
+			id: NEXT_CIRCUIT_ID.fetch_add(1, Ordering::Relaxed),
+			ops: vec![],
+			outputs: vec![],
+			foreign: None,
+		}
+	}
+}
+
+impl PartialEq for Circuit {
+	fn eq(
+		&self,
+		other: &Self,
+	) -> bool {
+		self.ops == other.ops && self.outputs == other.outputs && self.foreign == other.foreign
+	}
+}
+
+impl Eq for Circuit {}
+
+impl Circuit {
+	/// Creates an empty circuit.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn push(
+		&mut self,
+		op: Op,
+	) -> usize {
+		self.ops.push(op);
+		self.ops.len() - 1
+	}
+
+	fn wire(
+		&mut self,
+		op: Op,
+	) -> Wire {
+		Wire {
+			circuit: self.id,
+			index: self.push(op),
+		}
+	}
+
+	/// Returns the step a handle refers to, noting it if the handle comes
+	/// from another circuit.
+	fn step(
+		&mut self,
+		circuit: u64,
+		index: usize,
+	) -> usize {
+		if circuit != self.id {
+			self.foreign.get_or_insert(index);
+		}
+
+		index
+	}
+
+	fn cipher(
+		&mut self,
+		a: Wire,
+	) -> usize {
+		self.step(a.circuit, a.index)
+	}
+
+	fn plain(
+		&mut self,
+		a: PlainWire,
+	) -> usize {
+		self.step(a.circuit, a.index)
+	}
+
+	/// Declares an encrypted input, given by name when the circuit runs.
+	/// Inputs and constants share one namespace.
+	pub fn input(
+		&mut self,
+		name: &str,
+	) -> Wire {
+		self.wire(Op::Input(name.to_owned()))
+	}
+
+	/// Declares a plaintext constant, given by name when the circuit runs.
+	///
+	/// Under CKKS the constant is given at the first level and switched down
+	/// wherever it is needed.
+	pub fn constant(
+		&mut self,
+		name: &str,
+	) -> PlainWire {
+		PlainWire {
+			circuit: self.id,
+			index: self.push(Op::Constant(name.to_owned())),
+		}
+	}
+
+	/// Negates `a`.
+	pub fn negate(
+		&mut self,
+		a: Wire,
+	) -> Wire {
+		let a = self.cipher(a);
+		self.wire(Op::Negate(a))
+	}
+
+	/// Adds `a` and `b`.
+	pub fn add(
+		&mut self,
+		a: Wire,
+		b: Wire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.cipher(b));
+		self.wire(Op::Add(a, b))
+	}
+
+	/// Subtracts `b` from `a`.
+	pub fn sub(
+		&mut self,
+		a: Wire,
+		b: Wire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.cipher(b));
+		self.wire(Op::Sub(a, b))
+	}
+
+	/// Multiplies `a` and `b`.
+	pub fn multiply(
+		&mut self,
+		a: Wire,
+		b: Wire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.cipher(b));
+		self.wire(Op::Multiply(a, b))
+	}
+
+	/// Squares `a`.
+	pub fn square(
+		&mut self,
+		a: Wire,
+	) -> Wire {
+		let a = self.cipher(a);
+		self.wire(Op::Square(a))
+	}
+
+	/// Adds the plaintext `b` to `a`.
+	pub fn add_plain(
+		&mut self,
+		a: Wire,
+		b: PlainWire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.plain(b));
+		self.wire(Op::AddPlain(a, b))
+	}
+
+	/// Subtracts the plaintext `b` from `a`.
+	pub fn sub_plain(
+		&mut self,
+		a: Wire,
+		b: PlainWire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.plain(b));
+		self.wire(Op::SubPlain(a, b))
+	}
+
+	/// Multiplies `a` by the plaintext `b`.
+	pub fn multiply_plain(
+		&mut self,
+		a: Wire,
+		b: PlainWire,
+	) -> Wire {
+		let (a, b) = (self.cipher(a), self.plain(b));
+		self.wire(Op::MultiplyPlain(a, b))
+	}
+
+	/// Rotates the slots of `a` by `steps`: the rows under BFV, the whole
+	/// vector under CKKS.
+	pub fn rotate(
+		&mut self,
+		a: Wire,
+		steps: i32,
+	) -> Wire {
+		let a = self.cipher(a);
+		self.wire(Op::Rotate(a, steps))
+	}
+
+	/// Swaps the two rows of `a`. BFV only.
+	pub fn rotate_columns(
+		&mut self,
+		a: Wire,
+	) -> Wire {
+		let a = self.cipher(a);
+		self.wire(Op::RotateColumns(a))
+	}
+
+	/// Marks `a` as a result of the circuit, returned under `name`.
+	pub fn output(
+		&mut self,
+		name: &str,
+		a: Wire,
+	) {
+		let a = self.cipher(a);
+		self.outputs.push((name.to_owned(), a));
+	}
+
+	/// Prepares the circuit to run under `scheme`.
+	///
+	/// Relinearizes after every ciphertext multiplication and, under CKKS,
+	/// rescales after every multiplication; switches the operands of binary
+	/// operations down to the lower of their levels; under CKKS, brings the
+	/// operands of additions and subtractions to the same scale, as described
+	/// in the [module documentation](self); and drops operations that no
+	/// output depends on.
+	///
+	/// Fails with [`CircuitError::UnknownWire`] if a handle comes from
+	/// another circuit, [`CircuitError::DuplicateName`] if two inputs or
+	/// constants, or two outputs, share a name, and
+	/// [`CircuitError::Unsupported`] if an operation has no counterpart in
+	/// the scheme.
+	pub fn compile(
+		&self,
+		scheme: SchemeType,
+	) -> Result<CompiledCircuit> {
+		self.compile_within(scheme, usize::MAX)
+	}
+
+	/// Compiles as [`Circuit::compile`] does, but stops with
+	/// [`CircuitError::LimitExceeded`] as soon as more than `max_steps` steps
+	/// have been emitted, before dropping unused ones.
+	pub(crate) fn compile_within(
+		&self,
+		scheme: SchemeType,
+		max_steps: usize,
+	) -> Result<CompiledCircuit> {
+		if scheme == SchemeType::None {
+			return Err(CircuitError::Unsupported {
+				operation: "Circuit::compile",
+				scheme,
+			}
+			.into());
+		}
+
+		self.check()?;
+
+		let mut compiler = Compiler {
+			scheme,
+			ops: vec![],
+			infos: vec![],
+			scales: vec![],
+			lowered: HashMap::new(),
+		};
+
+		let mut map = Vec::with_capacity(self.ops.len());
+
+		for op in &self.ops {
+			let index = compiler.compile(&op.map_operands(|operand| map[operand]))?;
+			map.push(index);
+
+			// One step emits a bounded number of others, so this stops the
+			// compiler before it outgrows the limit by much.
+			if compiler.ops.len() > max_steps {
+				return Err(CircuitError::LimitExceeded {
+					limit: "compiled steps",
+					value: compiler.ops.len(),
+					max: max_steps,
+				}
+				.into());
+			}
+		}
+
+		let outputs = self
+			.outputs
+			.iter()
+			.map(|(name, index)| (name.clone(), map[*index]))
+			.collect();
+
+		Ok(CompiledCircuit::new(scheme, compiler.ops, outputs))
+	}
+}
+
+impl Circuit {
+	/// Returns the largest depth and level of any step once compiled for
+	/// `scheme`, without compiling. Compiling never adds depth, rescales
+	/// after every multiplication under CKKS and otherwise only drops steps,
+	/// so these bound those of the compiled circuit.
+	///
+	/// The circuit must have passed `check`.
+	pub(crate) fn measure(
+		&self,
+		scheme: SchemeType,
+	) -> (usize, usize) {
+		let mut infos: Vec<Info> = Vec::with_capacity(self.ops.len());
+
+		for op in &self.ops {
+			let info = Info::of(op, &infos);
+
+			infos.push(match op {
+				Op::Multiply(..) | Op::Square(_) | Op::MultiplyPlain(..)
+					if scheme == SchemeType::Ckks =>
+				{
+					Info {
+						level: info.level + 1,
+						..info
+					}
+				}
+				_ => info,
+			});
+		}
+
+		infos.iter().fold((0, 0), |(depth, level), info| {
+			(depth.max(info.depth), level.max(info.level))
+		})
+	}
+
+	/// Checks that every handle came from this circuit, that every step
+	/// reads earlier steps of the right kind, that outputs are ciphertexts,
+	/// and that names are unique.
+	fn check(&self) -> Result<()> {
+		if let Some(index) = self.foreign {
+			return Err(CircuitError::UnknownWire(index).into());
+		}
+
+		for (i, op) in self.ops.iter().enumerate() {
+			for (k, operand) in op.operands().into_iter().enumerate() {
+				if operand >= i || self.ops[operand].is_plain() != op.operand_is_plain(k) {
+					return Err(CircuitError::UnknownWire(operand).into());
+				}
+			}
+		}
+
+		for (_, index) in &self.outputs {
+			if !matches!(self.ops.get(*index), Some(op) if !op.is_plain()) {
+				return Err(CircuitError::UnknownWire(*index).into());
+			}
+		}
+
+		check_unique(self.ops.iter().filter_map(|op| match op {
+			Op::Input(name) | Op::Constant(name) => Some(name),
+			_ => None,
+		}))?;
+		check_unique(self.outputs.iter().map(|(name, _)| name))
+	}
+}
+
+/// Fails with [`CircuitError::DuplicateName`] if a name repeats.
+fn check_unique<'a>(names: impl Iterator<Item = &'a String>) -> Result<()> {
+	let mut seen = HashSet::new();
+
+	for name in names {
+		if !seen.insert(name) {
+			return Err(CircuitError::DuplicateName(name.clone()).into());
+		}
+	}
+
+	Ok(())
+}
+
+/// Rewrites the steps of a circuit for a scheme.
+struct Compiler {
+	scheme: SchemeType,
+	ops: Vec<Op>,
+	infos: Vec<Info>,
+	scales: Vec<Scale>,
+	/// Values already switched down, by step and level.
+	lowered: HashMap<(usize, usize), usize>,
+}
+
+impl Compiler {
+	fn push(
+		&mut self,
+		op: Op,
+	) -> usize {
+		let scale = match op {
+			Op::Input(_) | Op::Constant(_) => Scale::of(Factor::Encoding),
+			Op::Multiply(a, b) | Op::MultiplyPlain(a, b) => {
+				self.scales[a].times(&self.scales[b], 1)
+			}
+			Op::Square(a) => self.scales[a].times(&self.scales[a], 1),
+			Op::Rescale(a) => {
+				self.scales[a].times(&Scale::of(Factor::Prime(self.infos[a].level)), -1)
+			}
+			Op::MatchScale(..) => Scale::of(Factor::Aligned(self.ops.len())),
+			Op::MatchPlainScale(_, p) => self.scales[p].clone(),
+			Op::Negate(a)
+			| Op::Add(a, _)
+			| Op::Sub(a, _)
+			| Op::AddPlain(a, _)
+			| Op::SubPlain(a, _)
+			| Op::Rotate(a, _)
+			| Op::RotateColumns(a)
+			| Op::Relinearize(a)
+			| Op::ModSwitch(a)
+			| Op::ModSwitchPlain(a) => self.scales[a].clone(),
+		};
+
+		self.infos.push(Info::of(&op, &self.infos));
+		self.scales.push(scale);
+		self.ops.push(op);
+		self.ops.len() - 1
+	}
+
+	/// Returns step `index` switched down to `level`, switching it as needed.
+	fn lower(
+		&mut self,
+		index: usize,
+		level: usize,
+	) -> usize {
+		if self.infos[index].level >= level {
+			return index;
+		}
+
+		if let Some(&lowered) = self.lowered.get(&(index, level)) {
+			return lowered;
+		}
+
+		let above = self.lower(index, level - 1);
+		let lowered = if self.ops[index].is_plain() {
+			self.push(Op::ModSwitchPlain(above))
+		} else {
+			self.push(Op::ModSwitch(above))
+		};
+
+		self.lowered.insert((index, level), lowered);
+		lowered
+	}
+
+	/// Switches two operands down to the lower of their levels.
+	fn align(
+		&mut self,
+		a: usize,
+		b: usize,
+	) -> (usize, usize) {
+		let level = self.infos[a].level.max(self.infos[b].level);
+
+		(self.lower(a, level), self.lower(b, level))
+	}
+
+	/// Brings two CKKS ciphertexts at the same level to the higher of their
+	/// scales, unless their scales are known to be equal.
+	fn match_scales(
+		&mut self,
+		a: usize,
+		b: usize,
+	) -> (usize, usize) {
+		if self.scheme != SchemeType::Ckks || self.scales[a] == self.scales[b] {
+			return (a, b);
+		}
+
+		let a = self.push(Op::MatchScale(a, b));
+		let b = self.push(Op::MatchScale(b, a));
+
+		// Both now hold the scale picked for `a`.
+		self.scales[b] = self.scales[a].clone();
+
+		(a, b)
+	}
+
+	/// Brings a CKKS ciphertext up to the scale of a plaintext at its level,
+	/// unless their scales are known to be equal.
+	fn match_plain_scale(
+		&mut self,
+		a: usize,
+		p: usize,
+	) -> usize {
+		if self.scheme != SchemeType::Ckks || self.scales[a] == self.scales[p] {
+			return a;
+		}
+
+		self.push(Op::MatchPlainScale(a, p))
+	}
+
+	/// Relinearizes, and rescales under CKKS, the product at `index`.
+	fn finish_product(
+		&mut self,
+		index: usize,
+		relinearize: bool,
+	) -> usize {
+		let index = if relinearize {
+			self.push(Op::Relinearize(index))
+		} else {
+			index
+		};
+
+		if self.scheme == SchemeType::Ckks {
+			self.push(Op::Rescale(index))
+		} else {
+			index
+		}
+	}
+
+	fn unsupported(
+		&self,
+		operation: &'static str,
+	) -> Error {
+		CircuitError::Unsupported {
+			operation,
+			scheme: self.scheme,
+		}
+		.into()
+	}
+
+	/// Appends `op`, whose operands are already compiled, and the steps it
+	/// needs. Returns the step holding its result.
+	fn compile(
+		&mut self,
+		op: &Op,
+	) -> Result<usize> {
+		let ckks = self.scheme == SchemeType::Ckks;
+
+		let index = match *op {
+			Op::Add(a, b) => {
+				let (a, b) = self.align(a, b);
+				let (a, b) = self.match_scales(a, b);
+				self.push(Op::Add(a, b))
+			}
+			Op::Sub(a, b) => {
+				let (a, b) = self.align(a, b);
+				let (a, b) = self.match_scales(a, b);
+				self.push(Op::Sub(a, b))
+			}
+			Op::Multiply(a, b) => {
+				let (a, b) = self.align(a, b);
+				let product = self.push(Op::Multiply(a, b));
+				self.finish_product(product, true)
+			}
+			Op::Square(a) => {
+				let product = self.push(Op::Square(a));
+				self.finish_product(product, true)
+			}
+			Op::AddPlain(a, p) => {
+				let p = self.lower(p, self.infos[a].level);
+				let a = self.match_plain_scale(a, p);
+				self.push(Op::AddPlain(a, p))
+			}
+			Op::SubPlain(a, p) => {
+				let p = self.lower(p, self.infos[a].level);
+				let a = self.match_plain_scale(a, p);
+				self.push(Op::SubPlain(a, p))
+			}
+			Op::MultiplyPlain(a, p) => {
+				let p = self.lower(p, self.infos[a].level);
+				let product = self.push(Op::MultiplyPlain(a, p));
+				self.finish_product(product, false)
+			}
+			Op::RotateColumns(_) if ckks => return Err(self.unsupported("Circuit::rotate_columns")),
+			Op::Rescale(_) if !ckks => return Err(self.unsupported("Circuit::rescale")),
+			ref op => self.push(op.clone()),
+		};
+
+		Ok(index)
+	}
+}
+
+/// The keys a compiled circuit needs to run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyRequirements {
+	/// Whether the circuit relinearizes.
+	pub relinearization: bool,
+	/// The rotations the circuit performs, in slots.
+	pub galois_steps: BTreeSet<i32>,
+	/// Whether the circuit swaps the rows of a BFV ciphertext.
+	pub column_rotation: bool,
+	/// Whether the circuit brings CKKS ciphertexts to matching scales, which
+	/// may encode plaintexts.
+	pub encoder: bool,
+}
+
+impl KeyRequirements {
+	/// Returns whether the circuit needs Galois keys.
+	pub fn galois(&self) -> bool {
+		!self.galois_steps.is_empty() || self.column_rotation
+	}
+}
+
+/// The keys given to [`CompiledCircuit::run`].
+#[derive(Clone, Copy, Default)]
+pub struct CircuitKeys<'a> {
+	/// The relinearization key, needed if the circuit multiplies ciphertexts.
+	pub relinearization: Option<&'a RelinearizationKey>,
+	/// The Galois keys, needed if the circuit rotates.
+	pub galois: Option<&'a GaloisKey>,
+	/// The CKKS encoder, needed if the circuit matches scales.
+	pub encoder: Option<&'a CKKSEncoder>,
+}
+
+/// A circuit prepared by [`Circuit::compile`], ready to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledCircuit {
+	scheme: SchemeType,
+	ops: Vec<Op>,
+	outputs: Vec<(String, usize)>,
+	depth: usize,
+	levels: usize,
+	requirements: KeyRequirements,
+}
+
+impl CompiledCircuit {
+	/// Drops the steps no output depends on, and measures the rest. Inputs
+	/// and constants are kept, so that they are still required.
+	fn new(
+		scheme: SchemeType,
+		ops: Vec<Op>,
+		outputs: Vec<(String, usize)>,
+	) -> Self {
+		let mut live = vec![false; ops.len()];
+
+		for (_, index) in &outputs {
+			live[*index] = true;
+		}
+
+		for (i, op) in ops.iter().enumerate().rev() {
+			if live[i] || matches!(op, Op::Input(_) | Op::Constant(_)) {
+				live[i] = true;
+
+				for operand in op.operands() {
+					live[operand] = true;
+				}
+			}
+		}
+
+		let mut map = vec![0; ops.len()];
+		let mut kept = vec![];
+
+		for (i, op) in ops.iter().enumerate() {
+			if live[i] {
+				map[i] = kept.len();
+				kept.push(op.map_operands(|operand| map[operand]));
+			}
+		}
+
+		let mut infos = vec![];
+		let mut requirements = KeyRequirements::default();
+
+		for op in &kept {
+			infos.push(Info::of(op, &infos));
+
+			match *op {
+				Op::Relinearize(_) => requirements.relinearization = true,
+				Op::Rotate(_, steps) => {
+					requirements.galois_steps.insert(steps);
+				}
+				Op::RotateColumns(_) => requirements.column_rotation = true,
+				Op::MatchScale(..) | Op::MatchPlainScale(..) => requirements.encoder = true,
+				_ => {}
+			}
+		}
+
+		Self {
+			scheme,
+			depth: infos.iter().map(|info| info.depth).max().unwrap_or(0),
+			levels: infos.iter().map(|info| info.level).max().unwrap_or(0),
+			outputs: outputs
+				.into_iter()
+				.map(|(name, index)| (name, map[index]))
+				.collect(),
+			ops: kept,
+			requirements,
+		}
+	}
+
+	/// Returns the scheme the circuit was compiled for.
+	pub fn scheme(&self) -> SchemeType {
+		self.scheme
+	}
+
+	/// Returns the multiplicative depth: the largest number of ciphertext
+	/// multiplications on any path from an input to an output.
+	pub fn depth(&self) -> usize {
+		self.depth
+	}
+
+	/// Returns the number of levels the circuit uses up. The coefficient
+	/// modulus must have at least this many primes besides the special one
+	/// and the last data prime.
+	pub fn levels(&self) -> usize {
+		self.levels
+	}
+
+	/// Returns the keys the circuit needs.
+	pub fn requirements(&self) -> &KeyRequirements {
+		&self.requirements
+	}
+
+	/// Returns the names of the inputs the circuit reads.
+	pub fn inputs(&self) -> Vec<&str> {
+		self.ops
+			.iter()
+			.filter_map(|op| match op {
+				Op::Input(name) => Some(name.as_str()),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Returns the names of the constants the circuit reads.
+	pub fn constants(&self) -> Vec<&str> {
+		self.ops
+			.iter()
+			.filter_map(|op| match op {
+				Op::Constant(name) => Some(name.as_str()),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Runs the circuit and returns its outputs by name.
+	///
+	/// Under CKKS, inputs and constants must be at the first level. Every
+	/// intermediate value is dropped as soon as it is no longer needed.
+	///
+	/// Fails with [`CircuitError::MissingInput`],
+	/// [`CircuitError::MissingConstant`] or [`CircuitError::MissingKey`]
+	/// before running anything if a value or key is missing; otherwise with
+	/// the error of the first failing operation.
+	///
+	/// * `evaluator` - The evaluator to run the operations with.
+	/// * `keys` - The keys to relinearize and rotate with.
+	/// * `inputs` - The encrypted inputs, by name.
+	/// * `constants` - The plaintext constants, by name.
+	pub fn run<'a, E>(
+		&self,
+		evaluator: &E,
+		keys: &CircuitKeys,
+		inputs: &[(&str, &'a E::Ciphertext)],
+		constants: &[(&str, &'a E::Plaintext)],
+	) -> Result<BTreeMap<String, E::Ciphertext>>
+	where
+		E: CircuitEvaluator,
+		E::Ciphertext: Clone,
+		E::Plaintext: Clone,
+	{
+		if self.requirements.relinearization && keys.relinearization.is_none() {
+			return Err(CircuitError::MissingKey("a relinearization key").into());
+		}
+
+		if self.requirements.galois() && keys.galois.is_none() {
+			return Err(CircuitError::MissingKey("Galois keys").into());
+		}
+
+		if self.requirements.encoder && keys.encoder.is_none() {
+			return Err(CircuitError::MissingKey("a CKKS encoder").into());
+		}
+
+		let mut values = self
+			.ops
+			.iter()
+			.map(|op| match op {
+				Op::Input(name) => find(inputs, name)
+					.map(|value| Some(Value::Cipher(Cow::Borrowed(value))))
+					.ok_or_else(|| CircuitError::MissingInput(name.clone()).into()),
+				Op::Constant(name) => find(constants, name)
+					.map(|value| Some(Value::Plain(Cow::Borrowed(value))))
+					.ok_or_else(|| CircuitError::MissingConstant(name.clone()).into()),
+				_ => Ok(None),
+			})
+			.collect::<Result<Vec<Option<Value<E::Ciphertext, E::Plaintext>>>>>()?;
+
+		let mut last_use = vec![None; self.ops.len()];
+
+		for (i, op) in self.ops.iter().enumerate() {
+			for operand in op.operands() {
+				last_use[operand] = Some(i);
+			}
+		}
+
+		for (_, index) in &self.outputs {
+			last_use[*index] = Some(self.ops.len());
+		}
+
+		let relin_keys = || {
+			keys.relinearization
+				.ok_or_else(|| Error::from(CircuitError::MissingKey("a relinearization key")))
+		};
+		let galois_keys = || {
+			keys.galois
+				.ok_or_else(|| Error::from(CircuitError::MissingKey("Galois keys")))
+		};
+
+		for (i, op) in self.ops.iter().enumerate() {
+			let cipher = |index: usize| cipher(&values, index);
+			let plain = |index: usize| plain(&values, index);
+
+			let result = match *op {
+				Op::Input(_) | Op::Constant(_) => None,
+				Op::Negate(a) => Some(evaluator.negate(cipher(a)?)?),
+				Op::Add(a, b) => Some(evaluator.add(cipher(a)?, cipher(b)?)?),
+				Op::Sub(a, b) => Some(evaluator.sub(cipher(a)?, cipher(b)?)?),
+				Op::Multiply(a, b) => Some(evaluator.multiply(cipher(a)?, cipher(b)?)?),
+				Op::Square(a) => Some(evaluator.square(cipher(a)?)?),
+				Op::AddPlain(a, p) => Some(evaluator.add_plain(cipher(a)?, plain(p)?)?),
+				Op::SubPlain(a, p) => Some(evaluator.sub_plain(cipher(a)?, plain(p)?)?),
+				Op::MultiplyPlain(a, p) => Some(evaluator.multiply_plain(cipher(a)?, plain(p)?)?),
+				Op::Rotate(a, steps) => {
+					Some(evaluator.rotate_slots(cipher(a)?, steps, galois_keys()?)?)
+				}
+				Op::RotateColumns(a) => Some(evaluator.rotate_columns(cipher(a)?, galois_keys()?)?),
+				Op::Relinearize(a) => Some(evaluator.relinearize(cipher(a)?, relin_keys()?)?),
+				Op::Rescale(a) => Some(evaluator.rescale_to_next(cipher(a)?)?),
+				Op::ModSwitch(a) => Some(evaluator.mod_switch_to_next(cipher(a)?)?),
+				Op::MatchScale(a, b) => Some(match_scale(
+					evaluator,
+					keys,
+					cipher(a)?,
+					evaluator.scale(cipher(b)?)?,
+				)?),
+				Op::MatchPlainScale(a, p) => Some(match_scale(
+					evaluator,
+					keys,
+					cipher(a)?,
+					evaluator.plain_scale(plain(p)?)?,
+				)?),
+				Op::ModSwitchPlain(p) => {
+					values[i] = Some(Value::Plain(Cow::Owned(
+						evaluator.mod_switch_to_next_plaintext(plain(p)?)?,
+					)));
+					None
+				}
+			};
+
+			if let Some(result) = result {
+				values[i] = Some(Value::Cipher(Cow::Owned(result)));
+			}
+
+			if last_use[i].is_none() {
+				values[i] = None;
+			}
+
+			for operand in op.operands() {
+				if last_use[operand] == Some(i) {
+					values[operand] = None;
+				}
+			}
+		}
+
+		let mut outputs = BTreeMap::new();
+
+		for (k, (name, index)) in self.outputs.iter().enumerate() {
+			let shared = self.outputs[k + 1..]
+				.iter()
+				.any(|(_, other)| other == index);
+
+			let value = if shared {
+				cipher(&values, *index)?.clone()
+			} else {
+				match values[*index].take() {
+					Some(Value::Cipher(value)) => value.into_owned(),
+					_ => return Err(CircuitError::UnknownWire(*index).into()),
+				}
+			};
+
+			outputs.insert(name.clone(), value);
+		}
+
+		Ok(outputs)
+	}
+}
+
+/// Brings `a` up to the scale `target`, as described in the
+/// [module documentation](self). Leaves it unchanged if its scale already
+/// matches or is higher, in which case the other operand is raised instead.
+fn match_scale<E>(
+	evaluator: &E,
+	keys: &CircuitKeys,
+	a: &E::Ciphertext,
+	target: f64,
+) -> Result<E::Ciphertext>
+where
+	E: CircuitEvaluator,
+	E::Ciphertext: Clone,
+{
+	let scale = evaluator.scale(a)?;
+
+	if are_close(scale, target) {
+		Ok(a.clone())
+	} else if (scale / target - 1.0).abs() <= SCALE_TOLERANCE {
+		evaluator.set_scale(a, target)
+	} else if scale > target {
+		Ok(a.clone())
+	} else {
+		evaluator.raise_scale(a, target, keys)
+	}
+}
+
+/// Returns the value given for `name`.
+fn find<'a, T>(
+	values: &[(&str, &'a T)],
+	name: &str,
+) -> Option<&'a T> {
+	values
+		.iter()
+		.find(|(candidate, _)| *candidate == name)
+		.map(|(_, value)| *value)
+}
+
+/// The value of a step while a circuit runs. Inputs and constants are
+/// borrowed from the caller.
+enum Value<'a, C: Clone, P: Clone> {
+	Cipher(Cow<'a, C>),
+	Plain(Cow<'a, P>),
+}
+
+fn cipher<'v, C: Clone, P: Clone>(
+	values: &'v [Option<Value<C, P>>],
+	index: usize,
+) -> Result<&'v C> {
+	match &values[index] {
+		Some(Value::Cipher(value)) => Ok(&**value),
+		_ => Err(CircuitError::UnknownWire(index).into()),
+	}
+}
+
+fn plain<'v, C: Clone, P: Clone>(
+	values: &'v [Option<Value<C, P>>],
+	index: usize,
+) -> Result<&'v P> {
+	match &values[index] {
+		Some(Value::Plain(value)) => Ok(&**value),
+		_ => Err(CircuitError::UnknownWire(index).into()),
+	}
+}
+
+/// The scheme-specific operations a [`CompiledCircuit`] needs beyond
+/// [`Evaluator`].
+pub trait CircuitEvaluator: Evaluator {
+	/// Rescales `a` to the next level. Fails with
+	/// [`CircuitError::Unsupported`] under BFV.
+	fn rescale_to_next(
+		&self,
+		a: &Self::Ciphertext,
+	) -> Result<Self::Ciphertext>;
+
+	/// Rotates the slots of `a` by `steps`: the rows under BFV, the whole
+	/// vector under CKKS.
+	fn rotate_slots(
+		&self,
+		a: &Self::Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Self::Ciphertext>;
+
+	/// Returns the CKKS scale of `a`. Fails with
+	/// [`CircuitError::Unsupported`] under BFV.
+	fn scale(
+		&self,
+		a: &Self::Ciphertext,
+	) -> Result<f64>;
+
+	/// Returns the CKKS scale of the plaintext `a`. Fails with
+	/// [`CircuitError::Unsupported`] under BFV.
+	fn plain_scale(
+		&self,
+		a: &Self::Plaintext,
+	) -> Result<f64>;
+
+	/// Returns `a` with its scale overridden by `scale`, without touching
+	/// its data: what it decrypts to is multiplied by the old scale over the
+	/// new one. Fails with [`CircuitError::Unsupported`] under BFV.
+	fn set_scale(
+		&self,
+		a: &Self::Ciphertext,
+		scale: f64,
+	) -> Result<Self::Ciphertext>;
+
+	/// Brings `a` up to `scale` by multiplying it with ones encoded at its
+	/// level and at the ratio of the scales, using the encoder in `keys`.
+	/// Fails with [`CircuitError::Unsupported`] under BFV.
+	fn raise_scale(
+		&self,
+		a: &Self::Ciphertext,
+		scale: f64,
+		keys: &CircuitKeys,
+	) -> Result<Self::Ciphertext>;
+}
+
+fn bfv_unsupported(operation: &'static str) -> Error {
+	CircuitError::Unsupported {
+		operation,
+		scheme: SchemeType::Bfv,
+	}
+	.into()
+}
+
+impl CircuitEvaluator for BFVEvaluator {
+	fn rescale_to_next(
+		&self,
+		_a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		Err(bfv_unsupported("BFVEvaluator::rescale_to_next"))
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		self.rotate_rows(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		_a: &Ciphertext,
+	) -> Result<f64> {
+		Err(bfv_unsupported("BFVEvaluator::scale"))
+	}
+
+	fn plain_scale(
+		&self,
+		_a: &Plaintext,
+	) -> Result<f64> {
+		Err(bfv_unsupported("BFVEvaluator::plain_scale"))
+	}
+
+	fn set_scale(
+		&self,
+		_a: &Ciphertext,
+		_scale: f64,
+	) -> Result<Ciphertext> {
+		Err(bfv_unsupported("BFVEvaluator::set_scale"))
+	}
+
+	fn raise_scale(
+		&self,
+		_a: &Ciphertext,
+		_scale: f64,
+		_keys: &CircuitKeys,
+	) -> Result<Ciphertext> {
+		Err(bfv_unsupported("BFVEvaluator::raise_scale"))
+	}
+}
+
+impl CircuitEvaluator for CKKSEvaluator {
+	fn rescale_to_next(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		CKKSEvaluator::rescale_to_next(self, a)
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		self.rotate_vector(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		a: &Ciphertext,
+	) -> Result<f64> {
+		a.scale()
+	}
+
+	fn plain_scale(
+		&self,
+		a: &Plaintext,
+	) -> Result<f64> {
+		a.scale()
+	}
+
+	fn set_scale(
+		&self,
+		a: &Ciphertext,
+		scale: f64,
+	) -> Result<Ciphertext> {
+		let result = a.clone();
+		result.set_scale(scale)?;
+		Ok(result)
+	}
+
+	fn raise_scale(
+		&self,
+		a: &Ciphertext,
+		scale: f64,
+		keys: &CircuitKeys,
+	) -> Result<Ciphertext> {
+		let encoder = keys
+			.encoder
+			.ok_or_else(|| Error::from(CircuitError::MissingKey("a CKKS encoder")))?;
+
+		let ones = encoder.encode_f64_at(
+			&vec![1.0; encoder.get_slot_count()],
+			&a.parms_id()?,
+			scale / a.scale()?,
+		)?;
+		let result = self.multiply_plain(a, &ones)?;
+
+		// The product of the scales is only `scale` up to rounding, and SEAL
+		// compares scales almost exactly.
+		result.set_scale(scale)?;
+		Ok(result)
+	}
+}
+
+impl CircuitEvaluator for NoiseEstimator {
+	fn rescale_to_next(
+		&self,
+		_a: &NoiseCiphertext,
+	) -> Result<NoiseCiphertext> {
+		Err(bfv_unsupported("NoiseEstimator::rescale_to_next"))
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &NoiseCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<NoiseCiphertext> {
+		self.rotate_rows(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		_a: &NoiseCiphertext,
+	) -> Result<f64> {
+		Err(bfv_unsupported("NoiseEstimator::scale"))
+	}
+
+	fn plain_scale(
+		&self,
+		_a: &NoisePlaintext,
+	) -> Result<f64> {
+		Err(bfv_unsupported("NoiseEstimator::plain_scale"))
+	}
+
+	fn set_scale(
+		&self,
+		_a: &NoiseCiphertext,
+		_scale: f64,
+	) -> Result<NoiseCiphertext> {
+		Err(bfv_unsupported("NoiseEstimator::set_scale"))
+	}
+
+	fn raise_scale(
+		&self,
+		_a: &NoiseCiphertext,
+		_scale: f64,
+		_keys: &CircuitKeys,
+	) -> Result<NoiseCiphertext> {
+		Err(bfv_unsupported("NoiseEstimator::raise_scale"))
+	}
+}
+
+impl CircuitEvaluator for PlainEvaluator<u64> {
+	fn rescale_to_next(
+		&self,
+		_a: &PlainCiphertext<u64>,
+	) -> Result<PlainCiphertext<u64>> {
+		Err(bfv_unsupported("PlainEvaluator::rescale_to_next"))
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &PlainCiphertext<u64>,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<PlainCiphertext<u64>> {
+		self.rotate_rows(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		_a: &PlainCiphertext<u64>,
+	) -> Result<f64> {
+		Err(bfv_unsupported("PlainEvaluator::scale"))
+	}
+
+	fn plain_scale(
+		&self,
+		_a: &PlainPlaintext<u64>,
+	) -> Result<f64> {
+		Err(bfv_unsupported("PlainEvaluator::plain_scale"))
+	}
+
+	fn set_scale(
+		&self,
+		_a: &PlainCiphertext<u64>,
+		_scale: f64,
+	) -> Result<PlainCiphertext<u64>> {
+		Err(bfv_unsupported("PlainEvaluator::set_scale"))
+	}
+
+	fn raise_scale(
+		&self,
+		_a: &PlainCiphertext<u64>,
+		_scale: f64,
+		_keys: &CircuitKeys,
+	) -> Result<PlainCiphertext<u64>> {
+		Err(bfv_unsupported("PlainEvaluator::raise_scale"))
+	}
+}
+
+impl CircuitEvaluator for PlainEvaluator<f64> {
+	fn rescale_to_next(
+		&self,
+		a: &PlainCiphertext<f64>,
+	) -> Result<PlainCiphertext<f64>> {
+		PlainEvaluator::rescale_to_next(self, a)
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &PlainCiphertext<f64>,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<PlainCiphertext<f64>> {
+		self.rotate_vector(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		a: &PlainCiphertext<f64>,
+	) -> Result<f64> {
+		Ok(a.scale())
+	}
+
+	fn plain_scale(
+		&self,
+		a: &PlainPlaintext<f64>,
+	) -> Result<f64> {
+		Ok(a.scale())
+	}
+
+	fn set_scale(
+		&self,
+		a: &PlainCiphertext<f64>,
+		scale: f64,
+	) -> Result<PlainCiphertext<f64>> {
+		Ok(PlainEvaluator::set_scale(self, a, scale))
+	}
+
+	fn raise_scale(
+		&self,
+		a: &PlainCiphertext<f64>,
+		scale: f64,
+		_keys: &CircuitKeys,
+	) -> Result<PlainCiphertext<f64>> {
+		Ok(PlainEvaluator::raise_scale(self, a, scale))
+	}
+}
+
+impl CircuitEvaluator for PrecisionEstimator {
+	fn rescale_to_next(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<PrecisionCiphertext> {
+		PrecisionEstimator::rescale_to_next(self, a)
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &PrecisionCiphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<PrecisionCiphertext> {
+		self.rotate_vector(a, steps, galois_keys)
+	}
+
+	fn scale(
+		&self,
+		a: &PrecisionCiphertext,
+	) -> Result<f64> {
+		Ok(a.scale())
+	}
+
+	fn plain_scale(
+		&self,
+		a: &PrecisionPlaintext,
+	) -> Result<f64> {
+		Ok(a.scale())
+	}
+
+	fn set_scale(
+		&self,
+		a: &PrecisionCiphertext,
+		scale: f64,
+	) -> Result<PrecisionCiphertext> {
+		PrecisionEstimator::set_scale(self, a, scale)
+	}
+
+	fn raise_scale(
+		&self,
+		a: &PrecisionCiphertext,
+		scale: f64,
+		_keys: &CircuitKeys,
+	) -> Result<PrecisionCiphertext> {
+		PrecisionEstimator::raise_scale(self, a, scale)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn bfv_context() -> Context {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		Context::new(&params, true, SecurityLevel::TC128).unwrap()
+	}
+
+	/// Builds (a * b + c) rotated by one slot, plus a copy of `a`.
+	fn bfv_circuit() -> Circuit {
+		let mut circuit = Circuit::new();
+
+		let a = circuit.input("a");
+		let b = circuit.input("b");
+		let c = circuit.constant("c");
+
+		let product = circuit.multiply(a, b);
+		let sum = circuit.add_plain(product, c);
+		let rotated = circuit.rotate(sum, 1);
+
+		// Not an output: compiled away.
+		circuit.rotate_columns(a);
+
+		circuit.output("y", rotated);
+		circuit.output("a", a);
+		circuit
+	}
+
+	#[test]
+	fn can_run_bfv_circuit() {
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = BFVEncoder::new(&ctx).unwrap();
+		let encryptor =
+			Encryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = Decryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = BFVEvaluator::new(&ctx).unwrap();
+
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let compiled = bfv_circuit().compile(SchemeType::Bfv).unwrap();
+
+		assert_eq!(compiled.depth(), 1);
+		assert_eq!(compiled.levels(), 0);
+		assert!(compiled.requirements().relinearization);
+		assert_eq!(
+			compiled
+				.requirements()
+				.galois_steps
+				.iter()
+				.copied()
+				.collect::<Vec<_>>(),
+			vec![1]
+		);
+		assert!(!compiled.requirements().column_rotation);
+		assert_eq!(compiled.inputs(), vec!["a", "b"]);
+		assert_eq!(compiled.constants(), vec!["c"]);
+
+		let a = (0..8192).map(|x| x % 11).collect::<Vec<i64>>();
+		let b = (0..8192).map(|x| x % 13 - 6).collect::<Vec<i64>>();
+		let c = (0..8192).map(|x| x % 5).collect::<Vec<i64>>();
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_enc = encryptor.encrypt(&encoder.encode_i64(&b).unwrap()).unwrap();
+		let c_plain = encoder.encode_i64(&c).unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+
+		let outputs = compiled
+			.run(
+				&evaluator,
+				&keys,
+				&[("a", &a_enc), ("b", &b_enc)],
+				&[("c", &c_plain)],
+			)
+			.unwrap();
+
+		assert_eq!(outputs.len(), 2);
+		assert_eq!(outputs["y"].num_polynomials().unwrap(), 2);
+
+		let y = encoder
+			.decode_i64(&decryptor.decrypt(&outputs["y"]).unwrap())
+			.unwrap();
+		let expected = (0..8192)
+			.map(|i| {
+				// Rows of 4096 slots rotate separately.
+				let j = i / 4096 * 4096 + (i + 1) % 4096;
+				a[j] * b[j] + c[j]
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(y, expected);
+
+		let a_out = encoder
+			.decode_i64(&decryptor.decrypt(&outputs["a"]).unwrap())
+			.unwrap();
+		assert_eq!(a_out, a);
+	}
+
+	#[test]
+	fn can_run_ckks_circuit() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let scale = 2.0f64.powi(40);
+		let encoder = CKKSEncoder::new(&ctx, scale).unwrap();
+		let encryptor =
+			Encryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = Decryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = CKKSEvaluator::new(&ctx).unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		// x^3, with x switched down to meet x^2.
+		let mut circuit = Circuit::new();
+		let x = circuit.input("x");
+		let squared = circuit.square(x);
+		let cubed = circuit.multiply(squared, x);
+		circuit.output("y", cubed);
+
+		let compiled = circuit.compile(SchemeType::Ckks).unwrap();
+
+		assert_eq!(compiled.depth(), 2);
+		assert_eq!(compiled.levels(), 2);
+		assert!(!compiled.requirements().galois());
+
+		let x = (0..encoder.get_slot_count())
+			.map(|i| (i % 16) as f64 / 8.0 - 1.0)
+			.collect::<Vec<_>>();
+		let x_enc = encryptor.encrypt(&encoder.encode_f64(&x).unwrap()).unwrap();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			..Default::default()
+		};
+
+		let outputs = compiled
+			.run(&evaluator, &keys, &[("x", &x_enc)], &[])
+			.unwrap();
+
+		let y = encoder
+			.decode_f64(&decryptor.decrypt(&outputs["y"]).unwrap())
+			.unwrap();
+
+		for (actual, x) in y.iter().zip(&x) {
+			assert!((actual - x * x * x).abs() < 1e-3);
+		}
+
+		// The same circuit estimated rather than run.
+		let estimator = PrecisionEstimator::new(&ctx, scale).unwrap();
+		let x_est = estimator.encrypt(1.0);
+		let outputs = compiled
+			.run(&estimator, &keys, &[("x", &x_est)], &[])
+			.unwrap();
+
+		assert_eq!(outputs["y"].level(), 2);
+	}
+
+	/// Runs a * x + b under CKKS, encrypted and on a [`PlainEvaluator`], and
+	/// checks both against the exact values.
+	fn check_affine(
+		primes: &[i32],
+		scale: f64,
+	) {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, primes).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = CKKSEncoder::new(&ctx, scale).unwrap();
+		let encryptor =
+			Encryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = Decryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = CKKSEvaluator::new(&ctx).unwrap();
+
+		// The product is rescaled to scale^2 / q and meets b at scale.
+		let mut circuit = Circuit::new();
+		let x = circuit.input("x");
+		let a = circuit.constant("a");
+		let b = circuit.constant("b");
+		let product = circuit.multiply_plain(x, a);
+		let sum = circuit.add_plain(product, b);
+		circuit.output("y", sum);
+
+		let compiled = circuit.compile(SchemeType::Ckks).unwrap();
+		assert!(compiled.requirements().encoder);
+		assert!(
+			!circuit
+				.compile(SchemeType::Bfv)
+				.unwrap()
+				.requirements()
+				.encoder
+		);
+
+		let slots = encoder.get_slot_count();
+		let x = (0..slots)
+			.map(|i| (i % 16) as f64 / 8.0 - 1.0)
+			.collect::<Vec<_>>();
+		let a = (0..slots).map(|i| (i % 5) as f64 / 4.0).collect::<Vec<_>>();
+		let b = (0..slots).map(|i| (i % 3) as f64 - 1.0).collect::<Vec<_>>();
+		let expected = (0..slots).map(|i| a[i] * x[i] + b[i]).collect::<Vec<_>>();
+
+		let x_enc = encryptor.encrypt(&encoder.encode_f64(&x).unwrap()).unwrap();
+		let a_plain = encoder.encode_f64(&a).unwrap();
+		let b_plain = encoder.encode_f64(&b).unwrap();
+		let constants = [("a", &a_plain), ("b", &b_plain)];
+
+		assert_eq!(
+			compiled
+				.run(
+					&evaluator,
+					&CircuitKeys::default(),
+					&[("x", &x_enc)],
+					&constants
+				)
+				.err(),
+			Some(Error::from(CircuitError::MissingKey("a CKKS encoder")))
+		);
+
+		let keys = CircuitKeys {
+			encoder: Some(&encoder),
+			..Default::default()
+		};
+
+		let outputs = compiled
+			.run(&evaluator, &keys, &[("x", &x_enc)], &constants)
+			.unwrap();
+		let y = encoder
+			.decode_f64(&decryptor.decrypt(&outputs["y"]).unwrap())
+			.unwrap();
+
+		for (actual, expected) in y.iter().zip(&expected) {
+			assert!((actual - expected).abs() < 1e-3);
+		}
+
+		let reference = PlainEvaluator::ckks(&ctx, scale).unwrap();
+		let x_plain = reference.encrypt(&x).unwrap();
+		let a_plain = reference.plaintext(&a).unwrap();
+		let b_plain = reference.plaintext(&b).unwrap();
+
+		let outputs = compiled
+			.run(
+				&reference,
+				&keys,
+				&[("x", &x_plain)],
+				&[("a", &a_plain), ("b", &b_plain)],
+			)
+			.unwrap();
+
+		for (actual, expected) in outputs["y"].values().iter().zip(&expected) {
+			assert!((actual - expected).abs() < 1e-3);
+		}
+	}
+
+	#[test]
+	fn can_align_close_ckks_scales() {
+		// 40-bit primes are within the tolerance of 2^40: the scale is
+		// overridden.
+		check_affine(&[60, 40, 40, 60], 2.0f64.powi(40));
+	}
+
+	#[test]
+	fn can_align_distant_ckks_scales() {
+		// The product ends up at about 2^30: it is raised with encoded ones.
+		check_affine(&[60, 60, 60], 2.0f64.powi(45));
+	}
+
+	#[test]
+	fn can_estimate_noise_of_circuit() {
+		let ctx = bfv_context();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let estimator = NoiseEstimator::new(&ctx).unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let compiled = bfv_circuit().compile(SchemeType::Bfv).unwrap();
+
+		let a = estimator.encrypt();
+		let b = estimator.encrypt();
+		let c = estimator.dense_plaintext();
+
+		let keys = CircuitKeys {
+			relinearization: Some(&relin_keys),
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+
+		let outputs = compiled
+			.run(&estimator, &keys, &[("a", &a), ("b", &b)], &[("c", &c)])
+			.unwrap();
+
+		assert!(outputs["y"].noise_budget() < a.noise_budget());
+		assert_eq!(outputs["a"].noise_budget(), a.noise_budget());
+	}
+
+	#[test]
+	fn circuit_errors_are_reported() {
+		let mut circuit = Circuit::new();
+		let x = circuit.input("x");
+		let swapped = circuit.rotate_columns(x);
+		circuit.output("y", swapped);
+
+		assert_eq!(
+			circuit.compile(SchemeType::Ckks),
+			Err(Error::from(CircuitError::Unsupported {
+				operation: "Circuit::rotate_columns",
+				scheme: SchemeType::Ckks,
+			}))
+		);
+
+		let compiled = circuit.compile(SchemeType::Bfv).unwrap();
+		assert!(compiled.requirements().column_rotation);
+
+		let ctx = bfv_context();
+		let estimator = NoiseEstimator::new(&ctx).unwrap();
+		let x = estimator.encrypt();
+
+		assert_eq!(
+			compiled
+				.run(&estimator, &CircuitKeys::default(), &[("x", &x)], &[])
+				.err(),
+			Some(Error::from(CircuitError::MissingKey("Galois keys")))
+		);
+
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+		let keys = CircuitKeys {
+			galois: Some(&galois_keys),
+			..Default::default()
+		};
+
+		assert_eq!(
+			compiled.run(&estimator, &keys, &[("z", &x)], &[]).err(),
+			Some(Error::from(CircuitError::MissingInput("x".to_owned())))
+		);
+
+		let mut other = Circuit::new();
+		other.input("x");
+		other.input("x");
+
+		assert_eq!(
+			other.compile(SchemeType::Bfv),
+			Err(Error::from(CircuitError::DuplicateName("x".to_owned())))
+		);
+
+		// A wire of `circuit` used in a circuit with fewer steps.
+		let mut other = Circuit::new();
+		other.output("y", swapped);
+
+		assert_eq!(
+			other.compile(SchemeType::Bfv),
+			Err(Error::from(CircuitError::UnknownWire(1)))
+		);
+
+		// The same wire where step 1 of the other circuit is a ciphertext.
+		let mut other = Circuit::new();
+		let y = other.input("y");
+		let squared = other.square(y);
+		let sum = other.add(squared, swapped);
+		other.output("y", sum);
+
+		assert_eq!(
+			other.compile(SchemeType::Bfv),
+			Err(Error::from(CircuitError::UnknownWire(1)))
+		);
+
+		let mut copy = circuit.clone();
+		copy.output("z", swapped);
+		assert!(copy.compile(SchemeType::Bfv).is_ok());
+	}
+}
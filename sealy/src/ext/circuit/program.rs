@@ -0,0 +1,309 @@
+use super::{Circuit, Op};
+use crate::error::CircuitError;
+use crate::{Context, Error, Plaintext, Result, Tensor};
+
+/// Magic bytes that open a serialized [`CircuitProgram`].
+const MAGIC: [u8; 4] = *b"SLYP";
+
+/// The current version of the program format.
+const VERSION: u16 = 1;
+
+/// A circuit together with the values of its plaintext constants, in a form
+/// a client can send to a server to have it computed there.
+///
+/// Steps read their operands by index, inputs are given by position when
+/// the program runs, and the constants travel with the program. The program
+/// is compiled by the server for its own scheme; see
+/// [`CircuitInterpreter`](super::interpreter::CircuitInterpreter).
+///
+/// # Format
+/// All integers are little endian. Names are a `u32` byte length followed
+/// by UTF-8.
+///
+/// | Field          | Size       | Notes                                      |
+/// |----------------|------------|--------------------------------------------|
+/// | magic          | 4          | `SLYP`                                     |
+/// | version        | 2          | currently 1                                |
+/// | step count     | 4          |                                            |
+/// | steps          | per step   | opcode (1), then its operands (see below)  |
+/// | output count   | 4          |                                            |
+/// | outputs        | per output | name, step index (4)                       |
+/// | constants      | per constant | a tensor stream, see [`Tensor::save_to`] |
+///
+/// Operands are step indices (4 each), except rotation steps, which are an
+/// `i32`, and input and constant names. The opcodes are: 0 input (name),
+/// 1 constant (name), 2 negate (a), 3 add (a, b), 4 sub (a, b), 5 multiply
+/// (a, b), 6 square (a), 7 add plain (a, p), 8 sub plain (a, p), 9 multiply
+/// plain (a, p), 10 rotate (a, steps), 11 rotate columns (a), 12 relinearize
+/// (a), 13 rescale (a), 14 mod switch (a) and 15 mod switch plain (p).
+/// Constants follow in the order they are declared.
+#[derive(Debug, Clone)]
+pub struct CircuitProgram {
+	circuit: Circuit,
+	constants: Vec<Tensor<Plaintext>>,
+}
+
+impl CircuitProgram {
+	/// Bundles a circuit with the values of its constants, in the order the
+	/// constants were declared.
+	///
+	/// Fails with [`CircuitError::CountMismatch`] if the number of values
+	/// differs from the number of constants, and as [`Circuit::compile`] does
+	/// if the circuit is malformed.
+	pub fn new(
+		circuit: Circuit,
+		constants: Vec<Tensor<Plaintext>>,
+	) -> Result<Self> {
+		circuit.check()?;
+
+		let expected = circuit
+			.ops
+			.iter()
+			.filter(|op| matches!(op, Op::Constant(_)))
+			.count();
+
+		if constants.len() != expected {
+			return Err(CircuitError::CountMismatch {
+				what: "constants",
+				expected,
+				actual: constants.len(),
+			}
+			.into());
+		}
+
+		Ok(Self {
+			circuit,
+			constants,
+		})
+	}
+
+	/// Returns the circuit.
+	pub fn circuit(&self) -> &Circuit {
+		&self.circuit
+	}
+
+	/// Returns the values of the constants, in the order they were declared.
+	pub fn constants(&self) -> &[Tensor<Plaintext>] {
+		&self.constants
+	}
+
+	/// Serializes the program.
+	pub fn as_bytes(&self) -> Result<Vec<u8>> {
+		let mut bytes = Vec::new();
+
+		bytes.extend_from_slice(&MAGIC);
+		bytes.extend_from_slice(&VERSION.to_le_bytes());
+		write_u32(&mut bytes, self.circuit.ops.len())?;
+
+		for op in &self.circuit.ops {
+			write_op(&mut bytes, op)?;
+		}
+
+		write_u32(&mut bytes, self.circuit.outputs.len())?;
+
+		for (name, index) in &self.circuit.outputs {
+			write_name(&mut bytes, name)?;
+			write_u32(&mut bytes, *index)?;
+		}
+
+		for constant in &self.constants {
+			constant.save_to(&mut bytes)?;
+		}
+
+		Ok(bytes)
+	}
+
+	/// Loads a program, rejecting it if it is malformed.
+	///
+	/// * `context` - The context to load the constants under.
+	/// * `bytes` - The serialized program.
+	pub fn from_bytes(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let mut reader = bytes;
+
+		if take::<4>(&mut reader)? != MAGIC {
+			return Err(malformed("not a circuit program"));
+		}
+
+		let version = u16::from_le_bytes(take(&mut reader)?);
+
+		if version != VERSION {
+			return Err(malformed(&format!(
+				"unsupported program version {}",
+				version
+			)));
+		}
+
+		// Don't preallocate from untrusted counts; every entry takes at least
+		// one byte, so the input bounds how much we read.
+		let mut circuit = Circuit::new();
+
+		for _ in 0..read_u32(&mut reader)? {
+			let op = read_op(&mut reader)?;
+			circuit.ops.push(op);
+		}
+
+		for _ in 0..read_u32(&mut reader)? {
+			let name = read_name(&mut reader)?;
+			let index = read_u32(&mut reader)? as usize;
+			circuit.outputs.push((name, index));
+		}
+
+		let count = circuit
+			.ops
+			.iter()
+			.filter(|op| matches!(op, Op::Constant(_)))
+			.count();
+		let mut constants = Vec::new();
+
+		for _ in 0..count {
+			constants.push(Tensor::load_from(context, &mut reader)?);
+		}
+
+		if !reader.is_empty() {
+			return Err(malformed("trailing bytes after the last constant"));
+		}
+
+		Self::new(circuit, constants)
+	}
+}
+
+fn write_op(
+	bytes: &mut Vec<u8>,
+	op: &Op,
+) -> Result<()> {
+	let opcode = match op {
+		Op::Input(_) => 0,
+		Op::Constant(_) => 1,
+		Op::Negate(_) => 2,
+		Op::Add(..) => 3,
+		Op::Sub(..) => 4,
+		Op::Multiply(..) => 5,
+		Op::Square(_) => 6,
+		Op::AddPlain(..) => 7,
+		Op::SubPlain(..) => 8,
+		Op::MultiplyPlain(..) => 9,
+		Op::Rotate(..) => 10,
+		Op::RotateColumns(_) => 11,
+		Op::Relinearize(_) => 12,
+		Op::Rescale(_) => 13,
+		Op::ModSwitch(_) => 14,
+		Op::ModSwitchPlain(_) => 15,
+		// Only the compiler adds these, and programs hold uncompiled circuits.
+		Op::MatchScale(..) | Op::MatchPlainScale(..) => {
+			return Err(Error::InvalidArgument.in_operation("CircuitProgram::as_bytes"))
+		}
+	};
+
+	bytes.push(opcode);
+
+	match op {
+		Op::Input(name) | Op::Constant(name) => write_name(bytes, name)?,
+		Op::Rotate(a, steps) => {
+			write_u32(bytes, *a)?;
+			bytes.extend_from_slice(&steps.to_le_bytes());
+		}
+		op => {
+			for operand in op.operands() {
+				write_u32(bytes, operand)?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn read_op(reader: &mut &[u8]) -> Result<Op> {
+	let [opcode] = take(reader)?;
+
+	let op = match opcode {
+		0 => Op::Input(read_name(reader)?),
+		1 => Op::Constant(read_name(reader)?),
+		10 => Op::Rotate(
+			read_u32(reader)? as usize,
+			i32::from_le_bytes(take(reader)?),
+		),
+		2 | 6 | 11..=15 => {
+			let a = read_u32(reader)? as usize;
+
+			match opcode {
+				2 => Op::Negate(a),
+				6 => Op::Square(a),
+				11 => Op::RotateColumns(a),
+				12 => Op::Relinearize(a),
+				13 => Op::Rescale(a),
+				14 => Op::ModSwitch(a),
+				_ => Op::ModSwitchPlain(a),
+			}
+		}
+		3..=5 | 7..=9 => {
+			let a = read_u32(reader)? as usize;
+			let b = read_u32(reader)? as usize;
+
+			match opcode {
+				3 => Op::Add(a, b),
+				4 => Op::Sub(a, b),
+				5 => Op::Multiply(a, b),
+				7 => Op::AddPlain(a, b),
+				8 => Op::SubPlain(a, b),
+				_ => Op::MultiplyPlain(a, b),
+			}
+		}
+		opcode => return Err(malformed(&format!("unknown opcode {}", opcode))),
+	};
+
+	Ok(op)
+}
+
+fn write_u32(
+	bytes: &mut Vec<u8>,
+	value: usize,
+) -> Result<()> {
+	let value = u32::try_from(value).map_err(|_| Error::InvalidArgument)?;
+	bytes.extend_from_slice(&value.to_le_bytes());
+	Ok(())
+}
+
+fn read_u32(reader: &mut &[u8]) -> Result<u32> {
+	Ok(u32::from_le_bytes(take(reader)?))
+}
+
+fn write_name(
+	bytes: &mut Vec<u8>,
+	name: &str,
+) -> Result<()> {
+	write_u32(bytes, name.len())?;
+	bytes.extend_from_slice(name.as_bytes());
+	Ok(())
+}
+
+fn read_name(reader: &mut &[u8]) -> Result<String> {
+	let len = read_u32(reader)? as usize;
+
+	if reader.len() < len {
+		return Err(malformed("unexpected end of data"));
+	}
+
+	let (name, rest) = reader.split_at(len);
+	*reader = rest;
+
+	String::from_utf8(name.to_vec()).map_err(|_| malformed("name is not UTF-8"))
+}
+
+/// Reads `N` bytes, failing instead of panicking on truncated input.
+fn take<const N: usize>(reader: &mut &[u8]) -> Result<[u8; N]> {
+	if reader.len() < N {
+		return Err(malformed("unexpected end of data"));
+	}
+
+	let (head, rest) = reader.split_at(N);
+	*reader = rest;
+
+	Ok(head.try_into().unwrap())
+}
+
+fn malformed(reason: &str) -> Error {
+	Error::SerializationError(Box::new(format!("malformed circuit program: {}", reason)))
+}
@@ -0,0 +1,472 @@
+//! Secure federated averaging over CKKS.
+//!
+//! Three roles take part in a round:
+//! * the [`KeyHolder`] owns the secret key, hands out the public key and
+//!   decrypts the aggregated model,
+//! * every [`FederatedClient`] encrypts its model update with the public key
+//!   and sends it as a [`ClientUpdate`],
+//! * the [`Aggregator`] combines the encrypted updates without being able to
+//!   read them, either as a plain average or weighted by the number of
+//!   samples each client trained on.
+//!
+//! All three must share the same [`Context`] and scale.
+
+use crate::{
+	Asym, CKKSEncoder, CKKSEvaluator, Ciphertext, Context, Error, Evaluator, KeyGenerator,
+	PublicKey, Result, Shape, ShapeMismatch, Tensor, TensorContainer, TensorDecryptor,
+	TensorEncoder, TensorEncryptor, TensorEvaluator,
+};
+
+/// An encrypted model update and the number of samples it was computed on.
+#[derive(Debug, Clone)]
+pub struct ClientUpdate {
+	update: Tensor<Ciphertext>,
+	samples: u64,
+}
+
+impl ClientUpdate {
+	/// Creates an update from an encrypted tensor.
+	pub fn new(
+		update: Tensor<Ciphertext>,
+		samples: u64,
+	) -> Self {
+		Self {
+			update,
+			samples,
+		}
+	}
+
+	/// Returns the encrypted update.
+	pub fn tensor(&self) -> &Tensor<Ciphertext> {
+		&self.update
+	}
+
+	/// Returns the number of samples the update was computed on.
+	pub fn samples(&self) -> u64 {
+		self.samples
+	}
+
+	/// Serializes the update: the sample count as a little endian `u64`
+	/// followed by a [`TensorContainer`].
+	///
+	/// Fails with [`Error::ContextMismatch`] if the tensor records a slot
+	/// count other than the context's.
+	///
+	/// * `context` - The context the update was encrypted under.
+	pub fn as_bytes(
+		&self,
+		context: &Context,
+	) -> Result<Vec<u8>> {
+		let slot_count = crate::ext::tensor::slot_count(context)?;
+
+		if self.update.slot_count().is_some_and(|n| n != slot_count) {
+			return Err(Error::ContextMismatch);
+		}
+
+		let container = TensorContainer::from_tensor(self.update.clone(), slot_count);
+
+		let mut bytes = self.samples.to_le_bytes().to_vec();
+		bytes.extend_from_slice(&container.as_bytes(context)?);
+
+		Ok(bytes)
+	}
+
+	/// Loads an update serialized with [`ClientUpdate::as_bytes`].
+	///
+	/// * `context` - The context to load the update under.
+	/// * `bytes` - The serialized update.
+	pub fn from_bytes(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		if bytes.len() < 8 {
+			return Err(Error::InvalidArgument);
+		}
+
+		let (samples, container) = bytes.split_at(8);
+		let samples = u64::from_le_bytes(samples.try_into().map_err(|_| Error::InvalidArgument)?);
+		let container = TensorContainer::<Ciphertext>::from_bytes(context, container)?;
+
+		Ok(Self::new(container.into_tensor(), samples))
+	}
+}
+
+/// Encodes and encrypts model updates with the key holder's public key.
+pub struct FederatedClient {
+	encoder: TensorEncoder<CKKSEncoder>,
+	encryptor: TensorEncryptor<Asym>,
+}
+
+impl FederatedClient {
+	/// Creates a client.
+	///
+	/// * `ctx` - The context shared by all the participants.
+	/// * `public_key` - The key holder's public key.
+	/// * `scale` - The CKKS scale shared by all the participants.
+	pub fn new(
+		ctx: &Context,
+		public_key: &PublicKey,
+		scale: f64,
+	) -> Result<Self> {
+		Ok(Self {
+			encoder: TensorEncoder::new(CKKSEncoder::new(ctx, scale)?),
+			encryptor: TensorEncryptor::with_public_key(ctx, public_key)?,
+		})
+	}
+
+	/// Encrypts a flat model update computed on `samples` samples.
+	pub fn encrypt_update(
+		&self,
+		update: &[f64],
+		samples: u64,
+	) -> Result<ClientUpdate> {
+		self.encrypt_update_shaped(update, vec![update.len()], samples)
+	}
+
+	/// Encrypts a model update of the given shape, in row-major order,
+	/// computed on `samples` samples.
+	pub fn encrypt_update_shaped(
+		&self,
+		update: &[f64],
+		dims: Vec<usize>,
+		samples: u64,
+	) -> Result<ClientUpdate> {
+		let encoded = self.encoder.encode_f64_shaped(update, dims)?;
+
+		Ok(ClientUpdate::new(
+			self.encryptor.encrypt(&encoded)?,
+			samples,
+		))
+	}
+
+	/// Returns the number of slots per chunk.
+	pub fn get_slot_count(&self) -> usize {
+		self.encoder.get_slot_count()
+	}
+}
+
+/// Combines encrypted model updates.
+///
+/// Every update must have the same shape, and there must be at least as many
+/// as the configured minimum; otherwise aggregation fails with
+/// [`Error::ShapeMismatch`] or [`Error::InvalidArgument`].
+///
+/// The weights are applied by multiplying with a plaintext, so the result is
+/// at the scale of the updates times the encoder scale. It can be decrypted
+/// as is.
+pub struct Aggregator {
+	encoder: TensorEncoder<CKKSEncoder>,
+	evaluator: TensorEvaluator<CKKSEvaluator>,
+	shape: Option<Shape>,
+	min_clients: usize,
+}
+
+impl Aggregator {
+	/// Creates an aggregator that accepts updates of any shape from at least
+	/// one client.
+	///
+	/// * `ctx` - The context shared by all the participants.
+	/// * `scale` - The CKKS scale shared by all the participants.
+	pub fn new(
+		ctx: &Context,
+		scale: f64,
+	) -> Result<Self> {
+		Ok(Self {
+			encoder: TensorEncoder::new(CKKSEncoder::new(ctx, scale)?),
+			evaluator: TensorEvaluator::ckks(ctx)?,
+			shape: None,
+			min_clients: 1,
+		})
+	}
+
+	/// Only accepts updates of the given shape.
+	pub fn with_shape(
+		mut self,
+		shape: Shape,
+	) -> Self {
+		self.shape = Some(shape);
+		self
+	}
+
+	/// Requires at least `min_clients` updates per round.
+	pub fn with_min_clients(
+		mut self,
+		min_clients: usize,
+	) -> Self {
+		self.min_clients = min_clients.max(1);
+		self
+	}
+
+	/// Averages the updates, giving each client the same weight.
+	pub fn average(
+		&self,
+		updates: &[ClientUpdate],
+	) -> Result<Tensor<Ciphertext>> {
+		let tensors = self.check(updates)?;
+		let sum = self.evaluator.add_many(&tensors)?;
+
+		self.scale(&sum, 1.0 / updates.len() as f64)
+	}
+
+	/// Averages the updates, weighting each client by its number of samples.
+	///
+	/// Fails with [`Error::InvalidArgument`] if no client reports a sample.
+	pub fn weighted_average(
+		&self,
+		updates: &[ClientUpdate],
+	) -> Result<Tensor<Ciphertext>> {
+		let total = updates
+			.iter()
+			.try_fold(0u64, |acc, update| acc.checked_add(update.samples))
+			.ok_or(Error::InvalidArgument)?;
+
+		if total == 0 {
+			return Err(Error::InvalidArgument);
+		}
+
+		let weights = updates
+			.iter()
+			.map(|update| update.samples as f64 / total as f64)
+			.collect::<Vec<_>>();
+
+		self.weighted_sum(updates, &weights)
+	}
+
+	/// Returns the sum of the updates, each multiplied by its weight.
+	pub fn weighted_sum(
+		&self,
+		updates: &[ClientUpdate],
+		weights: &[f64],
+	) -> Result<Tensor<Ciphertext>> {
+		if weights.len() != updates.len() {
+			return Err(Error::InvalidArgument);
+		}
+
+		let tensors = self.check(updates)?;
+
+		let weighted = tensors
+			.iter()
+			.zip(weights)
+			.map(|(tensor, weight)| self.scale(tensor, *weight))
+			.collect::<Result<Vec<_>>>()?;
+
+		self.evaluator.add_many(&weighted)
+	}
+
+	/// Checks the number of updates and their shapes, and returns their
+	/// tensors.
+	fn check(
+		&self,
+		updates: &[ClientUpdate],
+	) -> Result<Vec<Tensor<Ciphertext>>> {
+		if updates.len() < self.min_clients {
+			return Err(Error::InvalidArgument);
+		}
+
+		if let Some(expected) = &self.shape {
+			for update in updates {
+				if update.update.shape() != Some(expected) {
+					return Err(Error::ShapeMismatch(Box::new(ShapeMismatch {
+						left: expected.dims().to_vec(),
+						right: update
+							.update
+							.shape()
+							.map(|shape| shape.dims().to_vec())
+							.unwrap_or_default(),
+					})));
+				}
+			}
+		}
+
+		let tensors = updates
+			.iter()
+			.map(|update| update.update.clone())
+			.collect::<Vec<_>>();
+
+		for tensor in &tensors[1..] {
+			tensors[0].check_same_shape(tensor)?;
+		}
+
+		Ok(tensors)
+	}
+
+	/// Multiplies every element of `a` by `factor`.
+	fn scale(
+		&self,
+		a: &Tensor<Ciphertext>,
+		factor: f64,
+	) -> Result<Tensor<Ciphertext>> {
+		let factor = self
+			.encoder
+			.encode_f64(&vec![factor; self.encoder.get_slot_count()])?;
+
+		self.evaluator.multiply_plain(a, &factor)
+	}
+}
+
+/// Holds the secret key and decrypts aggregated models.
+pub struct KeyHolder {
+	public_key: PublicKey,
+	encoder: TensorEncoder<CKKSEncoder>,
+	decryptor: TensorDecryptor,
+}
+
+impl KeyHolder {
+	/// Generates a fresh key pair.
+	///
+	/// * `ctx` - The context shared by all the participants.
+	/// * `scale` - The CKKS scale shared by all the participants.
+	pub fn new(
+		ctx: &Context,
+		scale: f64,
+	) -> Result<Self> {
+		let gen = KeyGenerator::new(ctx)?;
+
+		Ok(Self {
+			public_key: gen.create_public_key()?,
+			encoder: TensorEncoder::new(CKKSEncoder::new(ctx, scale)?),
+			decryptor: TensorDecryptor::new(ctx, &gen.secret_key()?)?,
+		})
+	}
+
+	/// Returns the public key to hand out to the clients.
+	pub fn public_key(&self) -> &PublicKey {
+		&self.public_key
+	}
+
+	/// Decrypts and decodes an aggregated model.
+	pub fn decrypt(
+		&self,
+		aggregate: &Tensor<Ciphertext>,
+	) -> Result<Vec<f64>> {
+		self.encoder.decode_f64(&self.decryptor.decrypt(aggregate)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn mk_ctx() -> Context {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		Context::new(&params, false, SecurityLevel::TC128).unwrap()
+	}
+
+	fn assert_close(
+		actual: &[f64],
+		expected: &[f64],
+	) {
+		assert_eq!(actual.len(), expected.len());
+		for (actual, expected) in actual.iter().zip(expected) {
+			assert!((actual - expected).abs() < 1e-4);
+		}
+	}
+
+	#[test]
+	fn can_average_client_updates() {
+		let ctx = mk_ctx();
+		let scale = 2.0f64.powi(40);
+
+		let key_holder = KeyHolder::new(&ctx, scale).unwrap();
+		let client = FederatedClient::new(&ctx, key_holder.public_key(), scale).unwrap();
+		let aggregator = Aggregator::new(&ctx, scale)
+			.unwrap()
+			.with_shape(Shape::new(vec![2, 3_000]).unwrap())
+			.with_min_clients(2);
+
+		let gradients = (0..3)
+			.map(|c| {
+				(0..6_000)
+					.map(|i| ((i + c) % 10) as f64 / 10.0)
+					.collect::<Vec<_>>()
+			})
+			.collect::<Vec<_>>();
+		let samples = [10, 30, 60];
+
+		// Updates travel to the aggregator serialized.
+		let updates = gradients
+			.iter()
+			.zip(samples)
+			.map(|(gradient, samples)| {
+				let update = client
+					.encrypt_update_shaped(gradient, vec![2, 3_000], samples)
+					.unwrap();
+				let bytes = update.as_bytes(&ctx).unwrap();
+
+				ClientUpdate::from_bytes(&ctx, &bytes).unwrap()
+			})
+			.collect::<Vec<_>>();
+
+		assert_eq!(updates[1].samples(), 30);
+
+		let foreign = ClientUpdate::new(updates[0].tensor().clone().with_slot_count(1), 10);
+		assert_eq!(foreign.as_bytes(&ctx).unwrap_err(), Error::ContextMismatch);
+
+		let average = key_holder
+			.decrypt(&aggregator.average(&updates).unwrap())
+			.unwrap();
+		let expected = (0..6_000)
+			.map(|i| gradients.iter().map(|g| g[i]).sum::<f64>() / 3.0)
+			.collect::<Vec<_>>();
+		assert_close(&average, &expected);
+
+		let weighted = key_holder
+			.decrypt(&aggregator.weighted_average(&updates).unwrap())
+			.unwrap();
+		let expected = (0..6_000)
+			.map(|i| {
+				gradients
+					.iter()
+					.zip(samples)
+					.map(|(g, s)| g[i] * s as f64)
+					.sum::<f64>() / 100.0
+			})
+			.collect::<Vec<_>>();
+		assert_close(&weighted, &expected);
+	}
+
+	#[test]
+	fn rejects_bad_rounds() {
+		let ctx = mk_ctx();
+		let scale = 2.0f64.powi(40);
+
+		let key_holder = KeyHolder::new(&ctx, scale).unwrap();
+		let client = FederatedClient::new(&ctx, key_holder.public_key(), scale).unwrap();
+		let aggregator = Aggregator::new(&ctx, scale)
+			.unwrap()
+			.with_shape(Shape::flat(4))
+			.with_min_clients(2);
+
+		let update = client.encrypt_update(&[1.0, 2.0, 3.0, 4.0], 5).unwrap();
+		let short = client.encrypt_update(&[1.0, 2.0, 3.0], 5).unwrap();
+		let empty = client.encrypt_update(&[1.0, 2.0, 3.0, 4.0], 0).unwrap();
+
+		assert_eq!(
+			aggregator.average(&[update.clone()]).unwrap_err(),
+			Error::InvalidArgument
+		);
+		assert!(matches!(
+			aggregator.average(&[update.clone(), short]),
+			Err(Error::ShapeMismatch(_))
+		));
+		assert_eq!(
+			aggregator
+				.weighted_average(&[empty.clone(), empty])
+				.unwrap_err(),
+			Error::InvalidArgument
+		);
+		assert_eq!(
+			aggregator
+				.weighted_sum(&[update.clone(), update], &[1.0])
+				.unwrap_err(),
+			Error::InvalidArgument
+		);
+	}
+}
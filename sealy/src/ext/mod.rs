@@ -1,5 +1,14 @@
 //! Extension modules for seal bindings.
 
+/// Computations over encrypted values, declared up front and run later.
+pub mod circuit;
+
+/// Secure federated averaging of model updates.
+pub mod federated;
+
 /// Operations in tensor of ciphertexts. It allows us to perform operations
 /// on multiple ciphertexts at once, dribbling the size limits of the scheme.
 pub mod tensor;
+
+/// Encrypted vectors with arithmetic operators.
+pub mod vector;
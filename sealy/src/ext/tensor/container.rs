@@ -0,0 +1,432 @@
+use super::{Shape, Tensor};
+use crate::{
+	CompressionType, Context, Error, FromBytes, MalformedInput, Result, ShapeMismatch, ToBytes,
+};
+
+/// Magic bytes that open a serialized [`TensorContainer`].
+const MAGIC: [u8; 4] = *b"SLYC";
+
+/// The current version of the container format.
+const VERSION: u16 = 1;
+
+/// A self-describing envelope for a tensor of ciphertexts or plaintexts.
+///
+/// Besides the chunks themselves, the container records the logical shape of
+/// the data, the number of real elements it holds (the last chunk is usually
+/// padded) and a fingerprint of the context that produced it, so a single blob
+/// can be stored or sent and loaded back safely.
+///
+/// # Format
+/// All integers are little endian.
+///
+/// | Field          | Size            | Notes                                  |
+/// |----------------|-----------------|----------------------------------------|
+/// | magic          | 4               | `SLYC`                                 |
+/// | version        | 2               | currently 1                            |
+/// | scheme         | 1               | [`crate::SchemeType`] as `u8`          |
+/// | reserved       | 1               | zero                                   |
+/// | fingerprint    | 32              | the context's key parms id             |
+/// | element count  | 8               | product of the shape                   |
+/// | rank           | 4               | number of dimensions                   |
+/// | dimensions     | 8 per dimension |                                        |
+/// | chunk count    | 8               |                                        |
+/// | chunks         | per chunk       | length (8), FNV-1a checksum (8), bytes |
+#[derive(Debug, Clone)]
+pub struct TensorContainer<T> {
+	tensor: Tensor<T>,
+}
+
+impl<T> TensorContainer<T> {
+	/// Wraps a tensor, recording its shape. Tensors without a known shape are
+	/// recorded as holding every slot of their chunks, `slot_count` per chunk.
+	pub fn from_tensor(
+		tensor: Tensor<T>,
+		slot_count: usize,
+	) -> Self {
+		let tensor = tensor.with_slot_count(slot_count);
+
+		if tensor.shape().is_some() {
+			return Self {
+				tensor,
+			};
+		}
+
+		let len = tensor.len() * slot_count;
+
+		Self {
+			tensor: tensor.with_known_shape(Shape::flat(len)),
+		}
+	}
+
+	/// Wraps a tensor holding a flat sequence of `len` elements.
+	///
+	/// Fails with [`Error::ShapeMismatch`] if the tensor has a known shape
+	/// holding a different number of elements.
+	pub fn new(
+		tensor: Tensor<T>,
+		len: usize,
+	) -> Result<Self> {
+		if let Some(shape) = tensor.shape() {
+			if shape.numel() != len {
+				return Err(shape_mismatch(shape.dims(), &[len]));
+			}
+		}
+
+		Ok(Self {
+			tensor: tensor.with_known_shape(Shape::flat(len)),
+		})
+	}
+
+	/// Wraps a tensor holding data of the given logical shape, stored in
+	/// row-major order.
+	///
+	/// Fails with [`Error::ShapeMismatch`] if the tensor has a different
+	/// known shape.
+	pub fn with_shape(
+		tensor: Tensor<T>,
+		shape: Vec<usize>,
+	) -> Result<Self> {
+		let shape = Shape::new(shape)?;
+
+		if let Some(known) = tensor.shape() {
+			if known != &shape {
+				return Err(shape_mismatch(known.dims(), shape.dims()));
+			}
+		}
+
+		Ok(Self {
+			tensor: tensor.with_known_shape(shape),
+		})
+	}
+
+	/// Returns the wrapped tensor, which always carries the recorded shape.
+	pub fn tensor(&self) -> &Tensor<T> {
+		&self.tensor
+	}
+
+	/// Consumes the container, returning the wrapped tensor.
+	pub fn into_tensor(self) -> Tensor<T> {
+		self.tensor
+	}
+
+	/// Returns the logical shape of the data.
+	pub fn shape(&self) -> &[usize] {
+		self.tensor.shape().map_or(&[], Shape::dims)
+	}
+
+	/// Returns the number of real elements in the data.
+	pub fn len(&self) -> usize {
+		self.shape().iter().product()
+	}
+
+	/// Returns true if the container holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<T> TensorContainer<T>
+where
+	T: ToBytes,
+{
+	/// Serializes the container, compressing the chunks with
+	/// [`CompressionType::ZStd`].
+	///
+	/// * `context` - The context the tensor was produced under.
+	pub fn as_bytes(
+		&self,
+		context: &Context,
+	) -> Result<Vec<u8>> {
+		self.as_bytes_with(context, CompressionType::default())
+	}
+
+	/// Serializes the container, compressing the chunks with the given mode.
+	///
+	/// * `context` - The context the tensor was produced under.
+	/// * `compression` - The compression mode for the chunks.
+	pub fn as_bytes_with(
+		&self,
+		context: &Context,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let mut bytes = Vec::new();
+
+		bytes.extend_from_slice(&MAGIC);
+		bytes.extend_from_slice(&VERSION.to_le_bytes());
+		write_fingerprint(&mut bytes, context)?;
+		bytes.extend_from_slice(&(self.len() as u64).to_le_bytes());
+		bytes.extend_from_slice(&(self.shape().len() as u32).to_le_bytes());
+
+		for dim in self.shape() {
+			bytes.extend_from_slice(&(*dim as u64).to_le_bytes());
+		}
+
+		bytes.extend_from_slice(&(self.tensor.len() as u64).to_le_bytes());
+
+		for chunk in self.tensor.iter() {
+			let chunk = chunk.as_bytes_with(compression)?;
+
+			bytes.extend_from_slice(&(chunk.len() as u64).to_le_bytes());
+			bytes.extend_from_slice(&checksum(&chunk).to_le_bytes());
+			bytes.extend_from_slice(&chunk);
+		}
+
+		Ok(bytes)
+	}
+}
+
+impl<T> TensorContainer<T>
+where
+	T: FromBytes<State = Context>,
+{
+	/// Loads a container, rejecting it if it was produced under a different
+	/// context or if any chunk fails its checksum.
+	///
+	/// Fails with [`Error::MalformedInput`] if the recorded shape overflows
+	/// or holds more elements than the chunks have slots.
+	///
+	/// * `context` - The context to load the tensor under.
+	/// * `bytes` - The serialized container.
+	pub fn from_bytes(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let mut reader = Reader(bytes);
+
+		if reader.take(MAGIC.len())? != MAGIC {
+			return Err(malformed("not a tensor container"));
+		}
+
+		let version = reader.u16()?;
+
+		if version != VERSION {
+			return Err(malformed(&format!(
+				"unsupported container version {}",
+				version
+			)));
+		}
+
+		let mut expected = Vec::new();
+		write_fingerprint(&mut expected, context)?;
+
+		if reader.take(expected.len())? != expected.as_slice() {
+			return Err(Error::ContextMismatch);
+		}
+
+		let len = reader.u64()?;
+		let rank = reader.u32()?;
+		let mut shape = Vec::new();
+
+		for _ in 0..rank {
+			shape.push(to_usize(reader.u64()?)?);
+		}
+
+		let shape = Shape::new(shape).map_err(|_| MalformedInput::ShapeOverflow)?;
+
+		if shape.numel() as u64 != len {
+			return Err(malformed("shape does not match the element count"));
+		}
+
+		let num_chunks = reader.u64()?;
+		let slot_count = super::slot_count(context)?;
+		let capacity = num_chunks.saturating_mul(slot_count as u64);
+
+		if len > capacity {
+			return Err(MalformedInput::ShapeExceedsData {
+				elements: len,
+				capacity,
+			}
+			.into());
+		}
+		let mut chunks = Vec::new();
+
+		for _ in 0..num_chunks {
+			let chunk_len = to_usize(reader.u64()?)?;
+			let sum = reader.u64()?;
+			let chunk = reader.take(chunk_len)?;
+
+			if checksum(chunk) != sum {
+				return Err(malformed("chunk checksum mismatch"));
+			}
+
+			chunks.push(T::from_bytes(context, chunk)?);
+		}
+
+		if !reader.0.is_empty() {
+			return Err(malformed("trailing bytes after the last chunk"));
+		}
+
+		Ok(Self {
+			tensor: Tensor::with_shape(chunks, shape).with_slot_count(slot_count),
+		})
+	}
+}
+
+/// Writes the scheme and key parms id of the context.
+fn write_fingerprint(
+	bytes: &mut Vec<u8>,
+	context: &Context,
+) -> Result<()> {
+	let scheme = context.get_encryption_parameters()?.get_scheme()?;
+
+	bytes.push(scheme.to_u8());
+	bytes.push(0);
+
+	for word in context.get_key_parms_id()? {
+		bytes.extend_from_slice(&word.to_le_bytes());
+	}
+
+	Ok(())
+}
+
+/// 64-bit FNV-1a. This guards against corruption, not tampering.
+fn checksum(bytes: &[u8]) -> u64 {
+	bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+		(hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+	})
+}
+
+fn shape_mismatch(
+	left: &[usize],
+	right: &[usize],
+) -> Error {
+	Error::ShapeMismatch(Box::new(ShapeMismatch {
+		left: left.to_vec(),
+		right: right.to_vec(),
+	}))
+}
+
+fn malformed(reason: &str) -> Error {
+	Error::SerializationError(Box::new(format!("malformed tensor container: {}", reason)))
+}
+
+fn to_usize(value: u64) -> Result<usize> {
+	usize::try_from(value).map_err(|_| malformed("length does not fit in memory"))
+}
+
+/// A cursor over the container bytes that fails instead of panicking on
+/// truncated input.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+	fn take(
+		&mut self,
+		len: usize,
+	) -> Result<&'a [u8]> {
+		if self.0.len() < len {
+			return Err(malformed("unexpected end of data"));
+		}
+
+		let (head, tail) = self.0.split_at(len);
+		self.0 = tail;
+
+		Ok(head)
+	}
+
+	fn u16(&mut self) -> Result<u16> {
+		Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+	}
+
+	fn u32(&mut self) -> Result<u32> {
+		Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+	}
+
+	fn u64(&mut self) -> Result<u64> {
+		Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn mk_ctx(bit_sizes: &[i32]) -> Context {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, bit_sizes).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		Context::new(&params, false, SecurityLevel::TC128).unwrap()
+	}
+
+	#[test]
+	fn can_roundtrip_container_and_reject_other_context() {
+		let ctx = mk_ctx(&[60, 40, 40, 60]);
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+
+		let data = (0..6_000).map(|x| x as f64).collect::<Vec<_>>();
+		let ciphertext = encryptor
+			.encrypt(&encoder.encode_f64(&data).unwrap())
+			.unwrap();
+
+		let container = TensorContainer::with_shape(
+			ciphertext.reshape(vec![2, 3_000]).unwrap(),
+			vec![2, 3_000],
+		)
+		.unwrap();
+		let mut bytes = container.as_bytes(&ctx).unwrap();
+
+		let loaded = TensorContainer::<Ciphertext>::from_bytes(&ctx, &bytes).unwrap();
+
+		assert_eq!(loaded.shape(), &[2, 3_000]);
+		assert_eq!(loaded.len(), 6_000);
+		assert_eq!(loaded.tensor().len(), container.tensor().len());
+		assert_eq!(loaded.tensor().shape().unwrap().dims(), &[2, 3_000]);
+
+		let other_ctx = mk_ctx(&[60, 40, 60]);
+
+		assert_eq!(
+			TensorContainer::<Ciphertext>::from_bytes(&other_ctx, &bytes).unwrap_err(),
+			Error::ContextMismatch
+		);
+
+		let last = bytes.len() - 1;
+		bytes[last] ^= 0xff;
+
+		assert!(TensorContainer::<Ciphertext>::from_bytes(&ctx, &bytes).is_err());
+	}
+
+	#[test]
+	fn rejects_shape_contradicting_tensor() {
+		let ctx = mk_ctx(&[60, 40, 40, 60]);
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+
+		let data = (0..6_000).map(|x| x as f64).collect::<Vec<_>>();
+		let plaintext = encoder.encode_f64(&data).unwrap();
+
+		assert!(matches!(
+			TensorContainer::with_shape(plaintext.clone(), vec![3, 2_000]),
+			Err(Error::ShapeMismatch(_))
+		));
+		assert!(matches!(
+			TensorContainer::new(plaintext.clone(), 5_000),
+			Err(Error::ShapeMismatch(_))
+		));
+
+		let container = TensorContainer::new(plaintext.clone(), 6_000).unwrap();
+
+		assert_eq!(container.shape(), &[6_000]);
+		assert_eq!(container.tensor().shape().unwrap().dims(), &[6_000]);
+
+		// Two chunks of 4096 slots can't hold the recorded shape.
+		let bytes = TensorContainer::new(Tensor::new(plaintext.into_chunks()), 10_000)
+			.unwrap()
+			.as_bytes(&ctx)
+			.unwrap();
+
+		assert_eq!(
+			TensorContainer::<Plaintext>::from_bytes(&ctx, &bytes).unwrap_err(),
+			Error::from(MalformedInput::ShapeExceedsData {
+				elements: 10_000,
+				capacity: 8_192,
+			})
+		);
+	}
+}
@@ -1,9 +1,14 @@
+use std::fmt;
+
 use super::Tensor;
-use crate::{Ciphertext, Context, Decryptor, Plaintext, Result, SecretKey};
+use crate::{Ciphertext, Context, Decryptor, Error, Plaintext, Result, SchemeType, SecretKey};
 
 /// Decrypts batches of ciphertexts.
 pub struct TensorDecryptor {
 	decryptor: Decryptor,
+	scheme: SchemeType,
+	/// The parms_ids of the data levels, from the first (highest) level down.
+	levels: Vec<Vec<u64>>,
 }
 
 impl TensorDecryptor {
@@ -14,6 +19,12 @@ impl TensorDecryptor {
 	) -> Result<Self> {
 		Ok(Self {
 			decryptor: Decryptor::new(ctx, secret_key)?,
+			scheme: ctx.get_encryption_parameters()?.get_scheme()?,
+			levels: ctx
+				.get_data_levels()?
+				.into_iter()
+				.map(|level| level.parms_id)
+				.collect(),
 		})
 	}
 }
@@ -26,8 +37,219 @@ impl TensorDecryptor {
 		&self,
 		ciphertext_batch: &Tensor<Ciphertext>,
 	) -> Result<Tensor<Plaintext>> {
-		ciphertext_batch
-			.map(|ciphertext| self.decryptor.decrypt(ciphertext))
-			.collect()
+		ciphertext_batch.try_map(|ciphertext| self.decryptor.decrypt(ciphertext))
+	}
+
+	/// Computes the invariant noise budget of every chunk, in bits. Returns
+	/// the budgets in chunk order together with the smallest one, which
+	/// decides whether the whole tensor still decrypts. BFV only; see
+	/// [`Decryptor::invariant_noise_budget`].
+	///
+	/// * `ciphertext_batch` - The ciphertexts to measure.
+	pub fn invariant_noise_budget(
+		&self,
+		ciphertext_batch: &Tensor<Ciphertext>,
+	) -> Result<(Vec<u32>, u32)> {
+		let budgets = ciphertext_batch
+			.iter()
+			.map(|ciphertext| self.decryptor.invariant_noise_budget(ciphertext))
+			.collect::<Result<Vec<_>>>()?;
+
+		let min = budgets
+			.iter()
+			.copied()
+			.min()
+			.ok_or(Error::InvalidArgument)?;
+
+		Ok((budgets, min))
+	}
+
+	/// Computes the invariant noise of every chunk. Returns the noise in chunk
+	/// order together with the largest, i.e. worst, one. BFV only; see
+	/// [`Decryptor::invariant_noise`].
+	///
+	/// * `ciphertext_batch` - The ciphertexts to measure.
+	pub fn invariant_noise(
+		&self,
+		ciphertext_batch: &Tensor<Ciphertext>,
+	) -> Result<(Vec<f64>, f64)> {
+		let noise = ciphertext_batch
+			.iter()
+			.map(|ciphertext| self.decryptor.invariant_noise(ciphertext))
+			.collect::<Result<Vec<_>>>()?;
+
+		let max = noise
+			.iter()
+			.copied()
+			.reduce(f64::max)
+			.ok_or(Error::InvalidArgument)?;
+
+		Ok((noise, max))
+	}
+
+	/// Describes every chunk of the tensor: its level, the primes and
+	/// polynomials it is made of and, under BFV, its noise budget. Useful to
+	/// find the chunk that ran out of depth.
+	///
+	/// * `ciphertext_batch` - The ciphertexts to describe.
+	pub fn diagnose(
+		&self,
+		ciphertext_batch: &Tensor<Ciphertext>,
+	) -> Result<TensorDiagnostics> {
+		let chunks = ciphertext_batch
+			.iter()
+			.map(|ciphertext| {
+				let parms_id = ciphertext.parms_id()?;
+				let level = self
+					.levels
+					.iter()
+					.position(|level| *level == parms_id)
+					.map(|index| (self.levels.len() - 1 - index) as u64);
+
+				let noise_budget = match self.scheme {
+					SchemeType::Bfv => Some(self.decryptor.invariant_noise_budget(ciphertext)?),
+					_ => None,
+				};
+
+				Ok(ChunkDiagnostics {
+					level,
+					coeff_modulus_size: ciphertext.coeff_modulus_size()?,
+					poly_modulus_degree: ciphertext.poly_modulus_degree()?,
+					num_polynomials: ciphertext.num_polynomials()?,
+					noise_budget,
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		Ok(TensorDiagnostics {
+			chunks,
+		})
+	}
+}
+
+/// A report on the ciphertexts of a tensor, produced by
+/// [`TensorDecryptor::diagnose`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorDiagnostics {
+	/// One entry per chunk, in chunk order.
+	pub chunks: Vec<ChunkDiagnostics>,
+}
+
+/// The state of one ciphertext chunk of a tensor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChunkDiagnostics {
+	/// The chain index of the chunk's level; the last level has index 0, so
+	/// this is how many more times the chunk can be rescaled or switched down.
+	/// `None` if the chunk is not at a data level of the decryptor's context.
+	pub level: Option<u64>,
+	/// The number of primes left in the chunk's coefficient modulus.
+	pub coeff_modulus_size: u64,
+	/// The degree of the chunk's polynomials.
+	pub poly_modulus_degree: u64,
+	/// The number of polynomials in the chunk; 2 unless it awaits
+	/// relinearization.
+	pub num_polynomials: u64,
+	/// The invariant noise budget in bits, or `None` under CKKS.
+	pub noise_budget: Option<u32>,
+}
+
+impl TensorDiagnostics {
+	/// Returns the number of chunks.
+	pub fn chunk_count(&self) -> usize {
+		self.chunks.len()
+	}
+
+	/// Returns the lowest level of any chunk.
+	pub fn min_level(&self) -> Option<u64> {
+		self.chunks.iter().filter_map(|chunk| chunk.level).min()
+	}
+
+	/// Returns the smallest noise budget of any chunk.
+	pub fn min_noise_budget(&self) -> Option<u32> {
+		self.chunks
+			.iter()
+			.filter_map(|chunk| chunk.noise_budget)
+			.min()
+	}
+}
+
+impl fmt::Display for TensorDiagnostics {
+	fn fmt(
+		&self,
+		f: &mut fmt::Formatter<'_>,
+	) -> fmt::Result {
+		write!(f, "{} chunks", self.chunk_count())?;
+
+		for (i, chunk) in self.chunks.iter().enumerate() {
+			write!(f, "\n  chunk {i}: ")?;
+
+			match chunk.level {
+				Some(level) => write!(f, "level {level}")?,
+				None => write!(f, "unknown level")?,
+			}
+
+			write!(
+				f,
+				", {} primes, degree {}, {} polynomials",
+				chunk.coeff_modulus_size, chunk.poly_modulus_degree, chunk.num_polynomials
+			)?;
+
+			if let Some(budget) = chunk.noise_budget {
+				write!(f, ", noise budget {budget} bits")?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn can_diagnose_bfv_tensors() {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+
+		let a = (0..10_000).map(|x| x % 7).collect::<Vec<i64>>();
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+
+		let (budgets, min) = decryptor.invariant_noise_budget(&a_enc).unwrap();
+		assert_eq!(budgets.len(), 2);
+		assert_eq!(min, *budgets.iter().min().unwrap());
+
+		let (noise, max) = decryptor.invariant_noise(&a_enc).unwrap();
+		assert_eq!(noise.len(), 2);
+		assert!(noise.iter().all(|n| *n <= max));
+
+		let squared = evaluator.multiply(&a_enc, &a_enc).unwrap();
+		let report = decryptor.diagnose(&squared).unwrap();
+
+		assert_eq!(report.chunk_count(), 2);
+		// [50, 30, 30, 50] leaves levels 3 to 0; fresh chunks are at the top.
+		assert_eq!(report.min_level(), Some(3));
+		assert_eq!(report.chunks[0].coeff_modulus_size, 4);
+		assert_eq!(report.chunks[0].poly_modulus_degree, 8192);
+		assert_eq!(report.chunks[0].num_polynomials, 3);
+		assert!(report.min_noise_budget().unwrap() < min);
+		assert!(report
+			.to_string()
+			.starts_with("2 chunks\n  chunk 0: level 3"));
 	}
 }
@@ -1,5 +1,5 @@
-use super::Tensor;
-use crate::{BFVEncoder, CKKSEncoder, Plaintext, Result};
+use super::{parallel, Shape, Tensor};
+use crate::{BFVEncoder, CKKSEncoder, Error, Plaintext, Result};
 
 /// An encoder that encodes data in tensors.
 #[derive(Clone)]
@@ -14,6 +14,11 @@ impl<E> TensorEncoder<E> {
 			encoder,
 		}
 	}
+
+	/// Returns the encoder used for each chunk.
+	pub(crate) fn inner(&self) -> &E {
+		&self.encoder
+	}
 }
 
 impl TensorEncoder<CKKSEncoder> {
@@ -28,16 +33,53 @@ impl TensorEncoder<CKKSEncoder> {
 		&self,
 		data: &[f64],
 	) -> Result<Tensor<Plaintext>> {
-		let mut plaintexts = Vec::new();
+		self.encode_f64_shaped(data, vec![data.len()])
+	}
 
-		let chunk_size = self.get_slot_count();
+	/// Encodes the given data, laid out in row-major order, as a tensor of the
+	/// given shape.
+	///
+	/// # Arguments
+	/// * `data` - The data to encode.
+	/// * `dims` - The dimensions of the data. Their product must match the
+	///   length of `data`.
+	pub fn encode_f64_shaped(
+		&self,
+		data: &[f64],
+		dims: Vec<usize>,
+	) -> Result<Tensor<Plaintext>> {
+		let shape = Shape::new(dims)?;
 
-		for chunk in data.chunks(chunk_size) {
-			let plaintext = self.encoder.encode_f64(chunk)?;
-			plaintexts.push(plaintext);
+		if shape.numel() != data.len() {
+			return Err(Error::InvalidArgument);
 		}
 
-		Ok(Tensor(plaintexts))
+		let plaintexts = parallel::try_map_chunks(data, self.get_slot_count(), |chunk| {
+			self.encoder.encode_f64(chunk)
+		})?;
+
+		Ok(Tensor::with_shape(plaintexts, shape).with_slot_count(self.get_slot_count()))
+	}
+
+	/// Encodes the given data at the given level and scale, e.g. to match a
+	/// ciphertext; see [`CKKSEncoder::encode_f64_at`].
+	///
+	/// # Arguments
+	/// * `data` - The data to encode.
+	/// * `parms_id` - The parms id of the level to encode at.
+	/// * `scale` - The scale to encode at.
+	pub fn encode_f64_at(
+		&self,
+		data: &[f64],
+		parms_id: &[u64],
+		scale: f64,
+	) -> Result<Tensor<Plaintext>> {
+		let plaintexts = parallel::try_map_chunks(data, self.get_slot_count(), |chunk| {
+			self.encoder.encode_f64_at(chunk, parms_id, scale)
+		})?;
+
+		Ok(Tensor::with_shape(plaintexts, Shape::flat(data.len()))
+			.with_slot_count(self.get_slot_count()))
 	}
 
 	/// Decodes the given plaintext into data.
@@ -46,16 +88,21 @@ impl TensorEncoder<CKKSEncoder> {
 	/// * `chunk` - The encoded data.
 	///
 	/// # Returns
-	/// The decoded data.
+	/// The decoded data as a flat vector, in row-major order of the tensor's
+	/// shape; use [`Tensor::shape`] to recover the dimensions. When the shape
+	/// is known the padding in the last chunk is dropped, otherwise every
+	/// slot is returned.
 	pub fn decode_f64(
 		&self,
 		chunk: &Tensor<Plaintext>,
 	) -> Result<Vec<f64>> {
-		let mut data = Vec::new();
+		let mut data = parallel::try_map(chunk.chunks(), |plaintext| {
+			self.encoder.decode_f64(plaintext)
+		})?
+		.concat();
 
-		for plaintext in chunk {
-			let decoded = self.encoder.decode_f64(plaintext)?;
-			data.extend(decoded);
+		if let Some(numel) = chunk.numel() {
+			data.truncate(numel);
 		}
 
 		Ok(data)
@@ -79,16 +126,32 @@ impl TensorEncoder<BFVEncoder> {
 		&self,
 		data: &[i64],
 	) -> Result<Tensor<Plaintext>> {
-		let mut plaintexts = Vec::new();
+		self.encode_i64_shaped(data, vec![data.len()])
+	}
 
-		let chunk_size = self.get_slot_count();
+	/// Encodes the given data, laid out in row-major order, as a tensor of the
+	/// given shape.
+	///
+	/// # Arguments
+	/// * `data` - The data to encode.
+	/// * `dims` - The dimensions of the data. Their product must match the
+	///   length of `data`.
+	pub fn encode_i64_shaped(
+		&self,
+		data: &[i64],
+		dims: Vec<usize>,
+	) -> Result<Tensor<Plaintext>> {
+		let shape = Shape::new(dims)?;
 
-		for chunk in data.chunks(chunk_size) {
-			let plaintext = self.encoder.encode_i64(chunk)?;
-			plaintexts.push(plaintext);
+		if shape.numel() != data.len() {
+			return Err(Error::InvalidArgument);
 		}
 
-		Ok(Tensor(plaintexts))
+		let plaintexts = parallel::try_map_chunks(data, self.get_slot_count(), |chunk| {
+			self.encoder.encode_i64(chunk)
+		})?;
+
+		Ok(Tensor::with_shape(plaintexts, shape).with_slot_count(self.get_slot_count()))
 	}
 
 	/// Decodes the given plaintext into data.
@@ -97,16 +160,21 @@ impl TensorEncoder<BFVEncoder> {
 	/// * `chunk` - The encoded data.
 	///
 	/// # Returns
-	/// The decoded data.
+	/// The decoded data as a flat vector, in row-major order of the tensor's
+	/// shape; use [`Tensor::shape`] to recover the dimensions. When the shape
+	/// is known the padding in the last chunk is dropped, otherwise every
+	/// slot is returned.
 	pub fn decode_i64(
 		&self,
 		chunk: &Tensor<Plaintext>,
 	) -> Result<Vec<i64>> {
-		let mut data = Vec::new();
+		let mut data = parallel::try_map(chunk.chunks(), |plaintext| {
+			self.encoder.decode_i64(plaintext)
+		})?
+		.concat();
 
-		for plaintext in chunk {
-			let decoded = self.encoder.decode_i64(plaintext)?;
-			data.extend(decoded);
+		if let Some(numel) = chunk.numel() {
+			data.truncate(numel);
 		}
 
 		Ok(data)
@@ -123,16 +191,32 @@ impl TensorEncoder<BFVEncoder> {
 		&self,
 		data: &[u64],
 	) -> Result<Tensor<Plaintext>> {
-		let mut plaintexts = Vec::new();
+		self.encode_u64_shaped(data, vec![data.len()])
+	}
 
-		let chunk_size = self.get_slot_count();
+	/// Encodes the given data, laid out in row-major order, as a tensor of the
+	/// given shape.
+	///
+	/// # Arguments
+	/// * `data` - The data to encode.
+	/// * `dims` - The dimensions of the data. Their product must match the
+	///   length of `data`.
+	pub fn encode_u64_shaped(
+		&self,
+		data: &[u64],
+		dims: Vec<usize>,
+	) -> Result<Tensor<Plaintext>> {
+		let shape = Shape::new(dims)?;
 
-		for chunk in data.chunks(chunk_size) {
-			let plaintext = self.encoder.encode_u64(chunk)?;
-			plaintexts.push(plaintext);
+		if shape.numel() != data.len() {
+			return Err(Error::InvalidArgument);
 		}
 
-		Ok(Tensor(plaintexts))
+		let plaintexts = parallel::try_map_chunks(data, self.get_slot_count(), |chunk| {
+			self.encoder.encode_u64(chunk)
+		})?;
+
+		Ok(Tensor::with_shape(plaintexts, shape).with_slot_count(self.get_slot_count()))
 	}
 
 	/// Decodes the given plaintext into data.
@@ -141,16 +225,21 @@ impl TensorEncoder<BFVEncoder> {
 	/// * `chunk` - The encoded data.
 	///
 	/// # Returns
-	/// The decoded data.
+	/// The decoded data as a flat vector, in row-major order of the tensor's
+	/// shape; use [`Tensor::shape`] to recover the dimensions. When the shape
+	/// is known the padding in the last chunk is dropped, otherwise every
+	/// slot is returned.
 	pub fn decode_u64(
 		&self,
 		chunk: &Tensor<Plaintext>,
 	) -> Result<Vec<u64>> {
-		let mut data = Vec::new();
+		let mut data = parallel::try_map(chunk.chunks(), |plaintext| {
+			self.encoder.decode_u64(plaintext)
+		})?
+		.concat();
 
-		for plaintext in chunk {
-			let decoded = self.encoder.decode_u64(plaintext)?;
-			data.extend(decoded);
+		if let Some(numel) = chunk.numel() {
+			data.truncate(numel);
 		}
 
 		Ok(data)
@@ -198,4 +287,39 @@ mod tests {
 
 		assert_eq!(data, data_2);
 	}
+
+	#[test]
+	fn decode_trims_padding_and_keeps_shape() {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+
+		let data = (0..3 * 5_000).map(|x| x as u64).collect::<Vec<_>>();
+
+		assert!(encoder.encode_u64_shaped(&data, vec![4, 5_000]).is_err());
+
+		let plaintext = encoder.encode_u64_shaped(&data, vec![3, 5_000]).unwrap();
+
+		assert_eq!(plaintext.len(), 2);
+		assert_eq!(plaintext.shape().unwrap().dims(), &[3, 5_000]);
+		assert_eq!(plaintext.shape().unwrap().strides(), &[5_000, 1]);
+		assert_eq!(encoder.decode_u64(&plaintext).unwrap(), data);
+
+		let plaintext = plaintext.reshape(vec![5_000, 3]).unwrap();
+		assert_eq!(plaintext.shape().unwrap().offset(&[2, 1]), Some(7));
+		assert!(plaintext.clone().reshape(vec![2]).is_err());
+
+		let plaintext = plaintext.flatten();
+		assert_eq!(plaintext.shape().unwrap().dims(), &[15_000]);
+		assert_eq!(encoder.decode_u64(&plaintext).unwrap(), data);
+	}
 }
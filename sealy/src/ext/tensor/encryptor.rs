@@ -1,8 +1,9 @@
 use crate::{
-	component_marker, Asym, AsymmetricComponents, Ciphertext, Context, Encryptor, Plaintext,
-	PublicKey, Result, SecretKey, Sym, SymAsym, SymmetricComponents,
+	component_marker, Asym, AsymmetricComponents, Ciphertext, CompactCiphertext, Context,
+	Encryptor, Plaintext, PublicKey, Result, SecretKey, Sym, SymAsym, SymmetricComponents,
 };
 
+use super::parallel::MaybeSync;
 use super::Tensor;
 
 /// Encryptor that can encrypt multiple messages at once.
@@ -61,7 +62,7 @@ impl TensorEncryptor {
 	}
 }
 
-impl<T: component_marker::Asym> TensorEncryptor<T> {
+impl<T: component_marker::Asym + MaybeSync> TensorEncryptor<T> {
 	/// Encrypts a plaintext with the public key and returns the ciphertext as
 	/// a serializable object.
 	///
@@ -76,9 +77,7 @@ impl<T: component_marker::Asym> TensorEncryptor<T> {
 		&self,
 		plaintext_tensor: &Tensor<Plaintext>,
 	) -> Result<Tensor<Ciphertext>> {
-		plaintext_tensor
-			.map(|plaintext| self.encryptor.encrypt(plaintext))
-			.collect()
+		plaintext_tensor.try_map(|plaintext| self.encryptor.encrypt(plaintext))
 	}
 
 	/// Encrypts a plaintext with the public key and returns the ciphertext as a
@@ -96,13 +95,11 @@ impl<T: component_marker::Asym> TensorEncryptor<T> {
 		&self,
 		plaintext_tensor: &Tensor<Plaintext>,
 	) -> Result<Tensor<(Ciphertext, AsymmetricComponents)>> {
-		plaintext_tensor
-			.map(|plaintext| self.encryptor.encrypt_return_components(plaintext))
-			.collect()
+		plaintext_tensor.try_map(|plaintext| self.encryptor.encrypt_return_components(plaintext))
 	}
 }
 
-impl<T: component_marker::Sym> TensorEncryptor<T> {
+impl<T: component_marker::Sym + MaybeSync> TensorEncryptor<T> {
 	/// Encrypts a plaintext with the secret key and returns the ciphertext as
 	/// a serializable object.
 	///
@@ -117,9 +114,19 @@ impl<T: component_marker::Sym> TensorEncryptor<T> {
 		&self,
 		plaintext_tensor: &Tensor<Plaintext>,
 	) -> Result<Tensor<Ciphertext>> {
-		plaintext_tensor
-			.map(|plaintext| self.encryptor.encrypt_symmetric(plaintext))
-			.collect()
+		plaintext_tensor.try_map(|plaintext| self.encryptor.encrypt_symmetric(plaintext))
+	}
+
+	/// Encrypts a plaintext with the secret key and returns compact
+	/// ciphertexts that serialize at roughly half the size. See
+	/// [`Encryptor::encrypt_symmetric_compact`].
+	///
+	/// * `plaintext_tensor` - The plaintext to encrypt.
+	pub fn encrypt_symmetric_compact(
+		&self,
+		plaintext_tensor: &Tensor<Plaintext>,
+	) -> Result<Tensor<CompactCiphertext>> {
+		plaintext_tensor.try_map(|plaintext| self.encryptor.encrypt_symmetric_compact(plaintext))
 	}
 
 	/// Encrypts a plaintext with the secret key and returns the ciphertext as a
@@ -136,11 +143,9 @@ impl<T: component_marker::Sym> TensorEncryptor<T> {
 		&self,
 		plaintext_tensor: &Tensor<Plaintext>,
 	) -> Result<Tensor<(Ciphertext, SymmetricComponents)>> {
-		plaintext_tensor
-			.map(|plaintext| {
-				self.encryptor
-					.encrypt_symmetric_return_components(plaintext)
-			})
-			.collect()
+		plaintext_tensor.try_map(|plaintext| {
+			self.encryptor
+				.encrypt_symmetric_return_components(plaintext)
+		})
 	}
 }
@@ -1,7 +1,25 @@
-use super::Tensor;
-use crate::{CKKSEvaluator, Context, Error, Evaluator, GaloisKey, RelinearizationKey, Result};
+use std::ops::Range;
+
+use super::encoder::TensorEncoder;
+use super::parallel::{self, MaybeSend, MaybeSync};
+use super::select::{SlotMove, SlotSelect};
+use super::{Shape, Tensor};
+use crate::{
+	BFVEvaluator, CKKSEvaluator, Ciphertext, CircuitEvaluator, CircuitKeys, Context, Error,
+	Evaluator, GaloisKey, Plaintext, RelinearizationKey, Result,
+};
 
 /// An evaluator that evaluates a tensor of data.
+///
+/// Operations between two ciphertext tensors require both to have the same
+/// number of chunks and, when both are known, the same shape; otherwise they
+/// fail with [`Error::ShapeMismatch`].
+///
+/// Operations with a plaintext tensor follow the same rule, except that a
+/// plaintext with a single chunk filling every slot is broadcast: it is
+/// applied to every chunk of the ciphertext. Encode a value repeated over all
+/// slots, e.g. `vec![x; encoder.get_slot_count()]`, to apply it to every
+/// element. See [`Tensor::broadcast_chunk`].
 pub struct TensorEvaluator<E> {
 	evaluator: E,
 }
@@ -27,11 +45,430 @@ impl TensorEvaluator<CKKSEvaluator> {
 	}
 }
 
+impl TensorEvaluator<BFVEvaluator> {
+	/// Creates a new tensor evaluator for the BFV scheme.
+	pub fn bfv(ctx: &Context) -> Result<Self> {
+		Ok(Self {
+			evaluator: BFVEvaluator::new(ctx)?,
+		})
+	}
+}
+
+/// How one output chunk of a gather is built.
+enum ChunkPlan {
+	/// The chunk is a whole source chunk, slot for slot.
+	Copy(usize),
+	/// The chunk is assembled from moved slots.
+	Select(Vec<SlotMove>),
+}
+
+impl<E> TensorEvaluator<E>
+where
+	E: SlotSelect + MaybeSync,
+	E::Encoder: MaybeSync,
+	E::Value: MaybeSync,
+{
+	/// Returns the elements of `a` at the given flat (row-major) positions,
+	/// in order, as a one-dimensional tensor.
+	///
+	/// Output chunks that match a whole input chunk are copied; all others
+	/// are assembled with rotations and masking plaintexts, so `galois_keys`
+	/// must be able to perform the rotations. With CKKS, masking uses up one
+	/// level, and copied chunks are switched down to match.
+	///
+	/// Fails with [`Error::InvalidArgument`] if a position is out of bounds.
+	pub fn gather(
+		&self,
+		a: &Tensor<Ciphertext>,
+		indices: &[usize],
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		let slots = E::slot_count(encoder.inner());
+		let numel = a.numel().unwrap_or(a.len() * slots);
+
+		if numel > a.len() * slots {
+			return Err(Error::InvalidArgument);
+		}
+
+		let elements = indices
+			.iter()
+			.map(|&i| {
+				if i < numel {
+					Ok((i / slots, i % slots))
+				} else {
+					Err(Error::InvalidArgument)
+				}
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		let sources = a.chunks().iter().collect::<Vec<_>>();
+		let filled = filled_slots(a.len(), numel, slots);
+
+		self.assemble(
+			&sources,
+			&filled,
+			&elements,
+			Shape::flat(indices.len()),
+			encoder,
+			galois_keys,
+		)
+	}
+
+	/// Returns the elements of `a` in the given range of flat (row-major)
+	/// positions as a one-dimensional tensor.
+	///
+	/// A range starting on a chunk boundary copies the covered chunks; see
+	/// [`TensorEvaluator::gather`] for the general case.
+	pub fn slice(
+		&self,
+		a: &Tensor<Ciphertext>,
+		range: Range<usize>,
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		let slots = E::slot_count(encoder.inner());
+		let numel = a.numel().unwrap_or(a.len() * slots);
+
+		// Check the bounds before building one index per element.
+		if range.start > range.end || range.end > numel {
+			return Err(Error::InvalidArgument);
+		}
+
+		self.gather(a, &range.collect::<Vec<_>>(), encoder, galois_keys)
+	}
+
+	/// Joins the given tensors end to end. All of their chunks must be at
+	/// the same level.
+	///
+	/// If all the shapes are known and agree on every dimension but the
+	/// first, the result is joined along the first dimension; otherwise it
+	/// is one-dimensional. Tensors holding a whole number of chunks are
+	/// copied as is; see [`TensorEvaluator::gather`] for the general case.
+	pub fn concat(
+		&self,
+		tensors: &[Tensor<Ciphertext>],
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		if tensors.is_empty() {
+			return Err(Error::InvalidArgument);
+		}
+
+		let slots = E::slot_count(encoder.inner());
+
+		let mut sources: Vec<&Ciphertext> = vec![];
+		let mut filled = vec![];
+		let mut elements = vec![];
+
+		for tensor in tensors {
+			let base = sources.len();
+			let numel = tensor.numel().unwrap_or(tensor.len() * slots);
+
+			if numel > tensor.len() * slots {
+				return Err(Error::InvalidArgument);
+			}
+
+			sources.extend(tensor.chunks());
+			filled.extend(filled_slots(tensor.len(), numel, slots));
+			elements.extend((0..numel).map(|i| (base + i / slots, i % slots)));
+		}
+
+		let shape = concat_shape(tensors).unwrap_or_else(|| Shape::flat(elements.len()));
+
+		self.assemble(&sources, &filled, &elements, shape, encoder, galois_keys)
+	}
+
+	/// Returns the dot product of two encrypted tensors as a tensor holding a
+	/// single element.
+	///
+	/// The chunks are multiplied, relinearized and summed, then the slots
+	/// are summed with rotations, so `galois_keys` must be able to rotate by
+	/// every power of two. Padding slots must be zero, as they are after
+	/// encoding. With CKKS this uses up one level.
+	pub fn dot(
+		&self,
+		a: &Tensor<Ciphertext>,
+		b: &Tensor<Ciphertext>,
+		relin_keys: &RelinearizationKey,
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		a.check_same_shape(b)?;
+
+		let products = a.try_zip(b, |a, b| {
+			let product = self.evaluator.multiply(a, b)?;
+			let product = self.evaluator.relinearize(&product, relin_keys)?;
+
+			self.evaluator.rescale(&product)
+		})?;
+
+		self.sum_all(products.chunks(), encoder, galois_keys)
+	}
+
+	/// Returns the dot product of an encrypted and a plaintext tensor as a
+	/// tensor holding a single element.
+	///
+	/// See [`TensorEvaluator::dot`]; the plaintext must be at the level of
+	/// the ciphertext.
+	pub fn dot_plain(
+		&self,
+		a: &Tensor<Ciphertext>,
+		b: &Tensor<Plaintext>,
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		a.check_same_shape(b)?;
+
+		let products = a.try_zip(b, |a, b| {
+			self.evaluator
+				.rescale(&self.evaluator.multiply_plain(a, b)?)
+		})?;
+
+		self.sum_all(products.chunks(), encoder, galois_keys)
+	}
+
+	/// Multiplies an encrypted `m x k` matrix by a plaintext `k x n` matrix
+	/// and returns the encrypted `m x n` result.
+	///
+	/// # Packing
+	/// `a` must have the shape `[m, k]` and hold its rows in row-major order,
+	/// as produced by the `encode_*_shaped` encoders. Each row must lie within
+	/// one rotation span (a whole chunk for CKKS, half a chunk for BFV): the
+	/// matrix must fit in a single span or `k` must divide the span, e.g. a
+	/// power of two. `b` holds the `k x n` matrix in row-major order. The
+	/// result has the shape `[m, n]` and the same layout.
+	///
+	/// # Cost
+	/// For each column of `b`, every chunk of `a` is multiplied by a
+	/// plaintext and its rows are summed with about `2 log2(k)` rotations.
+	/// The sums are then moved into place as by [`TensorEvaluator::gather`],
+	/// which takes one rotation per column and chunk when `k == n`, and up
+	/// to one per element otherwise. With CKKS this uses up two levels.
+	pub fn matmul_plain(
+		&self,
+		a: &Tensor<Ciphertext>,
+		b: &[E::Value],
+		n: usize,
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		let (m, k) = match a.shape().map(Shape::dims) {
+			Some(&[m, k]) => (m, k),
+			_ => return Err(Error::InvalidArgument),
+		};
+
+		let slots = E::slot_count(encoder.inner());
+		let span = E::rotation_span(encoder.inner());
+
+		let (mk, kn) = match (m.checked_mul(k), k.checked_mul(n)) {
+			(Some(mk), Some(kn)) => (mk, kn),
+			_ => return Err(Error::InvalidArgument),
+		};
+
+		if k == 0 || b.len() != kn || mk > a.len().saturating_mul(slots) {
+			return Err(Error::InvalidArgument);
+		}
+
+		if (0..m).any(|i| (i * k) % slots % span + k > span) {
+			return Err(Error::InvalidArgument);
+		}
+
+		let chunks = a.len();
+		let n_chunks = n.checked_mul(chunks).ok_or(Error::InvalidArgument)?;
+
+		// Chunk `j * chunks + c` holds, at the first slot of every row `i`
+		// in chunk `c`, the sum over `t` of `a[i][t] * b[t][j]`.
+		let sums = parallel::try_map_range(n_chunks, |index| {
+			let (j, c) = (index / chunks, index % chunks);
+
+			let values = (c * slots..((c + 1) * slots).min(mk))
+				.map(|g| b[(g % k) * n + j])
+				.collect::<Vec<_>>();
+
+			let products =
+				self.evaluator
+					.multiply_values(encoder.inner(), &a.chunks()[c], &values)?;
+
+			self.window_sum(&products, k, galois_keys)
+		})?;
+
+		let sources = sums.iter().collect::<Vec<_>>();
+		let filled = (0..n)
+			.flat_map(|_| filled_slots(chunks, mk, slots))
+			.collect::<Vec<_>>();
+
+		let elements = (0..m)
+			.flat_map(|i| (0..n).map(move |j| (j * chunks + i * k / slots, i * k % slots)))
+			.collect::<Vec<_>>();
+
+		self.assemble(
+			&sources,
+			&filled,
+			&elements,
+			Shape::new(vec![m, n])?,
+			encoder,
+			galois_keys,
+		)
+	}
+
+	/// Adds up the given chunks and then all of their slots.
+	fn sum_all(
+		&self,
+		chunks: &[Ciphertext],
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		let total = self.evaluator.add_many(chunks)?;
+		let total = self
+			.evaluator
+			.sum_slots(encoder.inner(), &total, galois_keys)?;
+
+		Ok(Tensor::with_shape(vec![total], Shape::flat(1)))
+	}
+
+	/// Returns a ciphertext whose slot `p` holds the sum of slots
+	/// `p..p + width` of `a`, within each rotation span. Combines windows of
+	/// doubling width, so it takes about `2 log2(width)` rotations.
+	fn window_sum(
+		&self,
+		a: &Ciphertext,
+		width: usize,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let mut window = a.clone();
+		let mut size = 1;
+		let mut offset = 0;
+		let mut remaining = width;
+		let mut sum: Option<Ciphertext> = None;
+
+		loop {
+			if remaining & 1 == 1 {
+				let part = match offset {
+					0 => window.clone(),
+					offset => self.evaluator.rotate(&window, offset as i32, galois_keys)?,
+				};
+
+				sum = Some(match sum {
+					Some(mut sum) => {
+						self.evaluator.add_inplace(&mut sum, &part)?;
+						sum
+					}
+					None => part,
+				});
+
+				offset += size;
+			}
+
+			remaining >>= 1;
+
+			if remaining == 0 {
+				break;
+			}
+
+			let rotated = self.evaluator.rotate(&window, size as i32, galois_keys)?;
+			self.evaluator.add_inplace(&mut window, &rotated)?;
+			size *= 2;
+		}
+
+		sum.ok_or(Error::InvalidArgument)
+	}
+
+	/// Builds a tensor whose `i`th element is slot `elements[i].1` of
+	/// `sources[elements[i].0]`. `filled` holds the number of slots in use in
+	/// each source chunk; the rest are assumed to be zero padding.
+	fn assemble(
+		&self,
+		sources: &[&Ciphertext],
+		filled: &[usize],
+		elements: &[(usize, usize)],
+		shape: Shape,
+		encoder: &TensorEncoder<E::Encoder>,
+		galois_keys: &GaloisKey,
+	) -> Result<Tensor<Ciphertext>> {
+		let slots = E::slot_count(encoder.inner());
+
+		let plans = elements
+			.chunks(slots)
+			.map(|chunk| {
+				let moves = chunk
+					.iter()
+					.enumerate()
+					.map(|(to, &(source, from))| SlotMove {
+						source,
+						from,
+						to,
+					})
+					.collect::<Vec<_>>();
+
+				let source = moves[0].source;
+				let is_copy = moves.len() == filled[source]
+					&& moves.iter().all(|m| m.source == source && m.from == m.to);
+
+				if is_copy {
+					ChunkPlan::Copy(source)
+				} else {
+					ChunkPlan::Select(moves)
+				}
+			})
+			.collect::<Vec<_>>();
+
+		let align = plans
+			.iter()
+			.any(|plan| matches!(plan, ChunkPlan::Select(_)));
+
+		let chunks = parallel::try_map(&plans, |plan| match plan {
+			ChunkPlan::Copy(source) if align => self.evaluator.align(sources[*source]),
+			ChunkPlan::Copy(source) => Ok(sources[*source].clone()),
+			ChunkPlan::Select(moves) => {
+				self.evaluator
+					.select(encoder.inner(), sources, moves, galois_keys)
+			}
+		})?;
+
+		Ok(Tensor::with_shape(chunks, shape))
+	}
+}
+
+/// Returns the number of slots in use in each of `len` chunks holding
+/// `numel` values.
+fn filled_slots(
+	len: usize,
+	numel: usize,
+	slots: usize,
+) -> Vec<usize> {
+	(0..len)
+		.map(|i| numel.saturating_sub(i * slots).min(slots))
+		.collect()
+}
+
+/// Returns the shape of the given tensors joined along their first
+/// dimension, if they all have known shapes that agree on the others.
+fn concat_shape(tensors: &[Tensor<Ciphertext>]) -> Option<Shape> {
+	let shapes = tensors
+		.iter()
+		.map(|tensor| tensor.shape().filter(|shape| shape.ndim() > 0))
+		.collect::<Option<Vec<_>>>()?;
+
+	let rest = &shapes[0].dims()[1..];
+
+	if shapes.iter().any(|shape| shape.dims()[1..] != *rest) {
+		return None;
+	}
+
+	let mut dims = vec![shapes
+		.iter()
+		.try_fold(0usize, |acc, shape| acc.checked_add(shape.dims()[0]))?];
+	dims.extend_from_slice(rest);
+
+	Shape::new(dims).ok()
+}
+
 impl<E> Evaluator for TensorEvaluator<E>
 where
-	E: Evaluator,
-	E::Ciphertext: Clone,
-	E::Plaintext: Clone,
+	E: Evaluator + MaybeSync,
+	E::Ciphertext: Clone + MaybeSend + MaybeSync,
+	E::Plaintext: Clone + MaybeSend + MaybeSync,
 {
 	type Plaintext = Tensor<E::Plaintext>;
 
@@ -41,18 +478,14 @@ where
 		&self,
 		a: &mut Self::Ciphertext,
 	) -> Result<()> {
-		for value in a.iter_mut() {
-			self.evaluator.negate_inplace(value)?;
-		}
-
-		Ok(())
+		a.try_for_each_mut(|value| self.evaluator.negate_inplace(value))
 	}
 
 	fn negate(
 		&self,
 		a: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.negate(value)).collect()
+		a.try_map(|value| self.evaluator.negate(value))
 	}
 
 	fn add_inplace(
@@ -60,11 +493,9 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.add_inplace(a, b)?;
-		}
+		a.check_same_shape(b)?;
 
-		Ok(())
+		a.try_zip_mut(b, |a, b| self.evaluator.add_inplace(a, b))
 	}
 
 	fn add(
@@ -72,32 +503,31 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		if a.len() != b.len() {
-			return Err(Error::InvalidArgument);
-		}
+		a.check_same_shape(b)?;
 
-		a.zip(b, |a, b| self.evaluator.add(a, b)).collect()
+		a.try_zip(b, |a, b| self.evaluator.add(a, b))
 	}
 
 	fn add_many(
 		&self,
 		a: &[Self::Ciphertext],
 	) -> Result<Self::Ciphertext> {
-		let mut result = Vec::with_capacity(a.len());
-		let length = a.first().ok_or_else(|| Error::InvalidArgument)?.len();
+		let first = a.first().ok_or_else(|| Error::InvalidArgument)?;
 
-		for i in 0..length {
-			let mut values = Vec::with_capacity(a.len());
+		for tensor in a {
+			first.check_same_shape(tensor)?;
+		}
 
-			for tensor in a.iter() {
-				let value = tensor.get_cloned(i).ok_or_else(|| Error::InvalidArgument)?;
-				values.push(value);
-			}
+		let result = parallel::try_map_range(first.len(), |i| {
+			let values = a
+				.iter()
+				.map(|tensor| tensor.get_cloned(i).ok_or(Error::InvalidArgument))
+				.collect::<Result<Vec<_>>>()?;
 
-			result.push(self.evaluator.add_many(values.as_slice())?);
-		}
+			self.evaluator.add_many(&values)
+		})?;
 
-		Ok(Tensor(result))
+		Ok(first.with_chunks(result))
 	}
 
 	fn multiply_many(
@@ -105,24 +535,22 @@ where
 		a: &[Self::Ciphertext],
 		relin_keys: &RelinearizationKey,
 	) -> Result<Self::Ciphertext> {
-		let mut result = Vec::with_capacity(a.len());
-		let length = a.first().ok_or_else(|| Error::InvalidArgument)?.len();
+		let first = a.first().ok_or_else(|| Error::InvalidArgument)?;
 
-		for i in 0..length {
-			let mut values = Vec::with_capacity(a.len());
+		for tensor in a {
+			first.check_same_shape(tensor)?;
+		}
 
-			for tensor in a.iter() {
-				let value = tensor.get_cloned(i).ok_or_else(|| Error::InvalidArgument)?;
-				values.push(value);
-			}
+		let result = parallel::try_map_range(first.len(), |i| {
+			let values = a
+				.iter()
+				.map(|tensor| tensor.get_cloned(i).ok_or(Error::InvalidArgument))
+				.collect::<Result<Vec<_>>>()?;
 
-			result.push(
-				self.evaluator
-					.multiply_many(values.as_slice(), relin_keys)?,
-			);
-		}
+			self.evaluator.multiply_many(&values, relin_keys)
+		})?;
 
-		Ok(Tensor(result))
+		Ok(first.with_chunks(result))
 	}
 
 	fn sub_inplace(
@@ -130,11 +558,9 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.sub_inplace(a, b)?;
-		}
+		a.check_same_shape(b)?;
 
-		Ok(())
+		a.try_zip_mut(b, |a, b| self.evaluator.sub_inplace(a, b))
 	}
 
 	fn sub(
@@ -142,7 +568,9 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		a.zip(b, |a, b| self.evaluator.sub(a, b)).collect()
+		a.check_same_shape(b)?;
+
+		a.try_zip(b, |a, b| self.evaluator.sub(a, b))
 	}
 
 	fn multiply_inplace(
@@ -150,11 +578,9 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.multiply_inplace(a, b)?;
-		}
+		a.check_same_shape(b)?;
 
-		Ok(())
+		a.try_zip_mut(b, |a, b| self.evaluator.multiply_inplace(a, b))
 	}
 
 	fn multiply(
@@ -162,63 +588,51 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		a.zip(b, |a, b| self.evaluator.multiply(a, b)).collect()
+		a.check_same_shape(b)?;
+
+		a.try_zip(b, |a, b| self.evaluator.multiply(a, b))
 	}
 
 	fn square_inplace(
 		&self,
 		a: &mut Self::Ciphertext,
 	) -> Result<()> {
-		for value in a.iter_mut() {
-			self.evaluator.square_inplace(value)?;
-		}
-
-		Ok(())
+		a.try_for_each_mut(|value| self.evaluator.square_inplace(value))
 	}
 
 	fn square(
 		&self,
 		a: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.square(value)).collect()
+		a.try_map(|value| self.evaluator.square(value))
 	}
 
 	fn mod_switch_to_next(
 		&self,
 		a: &Self::Ciphertext,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.mod_switch_to_next(value))
-			.collect()
+		a.try_map(|value| self.evaluator.mod_switch_to_next(value))
 	}
 
 	fn mod_switch_to_next_inplace(
 		&self,
 		a: &Self::Ciphertext,
 	) -> Result<()> {
-		for value in a.iter() {
-			self.evaluator.mod_switch_to_next_inplace(value)?;
-		}
-
-		Ok(())
+		a.try_for_each(|value| self.evaluator.mod_switch_to_next_inplace(value))
 	}
 
 	fn mod_switch_to_next_plaintext(
 		&self,
 		a: &Self::Plaintext,
 	) -> Result<Self::Plaintext> {
-		a.map(|value| self.evaluator.mod_switch_to_next_plaintext(value))
-			.collect()
+		a.try_map(|value| self.evaluator.mod_switch_to_next_plaintext(value))
 	}
 
 	fn mod_switch_to_next_inplace_plaintext(
 		&self,
 		a: &Self::Plaintext,
 	) -> Result<()> {
-		for value in a.iter() {
-			self.evaluator.mod_switch_to_next_inplace_plaintext(value)?;
-		}
-
-		Ok(())
+		a.try_for_each(|value| self.evaluator.mod_switch_to_next_inplace_plaintext(value))
 	}
 
 	fn exponentiate(
@@ -227,8 +641,7 @@ where
 		exponent: u64,
 		relin_keys: &RelinearizationKey,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.exponentiate(value, exponent, relin_keys))
-			.collect()
+		a.try_map(|value| self.evaluator.exponentiate(value, exponent, relin_keys))
 	}
 
 	fn exponentiate_inplace(
@@ -237,12 +650,10 @@ where
 		exponent: u64,
 		relin_keys: &RelinearizationKey,
 	) -> Result<()> {
-		for value in a.iter() {
+		a.try_for_each(|value| {
 			self.evaluator
-				.exponentiate_inplace(value, exponent, relin_keys)?;
-		}
-
-		Ok(())
+				.exponentiate_inplace(value, exponent, relin_keys)
+		})
 	}
 
 	fn add_plain(
@@ -250,7 +661,10 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<Self::Ciphertext> {
-		a.zip(b, |a, b| self.evaluator.add_plain(a, b)).collect()
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_map(|a| self.evaluator.add_plain(a, b)),
+			None => a.try_zip(b, |a, b| self.evaluator.add_plain(a, b)),
+		}
 	}
 
 	fn add_plain_inplace(
@@ -258,11 +672,10 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.add_plain_inplace(a, b)?;
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_for_each_mut(|a| self.evaluator.add_plain_inplace(a, b)),
+			None => a.try_zip_mut(b, |a, b| self.evaluator.add_plain_inplace(a, b)),
 		}
-
-		Ok(())
 	}
 
 	fn sub_plain(
@@ -270,7 +683,10 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<Self::Ciphertext> {
-		a.zip(b, |a, b| self.evaluator.sub_plain(a, b)).collect()
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_map(|a| self.evaluator.sub_plain(a, b)),
+			None => a.try_zip(b, |a, b| self.evaluator.sub_plain(a, b)),
+		}
 	}
 
 	fn sub_plain_inplace(
@@ -278,11 +694,10 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.sub_plain_inplace(a, b)?;
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_for_each_mut(|a| self.evaluator.sub_plain_inplace(a, b)),
+			None => a.try_zip_mut(b, |a, b| self.evaluator.sub_plain_inplace(a, b)),
 		}
-
-		Ok(())
 	}
 
 	fn multiply_plain(
@@ -290,8 +705,10 @@ where
 		a: &Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<Self::Ciphertext> {
-		a.zip(b, |a, b| self.evaluator.multiply_plain(a, b))
-			.collect()
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_map(|a| self.evaluator.multiply_plain(a, b)),
+			None => a.try_zip(b, |a, b| self.evaluator.multiply_plain(a, b)),
+		}
 	}
 
 	fn multiply_plain_inplace(
@@ -299,11 +716,10 @@ where
 		a: &mut Self::Ciphertext,
 		b: &Self::Plaintext,
 	) -> Result<()> {
-		for (a, b) in a.iter_mut().zip(b.iter()) {
-			self.evaluator.multiply_plain_inplace(a, b)?;
+		match a.broadcast_chunk(b)? {
+			Some(b) => a.try_for_each_mut(|a| self.evaluator.multiply_plain_inplace(a, b)),
+			None => a.try_zip_mut(b, |a, b| self.evaluator.multiply_plain_inplace(a, b)),
 		}
-
-		Ok(())
 	}
 
 	fn relinearize_inplace(
@@ -311,11 +727,7 @@ where
 		a: &mut Self::Ciphertext,
 		relin_keys: &RelinearizationKey,
 	) -> Result<()> {
-		for value in a.iter_mut() {
-			self.evaluator.relinearize_inplace(value, relin_keys)?;
-		}
-
-		Ok(())
+		a.try_for_each_mut(|value| self.evaluator.relinearize_inplace(value, relin_keys))
 	}
 
 	fn relinearize(
@@ -323,8 +735,7 @@ where
 		a: &Self::Ciphertext,
 		relin_keys: &RelinearizationKey,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.relinearize(value, relin_keys))
-			.collect()
+		a.try_map(|value| self.evaluator.relinearize(value, relin_keys))
 	}
 
 	fn rotate_rows(
@@ -333,8 +744,7 @@ where
 		steps: i32,
 		galois_keys: &GaloisKey,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.rotate_rows(value, steps, galois_keys))
-			.collect()
+		a.try_map(|value| self.evaluator.rotate_rows(value, steps, galois_keys))
 	}
 
 	fn rotate_rows_inplace(
@@ -343,12 +753,10 @@ where
 		steps: i32,
 		galois_keys: &GaloisKey,
 	) -> Result<()> {
-		for value in a.iter() {
+		a.try_for_each(|value| {
 			self.evaluator
-				.rotate_rows_inplace(value, steps, galois_keys)?;
-		}
-
-		Ok(())
+				.rotate_rows_inplace(value, steps, galois_keys)
+		})
 	}
 
 	fn rotate_columns(
@@ -356,8 +764,7 @@ where
 		a: &Self::Ciphertext,
 		galois_keys: &GaloisKey,
 	) -> Result<Self::Ciphertext> {
-		a.map(|value| self.evaluator.rotate_columns(value, galois_keys))
-			.collect()
+		a.try_map(|value| self.evaluator.rotate_columns(value, galois_keys))
 	}
 
 	fn rotate_columns_inplace(
@@ -365,10 +772,472 @@ where
 		a: &Self::Ciphertext,
 		galois_keys: &GaloisKey,
 	) -> Result<()> {
-		for value in a.iter() {
-			self.evaluator.rotate_columns_inplace(value, galois_keys)?;
+		a.try_for_each(|value| self.evaluator.rotate_columns_inplace(value, galois_keys))
+	}
+}
+
+impl<E> CircuitEvaluator for TensorEvaluator<E>
+where
+	E: CircuitEvaluator + MaybeSync,
+	E::Ciphertext: Clone + MaybeSend + MaybeSync,
+	E::Plaintext: Clone + MaybeSend + MaybeSync,
+{
+	fn rescale_to_next(
+		&self,
+		a: &Self::Ciphertext,
+	) -> Result<Self::Ciphertext> {
+		a.try_map(|value| self.evaluator.rescale_to_next(value))
+	}
+
+	fn rotate_slots(
+		&self,
+		a: &Self::Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Self::Ciphertext> {
+		a.try_map(|value| self.evaluator.rotate_slots(value, steps, galois_keys))
+	}
+
+	/// Returns the scale of the first chunk. Every chunk went through the
+	/// same operations, so they share it.
+	fn scale(
+		&self,
+		a: &Self::Ciphertext,
+	) -> Result<f64> {
+		match a.first() {
+			Some(value) => self.evaluator.scale(value),
+			None => Err(Error::InvalidArgument.in_operation("TensorEvaluator::scale")),
+		}
+	}
+
+	/// Returns the scale of the first chunk, which every chunk shares.
+	fn plain_scale(
+		&self,
+		a: &Self::Plaintext,
+	) -> Result<f64> {
+		match a.first() {
+			Some(value) => self.evaluator.plain_scale(value),
+			None => Err(Error::InvalidArgument.in_operation("TensorEvaluator::plain_scale")),
+		}
+	}
+
+	fn set_scale(
+		&self,
+		a: &Self::Ciphertext,
+		scale: f64,
+	) -> Result<Self::Ciphertext> {
+		a.try_map(|value| self.evaluator.set_scale(value, scale))
+	}
+
+	fn raise_scale(
+		&self,
+		a: &Self::Ciphertext,
+		scale: f64,
+		keys: &CircuitKeys,
+	) -> Result<Self::Ciphertext> {
+		a.try_map(|value| self.evaluator.raise_scale(value, scale, keys))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn mk_ctx() -> Context {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		Context::new(&params, false, SecurityLevel::TC128).unwrap()
+	}
+
+	#[test]
+	fn can_sum_and_rotate_bfv_tensors() {
+		let ctx = mk_ctx();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let a = (0..10_000).map(|x| x % 7).collect::<Vec<i64>>();
+		let b = (0..10_000).map(|x| x % 5).collect::<Vec<i64>>();
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_enc = encryptor.encrypt(&encoder.encode_i64(&b).unwrap()).unwrap();
+
+		let sum = evaluator.add_many(&[a_enc.clone(), b_enc]).unwrap();
+		let sum = encoder
+			.decode_i64(&decryptor.decrypt(&sum).unwrap())
+			.unwrap();
+
+		assert_eq!(sum.len(), a.len());
+		for (i, value) in sum.iter().enumerate() {
+			assert_eq!(*value, a[i] + b[i]);
 		}
 
-		Ok(())
+		// Each chunk is a 2 x 4096 matrix whose rows rotate independently.
+		let rotated = evaluator.rotate_rows(&a_enc, 1, &galois_keys).unwrap();
+		let rotated = encoder
+			.decode_i64(&decryptor.decrypt(&rotated).unwrap())
+			.unwrap();
+
+		assert_eq!(rotated[0], a[1]);
+		assert_eq!(rotated[4095], a[0]);
+		assert_eq!(rotated[8192], a[8193]);
+	}
+
+	#[test]
+	fn rejects_mismatched_shapes_and_broadcasts_single_chunk_plaintexts() {
+		let ctx = mk_ctx();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+
+		let a = (0..10_000).map(|x| x % 7).collect::<Vec<i64>>();
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_enc = encryptor
+			.encrypt(&encoder.encode_i64(&a[..9_000]).unwrap())
+			.unwrap();
+
+		let expected = Error::ShapeMismatch(Box::new(ShapeMismatch {
+			left: vec![10_000],
+			right: vec![9_000],
+		}));
+
+		assert_eq!(evaluator.sub(&a_enc, &b_enc).unwrap_err(), expected);
+		assert_eq!(
+			evaluator
+				.multiply_inplace(&mut a_enc.clone(), &b_enc)
+				.unwrap_err(),
+			expected
+		);
+
+		let a_plain = encoder.encode_i64(&a[..9_000]).unwrap();
+		assert!(evaluator.add_plain(&a_enc, &a_plain).is_err());
+
+		// A single-chunk plaintext shorter than the chunks doesn't broadcast.
+		let short = encoder.encode_i64(&a[..100]).unwrap();
+		assert_eq!(
+			evaluator.add_plain(&a_enc, &short).unwrap_err(),
+			Error::ShapeMismatch(Box::new(ShapeMismatch {
+				left: vec![10_000],
+				right: vec![100],
+			}))
+		);
+
+		// Neither does one of another shape than a single-chunk ciphertext.
+		let c_enc = encryptor
+			.encrypt(&encoder.encode_i64(&[1, 2, 3]).unwrap())
+			.unwrap();
+		let five = encoder.encode_i64(&[1, 2, 3, 4, 5]).unwrap();
+		assert_eq!(
+			evaluator.multiply_plain(&c_enc, &five).unwrap_err(),
+			Error::ShapeMismatch(Box::new(ShapeMismatch {
+				left: vec![3],
+				right: vec![5],
+			}))
+		);
+
+		// A single-chunk plaintext is applied to every chunk.
+		let two = encoder
+			.encode_i64(&vec![2; encoder.get_slot_count()])
+			.unwrap();
+		let doubled = evaluator.multiply_plain(&a_enc, &two).unwrap();
+		let doubled = encoder
+			.decode_i64(&decryptor.decrypt(&doubled).unwrap())
+			.unwrap();
+
+		assert_eq!(doubled.len(), a.len());
+		for (i, value) in doubled.iter().enumerate() {
+			assert_eq!(*value, 2 * a[i]);
+		}
+
+		let doubled = evaluator.multiply_plain(&c_enc, &two).unwrap();
+		assert_eq!(
+			encoder
+				.decode_i64(&decryptor.decrypt(&doubled).unwrap())
+				.unwrap(),
+			vec![2, 4, 6]
+		);
+	}
+
+	#[test]
+	fn can_slice_concat_and_gather_bfv_tensors() {
+		let ctx = mk_ctx();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let a = (0..10_000).map(|x| x % 1000).collect::<Vec<i64>>();
+		let b = vec![-1, -2, -3];
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_enc = encryptor.encrypt(&encoder.encode_i64(&b).unwrap()).unwrap();
+
+		let decode = |tensor: &Tensor<Ciphertext>| {
+			encoder
+				.decode_i64(&decryptor.decrypt(tensor).unwrap())
+				.unwrap()
+		};
+
+		// Starts on a chunk boundary, so the chunk is copied.
+		let aligned = evaluator
+			.slice(&a_enc, 8192..10_000, &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(aligned.len(), 1);
+		assert_eq!(decode(&aligned), a[8192..10_000]);
+
+		// Crosses both the row and the chunk boundaries.
+		let sliced = evaluator
+			.slice(&a_enc, 100..9_000, &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(sliced.shape().unwrap().dims(), &[8_900]);
+		assert_eq!(decode(&sliced), a[100..9_000]);
+
+		let gathered = evaluator
+			.gather(&a_enc, &[9_999, 0, 4_096, 5, 5], &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(
+			decode(&gathered),
+			vec![a[9_999], a[0], a[4_096], a[5], a[5]]
+		);
+
+		let joined = evaluator
+			.concat(&[b_enc, a_enc.clone()], &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(joined.shape().unwrap().dims(), &[10_003]);
+		assert_eq!(decode(&joined), [b, a].concat());
+
+		assert!(evaluator
+			.gather(&a_enc, &[10_000], &encoder, &galois_keys)
+			.is_err());
+
+		// Out of bounds before a single index is built.
+		assert_eq!(
+			evaluator
+				.slice(&a_enc, 0..usize::MAX, &encoder, &galois_keys)
+				.unwrap_err(),
+			Error::InvalidArgument
+		);
+	}
+
+	#[test]
+	fn can_slice_and_concat_ckks_tensors() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::ckks(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+
+		let a = (0..5_000)
+			.map(|x| (x % 100) as f64 / 10.0)
+			.collect::<Vec<_>>();
+		let a_enc = encryptor.encrypt(&encoder.encode_f64(&a).unwrap()).unwrap();
+
+		let decode = |tensor: &Tensor<Ciphertext>| {
+			encoder
+				.decode_f64(&decryptor.decrypt(tensor).unwrap())
+				.unwrap()
+		};
+
+		let assert_close = |actual: Vec<f64>, expected: &[f64]| {
+			assert_eq!(actual.len(), expected.len());
+			for (actual, expected) in actual.iter().zip(expected) {
+				assert!((actual - expected).abs() < 0.001);
+			}
+		};
+
+		let sliced = evaluator
+			.slice(&a_enc, 4_000..4_500, &encoder, &galois_keys)
+			.unwrap();
+		assert_close(decode(&sliced), &a[4_000..4_500]);
+
+		// The first chunk is copied and the rest is assembled; both end up
+		// at the same level.
+		let b = vec![-0.5; 500];
+		let b_enc = encryptor.encrypt(&encoder.encode_f64(&b).unwrap()).unwrap();
+
+		let joined = evaluator
+			.concat(&[a_enc, b_enc], &encoder, &galois_keys)
+			.unwrap();
+		assert_close(decode(&joined), &[a, b].concat());
+	}
+
+	#[test]
+	fn can_take_dot_products_and_multiply_matrices_bfv() {
+		let ctx = mk_ctx();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(BFVEncoder::new(&ctx).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::bfv(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let decode = |tensor: &Tensor<Ciphertext>| {
+			encoder
+				.decode_i64(&decryptor.decrypt(tensor).unwrap())
+				.unwrap()
+		};
+
+		let a = (0..10_000).map(|x| x % 7).collect::<Vec<i64>>();
+		let b = (0..10_000).map(|x| x % 5 - 2).collect::<Vec<i64>>();
+		let expected = a.iter().zip(&b).map(|(a, b)| a * b).sum::<i64>();
+
+		let a_enc = encryptor.encrypt(&encoder.encode_i64(&a).unwrap()).unwrap();
+		let b_plain = encoder.encode_i64(&b).unwrap();
+		let b_enc = encryptor.encrypt(&b_plain).unwrap();
+
+		let dot = evaluator
+			.dot(&a_enc, &b_enc, &relin_keys, &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(decode(&dot), vec![expected]);
+
+		let dot = evaluator
+			.dot_plain(&a_enc, &b_plain, &encoder, &galois_keys)
+			.unwrap();
+		assert_eq!(decode(&dot), vec![expected]);
+
+		let matmul = |x: &[i64], y: &[i64], m: usize, k: usize, n: usize| {
+			(0..m * n)
+				.map(|p| (0..k).map(|t| x[p / n * k + t] * y[t * n + p % n]).sum())
+				.collect::<Vec<i64>>()
+		};
+
+		// Square weights over several chunks: rows of 4 never straddle a span.
+		let x = (0..3_000 * 4).map(|v| v % 11 - 5).collect::<Vec<i64>>();
+		let w = (0..16).map(|v| v % 3 - 1).collect::<Vec<i64>>();
+
+		let x_enc = encryptor
+			.encrypt(&encoder.encode_i64_shaped(&x, vec![3_000, 4]).unwrap())
+			.unwrap();
+		let product = evaluator
+			.matmul_plain(&x_enc, &w, 4, &encoder, &galois_keys)
+			.unwrap();
+
+		assert_eq!(product.shape().unwrap().dims(), &[3_000, 4]);
+		assert_eq!(decode(&product), matmul(&x, &w, 3_000, 4, 4));
+
+		// A small rectangular matrix within one span.
+		let x = (0..15).collect::<Vec<i64>>();
+		let w = (0..10).map(|v| v - 4).collect::<Vec<i64>>();
+
+		let x_enc = encryptor
+			.encrypt(&encoder.encode_i64_shaped(&x, vec![3, 5]).unwrap())
+			.unwrap();
+		let product = evaluator
+			.matmul_plain(&x_enc, &w, 2, &encoder, &galois_keys)
+			.unwrap();
+
+		assert_eq!(product.shape().unwrap().dims(), &[3, 2]);
+		assert_eq!(decode(&product), matmul(&x, &w, 3, 5, 2));
+
+		// `k * n` overflows rather than wrapping onto the length of `w`.
+		assert_eq!(
+			evaluator
+				.matmul_plain(&x_enc, &w, usize::MAX / 2, &encoder, &galois_keys)
+				.unwrap_err(),
+			Error::InvalidArgument
+		);
+
+		// Rows of 3 straddle the spans once the matrix outgrows one.
+		let x = vec![1; 2_000 * 3];
+		let x_enc = encryptor
+			.encrypt(&encoder.encode_i64_shaped(&x, vec![2_000, 3]).unwrap())
+			.unwrap();
+		assert_eq!(
+			evaluator
+				.matmul_plain(&x_enc, &[1, 2, 3], 1, &encoder, &galois_keys)
+				.unwrap_err(),
+			Error::InvalidArgument
+		);
+	}
+
+	#[test]
+	fn can_multiply_ckks_matrices() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+		let evaluator = TensorEvaluator::ckks(&ctx).unwrap();
+		let galois_keys = gen.create_galois_keys().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		let decode = |tensor: &Tensor<Ciphertext>| {
+			encoder
+				.decode_f64(&decryptor.decrypt(tensor).unwrap())
+				.unwrap()
+		};
+
+		let x = (0..8).map(|v| v as f64 / 4.0).collect::<Vec<_>>();
+		let x_enc = encryptor
+			.encrypt(&encoder.encode_f64_shaped(&x, vec![2, 4]).unwrap())
+			.unwrap();
+
+		let dot = evaluator
+			.dot(&x_enc, &x_enc, &relin_keys, &encoder, &galois_keys)
+			.unwrap();
+		let expected = x.iter().map(|v| v * v).sum::<f64>();
+		assert!((decode(&dot)[0] - expected).abs() < 0.01);
+
+		// A 2 x 4 matrix by a 4 x 3 matrix.
+		let w = (0..12).map(|v| v as f64 / 2.0 - 3.0).collect::<Vec<_>>();
+		let product = evaluator
+			.matmul_plain(&x_enc, &w, 3, &encoder, &galois_keys)
+			.unwrap();
+
+		assert_eq!(product.shape().unwrap().dims(), &[2, 3]);
+
+		let actual = decode(&product);
+		for i in 0..2 {
+			for j in 0..3 {
+				let expected = (0..4).map(|t| x[i * 4 + t] * w[t * 3 + j]).sum::<f64>();
+				assert!((actual[i * 3 + j] - expected).abs() < 0.001);
+			}
+		}
 	}
 }
@@ -1,16 +1,56 @@
+use std::fmt;
+use std::io::{Read, Write};
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::CompressionType;
 use crate::Context;
+use crate::ContextSeed;
+use crate::Error;
 use crate::FromBytes;
 use crate::Result;
+use crate::SchemeType;
+use crate::ShapeMismatch;
 use crate::ToBytes;
 
+use parallel::{MaybeSend, MaybeSync};
+
+pub mod container;
 pub mod decryptor;
 pub mod encoder;
 pub mod encryptor;
 pub mod evaluator;
+pub mod parallel;
+pub mod select;
+pub mod shape;
+
+pub use shape::Shape;
 
 /// Struct to store a tensor of elements of the same type.
+///
+/// Each element is a chunk holding as many values as the encoder has slots.
+/// A tensor may also carry the logical [`Shape`] of the values it holds, which
+/// tells how many of the slots are real data and how they are arranged, and
+/// the number of slots in each chunk, which [`TensorEncoder`](encoder::TensorEncoder)
+/// records.
+///
+/// The chunks are private so the shape stays consistent with them. Build a
+/// tensor from chunks with `Tensor::from(chunks)` or [`Tensor::with_shape`],
+/// and take them back with [`Tensor::into_chunks`].
 #[derive(Debug, Clone)]
-pub struct Tensor<T>(pub Vec<T>);
+pub struct Tensor<T> {
+	chunks: Vec<T>,
+	shape: Option<Shape>,
+	slot_count: Option<usize>,
+}
+
+/// Magic bytes that open a tensor written with [`Tensor::save_to`].
+const STREAM_MAGIC: [u8; 4] = *b"SLYS";
+
+/// Magic bytes that opened tensor streams before the shape was recorded.
+const LEGACY_STREAM_MAGIC: [u8; 4] = *b"SLYT";
 
 /// A trait for converting chunk of objects into a list of byte arrays.
 pub trait ToChunk {
@@ -30,12 +70,18 @@ pub trait FromChunk {
 		Self: Sized;
 }
 
+impl<T> From<Vec<T>> for Tensor<T> {
+	fn from(chunks: Vec<T>) -> Self {
+		Self::new(chunks)
+	}
+}
+
 impl<T> IntoIterator for Tensor<T> {
 	type Item = T;
 	type IntoIter = std::vec::IntoIter<T>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.into_iter()
+		self.chunks.into_iter()
 	}
 }
 
@@ -44,11 +90,183 @@ impl<'a, T> IntoIterator for &'a Tensor<T> {
 	type IntoIter = std::slice::Iter<'a, T>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.iter()
+		self.chunks.iter()
 	}
 }
 
 impl<T> Tensor<T> {
+	/// Creates a tensor from the given chunks without a known shape.
+	pub fn new(chunks: Vec<T>) -> Self {
+		Self {
+			chunks,
+			shape: None,
+			slot_count: None,
+		}
+	}
+
+	/// Creates a tensor from the given chunks holding data of the given
+	/// shape in row-major order.
+	pub fn with_shape(
+		chunks: Vec<T>,
+		shape: Shape,
+	) -> Self {
+		Self {
+			chunks,
+			shape: Some(shape),
+			slot_count: None,
+		}
+	}
+
+	/// Records the number of slots in each chunk.
+	pub fn with_slot_count(
+		self,
+		slot_count: usize,
+	) -> Self {
+		Self {
+			slot_count: Some(slot_count),
+			..self
+		}
+	}
+
+	/// Returns the number of slots in each chunk, if known.
+	pub fn slot_count(&self) -> Option<usize> {
+		self.slot_count
+	}
+
+	/// Returns the logical shape of the data, if known.
+	pub fn shape(&self) -> Option<&Shape> {
+		self.shape.as_ref()
+	}
+
+	/// Returns the number of real values held by this tensor, if known.
+	pub fn numel(&self) -> Option<usize> {
+		self.shape.as_ref().map(Shape::numel)
+	}
+
+	/// Gives the data a new shape holding the same number of values.
+	///
+	/// The chunks are untouched since the data is stored in row-major order.
+	/// Fails if the shape is unknown or the element counts differ.
+	pub fn reshape(
+		self,
+		dims: Vec<usize>,
+	) -> Result<Self> {
+		let shape = self
+			.shape
+			.as_ref()
+			.ok_or(Error::InvalidArgument)?
+			.reshape(dims)?;
+
+		Ok(Self {
+			shape: Some(shape),
+			..self
+		})
+	}
+
+	/// Collapses the data into a single dimension, if the shape is known.
+	pub fn flatten(self) -> Self {
+		let shape = self.numel().map(Shape::flat);
+
+		Self {
+			shape,
+			..self
+		}
+	}
+
+	/// Returns this tensor with its shape replaced by the given one.
+	pub(crate) fn with_known_shape(
+		self,
+		shape: Shape,
+	) -> Self {
+		Self {
+			shape: Some(shape),
+			..self
+		}
+	}
+
+	/// Returns a tensor with this tensor's shape holding the given chunks.
+	pub(crate) fn with_chunks<U>(
+		&self,
+		chunks: Vec<U>,
+	) -> Tensor<U> {
+		Tensor {
+			chunks,
+			shape: self.shape.clone(),
+			slot_count: self.slot_count,
+		}
+	}
+
+	/// Checks that `other` can be combined element-wise with this tensor: both
+	/// need the same number of chunks and, when both shapes are known, the
+	/// same shape.
+	pub fn check_same_shape<U>(
+		&self,
+		other: &Tensor<U>,
+	) -> Result<()> {
+		let same_shape = match (&self.shape, &other.shape) {
+			(Some(left), Some(right)) => left == right,
+			_ => true,
+		};
+
+		if self.len() != other.len() || !same_shape {
+			return Err(Error::ShapeMismatch(Box::new(ShapeMismatch {
+				left: self.error_dims(),
+				right: other.error_dims(),
+			})));
+		}
+
+		Ok(())
+	}
+
+	/// Resolves how `other` combines with this tensor in an operation with a
+	/// plaintext operand.
+	///
+	/// A tensor with a single chunk broadcasts when it fills every slot of
+	/// that chunk, or when either shape is unknown: the chunk is applied to
+	/// every chunk of this tensor and returned. Otherwise the shapes must
+	/// match as in [`Tensor::check_same_shape`] and `None` is returned, so a
+	/// plaintext holding fewer or more values than this tensor is rejected.
+	pub fn broadcast_chunk<'a, U>(
+		&self,
+		other: &'a Tensor<U>,
+	) -> Result<Option<&'a U>> {
+		if other.len() == 1 {
+			let fills_slots = match (&self.shape, &other.shape) {
+				(Some(_), Some(shape)) => {
+					Some(shape.numel()) == self.slot_count.or(other.slot_count)
+				}
+				_ => true,
+			};
+
+			if fills_slots {
+				return Ok(other.chunks.first());
+			}
+		}
+
+		self.check_same_shape(other)?;
+
+		Ok(None)
+	}
+
+	/// Returns the dimensions to report in errors: the logical shape when
+	/// known, otherwise the number of chunks.
+	fn error_dims(&self) -> Vec<usize> {
+		match &self.shape {
+			Some(shape) => shape.dims().to_vec(),
+			None => vec![self.len()],
+		}
+	}
+
+	/// Returns the chunks of this tensor.
+	pub fn chunks(&self) -> &[T] {
+		&self.chunks
+	}
+
+	/// Consumes the tensor, returning its chunks.
+	pub fn into_chunks(self) -> Vec<T> {
+		self.chunks
+	}
+
 	/// Returns the first element in this tensor.
 	pub fn first(&self) -> Option<&T> {
 		self.get(0)
@@ -59,27 +277,28 @@ impl<T> Tensor<T> {
 		&self,
 		index: usize,
 	) -> Option<&T> {
-		self.0.get(index)
+		self.chunks.get(index)
 	}
 
-	/// Returns the number of elements in this tensor.
+	/// Returns the number of chunks in this tensor. See [`Tensor::numel`]
+	/// for the number of values.
 	pub fn len(&self) -> usize {
-		self.0.len()
+		self.chunks.len()
 	}
 
 	/// Returns true if this tensor contains no elements.
 	pub fn is_empty(&self) -> bool {
-		self.0.is_empty()
+		self.chunks.is_empty()
 	}
 
 	/// Returns an iterator over the elements of this tensor.
 	pub fn iter(&self) -> std::slice::Iter<T> {
-		self.0.iter()
+		self.chunks.iter()
 	}
 
 	/// Returns a mutable iterator over the elements of this tensor.
 	pub fn iter_mut(&mut self) -> std::slice::IterMut<T> {
-		self.0.iter_mut()
+		self.chunks.iter_mut()
 	}
 
 	/// Applies the given function to each element in this tensor, returning a new tensor with the results.
@@ -90,10 +309,15 @@ impl<T> Tensor<T> {
 	where
 		F: FnMut(&T) -> U,
 	{
-		Tensor(self.0.iter().map(f).collect())
+		Tensor {
+			chunks: self.chunks.iter().map(f).collect(),
+			shape: self.shape.clone(),
+			slot_count: self.slot_count,
+		}
 	}
 
 	/// zips two tensors together, applying the given function to each pair of elements.
+	/// The result takes the shape of `self`.
 	pub fn zip<U, V, F>(
 		&self,
 		other: &Tensor<U>,
@@ -102,13 +326,90 @@ impl<T> Tensor<T> {
 	where
 		F: FnMut(&T, &U) -> V,
 	{
-		Tensor(
-			self.0
+		Tensor {
+			chunks: self
+				.chunks
 				.iter()
-				.zip(other.0.iter())
+				.zip(other.chunks.iter())
 				.map(|(a, b)| f(a, b))
 				.collect(),
-		)
+			shape: self.shape.clone(),
+			slot_count: self.slot_count,
+		}
+	}
+}
+
+impl<T> Tensor<T>
+where
+	T: MaybeSync,
+{
+	/// Applies the fallible function to each chunk, keeping the shape. With
+	/// the `parallel` feature the chunks are processed concurrently.
+	pub(crate) fn try_map<U, F>(
+		&self,
+		f: F,
+	) -> Result<Tensor<U>>
+	where
+		U: MaybeSend,
+		F: Fn(&T) -> Result<U> + MaybeSync + MaybeSend,
+	{
+		Ok(self.with_chunks(parallel::try_map(&self.chunks, f)?))
+	}
+
+	/// Applies the fallible function to each pair of chunks, keeping the shape
+	/// of `self`. With the `parallel` feature the pairs are processed
+	/// concurrently.
+	pub(crate) fn try_zip<U, V, F>(
+		&self,
+		other: &Tensor<U>,
+		f: F,
+	) -> Result<Tensor<V>>
+	where
+		U: MaybeSync,
+		V: MaybeSend,
+		F: Fn(&T, &U) -> Result<V> + MaybeSync + MaybeSend,
+	{
+		Ok(self.with_chunks(parallel::try_zip(&self.chunks, &other.chunks, f)?))
+	}
+
+	/// Runs the fallible function on each chunk.
+	pub(crate) fn try_for_each<F>(
+		&self,
+		f: F,
+	) -> Result<()>
+	where
+		F: Fn(&T) -> Result<()> + MaybeSync + MaybeSend,
+	{
+		parallel::try_for_each(&self.chunks, f)
+	}
+}
+
+impl<T> Tensor<T>
+where
+	T: MaybeSend,
+{
+	/// Runs the fallible function on each chunk, mutably.
+	pub(crate) fn try_for_each_mut<F>(
+		&mut self,
+		f: F,
+	) -> Result<()>
+	where
+		F: Fn(&mut T) -> Result<()> + MaybeSync + MaybeSend,
+	{
+		parallel::try_for_each_mut(&mut self.chunks, f)
+	}
+
+	/// Runs the fallible function on each pair of chunks, the first mutably.
+	pub(crate) fn try_zip_mut<U, F>(
+		&mut self,
+		other: &Tensor<U>,
+		f: F,
+	) -> Result<()>
+	where
+		U: MaybeSync,
+		F: Fn(&mut T, &U) -> Result<()> + MaybeSync + MaybeSend,
+	{
+		parallel::try_zip_mut(&mut self.chunks, &other.chunks, f)
 	}
 }
 
@@ -124,7 +425,178 @@ where
 			.iter()
 			.map(|bytes| T::from_bytes(context, bytes))
 			.collect::<Result<Vec<_>>>()?;
-		Ok(Tensor(values))
+		Ok(Tensor::new(values))
+	}
+}
+
+/// The fields a tensor serializes with serde.
+const FIELDS: &[&str] = &["shape", "chunks"];
+
+/// Serializes as a struct holding the dimensions of the shape, if known, and
+/// the chunks.
+impl<T> Serialize for Tensor<T>
+where
+	T: Serialize,
+{
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_struct("Tensor", FIELDS.len())?;
+
+		state.serialize_field("shape", &self.shape.as_ref().map(Shape::dims))?;
+		state.serialize_field("chunks", &self.chunks)?;
+
+		state.end()
+	}
+}
+
+/// Loads a tensor serialized with serde. The bare sequence of chunks written
+/// before the shape was recorded still loads, as a tensor without a shape.
+impl<'de, T> DeserializeSeed<'de> for ContextSeed<'_, Tensor<T>>
+where
+	T: FromBytes<State = Context>,
+{
+	type Value = Tensor<T>;
+
+	fn deserialize<D>(
+		self,
+		deserializer: D,
+	) -> std::result::Result<Self::Value, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_struct(
+			"Tensor",
+			FIELDS,
+			TensorVisitor(ContextSeed::new(self.context())),
+		)
+	}
+}
+
+struct TensorVisitor<'a, T>(ContextSeed<'a, T>);
+
+impl<'de, T> Visitor<'de> for TensorVisitor<'_, T>
+where
+	T: FromBytes<State = Context>,
+{
+	type Value = Tensor<T>;
+
+	fn expecting(
+		&self,
+		formatter: &mut fmt::Formatter,
+	) -> fmt::Result {
+		formatter.write_str("a tensor of serialized SEAL objects")
+	}
+
+	fn visit_seq<A>(
+		self,
+		seq: A,
+	) -> std::result::Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let context = self.0.context();
+		let chunks = ChunksVisitor(self.0).visit_seq(seq)?;
+
+		Ok(Tensor::new(chunks).with_slot_count(slot_count(context).map_err(de::Error::custom)?))
+	}
+
+	fn visit_map<A>(
+		self,
+		mut map: A,
+	) -> std::result::Result<Self::Value, A::Error>
+	where
+		A: MapAccess<'de>,
+	{
+		let mut shape: Option<Option<Vec<usize>>> = None;
+		let mut chunks = None;
+
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"shape" => {
+					if shape.is_some() {
+						return Err(de::Error::duplicate_field("shape"));
+					}
+
+					shape = Some(map.next_value()?);
+				}
+				"chunks" => {
+					if chunks.is_some() {
+						return Err(de::Error::duplicate_field("chunks"));
+					}
+
+					chunks = Some(map.next_value_seed(ChunksVisitor(self.0))?);
+				}
+				_ => return Err(de::Error::unknown_field(&key, FIELDS)),
+			}
+		}
+
+		let chunks = chunks.ok_or_else(|| de::Error::missing_field("chunks"))?;
+		let slots = slot_count(self.0.context()).map_err(de::Error::custom)?;
+
+		match shape.flatten() {
+			Some(dims) => {
+				let shape =
+					Shape::new(dims).map_err(|_| de::Error::custom("shape is too large"))?;
+
+				Ok(Tensor::with_shape(chunks, shape).with_slot_count(slots))
+			}
+			None => Ok(Tensor::new(chunks).with_slot_count(slots)),
+		}
+	}
+}
+
+/// Loads the chunks of a tensor.
+struct ChunksVisitor<'a, T>(ContextSeed<'a, T>);
+
+impl<'de, T> DeserializeSeed<'de> for ChunksVisitor<'_, T>
+where
+	T: FromBytes<State = Context>,
+{
+	type Value = Vec<T>;
+
+	fn deserialize<D>(
+		self,
+		deserializer: D,
+	) -> std::result::Result<Self::Value, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_seq(self)
+	}
+}
+
+impl<'de, T> Visitor<'de> for ChunksVisitor<'_, T>
+where
+	T: FromBytes<State = Context>,
+{
+	type Value = Vec<T>;
+
+	fn expecting(
+		&self,
+		formatter: &mut fmt::Formatter,
+	) -> fmt::Result {
+		formatter.write_str("a sequence of serialized SEAL objects")
+	}
+
+	fn visit_seq<A>(
+		self,
+		mut seq: A,
+	) -> std::result::Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut values = Vec::new();
+
+		while let Some(value) = seq.next_element_seed(self.0)? {
+			values.push(value);
+		}
+
+		Ok(values)
 	}
 }
 
@@ -133,10 +605,149 @@ where
 	T: ToBytes,
 {
 	fn to_chunk(&self) -> Result<Vec<Vec<u8>>> {
-		self.0.iter().map(|value| value.as_bytes()).collect()
+		self.chunks.iter().map(|value| value.as_bytes()).collect()
+	}
+}
+
+impl<T> Tensor<T>
+where
+	T: ToBytes,
+{
+	/// Serializes every element with the given compression mode.
+	pub fn to_chunk_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<Vec<u8>>> {
+		self.chunks
+			.iter()
+			.map(|value| value.as_bytes_with(compression))
+			.collect()
+	}
+
+	/// Writes the tensor to the given writer as a framed stream.
+	///
+	/// The stream starts with the magic bytes `SLYS` and the shape: a byte
+	/// that is 1 if the shape is known, then the number of dimensions as a
+	/// little endian `u32` and each dimension as a `u64`. The number of
+	/// elements follows as a `u64`, then each element in SEAL's own
+	/// self-delimiting format. Elements are serialized and written one at a
+	/// time, so at most one serialized element is held in memory.
+	pub fn save_to<W: Write>(
+		&self,
+		writer: &mut W,
+	) -> Result<()> {
+		writer.write_all(&STREAM_MAGIC)?;
+
+		match &self.shape {
+			Some(shape) => {
+				writer.write_all(&[1])?;
+				writer.write_all(&(shape.ndim() as u32).to_le_bytes())?;
+
+				for dim in shape.dims() {
+					writer.write_all(&(*dim as u64).to_le_bytes())?;
+				}
+			}
+			None => writer.write_all(&[0])?,
+		}
+
+		writer.write_all(&(self.chunks.len() as u64).to_le_bytes())?;
+
+		for value in &self.chunks {
+			value.save_to(writer)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl<T> Tensor<T>
+where
+	T: FromBytes<State = Context>,
+{
+	/// Reads a tensor written by [`Tensor::save_to`] from the given reader.
+	/// Streams opening with `SLYT`, written before the shape was recorded,
+	/// load without a shape.
+	pub fn load_from<R: Read>(
+		context: &Context,
+		reader: &mut R,
+	) -> Result<Self> {
+		let mut magic = [0u8; 4];
+		reader.read_exact(&mut magic)?;
+
+		let shape = match magic {
+			STREAM_MAGIC => read_shape(reader)?,
+			LEGACY_STREAM_MAGIC => None,
+			_ => return Err(stream_error("not a tensor stream")),
+		};
+
+		let len = read_u64(reader)?;
+
+		// Don't preallocate from an untrusted length; the elements themselves
+		// bound how much we read.
+		let mut values = Vec::new();
+
+		for _ in 0..len {
+			values.push(T::load_from(context, reader)?);
+		}
+
+		Ok(Tensor {
+			chunks: values,
+			shape,
+			slot_count: Some(slot_count(context)?),
+		})
 	}
 }
 
+/// Returns the number of slots of a chunk encoded under the context.
+pub(crate) fn slot_count(context: &Context) -> Result<usize> {
+	let params = context.get_encryption_parameters()?;
+	let degree = params.get_poly_modulus_degree()? as usize;
+
+	Ok(match params.get_scheme()? {
+		SchemeType::Ckks => degree / 2,
+		_ => degree,
+	})
+}
+
+/// Reads the shape recorded by [`Tensor::save_to`].
+fn read_shape<R: Read>(reader: &mut R) -> Result<Option<Shape>> {
+	let mut known = [0u8; 1];
+	reader.read_exact(&mut known)?;
+
+	match known[0] {
+		0 => return Ok(None),
+		1 => {}
+		_ => return Err(stream_error("invalid shape marker")),
+	}
+
+	let mut rank = [0u8; 4];
+	reader.read_exact(&mut rank)?;
+
+	let mut dims = Vec::new();
+
+	for _ in 0..u32::from_le_bytes(rank) {
+		let dim = usize::try_from(read_u64(reader)?)
+			.map_err(|_| stream_error("dimension does not fit in memory"))?;
+
+		dims.push(dim);
+	}
+
+	Shape::new(dims)
+		.map(Some)
+		.map_err(|_| stream_error("shape is too large"))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+	let mut bytes = [0u8; 8];
+	reader.read_exact(&mut bytes)?;
+
+	Ok(u64::from_le_bytes(bytes))
+}
+
+fn stream_error(reason: &str) -> Error {
+	Error::SerializationError(Box::new(reason.to_string()))
+}
+
 impl<T> Tensor<T>
 where
 	T: Clone,
@@ -153,10 +764,162 @@ where
 impl<T, E> Tensor<std::result::Result<T, E>> {
 	/// Collects the results in this tensor, returning the successful values.
 	pub fn collect(self) -> std::result::Result<Tensor<T>, E> {
-		let values = self
-			.0
+		let chunks = self
+			.chunks
 			.into_iter()
 			.collect::<std::result::Result<Vec<_>, _>>()?;
-		Ok(Tensor(values))
+		Ok(Tensor {
+			chunks,
+			shape: self.shape,
+			slot_count: self.slot_count,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	#[test]
+	fn rejects_overflowing_shapes() {
+		let huge = 1usize << (usize::BITS / 2 + 4);
+
+		// No elements, but the strides overflow.
+		assert_eq!(
+			Shape::new(vec![0, huge, huge]).unwrap_err(),
+			Error::InvalidArgument
+		);
+		assert_eq!(
+			Shape::new(vec![huge, huge, 0]).unwrap_err(),
+			Error::InvalidArgument
+		);
+
+		let shape = Shape::new(vec![0, 3]).unwrap();
+		assert_eq!(shape.strides(), &[3, 1]);
+		assert_eq!(
+			shape.reshape(vec![huge, huge, 0]).unwrap_err(),
+			Error::InvalidArgument
+		);
+		assert_eq!(shape.reshape(vec![3, 0]).unwrap().strides(), &[0, 1]);
+	}
+
+	#[test]
+	fn can_stream_tensor_roundtrip() {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+		let decryptor = TensorDecryptor::new(&ctx, &gen.secret_key().unwrap()).unwrap();
+
+		let data = (0..10_000).map(|x| x as f64).collect::<Vec<_>>();
+		let ciphertext = encryptor
+			.encrypt(&encoder.encode_f64(&data).unwrap())
+			.unwrap()
+			.reshape(vec![100, 100])
+			.unwrap();
+
+		let mut stream = Vec::new();
+		ciphertext.save_to(&mut stream).unwrap();
+		// A trailing object must not be consumed by the tensor reader.
+		ciphertext.chunks()[0].save_to(&mut stream).unwrap();
+
+		let mut reader = stream.as_slice();
+		let loaded = Tensor::<Ciphertext>::load_from(&ctx, &mut reader).unwrap();
+		let trailing = Ciphertext::load_from(&ctx, &mut reader).unwrap();
+
+		assert!(reader.is_empty());
+		assert_eq!(loaded.len(), ciphertext.len());
+		assert_eq!(loaded.shape(), ciphertext.shape());
+		assert!(trailing == ciphertext.chunks()[0]);
+
+		// Streams written before the shape was recorded still load.
+		let mut legacy = b"SLYT".to_vec();
+		legacy.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+
+		for chunk in ciphertext.iter() {
+			chunk.save_to(&mut legacy).unwrap();
+		}
+
+		let legacy = Tensor::<Ciphertext>::load_from(&ctx, &mut legacy.as_slice()).unwrap();
+
+		assert_eq!(legacy.len(), ciphertext.len());
+		assert!(legacy.shape().is_none());
+
+		let decoded = encoder
+			.decode_f64(&decryptor.decrypt(&loaded).unwrap())
+			.unwrap();
+
+		for (expected, actual) in data.iter().zip(decoded.iter()) {
+			assert!((expected - actual).abs() < 1e-3);
+		}
+	}
+
+	#[test]
+	fn can_serde_tensor_with_context_seed() {
+		use serde::de::DeserializeSeed;
+
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let encoder = TensorEncoder::new(CKKSEncoder::new(&ctx, 2.0f64.powi(40)).unwrap());
+		let encryptor =
+			TensorEncryptor::with_public_key(&ctx, &gen.create_public_key().unwrap()).unwrap();
+
+		let data = (0..5_000).map(|x| x as f64).collect::<Vec<_>>();
+		let ciphertext = encryptor
+			.encrypt(&encoder.encode_f64(&data).unwrap())
+			.unwrap()
+			.reshape(vec![50, 100])
+			.unwrap();
+
+		let json = serde_json::to_string(&ciphertext).unwrap();
+
+		let mut deserializer = serde_json::Deserializer::from_str(&json);
+		let loaded: Tensor<Ciphertext> = ContextSeed::new(&ctx)
+			.deserialize(&mut deserializer)
+			.unwrap();
+
+		assert_eq!(loaded.len(), ciphertext.len());
+		assert_eq!(loaded.shape().unwrap().dims(), &[50, 100]);
+
+		// The bare sequence of chunks written before the shape was recorded
+		// still loads.
+		let json = serde_json::to_string(ciphertext.chunks()).unwrap();
+
+		let mut deserializer = serde_json::Deserializer::from_str(&json);
+		let legacy: Tensor<Ciphertext> = ContextSeed::new(&ctx)
+			.deserialize(&mut deserializer)
+			.unwrap();
+
+		assert_eq!(legacy.len(), ciphertext.len());
+		assert!(legacy.shape().is_none());
+
+		for (a, b) in loaded.iter().zip(ciphertext.iter()) {
+			assert!(a == b);
+		}
+
+		let params_json = serde_json::to_string(&params).unwrap();
+		let params_2: EncryptionParameters = serde_json::from_str(&params_json).unwrap();
+
+		assert_eq!(params_2.get_scheme().unwrap(), SchemeType::Ckks);
+		assert_eq!(params_2.as_bytes().unwrap(), params.as_bytes().unwrap());
 	}
 }
@@ -0,0 +1,233 @@
+//! Chunk-level iteration for tensor operations.
+//!
+//! With the `parallel` feature, the chunks of a tensor are processed on the
+//! global rayon pool; otherwise they are processed in order on the calling
+//! thread. Either way the result keeps the order of the chunks and the first
+//! error encountered is returned.
+//!
+//! # Thread safety
+//! Sharing the SEAL objects across threads is sound because:
+//! * ciphertexts, plaintexts and keys are plain data; concurrent reads are
+//!   fine and each operation writes only to its own output object,
+//! * the evaluators, encoders, encryptors and decryptors only read their
+//!   state (the context, keys and precomputed tables) once constructed,
+//! * allocations come from pools that are safe to share. The global pool,
+//!   the pools of [`crate::MemoryPool::new`] and those created by the `New`
+//!   and `Fixed` profiles are guarded by a lock in SEAL, so an object given
+//!   a pool with `with_memory_pool` may be used from every rayon worker at
+//!   once. The pools of the `ThreadLocal` profile are not, and an object
+//!   allocated from one must stay on its thread, so
+//!   [`crate::MemoryManager::switch_profile`] refuses that profile with this
+//!   feature,
+//! * encryptors draw from a fresh random generator created per call.
+//!
+//! In-place operations on the same object still need exclusive access,
+//! which the borrow checker enforces.
+
+use static_assertions::assert_impl_all;
+
+use crate::{
+	BFVEncoder, BFVEvaluator, CKKSEncoder, CKKSEvaluator, Ciphertext, Context, Decryptor,
+	Encryptor, GaloisKey, MemoryPool, Plaintext, PublicKey, RelinearizationKey, Result, SecretKey,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+assert_impl_all!(Ciphertext: Send, Sync);
+assert_impl_all!(Plaintext: Send, Sync);
+assert_impl_all!(Context: Send, Sync);
+assert_impl_all!(PublicKey: Send, Sync);
+assert_impl_all!(SecretKey: Send, Sync);
+assert_impl_all!(RelinearizationKey: Send, Sync);
+assert_impl_all!(GaloisKey: Send, Sync);
+assert_impl_all!(BFVEncoder: Send, Sync);
+assert_impl_all!(CKKSEncoder: Send, Sync);
+assert_impl_all!(BFVEvaluator: Send, Sync);
+assert_impl_all!(CKKSEvaluator: Send, Sync);
+assert_impl_all!(Encryptor: Send, Sync);
+assert_impl_all!(Decryptor: Send, Sync);
+assert_impl_all!(MemoryPool: Send, Sync);
+
+/// A [`Send`] bound that only applies with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub trait MaybeSend: Send {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send> MaybeSend for T {}
+
+/// A [`Send`] bound that only applies with the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSend {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSend for T {}
+
+/// A [`Sync`] bound that only applies with the `parallel` feature.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Sync> MaybeSync for T {}
+
+/// A [`Sync`] bound that only applies with the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// Applies `f` to every item.
+pub(crate) fn try_map<T, U, F>(
+	items: &[T],
+	f: F,
+) -> Result<Vec<U>>
+where
+	T: MaybeSync,
+	U: MaybeSend,
+	F: Fn(&T) -> Result<U> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		items.par_iter().map(f).collect()
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.iter().map(f).collect()
+	}
+}
+
+/// Applies `f` to consecutive slices of `size` items; the last one may be
+/// shorter.
+pub(crate) fn try_map_chunks<T, U, F>(
+	items: &[T],
+	size: usize,
+	f: F,
+) -> Result<Vec<U>>
+where
+	T: MaybeSync,
+	U: MaybeSend,
+	F: Fn(&[T]) -> Result<U> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		items.par_chunks(size).map(f).collect()
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.chunks(size).map(f).collect()
+	}
+}
+
+/// Applies `f` to every index in `0..len`.
+pub(crate) fn try_map_range<U, F>(
+	len: usize,
+	f: F,
+) -> Result<Vec<U>>
+where
+	U: MaybeSend,
+	F: Fn(usize) -> Result<U> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		(0..len).into_par_iter().map(f).collect()
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		(0..len).map(f).collect()
+	}
+}
+
+/// Applies `f` to pairs of items, stopping at the end of the shorter slice.
+pub(crate) fn try_zip<T, U, V, F>(
+	a: &[T],
+	b: &[U],
+	f: F,
+) -> Result<Vec<V>>
+where
+	T: MaybeSync,
+	U: MaybeSync,
+	V: MaybeSend,
+	F: Fn(&T, &U) -> Result<V> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		a.par_iter()
+			.zip(b.par_iter())
+			.map(|(a, b)| f(a, b))
+			.collect()
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		a.iter().zip(b.iter()).map(|(a, b)| f(a, b)).collect()
+	}
+}
+
+/// Runs `f` on every item.
+pub(crate) fn try_for_each<T, F>(
+	items: &[T],
+	f: F,
+) -> Result<()>
+where
+	T: MaybeSync,
+	F: Fn(&T) -> Result<()> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		items.par_iter().try_for_each(f)
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.iter().try_for_each(f)
+	}
+}
+
+/// Runs `f` on every item, mutably.
+pub(crate) fn try_for_each_mut<T, F>(
+	items: &mut [T],
+	f: F,
+) -> Result<()>
+where
+	T: MaybeSend,
+	F: Fn(&mut T) -> Result<()> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		items.par_iter_mut().try_for_each(f)
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		items.iter_mut().try_for_each(f)
+	}
+}
+
+/// Runs `f` on pairs of items, the first mutably, stopping at the end of the
+/// shorter slice.
+pub(crate) fn try_zip_mut<T, U, F>(
+	a: &mut [T],
+	b: &[U],
+	f: F,
+) -> Result<()>
+where
+	T: MaybeSend,
+	U: MaybeSync,
+	F: Fn(&mut T, &U) -> Result<()> + MaybeSync + MaybeSend,
+{
+	#[cfg(feature = "parallel")]
+	{
+		a.par_iter_mut()
+			.zip(b.par_iter())
+			.try_for_each(|(a, b)| f(a, b))
+	}
+
+	#[cfg(not(feature = "parallel"))]
+	{
+		a.iter_mut().zip(b.iter()).try_for_each(|(a, b)| f(a, b))
+	}
+}
@@ -0,0 +1,362 @@
+//! Moving and combining encrypted values across slots.
+//!
+//! A value can only be moved to another slot by rotating the whole
+//! ciphertext, so the moves that need the same rotation are grouped: each
+//! group costs one rotation, one multiplication by a 0/1 mask plaintext that
+//! keeps only the moved slots, and one addition.
+
+use std::collections::BTreeMap;
+
+use crate::{
+	BFVEncoder, BFVEvaluator, CKKSEncoder, CKKSEvaluator, Ciphertext, Error, Evaluator, GaloisKey,
+	Plaintext, Result,
+};
+
+/// Moves the value in slot `from` of source chunk `source` to slot `to`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SlotMove {
+	/// The index of the source chunk.
+	pub source: usize,
+	/// The slot to read from.
+	pub from: usize,
+	/// The slot to write to.
+	pub to: usize,
+}
+
+/// Scheme-specific slot operations used by
+/// [`TensorEvaluator::gather`](super::evaluator::TensorEvaluator::gather),
+/// [`TensorEvaluator::dot`](super::evaluator::TensorEvaluator::dot) and the
+/// operations built on them.
+pub trait SlotSelect: Evaluator<Plaintext = Plaintext, Ciphertext = Ciphertext> {
+	/// The encoder used to build mask plaintexts.
+	type Encoder;
+
+	/// The type of the values held in the slots.
+	type Value: Copy + Default;
+
+	/// Returns the number of slots in each chunk.
+	fn slot_count(encoder: &Self::Encoder) -> usize;
+
+	/// Returns the number of consecutive slots that [`SlotSelect::rotate`]
+	/// rotates as a cycle. It divides the slot count.
+	fn rotation_span(encoder: &Self::Encoder) -> usize;
+
+	/// Rotates every span of `a` cyclically, so that slot `i` of the result
+	/// holds slot `i + steps` of the same span.
+	fn rotate(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext>;
+
+	/// Returns a ciphertext holding the sum of all the slots of `a` in every
+	/// slot.
+	fn sum_slots(
+		&self,
+		encoder: &Self::Encoder,
+		a: &Ciphertext,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext>;
+
+	/// Multiplies every slot of `a` by the value at the same position of
+	/// `values`; missing values are zero. The result has the scale of `a`.
+	fn multiply_values(
+		&self,
+		encoder: &Self::Encoder,
+		a: &Ciphertext,
+		values: &[Self::Value],
+	) -> Result<Ciphertext>;
+
+	/// Brings the product of two ciphertexts, or of a ciphertext and a
+	/// plaintext, back to a manageable scale.
+	fn rescale(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext>;
+
+	/// Returns a ciphertext holding the values described by `moves` and zero
+	/// in every other slot.
+	///
+	/// `moves` must not be empty and must not write the same slot twice.
+	fn select(
+		&self,
+		encoder: &Self::Encoder,
+		sources: &[&Ciphertext],
+		moves: &[SlotMove],
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext>;
+
+	/// Brings a chunk copied as is to the level and scale of the chunks
+	/// built by [`SlotSelect::select`].
+	fn align(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext>;
+}
+
+/// Converts a left rotation by `shift` slots of a cycle of `len` slots into
+/// the step with the smallest magnitude.
+fn signed_steps(
+	shift: usize,
+	len: usize,
+) -> i32 {
+	if shift > len / 2 {
+		shift as i32 - len as i32
+	} else {
+		shift as i32
+	}
+}
+
+/// Adds `value` to the running sum in `acc`.
+fn accumulate<E: Evaluator<Ciphertext = Ciphertext>>(
+	evaluator: &E,
+	acc: Option<Ciphertext>,
+	value: Ciphertext,
+) -> Result<Ciphertext> {
+	match acc {
+		Some(mut acc) => {
+			evaluator.add_inplace(&mut acc, &value)?;
+			Ok(acc)
+		}
+		None => Ok(value),
+	}
+}
+
+impl SlotSelect for BFVEvaluator {
+	type Encoder = BFVEncoder;
+	type Value = i64;
+
+	fn slot_count(encoder: &BFVEncoder) -> usize {
+		encoder.get_slot_count()
+	}
+
+	/// The slots form a 2 x N/2 matrix whose rows rotate independently.
+	fn rotation_span(encoder: &BFVEncoder) -> usize {
+		encoder.get_slot_count() / 2
+	}
+
+	fn rotate(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		self.rotate_rows(a, steps, galois_keys)
+	}
+
+	fn sum_slots(
+		&self,
+		encoder: &BFVEncoder,
+		a: &Ciphertext,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let mut sum = a.clone();
+		let mut step = 1;
+
+		while step < encoder.get_slot_count() / 2 {
+			let rotated = self.rotate_rows(&sum, step as i32, galois_keys)?;
+			self.add_inplace(&mut sum, &rotated)?;
+			step *= 2;
+		}
+
+		let swapped = self.rotate_columns(&sum, galois_keys)?;
+		self.add_inplace(&mut sum, &swapped)?;
+
+		Ok(sum)
+	}
+
+	fn multiply_values(
+		&self,
+		encoder: &BFVEncoder,
+		a: &Ciphertext,
+		values: &[i64],
+	) -> Result<Ciphertext> {
+		self.multiply_plain(a, &encoder.encode_i64(values)?)
+	}
+
+	fn rescale(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		Ok(a.clone())
+	}
+
+	/// The slots form a 2 x N/2 matrix: a move rotates the rows so the column
+	/// lines up, then swaps the rows if the value changes row.
+	fn select(
+		&self,
+		encoder: &BFVEncoder,
+		sources: &[&Ciphertext],
+		moves: &[SlotMove],
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let slots = encoder.get_slot_count();
+		let row = slots / 2;
+
+		let mut groups: BTreeMap<(usize, usize, bool), Vec<usize>> = BTreeMap::new();
+
+		for m in moves {
+			let shift = (m.from % row + row - m.to % row) % row;
+			let swap = m.from / row != m.to / row;
+
+			groups
+				.entry((m.source, shift, swap))
+				.or_default()
+				.push(m.to);
+		}
+
+		let mut acc = None;
+
+		for ((source, shift, swap), targets) in groups {
+			let a = sources.get(source).ok_or(Error::InvalidArgument)?;
+
+			let mut rotated = match shift {
+				0 => (*a).clone(),
+				shift => self.rotate_rows(a, signed_steps(shift, row), galois_keys)?,
+			};
+
+			if swap {
+				rotated = self.rotate_columns(&rotated, galois_keys)?;
+			}
+
+			let mut mask = vec![0u64; slots];
+			for to in targets {
+				mask[to] = 1;
+			}
+
+			self.multiply_plain_inplace(&mut rotated, &encoder.encode_u64(&mask)?)?;
+			acc = Some(accumulate(self, acc, rotated)?);
+		}
+
+		acc.ok_or(Error::InvalidArgument)
+	}
+
+	fn align(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		Ok(a.clone())
+	}
+}
+
+impl SlotSelect for CKKSEvaluator {
+	type Encoder = CKKSEncoder;
+	type Value = f64;
+
+	fn slot_count(encoder: &CKKSEncoder) -> usize {
+		encoder.get_slot_count()
+	}
+
+	/// The slots form a single vector.
+	fn rotation_span(encoder: &CKKSEncoder) -> usize {
+		encoder.get_slot_count()
+	}
+
+	fn rotate(
+		&self,
+		a: &Ciphertext,
+		steps: i32,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		self.rotate_vector(a, steps, galois_keys)
+	}
+
+	fn sum_slots(
+		&self,
+		encoder: &CKKSEncoder,
+		a: &Ciphertext,
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let mut sum = a.clone();
+		let mut step = 1;
+
+		while step < encoder.get_slot_count() {
+			let rotated = self.rotate_vector(&sum, step as i32, galois_keys)?;
+			self.add_inplace(&mut sum, &rotated)?;
+			step *= 2;
+		}
+
+		Ok(sum)
+	}
+
+	/// The values are encoded with the scale set to the last prime of the
+	/// ciphertext's level and the product is rescaled, which uses up one
+	/// level.
+	fn multiply_values(
+		&self,
+		encoder: &CKKSEncoder,
+		a: &Ciphertext,
+		values: &[f64],
+	) -> Result<Ciphertext> {
+		let prime = encoder
+			.rescale_prime(a.coeff_modulus_size()?)
+			.ok_or(Error::InvalidArgument)?;
+
+		let values = encoder.encode_f64_at(values, &a.parms_id()?, prime as f64)?;
+
+		self.rescale_to_next(&self.multiply_plain(a, &values)?)
+	}
+
+	fn rescale(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		self.rescale_to_next(a)
+	}
+
+	/// The slots form a single vector. Masks are encoded with the scale set
+	/// to the last prime of the ciphertext's level, so rescaling the sum
+	/// restores the original scale and uses up exactly one level.
+	fn select(
+		&self,
+		encoder: &CKKSEncoder,
+		sources: &[&Ciphertext],
+		moves: &[SlotMove],
+		galois_keys: &GaloisKey,
+	) -> Result<Ciphertext> {
+		let slots = encoder.get_slot_count();
+
+		let mut groups: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+
+		for m in moves {
+			let shift = (m.from + slots - m.to) % slots;
+
+			groups.entry((m.source, shift)).or_default().push(m.to);
+		}
+
+		let mut acc = None;
+
+		for ((source, shift), targets) in groups {
+			let a = sources.get(source).ok_or(Error::InvalidArgument)?;
+
+			let mut rotated = match shift {
+				0 => (*a).clone(),
+				shift => self.rotate_vector(a, signed_steps(shift, slots), galois_keys)?,
+			};
+
+			let prime = encoder
+				.rescale_prime(rotated.coeff_modulus_size()?)
+				.ok_or(Error::InvalidArgument)?;
+
+			let mut mask = vec![0.0; slots];
+			for to in targets {
+				mask[to] = 1.0;
+			}
+
+			let mask = encoder.encode_f64_at(&mask, &rotated.parms_id()?, prime as f64)?;
+
+			self.multiply_plain_inplace(&mut rotated, &mask)?;
+			acc = Some(accumulate(self, acc, rotated)?);
+		}
+
+		self.rescale_to_next(&acc.ok_or(Error::InvalidArgument)?)
+	}
+
+	fn align(
+		&self,
+		a: &Ciphertext,
+	) -> Result<Ciphertext> {
+		self.mod_switch_to_next(a)
+	}
+}
@@ -0,0 +1,106 @@
+use crate::{Error, Result};
+
+/// The logical shape of the data held by a [`super::Tensor`].
+///
+/// Data is laid out in row-major order across the slots of consecutive
+/// chunks, so the strides are those of a C-contiguous array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shape {
+	dims: Vec<usize>,
+	strides: Vec<usize>,
+}
+
+impl Shape {
+	/// Creates a row-major shape with the given dimensions.
+	///
+	/// Fails with [`Error::InvalidArgument`] if the number of elements, or
+	/// any stride, overflows `usize`.
+	pub fn new(dims: Vec<usize>) -> Result<Self> {
+		let mut strides = vec![1; dims.len()];
+
+		for i in (0..dims.len().saturating_sub(1)).rev() {
+			strides[i] = strides[i + 1]
+				.checked_mul(dims[i + 1])
+				.ok_or(Error::InvalidArgument)?;
+		}
+
+		// A zero dimension keeps the strides in range but not the product of
+		// the others, which `numel` computes.
+		if numel(&dims).is_none() {
+			return Err(Error::InvalidArgument);
+		}
+
+		Ok(Self {
+			dims,
+			strides,
+		})
+	}
+
+	/// Creates a one-dimensional shape of `len` elements.
+	pub fn flat(len: usize) -> Self {
+		Self {
+			dims: vec![len],
+			strides: vec![1],
+		}
+	}
+
+	/// Returns the size of each dimension.
+	pub fn dims(&self) -> &[usize] {
+		&self.dims
+	}
+
+	/// Returns the number of elements to skip to advance one step along each
+	/// dimension.
+	pub fn strides(&self) -> &[usize] {
+		&self.strides
+	}
+
+	/// Returns the number of dimensions.
+	pub fn ndim(&self) -> usize {
+		self.dims.len()
+	}
+
+	/// Returns the number of elements described by this shape.
+	pub fn numel(&self) -> usize {
+		self.dims.iter().product()
+	}
+
+	/// Returns the flat position of the element at the given index, or
+	/// `None` if it is out of bounds.
+	pub fn offset(
+		&self,
+		index: &[usize],
+	) -> Option<usize> {
+		if index.len() != self.dims.len() {
+			return None;
+		}
+
+		index
+			.iter()
+			.zip(self.dims.iter().zip(self.strides.iter()))
+			.try_fold(0, |acc, (i, (dim, stride))| {
+				(i < dim).then_some(acc + i * stride)
+			})
+	}
+
+	/// Returns a shape with the given dimensions holding the same number of
+	/// elements.
+	pub fn reshape(
+		&self,
+		dims: Vec<usize>,
+	) -> Result<Self> {
+		let shape = Self::new(dims)?;
+
+		if shape.numel() != self.numel() {
+			return Err(Error::InvalidArgument);
+		}
+
+		Ok(shape)
+	}
+}
+
+/// Returns the number of elements of the dimensions, unless it overflows.
+fn numel(dims: &[usize]) -> Option<usize> {
+	dims.iter()
+		.try_fold(1usize, |acc, dim| acc.checked_mul(*dim))
+}
@@ -0,0 +1,379 @@
+use std::sync::Arc;
+
+use super::{align, check_len, impl_vector_ops};
+use crate::{
+	Asym, BFVEncoder, BFVEvaluator, Ciphertext, Context, Error, Evaluator, Plaintext, PublicKey,
+	RelinearizationKey, Result, SchemeType, SecretKey, Shape, Tensor, TensorDecryptor,
+	TensorEncoder, TensorEncryptor, TensorEvaluator,
+};
+
+/// Everything a [`BfvVector`] needs besides its ciphertexts, shared by all
+/// the vectors computed from the same values.
+struct Shared {
+	context: Context,
+	encoder: TensorEncoder<BFVEncoder>,
+	encryptor: TensorEncryptor<Asym>,
+	evaluator: TensorEvaluator<BFVEvaluator>,
+	relin_keys: RelinearizationKey,
+}
+
+/// A cheaply cloned handle to a BFV context, its encoder, encryptor and
+/// evaluator, and the relinearization key, for [`BfvVector`]s.
+#[derive(Clone)]
+pub struct BfvContext {
+	shared: Arc<Shared>,
+}
+
+impl BfvContext {
+	/// Creates a handle for vectors encrypted under the given context.
+	///
+	/// Fails with [`Error::InvalidOperation`] unless the context uses BFV.
+	///
+	/// * `context` - The BFV context. Its plain modulus must allow batching.
+	/// * `public_key` - The key to encrypt with.
+	/// * `relin_keys` - The key to relinearize products with.
+	pub fn new(
+		context: Context,
+		public_key: &PublicKey,
+		relin_keys: &RelinearizationKey,
+	) -> Result<Self> {
+		if context.get_encryption_parameters()?.get_scheme()? != SchemeType::Bfv {
+			return Err(Error::InvalidOperation);
+		}
+
+		let shared = Shared {
+			encoder: TensorEncoder::new(BFVEncoder::new(&context)?),
+			encryptor: TensorEncryptor::with_public_key(&context, public_key)?,
+			evaluator: TensorEvaluator::bfv(&context)?,
+			relin_keys: relin_keys.clone(),
+			context,
+		};
+
+		Ok(Self {
+			shared: Arc::new(shared),
+		})
+	}
+
+	/// Returns the underlying context.
+	pub fn context(&self) -> &Context {
+		&self.shared.context
+	}
+
+	/// Returns the number of values each ciphertext holds.
+	pub fn slot_count(&self) -> usize {
+		self.shared.encoder.get_slot_count()
+	}
+}
+
+/// A vector of integers modulo the plain modulus, encrypted under BFV.
+///
+/// Products are relinearized right away, and operands at different levels
+/// are switched down to the lower one. Values are signed: those above half
+/// the plain modulus decrypt as negative.
+#[derive(Clone)]
+pub struct BfvVector {
+	context: BfvContext,
+	data: Tensor<Ciphertext>,
+	len: usize,
+}
+
+impl BfvVector {
+	/// Encodes and encrypts the given values.
+	///
+	/// Fails with [`Error::InvalidArgument`] if there are none.
+	pub fn encrypt(
+		context: &BfvContext,
+		values: &[i64],
+	) -> Result<Self> {
+		if values.is_empty() {
+			return Err(Error::InvalidArgument);
+		}
+
+		let shared = &context.shared;
+		let data = shared
+			.encryptor
+			.encrypt(&shared.encoder.encode_i64(values)?)?;
+
+		Ok(Self {
+			context: context.clone(),
+			data,
+			len: values.len(),
+		})
+	}
+
+	/// Wraps ciphertexts encrypted under the context, holding `len` values in
+	/// order.
+	///
+	/// Fails with [`Error::InvalidArgument`] if `len` is zero or the chunks
+	/// can't hold `len` values.
+	pub fn from_tensor(
+		context: &BfvContext,
+		data: Tensor<Ciphertext>,
+		len: usize,
+	) -> Result<Self> {
+		if len == 0 || len > data.len() * context.slot_count() {
+			return Err(Error::InvalidArgument);
+		}
+
+		Ok(Self {
+			context: context.clone(),
+			data: data
+				.with_known_shape(Shape::flat(len))
+				.with_slot_count(context.slot_count()),
+			len,
+		})
+	}
+
+	/// Wraps a single ciphertext holding `len` values.
+	pub fn from_ciphertext(
+		context: &BfvContext,
+		ciphertext: Ciphertext,
+		len: usize,
+	) -> Result<Self> {
+		Self::from_tensor(context, Tensor::new(vec![ciphertext]), len)
+	}
+
+	/// Returns the number of values.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns true if the vector holds no values. Never the case.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Returns the handle the vector was created with.
+	pub fn context(&self) -> &BfvContext {
+		&self.context
+	}
+
+	/// Returns the ciphertexts.
+	pub fn tensor(&self) -> &Tensor<Ciphertext> {
+		&self.data
+	}
+
+	/// Consumes the vector, returning the ciphertexts.
+	pub fn into_tensor(self) -> Tensor<Ciphertext> {
+		self.data
+	}
+
+	/// Decrypts and decodes the values.
+	pub fn decrypt(
+		&self,
+		secret_key: &SecretKey,
+	) -> Result<Vec<i64>> {
+		let shared = &self.context.shared;
+		let decryptor = TensorDecryptor::new(&shared.context, secret_key)?;
+
+		shared.encoder.decode_i64(&decryptor.decrypt(&self.data)?)
+	}
+
+	fn with_data(
+		&self,
+		data: Tensor<Ciphertext>,
+	) -> Self {
+		Self {
+			context: self.context.clone(),
+			data,
+			len: self.len,
+		}
+	}
+
+	/// Fails unless `other` is of the same context and length.
+	fn check_other(
+		&self,
+		other: &BfvVector,
+	) -> Result<()> {
+		if !Arc::ptr_eq(&self.context.shared, &other.context.shared) {
+			return Err(Error::ContextMismatch);
+		}
+
+		check_len(self.len, other.len)
+	}
+
+	/// Encodes `values`, one per element.
+	fn encode(
+		&self,
+		values: &[i64],
+	) -> Result<Tensor<Plaintext>> {
+		check_len(self.len, values.len())?;
+
+		self.context.shared.encoder.encode_i64(values)
+	}
+
+	/// Adds two vectors.
+	pub fn add_vector(
+		&self,
+		other: &BfvVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let evaluator = &self.context.shared.evaluator;
+		let (a, b) = align(evaluator, &self.data, &other.data)?;
+
+		Ok(self.with_data(evaluator.add(&a, &b)?))
+	}
+
+	/// Subtracts `other` from this vector.
+	pub fn sub_vector(
+		&self,
+		other: &BfvVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let evaluator = &self.context.shared.evaluator;
+		let (a, b) = align(evaluator, &self.data, &other.data)?;
+
+		Ok(self.with_data(evaluator.sub(&a, &b)?))
+	}
+
+	/// Multiplies two vectors element-wise, then relinearizes the product.
+	pub fn multiply_vector(
+		&self,
+		other: &BfvVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let shared = &self.context.shared;
+		let (a, b) = align(&shared.evaluator, &self.data, &other.data)?;
+
+		let product = shared.evaluator.multiply(&a, &b)?;
+
+		Ok(self.with_data(shared.evaluator.relinearize(&product, &shared.relin_keys)?))
+	}
+
+	/// Adds plaintext values, one per element.
+	pub fn add_plain(
+		&self,
+		values: &[i64],
+	) -> Result<Self> {
+		let plain = self.encode(values)?;
+
+		Ok(self.with_data(
+			self.context
+				.shared
+				.evaluator
+				.add_plain(&self.data, &plain)?,
+		))
+	}
+
+	/// Subtracts plaintext values, one per element.
+	pub fn sub_plain(
+		&self,
+		values: &[i64],
+	) -> Result<Self> {
+		let plain = self.encode(values)?;
+
+		Ok(self.with_data(
+			self.context
+				.shared
+				.evaluator
+				.sub_plain(&self.data, &plain)?,
+		))
+	}
+
+	/// Multiplies by plaintext values, one per element.
+	pub fn multiply_plain(
+		&self,
+		values: &[i64],
+	) -> Result<Self> {
+		let plain = self.encode(values)?;
+
+		Ok(self.with_data(
+			self.context
+				.shared
+				.evaluator
+				.multiply_plain(&self.data, &plain)?,
+		))
+	}
+
+	/// Adds `value` to every element.
+	pub fn add_scalar(
+		&self,
+		value: i64,
+	) -> Result<Self> {
+		self.add_plain(&vec![value; self.len])
+	}
+
+	/// Subtracts `value` from every element.
+	pub fn sub_scalar(
+		&self,
+		value: i64,
+	) -> Result<Self> {
+		self.sub_plain(&vec![value; self.len])
+	}
+
+	/// Multiplies every element by `value`.
+	pub fn multiply_scalar(
+		&self,
+		value: i64,
+	) -> Result<Self> {
+		self.multiply_plain(&vec![value; self.len])
+	}
+
+	/// Negates every element.
+	pub fn negate(&self) -> Result<Self> {
+		Ok(self.with_data(self.context.shared.evaluator.negate(&self.data)?))
+	}
+}
+
+impl_vector_ops!(BfvVector, i64);
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn make_context() -> (BfvContext, SecretKey) {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let public_key = gen.create_public_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+
+		let context = BfvContext::new(ctx, &public_key, &relin_keys).unwrap();
+
+		(context, secret_key)
+	}
+
+	#[test]
+	fn can_compute_on_bfv_vectors() {
+		let (context, secret_key) = make_context();
+
+		let x = (0..10_000).map(|i| i % 13 - 6).collect::<Vec<i64>>();
+		let y = (0..10_000).map(|i| i % 5).collect::<Vec<i64>>();
+
+		let x_enc = BfvVector::encrypt(&context, &x).unwrap();
+		let y_enc = BfvVector::encrypt(&context, &y).unwrap();
+
+		// (x * y - x) * 3 + 7
+		let result = ((&x_enc * &y_enc).unwrap() - &x_enc).unwrap();
+		let result = ((result * 3).unwrap() + 7).unwrap();
+
+		let expected = x
+			.iter()
+			.zip(&y)
+			.map(|(x, y)| (x * y - x) * 3 + 7)
+			.collect::<Vec<_>>();
+		assert_eq!(result.decrypt(&secret_key).unwrap(), expected);
+
+		let result = (-(&x_enc + y.as_slice()).unwrap()).unwrap();
+		let expected = x.iter().zip(&y).map(|(x, y)| -(x + y)).collect::<Vec<_>>();
+		assert_eq!(result.decrypt(&secret_key).unwrap(), expected);
+
+		assert!(matches!(
+			(&x_enc - [1, 2, 3].as_slice()).err(),
+			Some(Error::ShapeMismatch(_))
+		));
+	}
+}
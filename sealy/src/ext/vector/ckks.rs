@@ -0,0 +1,461 @@
+use std::sync::Arc;
+
+use super::{align, check_len, coeff_modulus_size, impl_vector_ops};
+use crate::{
+	Asym, CKKSEncoder, CKKSEvaluator, Ciphertext, CircuitEvaluator, Context, Error, Evaluator,
+	Plaintext, PublicKey, RelinearizationKey, Result, SchemeType, SecretKey, Shape, Tensor,
+	TensorDecryptor, TensorEncoder, TensorEncryptor, TensorEvaluator,
+};
+
+/// Everything a [`CkksVector`] needs besides its ciphertexts, shared by all
+/// the vectors computed from the same values.
+struct Shared {
+	context: Context,
+	scale: f64,
+	encoder: TensorEncoder<CKKSEncoder>,
+	encryptor: TensorEncryptor<Asym>,
+	evaluator: TensorEvaluator<CKKSEvaluator>,
+	relin_keys: RelinearizationKey,
+}
+
+/// A cheaply cloned handle to a CKKS context, its encoder, encryptor and
+/// evaluator, and the relinearization key, for [`CkksVector`]s.
+#[derive(Clone)]
+pub struct CkksContext {
+	shared: Arc<Shared>,
+}
+
+impl CkksContext {
+	/// Creates a handle for vectors encoded at the given scale.
+	///
+	/// Fails with [`Error::InvalidOperation`] unless the context uses CKKS.
+	///
+	/// * `context` - The CKKS context.
+	/// * `scale` - The scale to encode values at; every vector is kept at it.
+	/// * `public_key` - The key to encrypt with.
+	/// * `relin_keys` - The key to relinearize products with.
+	pub fn new(
+		context: Context,
+		scale: f64,
+		public_key: &PublicKey,
+		relin_keys: &RelinearizationKey,
+	) -> Result<Self> {
+		if context.get_encryption_parameters()?.get_scheme()? != SchemeType::Ckks {
+			return Err(Error::InvalidOperation);
+		}
+
+		let shared = Shared {
+			scale,
+			encoder: TensorEncoder::new(CKKSEncoder::new(&context, scale)?),
+			encryptor: TensorEncryptor::with_public_key(&context, public_key)?,
+			evaluator: TensorEvaluator::ckks(&context)?,
+			relin_keys: relin_keys.clone(),
+			context,
+		};
+
+		Ok(Self {
+			shared: Arc::new(shared),
+		})
+	}
+
+	/// Returns the underlying context.
+	pub fn context(&self) -> &Context {
+		&self.shared.context
+	}
+
+	/// Returns the scale every vector is kept at.
+	pub fn scale(&self) -> f64 {
+		self.shared.scale
+	}
+
+	/// Returns the number of values each ciphertext holds.
+	pub fn slot_count(&self) -> usize {
+		self.shared.encoder.get_slot_count()
+	}
+}
+
+/// A vector of real numbers encrypted under CKKS.
+///
+/// Products are relinearized and rescaled right away, and operands at
+/// different levels are switched down to the lower one, so any two vectors
+/// of the same length can be combined until the modulus chain runs out.
+///
+/// Every vector is kept at the scale of its [`CkksContext`]: after a product
+/// of two vectors is rescaled by a prime q, its scale Δ²/q is reset to Δ,
+/// which multiplies its values by about Δ/q. Pick primes close to the scale
+/// to keep this error small. Products with plaintexts and scalars are
+/// encoded at q itself and are exact.
+#[derive(Clone)]
+pub struct CkksVector {
+	context: CkksContext,
+	data: Tensor<Ciphertext>,
+	len: usize,
+}
+
+impl CkksVector {
+	/// Encodes and encrypts the given values.
+	///
+	/// Fails with [`Error::InvalidArgument`] if there are none.
+	pub fn encrypt(
+		context: &CkksContext,
+		values: &[f64],
+	) -> Result<Self> {
+		if values.is_empty() {
+			return Err(Error::InvalidArgument);
+		}
+
+		let shared = &context.shared;
+		let data = shared
+			.encryptor
+			.encrypt(&shared.encoder.encode_f64(values)?)?;
+
+		Ok(Self {
+			context: context.clone(),
+			data,
+			len: values.len(),
+		})
+	}
+
+	/// Wraps ciphertexts encrypted under the context at its scale, holding
+	/// `len` values in order.
+	///
+	/// Fails with [`Error::InvalidArgument`] if `len` is zero or the chunks
+	/// can't hold `len` values.
+	pub fn from_tensor(
+		context: &CkksContext,
+		data: Tensor<Ciphertext>,
+		len: usize,
+	) -> Result<Self> {
+		if len == 0 || len > data.len() * context.slot_count() {
+			return Err(Error::InvalidArgument);
+		}
+
+		Ok(Self {
+			context: context.clone(),
+			data: data
+				.with_known_shape(Shape::flat(len))
+				.with_slot_count(context.slot_count()),
+			len,
+		})
+	}
+
+	/// Wraps a single ciphertext holding `len` values.
+	pub fn from_ciphertext(
+		context: &CkksContext,
+		ciphertext: Ciphertext,
+		len: usize,
+	) -> Result<Self> {
+		Self::from_tensor(context, Tensor::new(vec![ciphertext]), len)
+	}
+
+	/// Returns the number of values.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Returns true if the vector holds no values. Never the case.
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Returns the handle the vector was created with.
+	pub fn context(&self) -> &CkksContext {
+		&self.context
+	}
+
+	/// Returns the ciphertexts.
+	pub fn tensor(&self) -> &Tensor<Ciphertext> {
+		&self.data
+	}
+
+	/// Consumes the vector, returning the ciphertexts.
+	pub fn into_tensor(self) -> Tensor<Ciphertext> {
+		self.data
+	}
+
+	/// Decrypts and decodes the values.
+	pub fn decrypt(
+		&self,
+		secret_key: &SecretKey,
+	) -> Result<Vec<f64>> {
+		let shared = &self.context.shared;
+		let decryptor = TensorDecryptor::new(&shared.context, secret_key)?;
+
+		shared.encoder.decode_f64(&decryptor.decrypt(&self.data)?)
+	}
+
+	fn with_data(
+		&self,
+		data: Tensor<Ciphertext>,
+	) -> Self {
+		Self {
+			context: self.context.clone(),
+			data,
+			len: self.len,
+		}
+	}
+
+	/// Fails unless `other` is of the same context and length.
+	fn check_other(
+		&self,
+		other: &CkksVector,
+	) -> Result<()> {
+		if !Arc::ptr_eq(&self.context.shared, &other.context.shared) {
+			return Err(Error::ContextMismatch);
+		}
+
+		check_len(self.len, other.len)
+	}
+
+	/// Encodes `values` at the level of the vector and the given scale.
+	fn encode_at(
+		&self,
+		values: &[f64],
+		scale: f64,
+	) -> Result<Tensor<Plaintext>> {
+		check_len(self.len, values.len())?;
+
+		let parms_id = self
+			.data
+			.first()
+			.ok_or(Error::InvalidArgument)?
+			.parms_id()?;
+
+		self.context
+			.shared
+			.encoder
+			.encode_f64_at(values, &parms_id, scale)
+	}
+
+	/// Adds two vectors.
+	pub fn add_vector(
+		&self,
+		other: &CkksVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let evaluator = &self.context.shared.evaluator;
+		let (a, b) = align(evaluator, &self.data, &other.data)?;
+
+		Ok(self.with_data(evaluator.add(&a, &b)?))
+	}
+
+	/// Subtracts `other` from this vector.
+	pub fn sub_vector(
+		&self,
+		other: &CkksVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let evaluator = &self.context.shared.evaluator;
+		let (a, b) = align(evaluator, &self.data, &other.data)?;
+
+		Ok(self.with_data(evaluator.sub(&a, &b)?))
+	}
+
+	/// Multiplies two vectors element-wise, then relinearizes and rescales
+	/// the product. Uses up one level.
+	pub fn multiply_vector(
+		&self,
+		other: &CkksVector,
+	) -> Result<Self> {
+		self.check_other(other)?;
+
+		let shared = &self.context.shared;
+		let (a, b) = align(&shared.evaluator, &self.data, &other.data)?;
+
+		let product = shared.evaluator.multiply(&a, &b)?;
+		let product = shared.evaluator.relinearize(&product, &shared.relin_keys)?;
+		let product = shared.evaluator.rescale_to_next(&product)?;
+
+		for chunk in product.iter() {
+			chunk.set_scale(shared.scale)?;
+		}
+
+		Ok(self.with_data(product))
+	}
+
+	/// Adds plaintext values, one per element.
+	pub fn add_plain(
+		&self,
+		values: &[f64],
+	) -> Result<Self> {
+		let scale = self.data.first().ok_or(Error::InvalidArgument)?.scale()?;
+		let plain = self.encode_at(values, scale)?;
+
+		Ok(self.with_data(
+			self.context
+				.shared
+				.evaluator
+				.add_plain(&self.data, &plain)?,
+		))
+	}
+
+	/// Subtracts plaintext values, one per element.
+	pub fn sub_plain(
+		&self,
+		values: &[f64],
+	) -> Result<Self> {
+		let scale = self.data.first().ok_or(Error::InvalidArgument)?.scale()?;
+		let plain = self.encode_at(values, scale)?;
+
+		Ok(self.with_data(
+			self.context
+				.shared
+				.evaluator
+				.sub_plain(&self.data, &plain)?,
+		))
+	}
+
+	/// Multiplies by plaintext values, one per element, then rescales. Uses
+	/// up one level.
+	pub fn multiply_plain(
+		&self,
+		values: &[f64],
+	) -> Result<Self> {
+		let shared = &self.context.shared;
+
+		// Encoding at the prime the product is divided by keeps the scale.
+		let prime = shared
+			.encoder
+			.inner()
+			.rescale_prime(coeff_modulus_size(&self.data)?)
+			.ok_or(Error::InvalidArgument)?;
+		let plain = self.encode_at(values, prime as f64)?;
+
+		let product = shared.evaluator.multiply_plain(&self.data, &plain)?;
+
+		Ok(self.with_data(shared.evaluator.rescale_to_next(&product)?))
+	}
+
+	/// Adds `value` to every element.
+	pub fn add_scalar(
+		&self,
+		value: f64,
+	) -> Result<Self> {
+		self.add_plain(&vec![value; self.len])
+	}
+
+	/// Subtracts `value` from every element.
+	pub fn sub_scalar(
+		&self,
+		value: f64,
+	) -> Result<Self> {
+		self.sub_plain(&vec![value; self.len])
+	}
+
+	/// Multiplies every element by `value`, then rescales. Uses up one level.
+	pub fn multiply_scalar(
+		&self,
+		value: f64,
+	) -> Result<Self> {
+		self.multiply_plain(&vec![value; self.len])
+	}
+
+	/// Negates every element.
+	pub fn negate(&self) -> Result<Self> {
+		Ok(self.with_data(self.context.shared.evaluator.negate(&self.data)?))
+	}
+}
+
+impl_vector_ops!(CkksVector, f64);
+
+#[cfg(test)]
+mod tests {
+	use crate::*;
+
+	fn make_context() -> (CkksContext, SecretKey) {
+		let params = CKKSEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[60, 40, 40, 60]).unwrap(),
+			)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, true, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+
+		let public_key = gen.create_public_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+		let secret_key = gen.secret_key().unwrap();
+
+		let context = CkksContext::new(ctx, 2.0f64.powi(40), &public_key, &relin_keys).unwrap();
+
+		(context, secret_key)
+	}
+
+	fn assert_close(
+		actual: &[f64],
+		expected: &[f64],
+	) {
+		assert_eq!(actual.len(), expected.len());
+
+		for (a, e) in actual.iter().zip(expected) {
+			assert!((a - e).abs() < 1e-3, "{} != {}", a, e);
+		}
+	}
+
+	#[test]
+	fn can_compute_on_ckks_vectors() {
+		let (context, secret_key) = make_context();
+
+		// More values than a ciphertext holds.
+		let x = (0..5_000)
+			.map(|i| (i % 10) as f64 / 4.0)
+			.collect::<Vec<_>>();
+		let y = (0..5_000)
+			.map(|i| 1.0 - (i % 7) as f64 / 8.0)
+			.collect::<Vec<_>>();
+
+		let x_enc = CkksVector::encrypt(&context, &x).unwrap();
+		let y_enc = CkksVector::encrypt(&context, &y).unwrap();
+		assert_eq!(x_enc.tensor().len(), 2);
+
+		// x * y + x - 0.5, mixing levels.
+		let result = ((&x_enc * &y_enc).unwrap() + &x_enc).unwrap();
+		let result = (result - 0.5).unwrap();
+
+		let expected = x
+			.iter()
+			.zip(&y)
+			.map(|(x, y)| x * y + x - 0.5)
+			.collect::<Vec<_>>();
+		assert_close(&result.decrypt(&secret_key).unwrap(), &expected);
+
+		let result = (-(&x_enc * y.as_slice()).unwrap()).unwrap();
+		let expected = x.iter().zip(&y).map(|(x, y)| -x * y).collect::<Vec<_>>();
+		assert_close(&result.decrypt(&secret_key).unwrap(), &expected);
+
+		let result = ((&x_enc - &y_enc).unwrap() * 2.0).unwrap();
+		let expected = x
+			.iter()
+			.zip(&y)
+			.map(|(x, y)| (x - y) * 2.0)
+			.collect::<Vec<_>>();
+		assert_close(&result.decrypt(&secret_key).unwrap(), &expected);
+	}
+
+	#[test]
+	fn ckks_vector_lengths_must_match() {
+		let (context, _) = make_context();
+
+		let a = CkksVector::encrypt(&context, &[1.0, 2.0, 3.0]).unwrap();
+		let b = CkksVector::encrypt(&context, &[1.0, 2.0]).unwrap();
+
+		assert!(matches!((&a + &b).err(), Some(Error::ShapeMismatch(_))));
+		assert!(matches!(
+			(&a * [1.0, 2.0].as_slice()).err(),
+			Some(Error::ShapeMismatch(_))
+		));
+
+		let (other, _) = make_context();
+		let c = CkksVector::encrypt(&other, &[1.0, 2.0, 3.0]).unwrap();
+
+		assert_eq!((&a - &c).err(), Some(Error::ContextMismatch));
+		assert_eq!(
+			CkksVector::encrypt(&context, &[]).err(),
+			Some(Error::InvalidArgument)
+		);
+	}
+}
@@ -0,0 +1,183 @@
+//! Encrypted vectors that carry everything needed to compute on them.
+//!
+//! A [`CkksVector`](ckks::CkksVector) or [`BfvVector`](bfv::BfvVector) holds
+//! its ciphertexts, a shared handle to the context, encoder, encryptor,
+//! evaluator and relinearization key, and the number of values it holds.
+//! Arithmetic goes through the `+`, `-`, `*` and unary `-` operators, whose
+//! output is a [`Result`], with other vectors, plaintext slices and scalars:
+//!
+//! ```ignore
+//! let y = ((&x * &x)? + 1.0)?;
+//! ```
+
+use std::borrow::Cow;
+
+use crate::error::ShapeMismatch;
+use crate::{Ciphertext, Error, Evaluator, Result, Tensor};
+
+pub mod bfv;
+pub mod ckks;
+
+/// Fails with [`Error::ShapeMismatch`] unless both lengths are equal.
+fn check_len(
+	left: usize,
+	right: usize,
+) -> Result<()> {
+	if left != right {
+		return Err(Error::ShapeMismatch(Box::new(ShapeMismatch {
+			left: vec![left],
+			right: vec![right],
+		})));
+	}
+
+	Ok(())
+}
+
+/// Returns the number of primes left in the chunks of `a`.
+fn coeff_modulus_size(a: &Tensor<Ciphertext>) -> Result<u64> {
+	a.first()
+		.ok_or(Error::InvalidArgument)?
+		.coeff_modulus_size()
+}
+
+/// Switches `a` down until only `size` primes are left.
+fn lower<'a, E>(
+	evaluator: &E,
+	a: &'a Tensor<Ciphertext>,
+	size: u64,
+) -> Result<Cow<'a, Tensor<Ciphertext>>>
+where
+	E: Evaluator<Ciphertext = Tensor<Ciphertext>>,
+{
+	let mut a = Cow::Borrowed(a);
+
+	while coeff_modulus_size(&a)? > size {
+		a = Cow::Owned(evaluator.mod_switch_to_next(&a)?);
+	}
+
+	Ok(a)
+}
+
+/// Switches the operand at the higher level down to the level of the other.
+fn align<'a, E>(
+	evaluator: &E,
+	a: &'a Tensor<Ciphertext>,
+	b: &'a Tensor<Ciphertext>,
+) -> Result<(Cow<'a, Tensor<Ciphertext>>, Cow<'a, Tensor<Ciphertext>>)>
+where
+	E: Evaluator<Ciphertext = Tensor<Ciphertext>>,
+{
+	let size = coeff_modulus_size(a)?.min(coeff_modulus_size(b)?);
+
+	Ok((lower(evaluator, a, size)?, lower(evaluator, b, size)?))
+}
+
+/// Implements the arithmetic operators of a vector type on top of its
+/// `*_vector`, `*_plain`, `*_scalar` and `negate` methods, for owned and
+/// borrowed operands.
+macro_rules! impl_vector_ops {
+	($vector:ty, $scalar:ty) => {
+		impl_vector_ops!(@binary $vector, $scalar, Add, add, add_vector, add_plain, add_scalar);
+		impl_vector_ops!(@binary $vector, $scalar, Sub, sub, sub_vector, sub_plain, sub_scalar);
+		impl_vector_ops!(
+			@binary $vector, $scalar, Mul, mul, multiply_vector, multiply_plain, multiply_scalar
+		);
+
+		impl std::ops::Neg for &$vector {
+			type Output = Result<$vector>;
+
+			fn neg(self) -> Self::Output {
+				self.negate()
+			}
+		}
+
+		impl std::ops::Neg for $vector {
+			type Output = Result<$vector>;
+
+			fn neg(self) -> Self::Output {
+				self.negate()
+			}
+		}
+	};
+
+	(@binary $vector:ty, $scalar:ty, $op:ident, $method:ident, $vector_fn:ident, $plain_fn:ident, $scalar_fn:ident) => {
+		impl std::ops::$op<&$vector> for &$vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: &$vector,
+			) -> Self::Output {
+				self.$vector_fn(rhs)
+			}
+		}
+
+		impl std::ops::$op<&$vector> for $vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: &$vector,
+			) -> Self::Output {
+				self.$vector_fn(rhs)
+			}
+		}
+
+		impl std::ops::$op<$vector> for $vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: $vector,
+			) -> Self::Output {
+				self.$vector_fn(&rhs)
+			}
+		}
+
+		impl std::ops::$op<&[$scalar]> for &$vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: &[$scalar],
+			) -> Self::Output {
+				self.$plain_fn(rhs)
+			}
+		}
+
+		impl std::ops::$op<&[$scalar]> for $vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: &[$scalar],
+			) -> Self::Output {
+				self.$plain_fn(rhs)
+			}
+		}
+
+		impl std::ops::$op<$scalar> for &$vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: $scalar,
+			) -> Self::Output {
+				self.$scalar_fn(rhs)
+			}
+		}
+
+		impl std::ops::$op<$scalar> for $vector {
+			type Output = Result<$vector>;
+
+			fn $method(
+				self,
+				rhs: $scalar,
+			) -> Self::Output {
+				self.$scalar_fn(rhs)
+			}
+		}
+	};
+}
+
+use impl_vector_ops;
@@ -4,12 +4,13 @@ use std::sync::atomic::AtomicPtr;
 use std::sync::atomic::Ordering;
 
 use crate::bindgen;
+use crate::context::ChainLevel;
 use crate::error::*;
-use crate::serialization::CompressionType;
+use crate::serialization::{load_checked, CompressionType};
 use crate::try_seal;
-use crate::{Context, FromBytes, ToBytes};
+use crate::{Context, EncryptionParameters, FromBytes, ToBytes};
 
-use serde::ser::Error;
+use serde::ser::Error as _;
 use serde::{Serialize, Serializer};
 
 /// Generates matching secret key and public key. An existing KeyGenerator can
@@ -59,15 +60,14 @@ impl KeyGenerator {
 	}
 
 	/// Returns a copy of the secret key.
-	pub fn secret_key(&self) -> SecretKey {
+	pub fn secret_key(&self) -> Result<SecretKey> {
 		let mut handle = null_mut();
 
-		try_seal!(unsafe { bindgen::KeyGenerator_SecretKey(self.get_handle(), &mut handle) })
-			.expect("Fatal error in KeyGenerator::secret_key");
+		try_seal!(unsafe { bindgen::KeyGenerator_SecretKey(self.get_handle(), &mut handle) })?;
 
-		SecretKey {
+		Ok(SecretKey {
 			handle,
-		}
+		})
 	}
 
 	/// Returns the handle to the underlying SEAL object.
@@ -76,7 +76,7 @@ impl KeyGenerator {
 	}
 
 	/// Generates and returns a new public key.
-	pub fn create_public_key(&self) -> PublicKey {
+	pub fn create_public_key(&self) -> Result<PublicKey> {
 		self.create_public_key_internal(false)
 	}
 
@@ -86,24 +86,23 @@ impl KeyGenerator {
 	///  the object size. The resulting serializable object cannot be used
 	///  directly and is meant to be serialized for the size reduction to have an
 	///  impact.
-	pub fn create_compact_public_key(&self) -> CompactPublicKey {
-		CompactPublicKey(self.create_public_key_internal(true))
+	pub fn create_compact_public_key(&self) -> Result<CompactPublicKey> {
+		Ok(CompactPublicKey(self.create_public_key_internal(true)?))
 	}
 
 	fn create_public_key_internal(
 		&self,
 		save_seed: bool,
-	) -> PublicKey {
+	) -> Result<PublicKey> {
 		let mut handle = null_mut();
 
 		try_seal!(unsafe {
 			bindgen::KeyGenerator_CreatePublicKey(self.get_handle(), save_seed, &mut handle)
-		})
-		.expect("Fatal error in KeyGenerator::public_key");
+		})?;
 
-		PublicKey {
+		Ok(PublicKey {
 			handle,
-		}
+		})
 	}
 
 	/// Creates relinearization keys
@@ -190,8 +189,9 @@ impl KeyGenerator {
 
 impl Drop for KeyGenerator {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::KeyGenerator_Destroy(self.get_handle()) })
-			.expect("Fatal error in KeyGenerator::drop");
+		unsafe {
+			bindgen::KeyGenerator_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -206,12 +206,28 @@ unsafe impl Send for PublicKey {}
 
 impl ToBytes for PublicKey {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::PublicKey_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+			bindgen::PublicKey_SaveSize(self.handle, compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -221,8 +237,8 @@ impl ToBytes for PublicKey {
 			bindgen::PublicKey_Save(
 				self.handle,
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -242,23 +258,45 @@ impl PartialEq for PublicKey {
 	}
 }
 
+impl Serialize for PublicKey {
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let data = self
+			.as_bytes()
+			.map_err(|e| S::Error::custom(format!("Failed to get public key bytes: {}", e)))?;
+
+		serializer.serialize_bytes(&data)
+	}
+}
+
 impl FromBytes for PublicKey {
 	type State = Context;
 	fn from_bytes(
 		context: &Context,
 		bytes: &[u8],
 	) -> Result<Self> {
-		let key = PublicKey::new()?;
-		let mut bytes_read = 0;
+		// SAFETY: the object is checked for the context before it is returned.
+		let key = unsafe { Self::from_bytes_unchecked(context, bytes)? };
 
-		try_seal!(unsafe {
-			bindgen::PublicKey_Load(
-				key.handle,
-				context.get_handle(),
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut bytes_read,
-			)
+		if !key.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
+
+		Ok(key)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let key = PublicKey::new()?;
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::PublicKey_UnsafeLoad(key.handle, context.get_handle(), data, len, bytes_read)
 		})?;
 
 		Ok(key)
@@ -281,12 +319,63 @@ impl PublicKey {
 	pub fn get_handle(&self) -> *mut c_void {
 		self.handle
 	}
+
+	/// Returns the parms_id of the encryption parameters this key is valid for.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+		try_seal!(unsafe { bindgen::PublicKey_ParmsId(self.handle, parms_id.as_mut_ptr()) })?;
+
+		Ok(parms_id)
+	}
+
+	/// Checks whether the key data and metadata are valid for the given
+	/// context.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe {
+			bindgen::ValCheck_PublicKey_IsValidFor(self.handle, context.get_handle(), &mut result)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Checks whether the key metadata matches the key level of the given
+	/// context without looking at the coefficients.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let level = match key_level(context, &self.parms_id()?)? {
+			Some(level) => level,
+			None => return Ok(false),
+		};
+
+		let mut data: *mut c_void = null_mut();
+		let mut size: u64 = 0;
+		let mut coeff_modulus_size: u64 = 0;
+		let mut degree: u64 = 0;
+
+		try_seal!(unsafe { bindgen::PublicKey_Data(self.handle, &mut data) })?;
+		try_seal!(unsafe { bindgen::Ciphertext_Size(data, &mut size) })?;
+		try_seal!(unsafe { bindgen::Ciphertext_CoeffModulusSize(data, &mut coeff_modulus_size) })?;
+		try_seal!(unsafe { bindgen::Ciphertext_PolyModulusDegree(data, &mut degree) })?;
+
+		Ok(size == 2
+			&& coeff_modulus_size == level.coeff_modulus_size
+			&& degree == level.poly_modulus_degree)
+	}
 }
 
 impl Drop for PublicKey {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::PublicKey_Destroy(self.handle) })
-			.expect("Fatal error in PublicKey::drop")
+		unsafe {
+			bindgen::PublicKey_Destroy(self.handle);
+		}
 	}
 }
 
@@ -318,6 +407,14 @@ impl CompactPublicKey {
 	pub fn as_bytes(&self) -> Result<Vec<u8>> {
 		self.0.as_bytes()
 	}
+
+	/// Returns the key as a byte array serialized with the given compression mode.
+	pub fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		self.0.as_bytes_with(compression)
+	}
 }
 
 /// Class to store a secret key.
@@ -344,6 +441,50 @@ impl SecretKey {
 	pub fn get_handle(&self) -> *mut c_void {
 		self.handle
 	}
+
+	/// Returns the parms_id of the encryption parameters this key is valid for.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+		try_seal!(unsafe { bindgen::SecretKey_ParmsId(self.handle, parms_id.as_mut_ptr()) })?;
+
+		Ok(parms_id)
+	}
+
+	/// Checks whether the key data and metadata are valid for the given
+	/// context.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe {
+			bindgen::ValCheck_SecretKey_IsValidFor(self.handle, context.get_handle(), &mut result)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Checks whether the key metadata matches the key level of the given
+	/// context without looking at the coefficients.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let level = match key_level(context, &self.parms_id()?)? {
+			Some(level) => level,
+			None => return Ok(false),
+		};
+
+		let mut data: *mut c_void = null_mut();
+		let mut coeff_count: u64 = 0;
+
+		try_seal!(unsafe { bindgen::SecretKey_Data(self.handle, &mut data) })?;
+		try_seal!(unsafe { bindgen::Plaintext_CoeffCount(data, &mut coeff_count) })?;
+
+		Ok(coeff_count == level.poly_modulus_degree * level.coeff_modulus_size)
+	}
 }
 
 impl PartialEq for SecretKey {
@@ -356,14 +497,29 @@ impl PartialEq for SecretKey {
 }
 
 impl ToBytes for SecretKey {
-	/// Returns the key as a byte array.
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::SecretKey_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+			bindgen::SecretKey_SaveSize(self.handle, compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -373,8 +529,8 @@ impl ToBytes for SecretKey {
 			bindgen::SecretKey_Save(
 				self.handle,
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -391,17 +547,23 @@ impl FromBytes for SecretKey {
 		context: &Context,
 		bytes: &[u8],
 	) -> Result<Self> {
-		let key = SecretKey::new()?;
-		let mut bytes_read = 0;
+		// SAFETY: the object is checked for the context before it is returned.
+		let key = unsafe { Self::from_bytes_unchecked(context, bytes)? };
 
-		try_seal!(unsafe {
-			bindgen::SecretKey_Load(
-				key.handle,
-				context.get_handle(),
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut bytes_read,
-			)
+		if !key.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
+
+		Ok(key)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let key = SecretKey::new()?;
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::SecretKey_UnsafeLoad(key.handle, context.get_handle(), data, len, bytes_read)
 		})?;
 
 		Ok(key)
@@ -410,8 +572,9 @@ impl FromBytes for SecretKey {
 
 impl Drop for SecretKey {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::SecretKey_Destroy(self.handle) })
-			.expect("Fatal error in SecretKey::drop")
+		unsafe {
+			bindgen::SecretKey_Destroy(self.handle);
+		}
 	}
 }
 
@@ -498,6 +661,35 @@ impl RelinearizationKey {
 		self.handle
 	}
 
+	/// Returns the parms_id of the encryption parameters these keys are valid for.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		kswitch_keys_parms_id(self.handle)
+	}
+
+	/// Checks whether the key data and metadata are valid for the given
+	/// context.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe {
+			bindgen::ValCheck_RelinKeys_IsValidFor(self.handle, context.get_handle(), &mut result)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Checks whether the keys were generated for the key level of the given
+	/// context without looking at the key data.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		Ok(key_level(context, &self.parms_id()?)?.is_some())
+	}
+
 	/// Creates a new RelinearizationKeys.
 	pub fn new() -> Result<Self> {
 		let mut handle: *mut c_void = null_mut();
@@ -549,12 +741,28 @@ impl PartialEq for RelinearizationKey {
 
 impl ToBytes for RelinearizationKey {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::KSwitchKeys_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+			bindgen::KSwitchKeys_SaveSize(self.handle, compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -564,8 +772,8 @@ impl ToBytes for RelinearizationKey {
 			bindgen::KSwitchKeys_Save(
 				self.handle,
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -576,38 +784,59 @@ impl ToBytes for RelinearizationKey {
 	}
 }
 
+impl Serialize for RelinearizationKey {
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let data = self.as_bytes().map_err(|e| {
+			S::Error::custom(format!("Failed to get relinearization key bytes: {}", e))
+		})?;
+
+		serializer.serialize_bytes(&data)
+	}
+}
+
 impl FromBytes for RelinearizationKey {
 	type State = Context;
 	fn from_bytes(
 		context: &Context,
 		bytes: &[u8],
 	) -> Result<Self> {
-		let keys = RelinearizationKey::new()?;
-		let mut write_bytes: i64 = 0;
+		// SAFETY: the object is checked for the context before it is returned.
+		let key = unsafe { Self::from_bytes_unchecked(context, bytes)? };
 
-		try_seal!(unsafe {
-			bindgen::KSwitchKeys_Load(
-				keys.handle,
-				context.get_handle(),
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut write_bytes,
-			)
+		if !key.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
+
+		Ok(key)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let key = RelinearizationKey::new()?;
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::KSwitchKeys_UnsafeLoad(key.handle, context.get_handle(), data, len, bytes_read)
 		})?;
 
-		Ok(keys)
+		Ok(key)
 	}
 }
 
 impl Drop for RelinearizationKey {
 	fn drop(&mut self) {
-		try_seal!(unsafe {
+		unsafe {
 			// RelinKeys doesn't have a destructor, but inherits
 			// from KSwitchKeys, which does. Just call the base class's
 			// destructor.
-			bindgen::KSwitchKeys_Destroy(self.handle)
-		})
-		.expect("Fatal error in PublicKey::drop()")
+			bindgen::KSwitchKeys_Destroy(self.handle);
+		}
 	}
 }
 
@@ -638,6 +867,14 @@ impl CompactRelinearizationKey {
 	pub fn as_bytes(&self) -> Result<Vec<u8>> {
 		self.0.as_bytes()
 	}
+
+	/// Returns the key as a byte array serialized with the given compression mode.
+	pub fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		self.0.as_bytes_with(compression)
+	}
 }
 
 /// Class to store Galois keys.
@@ -665,6 +902,35 @@ impl GaloisKey {
 		self.handle
 	}
 
+	/// Returns the parms_id of the encryption parameters these keys are valid for.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		kswitch_keys_parms_id(self.handle)
+	}
+
+	/// Checks whether the key data and metadata are valid for the given
+	/// context.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe {
+			bindgen::ValCheck_GaloisKeys_IsValidFor(self.handle, context.get_handle(), &mut result)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Checks whether the keys were generated for the key level of the given
+	/// context without looking at the key data.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		Ok(key_level(context, &self.parms_id()?)?.is_some())
+	}
+
 	/// Creates a new GaloisKey.
 	pub fn new() -> Result<GaloisKey> {
 		let mut handle: *mut c_void = null_mut();
@@ -688,12 +954,28 @@ impl PartialEq for GaloisKey {
 
 impl ToBytes for GaloisKey {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::KSwitchKeys_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+			bindgen::KSwitchKeys_SaveSize(self.handle, compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -703,8 +985,8 @@ impl ToBytes for GaloisKey {
 			bindgen::KSwitchKeys_Save(
 				self.handle,
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -715,38 +997,59 @@ impl ToBytes for GaloisKey {
 	}
 }
 
+impl Serialize for GaloisKey {
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let data = self
+			.as_bytes()
+			.map_err(|e| S::Error::custom(format!("Failed to get galois key bytes: {}", e)))?;
+
+		serializer.serialize_bytes(&data)
+	}
+}
+
 impl FromBytes for GaloisKey {
 	type State = Context;
 	fn from_bytes(
 		context: &Context,
 		bytes: &[u8],
 	) -> Result<Self> {
-		let keys = GaloisKey::new()?;
-		let mut write_bytes: i64 = 0;
+		// SAFETY: the object is checked for the context before it is returned.
+		let key = unsafe { Self::from_bytes_unchecked(context, bytes)? };
 
-		try_seal!(unsafe {
-			bindgen::KSwitchKeys_Load(
-				keys.handle,
-				context.get_handle(),
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut write_bytes,
-			)
+		if !key.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
+
+		Ok(key)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		bytes: &[u8],
+	) -> Result<Self> {
+		let key = GaloisKey::new()?;
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::KSwitchKeys_UnsafeLoad(key.handle, context.get_handle(), data, len, bytes_read)
 		})?;
 
-		Ok(keys)
+		Ok(key)
 	}
 }
 
 impl Drop for GaloisKey {
 	fn drop(&mut self) {
-		try_seal!(unsafe {
+		unsafe {
 			// GaloisKeys doesn't have a destructor, but inherits
 			// from KSwitchKeys, which does. Just call the base class's
 			// destructor.
-			bindgen::KSwitchKeys_Destroy(self.handle)
-		})
-		.expect("Fatal error in GaloisKeys::drop()")
+			bindgen::KSwitchKeys_Destroy(self.handle);
+		}
 	}
 }
 
@@ -777,6 +1080,34 @@ impl CompactGaloisKeys {
 	pub fn as_bytes(&self) -> Result<Vec<u8>> {
 		self.0.as_bytes()
 	}
+
+	/// Returns the key as a byte array serialized with the given compression mode.
+	pub fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		self.0.as_bytes_with(compression)
+	}
+}
+
+/// Returns the key level of the context if `parms_id` identifies it.
+fn key_level(
+	context: &Context,
+	parms_id: &[u64],
+) -> Result<Option<ChainLevel>> {
+	if parms_id != context.get_key_parms_id()?.as_slice() {
+		return Ok(None);
+	}
+
+	context.get_chain_level(parms_id)
+}
+
+fn kswitch_keys_parms_id(handle: *mut c_void) -> Result<Vec<u64>> {
+	let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+	try_seal!(unsafe { bindgen::KSwitchKeys_GetParmsId(handle, parms_id.as_mut_ptr()) })?;
+
+	Ok(parms_id)
 }
 
 #[cfg(test)]
@@ -797,11 +1128,11 @@ mod tests {
 		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let secret_key = gen.secret_key();
+		let secret_key = gen.secret_key().unwrap();
 
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let secret_key_2 = gen.secret_key();
+		let secret_key_2 = gen.secret_key().unwrap();
 
 		// Different generators should give different keys.
 		assert_ne!(
@@ -824,7 +1155,7 @@ mod tests {
 		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		gen.create_public_key();
+		gen.create_public_key().unwrap();
 	}
 
 	#[test]
@@ -875,11 +1206,11 @@ mod tests {
 		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
 		let gen = KeyGenerator::new(&ctx).unwrap();
 
-		let secret_key = gen.secret_key();
+		let secret_key = gen.secret_key().unwrap();
 
 		let gen = KeyGenerator::new_from_secret_key(&ctx, &secret_key).unwrap();
 
-		let secret_key_2 = gen.secret_key();
+		let secret_key_2 = gen.secret_key().unwrap();
 
 		// Since we used the secret key from the first generator for the second,
 		// we should get the same key.
@@ -888,4 +1219,103 @@ mod tests {
 			serde_json::to_string(&secret_key).unwrap()
 		);
 	}
+
+	#[test]
+	fn can_serialize_public_key_with_any_compression() {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus_u64(1234)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+
+		for compression in [
+			CompressionType::None,
+			CompressionType::ZLib,
+			CompressionType::ZStd,
+		] {
+			let bytes = public_key.as_bytes_with(compression).unwrap();
+
+			assert!(bytes.len() as u64 <= public_key.save_size(compression).unwrap());
+			assert!(PublicKey::from_bytes(&ctx, &bytes).unwrap() == public_key);
+		}
+
+		assert_eq!(
+			public_key.as_bytes().unwrap(),
+			public_key.as_bytes_with(CompressionType::ZStd).unwrap()
+		);
+	}
+
+	#[test]
+	fn loaded_keys_are_checked_against_context() {
+		let mk_ctx = |bit_sizes: &[i32]| {
+			let params = BFVEncryptionParametersBuilder::new()
+				.set_poly_modulus_degree(DegreeType::D8192)
+				.set_coefficient_modulus(
+					CoefficientModulusFactory::build(DegreeType::D8192, bit_sizes).unwrap(),
+				)
+				.set_plain_modulus_u64(1234)
+				.build()
+				.unwrap();
+
+			Context::new(&params, false, SecurityLevel::TC128).unwrap()
+		};
+
+		let ctx = mk_ctx(&[50, 30, 30, 50, 50]);
+		let other_ctx = mk_ctx(&[50, 30, 50]);
+
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+		let relin_keys = gen.create_relinearization_keys().unwrap();
+
+		assert!(public_key.is_valid_for(&ctx).unwrap());
+		assert!(public_key.is_metadata_valid_for(&ctx).unwrap());
+		assert!(!public_key.is_metadata_valid_for(&other_ctx).unwrap());
+		assert!(relin_keys.is_metadata_valid_for(&ctx).unwrap());
+		assert!(!relin_keys.is_metadata_valid_for(&other_ctx).unwrap());
+
+		let bytes = public_key.as_bytes().unwrap();
+
+		assert!(PublicKey::from_bytes(&ctx, &bytes).is_ok());
+		assert!(PublicKey::from_bytes(&other_ctx, &bytes).is_err());
+	}
+
+	#[test]
+	fn unchecked_load_skips_validity_check() {
+		let params = BFVEncryptionParametersBuilder::new()
+			.set_poly_modulus_degree(DegreeType::D8192)
+			.set_coefficient_modulus(
+				CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap(),
+			)
+			.set_plain_modulus_u64(1234)
+			.build()
+			.unwrap();
+
+		let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+		let gen = KeyGenerator::new(&ctx).unwrap();
+		let public_key = gen.create_public_key().unwrap();
+
+		// The key's data ends with its last coefficient; push it past the
+		// coefficient modulus.
+		let mut bytes = public_key.as_bytes_with(CompressionType::None).unwrap();
+		let last = bytes.len() - 8;
+		bytes[last..].copy_from_slice(&u64::MAX.to_le_bytes());
+
+		assert_eq!(
+			PublicKey::from_bytes(&ctx, &bytes).unwrap_err(),
+			Error::InvalidForContext
+		);
+
+		// SAFETY: the key is only checked, never used.
+		let key = unsafe { PublicKey::from_bytes_unchecked(&ctx, &bytes) }.unwrap();
+
+		assert!(key.is_metadata_valid_for(&ctx).unwrap());
+		assert!(!key.is_valid_for(&ctx).unwrap());
+	}
 }
@@ -22,8 +22,8 @@
 //!
 //!     let encoder = BFVEncoder::new(&ctx)?;
 //!
-//!     let public_key = gen.create_public_key();
-//!     let secret_key = gen.secret_key();
+//!     let public_key = gen.create_public_key()?;
+//!     let secret_key = gen.secret_key()?;
 //!
 //!     let encryptor = Encryptor::with_public_key(&ctx, &public_key)?;
 //!     let decryptor = Decryptor::new(&ctx, &secret_key)?;
@@ -87,29 +87,52 @@ mod plaintext;
 mod poly_array;
 mod serialization;
 
-pub use ciphertext::Ciphertext;
+pub use ciphertext::{Ciphertext, CompactCiphertext};
 pub use components::{
 	marker as component_marker, Asym, AsymmetricComponents, Sym, SymAsym, SymmetricComponents,
 };
 pub use context::Context;
-pub use decryptor::Decryptor;
+pub use decryptor::{Decryptor, Precision};
+pub use encoder::any::AnyEncoder;
 pub use encoder::bfv::BFVEncoder;
 pub use encoder::ckks::CKKSEncoder;
 pub use encryptor::{AsymmetricEncryptor, Encryptor, SymmetricEncryptor};
-pub use error::{Error, Result};
+pub use error::{
+	CircuitError, Error, IoError, KeyMismatch, MalformedInput, OperationError, ParmsMismatch,
+	Result, ResultMismatch, ScaleMismatch, ShapeMismatch,
+};
+pub use evaluator::any::{AnyCiphertext, AnyEvaluator, AnyPlaintext, DynEvaluator};
 pub use evaluator::bfv::BFVEvaluator;
 pub use evaluator::ckks::CKKSEvaluator;
+pub use evaluator::noise::{NoiseCiphertext, NoiseEstimator, NoisePlaintext};
+pub use evaluator::plain::{
+	Computation, Differential, PlainCiphertext, PlainEvaluator, PlainPlaintext, PlainSlot,
+};
+pub use evaluator::precision::{
+	PrecisionCiphertext, PrecisionEstimator, PrecisionPlaintext, PrecisionStep,
+};
 pub use evaluator::Evaluator;
+pub use ext::circuit::{
+	interpreter::CircuitInterpreter, interpreter::CircuitLimits, program::CircuitProgram, Circuit,
+	CircuitEvaluator, CircuitKeys, CompiledCircuit, KeyRequirements, PlainWire, Wire,
+};
+pub use ext::federated::{Aggregator, ClientUpdate, FederatedClient, KeyHolder};
 pub use ext::tensor::{
-	decryptor::TensorDecryptor, encoder::TensorEncoder, encryptor::TensorEncryptor,
-	evaluator::TensorEvaluator, FromChunk, Tensor, ToChunk,
+	container::TensorContainer, decryptor::ChunkDiagnostics, decryptor::TensorDecryptor,
+	decryptor::TensorDiagnostics, encoder::TensorEncoder, encryptor::TensorEncryptor,
+	evaluator::TensorEvaluator, parallel::MaybeSend, parallel::MaybeSync, select::SlotMove,
+	select::SlotSelect, FromChunk, Shape, Tensor, ToChunk,
 };
+pub use ext::vector::{bfv::BfvContext, bfv::BfvVector, ckks::CkksContext, ckks::CkksVector};
 pub use key_generator::{GaloisKey, KeyGenerator, PublicKey, RelinearizationKey, SecretKey};
-pub use memory::MemoryPool;
+pub use memory::{MemoryManager, MemoryPool, MemoryProfile};
 pub use modulus::{
 	CoefficientModulusFactory, DegreeType, Modulus, PlainModulusFactory, SecurityLevel,
 };
 pub use parameters::*;
 pub use plaintext::Plaintext;
 pub use poly_array::PolynomialArray;
-pub use serialization::{FromBytes, ToBytes};
+pub use serialization::{
+	max_load_size, set_max_load_size, CompressionType, ContextSeed, FromBytes, ToBytes,
+	DEFAULT_MAX_LOAD_SIZE,
+};
@@ -1,8 +1,11 @@
-use crate::{error::Result, try_seal};
+use crate::{error::Error, error::Result, try_seal};
 use std::{
 	ffi::c_void,
 	ptr::null_mut,
-	sync::atomic::{AtomicPtr, Ordering},
+	sync::{
+		atomic::{AtomicPtr, Ordering},
+		Arc, Mutex, PoisonError,
+	},
 };
 
 use crate::bindgen;
@@ -14,13 +17,30 @@ use crate::bindgen;
 /// the large amount of memory space required by SEAL. The custom memory pool is designed to
 /// reduce this overhead by providing a way to allocate memory in advance and reuse it.
 ///
-/// In the FFI, the memory pool is read-only and cannot be modified. The memory pool is
-/// initialized by the library and is used by the library to allocate memory. The methods
-/// provided by this crate are just to check the pool's health and status.
-#[derive(Debug)]
+/// Encoders, encryptors and evaluators draw from the pool of the current
+/// [`MemoryProfile`] unless they are given a pool with `with_memory_pool`.
+/// Cloning a pool returns another handle to the same pool.
+///
+/// Every pool this type can hold is guarded by a lock in SEAL, so a pool may
+/// be shared by objects used from several threads at once.
+#[derive(Debug, Clone)]
 pub struct MemoryPool {
-	/// A pointer to the underlying SEAL memory pool.
-	pub(crate) handle: AtomicPtr<c_void>,
+	handle: Arc<PoolHandle>,
+}
+
+/// The SEAL handle behind a [`MemoryPool`] and all its clones, destroyed
+/// with the last of them.
+#[derive(Debug)]
+struct PoolHandle(AtomicPtr<c_void>);
+
+impl Drop for PoolHandle {
+	fn drop(&mut self) {
+		// Destroying a handle only fails if it is null, and a panic here could
+		// abort a thread that is already unwinding.
+		unsafe {
+			bindgen::MemoryPoolHandle_Destroy(self.0.load(Ordering::SeqCst));
+		}
+	}
 }
 
 impl MemoryPool {
@@ -31,9 +51,17 @@ impl MemoryPool {
 
 		try_seal!(unsafe { bindgen::MemoryPoolHandle_New(clear_on_destruction, &mut handle) })?;
 
-		Ok(MemoryPool {
-			handle: AtomicPtr::new(handle),
-		})
+		Ok(MemoryPool::from_handle(handle))
+	}
+
+	/// Returns a handle to the global memory pool, which is shared by every
+	/// thread.
+	pub fn global() -> Result<Self> {
+		let mut handle: *mut c_void = null_mut();
+
+		try_seal!(unsafe { bindgen::MemoryPoolHandle_Global(&mut handle) })?;
+
+		Ok(MemoryPool::from_handle(handle))
 	}
 
 	/// Returns the number of allocations in the pool.
@@ -76,18 +104,114 @@ impl MemoryPool {
 		Ok(result)
 	}
 
+	fn from_handle(handle: *mut c_void) -> Self {
+		MemoryPool {
+			handle: Arc::new(PoolHandle(AtomicPtr::new(handle))),
+		}
+	}
+
 	/// Returns handle to the underlying SEAL object.
 	pub(crate) unsafe fn get_handle(&self) -> *mut c_void {
-		self.handle.load(Ordering::SeqCst)
+		self.handle.0.load(Ordering::SeqCst)
 	}
 }
 
-impl Drop for MemoryPool {
-	fn drop(&mut self) {
-		if let Err(err) = try_seal!(unsafe { bindgen::MemoryPoolHandle_Destroy(self.get_handle()) })
-		{
-			panic!("Failed to destroy memory pool: {:?}", err);
+/// Returns the handle of the given pool, or null to let SEAL use the pool of
+/// the current memory manager profile.
+pub(crate) unsafe fn pool_handle(pool: Option<&MemoryPool>) -> *mut c_void {
+	match pool {
+		Some(pool) => pool.get_handle(),
+		None => null_mut(),
+	}
+}
+
+/// Decides which memory pool SEAL allocates from when an operation is not
+/// given a pool.
+#[derive(Debug, Clone)]
+pub enum MemoryProfile {
+	/// Always use the global memory pool. This is SEAL's default.
+	Global,
+	/// Use a pool local to the calling thread, which avoids contention when
+	/// several threads allocate at once.
+	///
+	/// These pools are not guarded by a lock, and ciphertexts and plaintexts
+	/// free their data into the pool they were allocated from. Objects
+	/// created under this profile must never be moved to, resized on or
+	/// dropped on another thread, so the profile is unavailable with the
+	/// `parallel` feature.
+	ThreadLocal,
+	/// Create a new pool for every operation.
+	New,
+	/// Always use the given pool.
+	Fixed(MemoryPool),
+}
+
+/// The profile last switched to, which SEAL doesn't report back.
+static PROFILE: Mutex<MemoryProfile> = Mutex::new(MemoryProfile::Global);
+
+/// Controls the memory manager profile used across the process.
+pub struct MemoryManager;
+
+impl MemoryManager {
+	/// Switches the profile used by every operation that is not given a
+	/// pool, in every thread.
+	///
+	/// Fails with [`Error::InvalidOperation`] when switching to
+	/// [`MemoryProfile::ThreadLocal`] with the `parallel` feature, since
+	/// tensor operations then move objects between threads.
+	pub fn switch_profile(profile: &MemoryProfile) -> Result<()> {
+		if cfg!(feature = "parallel") && matches!(profile, MemoryProfile::ThreadLocal) {
+			return Err(Error::InvalidOperation);
+		}
+
+		let mut current = PROFILE.lock().unwrap_or_else(PoisonError::into_inner);
+		let mut handle: *mut c_void = null_mut();
+
+		try_seal!(unsafe {
+			match profile {
+				MemoryProfile::Global => bindgen::MMProf_CreateGlobal(&mut handle),
+				MemoryProfile::ThreadLocal => bindgen::MMProf_CreateThreadLocal(&mut handle),
+				MemoryProfile::New => bindgen::MMProf_CreateNew(&mut handle),
+				MemoryProfile::Fixed(pool) => {
+					bindgen::MMProf_CreateFixed(pool.get_handle(), &mut handle)
+				}
+			}
+		})?;
+
+		// The memory manager takes ownership of the profile.
+		try_seal!(unsafe { bindgen::MemoryManager_SwitchProfile(handle) })?;
+
+		*current = profile.clone();
+
+		Ok(())
+	}
+
+	/// Returns the current profile.
+	pub fn profile() -> MemoryProfile {
+		PROFILE
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.clone()
+	}
+
+	/// Returns the pool that the current profile hands out to the calling
+	/// thread.
+	///
+	/// Fails with [`Error::InvalidOperation`] under
+	/// [`MemoryProfile::ThreadLocal`]: the pools of that profile are not
+	/// guarded by a lock, so a handle to one must not leave its thread.
+	pub fn get_pool() -> Result<MemoryPool> {
+		let current = PROFILE.lock().unwrap_or_else(PoisonError::into_inner);
+
+		if matches!(*current, MemoryProfile::ThreadLocal) {
+			return Err(Error::InvalidOperation);
 		}
+
+		let mut handle: *mut c_void = null_mut();
+
+		try_seal!(unsafe { bindgen::MemoryManager_GetPool2(&mut handle) })?;
+
+		Ok(MemoryPool::from_handle(handle))
 	}
 }
 
@@ -102,6 +226,23 @@ mod tests {
 		std::mem::drop(memory_pool);
 	}
 
+	#[test]
+	fn clones_share_the_pool() {
+		let memory_pool = MemoryPool::new().unwrap();
+		let clone = memory_pool.clone();
+
+		let _plaintext = Plaintext::new_with_pool(&memory_pool).unwrap();
+
+		assert_eq!(
+			clone.pool_count().unwrap(),
+			memory_pool.pool_count().unwrap()
+		);
+		assert_eq!(
+			clone.pool_allocated_byte_count().unwrap(),
+			memory_pool.pool_allocated_byte_count().unwrap()
+		);
+	}
+
 	#[test]
 	fn can_get_pool_count() {
 		let memory_pool = MemoryPool::new().unwrap();
@@ -144,13 +144,12 @@ impl Modulus {
 	}
 
 	/// The value of the modulus
-	pub fn value(&self) -> u64 {
+	pub fn value(&self) -> Result<u64> {
 		let mut val: u64 = 0;
 
-		try_seal!(unsafe { bindgen::Modulus_Value(self.get_handle(), &mut val) })
-			.expect("Internal error. Could not get modulus value.");
+		try_seal!(unsafe { bindgen::Modulus_Value(self.get_handle(), &mut val) })?;
 
-		val
+		Ok(val)
 	}
 
 	/// The handle to the internal SEAL Modulus object.
@@ -168,7 +167,10 @@ impl std::fmt::Debug for Modulus {
 		&self,
 		f: &mut std::fmt::Formatter<'_>,
 	) -> std::result::Result<(), std::fmt::Error> {
-		write!(f, "{}", self.value())
+		match self.value() {
+			Ok(value) => write!(f, "{}", value),
+			Err(_) => f.write_str("<invalid modulus>"),
+		}
 	}
 }
 
@@ -177,14 +179,15 @@ impl PartialEq for Modulus {
 		&self,
 		other: &Self,
 	) -> bool {
-		self.value() == other.value()
+		matches!((self.value(), other.value()), (Ok(a), Ok(b)) if a == b)
 	}
 }
 
 impl Drop for Modulus {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Modulus_Destroy(self.get_handle()) })
-			.expect("Internal error in Modulus::drop().");
+		unsafe {
+			bindgen::Modulus_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -306,14 +309,19 @@ impl CoefficientModulusFactory {
 	pub fn max_bit_count(
 		degree: u64,
 		security_level: SecurityLevel,
-	) -> u32 {
+	) -> Result<u32> {
 		let mut bits: i32 = 0;
 
-		unsafe { bindgen::CoeffModulus_MaxBitCount(degree, security_level as i32, &mut bits) };
+		try_seal!(unsafe {
+			bindgen::CoeffModulus_MaxBitCount(degree, security_level as i32, &mut bits)
+		})?;
 
-		assert!(bits > 0);
+		// SEAL returns 0 for degrees it has no security estimate for.
+		if bits <= 0 {
+			return Err(Error::InvalidArgument);
+		}
 
-		bits as u32
+		Ok(bits as u32)
 	}
 }
 
@@ -351,7 +359,7 @@ mod tests {
 	fn can_create_plain_modulus() {
 		let modulus = PlainModulusFactory::batching(DegreeType::D1024, 20).unwrap();
 
-		assert_eq!(modulus.value(), 1038337);
+		assert_eq!(modulus.value().unwrap(), 1038337);
 	}
 
 	#[test]
@@ -360,19 +368,19 @@ mod tests {
 			CoefficientModulusFactory::bfv(DegreeType::D1024, SecurityLevel::TC128).unwrap();
 
 		assert_eq!(modulus.len(), 1);
-		assert_eq!(modulus[0].value(), 132120577);
+		assert_eq!(modulus[0].value().unwrap(), 132120577);
 
 		let modulus =
 			CoefficientModulusFactory::bfv(DegreeType::D1024, SecurityLevel::TC192).unwrap();
 
 		assert_eq!(modulus.len(), 1);
-		assert_eq!(modulus[0].value(), 520193);
+		assert_eq!(modulus[0].value().unwrap(), 520193);
 
 		let modulus =
 			CoefficientModulusFactory::bfv(DegreeType::D1024, SecurityLevel::TC256).unwrap();
 
 		assert_eq!(modulus.len(), 1);
-		assert_eq!(modulus[0].value(), 12289);
+		assert_eq!(modulus[0].value().unwrap(), 12289);
 	}
 
 	#[test]
@@ -381,11 +389,23 @@ mod tests {
 			CoefficientModulusFactory::build(DegreeType::D8192, &[50, 30, 30, 50, 50]).unwrap();
 
 		assert_eq!(modulus.len(), 5);
-		assert_eq!(modulus[0].value(), 1125899905744897);
-		assert_eq!(modulus[1].value(), 1073643521);
-		assert_eq!(modulus[2].value(), 1073692673);
-		assert_eq!(modulus[3].value(), 1125899906629633);
-		assert_eq!(modulus[4].value(), 1125899906826241);
+		assert_eq!(modulus[0].value().unwrap(), 1125899905744897);
+		assert_eq!(modulus[1].value().unwrap(), 1073643521);
+		assert_eq!(modulus[2].value().unwrap(), 1073692673);
+		assert_eq!(modulus[3].value().unwrap(), 1125899906629633);
+		assert_eq!(modulus[4].value().unwrap(), 1125899906826241);
+	}
+
+	#[test]
+	fn max_bit_count_rejects_unknown_degrees() {
+		assert_eq!(
+			CoefficientModulusFactory::max_bit_count(8192, SecurityLevel::TC128).unwrap(),
+			218
+		);
+		assert_eq!(
+			CoefficientModulusFactory::max_bit_count(1000, SecurityLevel::TC128),
+			Err(Error::InvalidArgument)
+		);
 	}
 
 	#[test]
@@ -120,11 +120,16 @@ mod tests {
 			.build()
 			.unwrap();
 
-		assert_eq!(params.get_poly_modulus_degree(), 1024);
-		assert_eq!(params.get_scheme(), SchemeType::Bfv);
-		assert_eq!(params.get_plain_modulus().value(), 1234);
-		assert_eq!(params.get_coefficient_modulus().len(), 1);
-		assert_eq!(params.get_coefficient_modulus()[0].value(), 132120577);
+		assert_eq!(params.get_poly_modulus_degree().unwrap(), 1024);
+		assert_eq!(params.get_scheme().unwrap(), SchemeType::Bfv);
+		assert_eq!(params.get_plain_modulus().unwrap().value().unwrap(), 1234);
+		assert_eq!(params.get_coefficient_modulus().unwrap().len(), 1);
+		assert_eq!(
+			params.get_coefficient_modulus().unwrap()[0]
+				.value()
+				.unwrap(),
+			132120577
+		);
 
 		let params = BFVEncryptionParametersBuilder::new()
 			.set_poly_modulus_degree(DegreeType::D1024)
@@ -135,16 +140,16 @@ mod tests {
 			.build()
 			.unwrap();
 
-		let modulus = params.get_coefficient_modulus();
+		let modulus = params.get_coefficient_modulus().unwrap();
 
-		assert_eq!(params.get_poly_modulus_degree(), 1024);
-		assert_eq!(params.get_scheme(), SchemeType::Bfv);
-		assert_eq!(params.get_plain_modulus().value(), 1234);
+		assert_eq!(params.get_poly_modulus_degree().unwrap(), 1024);
+		assert_eq!(params.get_scheme().unwrap(), SchemeType::Bfv);
+		assert_eq!(params.get_plain_modulus().unwrap().value().unwrap(), 1234);
 		assert_eq!(modulus.len(), 5);
-		assert_eq!(modulus[0].value(), 1125899905744897);
-		assert_eq!(modulus[1].value(), 1073643521);
-		assert_eq!(modulus[2].value(), 1073692673);
-		assert_eq!(modulus[3].value(), 1125899906629633);
-		assert_eq!(modulus[4].value(), 1125899906826241);
+		assert_eq!(modulus[0].value().unwrap(), 1125899905744897);
+		assert_eq!(modulus[1].value().unwrap(), 1073643521);
+		assert_eq!(modulus[2].value().unwrap(), 1073692673);
+		assert_eq!(modulus[3].value().unwrap(), 1125899906629633);
+		assert_eq!(modulus[4].value().unwrap(), 1125899906826241);
 	}
 }
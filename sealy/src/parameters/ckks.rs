@@ -89,9 +89,9 @@ mod tests {
 			.build()
 			.unwrap();
 
-		assert_eq!(params.get_poly_modulus_degree(), 1024);
-		assert_eq!(params.get_scheme(), SchemeType::Ckks);
-		assert_eq!(params.get_coefficient_modulus().len(), 4);
+		assert_eq!(params.get_poly_modulus_degree().unwrap(), 1024);
+		assert_eq!(params.get_scheme().unwrap(), SchemeType::Ckks);
+		assert_eq!(params.get_coefficient_modulus().unwrap().len(), 4);
 
 		let params = CKKSEncryptionParametersBuilder::new()
 			.set_poly_modulus_degree(DegreeType::D1024)
@@ -101,15 +101,15 @@ mod tests {
 			.build()
 			.unwrap();
 
-		let modulus = params.get_coefficient_modulus();
+		let modulus = params.get_coefficient_modulus().unwrap();
 
-		assert_eq!(params.get_poly_modulus_degree(), 1024);
-		assert_eq!(params.get_scheme(), SchemeType::Ckks);
+		assert_eq!(params.get_poly_modulus_degree().unwrap(), 1024);
+		assert_eq!(params.get_scheme().unwrap(), SchemeType::Ckks);
 		assert_eq!(modulus.len(), 5);
-		assert_eq!(modulus[0].value(), 1125899905744897);
-		assert_eq!(modulus[1].value(), 1073643521);
-		assert_eq!(modulus[2].value(), 1073692673);
-		assert_eq!(modulus[3].value(), 1125899906629633);
-		assert_eq!(modulus[4].value(), 1125899906826241);
+		assert_eq!(modulus[0].value().unwrap(), 1125899905744897);
+		assert_eq!(modulus[1].value().unwrap(), 1073643521);
+		assert_eq!(modulus[2].value().unwrap(), 1073692673);
+		assert_eq!(modulus[3].value().unwrap(), 1125899906629633);
+		assert_eq!(modulus[4].value().unwrap(), 1125899906826241);
 	}
 }
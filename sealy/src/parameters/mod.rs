@@ -5,11 +5,13 @@ use std::ptr::null_mut;
 
 use crate::bindgen::{self};
 use crate::error::convert_seal_error;
+use crate::error::Error;
 use crate::error::Result;
-use crate::serialization::CompressionType;
+use crate::serialization::{load_checked, BytesVisitor, CompressionType};
 use crate::{try_seal, FromBytes, Modulus, ToBytes};
 
-use serde::{Deserialize, Serialize};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// BFV encryption parameters.
 mod bfv;
@@ -34,13 +36,14 @@ pub enum SchemeType {
 }
 
 impl SchemeType {
-	/// Converts a u8 to a SchemeType.
-	pub fn from_u8(val: u8) -> Self {
+	/// Converts a u8 to a SchemeType. Fails with [`Error::InvalidArgument`]
+	/// for unknown values.
+	pub fn from_u8(val: u8) -> Result<Self> {
 		match val {
-			0x0 => SchemeType::None,
-			0x1 => SchemeType::Bfv,
-			0x2 => SchemeType::Ckks,
-			_ => panic!("Illegal scheme type"),
+			0x0 => Ok(SchemeType::None),
+			0x1 => Ok(SchemeType::Bfv),
+			0x2 => Ok(SchemeType::Ckks),
+			_ => Err(Error::InvalidArgument),
 		}
 	}
 
@@ -110,42 +113,39 @@ impl EncryptionParameters {
 	}
 
 	/// Returns the polynomial degree of the underlying CKKS or BFV scheme.
-	pub fn get_poly_modulus_degree(&self) -> u64 {
+	pub fn get_poly_modulus_degree(&self) -> Result<u64> {
 		let mut degree: u64 = 0;
 
 		unsafe {
 			convert_seal_error(bindgen::EncParams_GetPolyModulusDegree(
 				self.handle,
 				&mut degree,
-			))
-			.expect("Internal error");
+			))?;
 		};
 
-		degree
+		Ok(degree)
 	}
 
 	/// Get the underlying scheme.
-	pub fn get_scheme(&self) -> SchemeType {
+	pub fn get_scheme(&self) -> Result<SchemeType> {
 		let mut scheme: u8 = 0;
 
 		unsafe {
-			convert_seal_error(bindgen::EncParams_GetScheme(self.handle, &mut scheme))
-				.expect("Internal error");
+			convert_seal_error(bindgen::EncParams_GetScheme(self.handle, &mut scheme))?;
 		};
 
 		SchemeType::from_u8(scheme)
 	}
 
 	/// Returns the plain text modulus for the encryption scheme.
-	pub fn get_plain_modulus(&self) -> Modulus {
+	pub fn get_plain_modulus(&self) -> Result<Modulus> {
 		let mut borrowed_modulus = null_mut();
 
 		unsafe {
 			convert_seal_error(bindgen::EncParams_GetPlainModulus(
 				self.handle,
 				&mut borrowed_modulus,
-			))
-			.expect("Internal error")
+			))?
 		};
 
 		let borrowed_modulus = unsafe { Modulus::new_unchecked_from_handle(borrowed_modulus) };
@@ -155,11 +155,11 @@ impl EncryptionParameters {
 		let ret = borrowed_modulus.clone();
 		forget(borrowed_modulus);
 
-		ret
+		Ok(ret)
 	}
 
 	/// Returns the coefficient modulus for the encryption scheme.
-	pub fn get_coefficient_modulus(&self) -> Vec<Modulus> {
+	pub fn get_coefficient_modulus(&self) -> Result<Vec<Modulus>> {
 		let mut len: u64 = 0;
 
 		unsafe {
@@ -167,8 +167,7 @@ impl EncryptionParameters {
 				self.handle,
 				&mut len,
 				null_mut(),
-			))
-			.expect("Internal error")
+			))?
 		};
 
 		let mut borrowed_modulus = Vec::with_capacity(len as usize);
@@ -179,13 +178,12 @@ impl EncryptionParameters {
 				self.handle,
 				&mut len,
 				borrowed_modulus_ptr,
-			))
-			.expect("Internal error");
+			))?;
 
 			borrowed_modulus.set_len(len as usize);
 		};
 
-		borrowed_modulus
+		Ok(borrowed_modulus
 			.iter()
 			.map(|h| {
 				let modulus = unsafe { Modulus::new_unchecked_from_handle(*h) };
@@ -195,19 +193,18 @@ impl EncryptionParameters {
 
 				ret
 			})
-			.collect()
+			.collect())
 	}
 
 	/// Returns the parms id.
-	pub fn get_parms_id(&self) -> u64 {
+	pub fn get_parms_id(&self) -> Result<u64> {
 		let mut parms_id: c_ulong = 0;
 
 		unsafe {
-			convert_seal_error(bindgen::EncParams_GetParmsId(self.handle, &mut parms_id))
-				.expect("Internal error");
+			convert_seal_error(bindgen::EncParams_GetParmsId(self.handle, &mut parms_id))?;
 		}
 
-		parms_id
+		Ok(parms_id)
 	}
 
 	/// Sets the polynomial modulus degree.
@@ -280,19 +277,36 @@ pub enum PlainModulusType {
 
 impl Drop for EncryptionParameters {
 	fn drop(&mut self) {
-		convert_seal_error(unsafe { bindgen::EncParams_Destroy(self.handle) })
-			.expect("Internal error in EncryptionParameters::drop().");
+		unsafe {
+			bindgen::EncParams_Destroy(self.handle);
+		}
 	}
 }
 
 impl ToBytes for EncryptionParameters {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		convert_seal_error(unsafe {
-			bindgen::EncParams_SaveSize(self.handle, CompressionType::ZStd as u8, &mut num_bytes)
+			bindgen::EncParams_SaveSize(self.handle, compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -302,8 +316,8 @@ impl ToBytes for EncryptionParameters {
 			bindgen::EncParams_Save(
 				self.handle,
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -314,6 +328,35 @@ impl ToBytes for EncryptionParameters {
 	}
 }
 
+impl Serialize for EncryptionParameters {
+	fn serialize<S>(
+		&self,
+		serializer: S,
+	) -> std::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let data = self.as_bytes().map_err(|e| {
+			S::Error::custom(format!("Failed to get encryption parameters bytes: {}", e))
+		})?;
+
+		serializer.serialize_bytes(&data)
+	}
+}
+
+/// Encryption parameters record their scheme in the serialized bytes, so unlike
+/// keys and ciphertexts they deserialize without a context.
+impl<'de> Deserialize<'de> for EncryptionParameters {
+	fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+
+		Self::from_bytes(&SchemeType::None, &bytes).map_err(serde::de::Error::custom)
+	}
+}
+
 impl FromBytes for EncryptionParameters {
 	type State = SchemeType;
 	fn from_bytes(
@@ -321,17 +364,18 @@ impl FromBytes for EncryptionParameters {
 		bytes: &[u8],
 	) -> Result<Self> {
 		let key = Self::new(*scheme)?;
-		let mut bytes_read = 0;
-
-		convert_seal_error(unsafe {
-			bindgen::EncParams_Load(
-				key.handle,
-				bytes.as_ptr() as *mut u8,
-				bytes.len() as u64,
-				&mut bytes_read,
-			)
+		load_checked(bytes, |data, len, bytes_read| unsafe {
+			bindgen::EncParams_Load(key.handle, data, len, bytes_read)
 		})?;
 
 		Ok(key)
 	}
+	/// Parameters have no state to be checked against, so this is the same as
+	/// [`FromBytes::from_bytes`].
+	unsafe fn from_bytes_unchecked(
+		scheme: &SchemeType,
+		bytes: &[u8],
+	) -> Result<Self> {
+		Self::from_bytes(scheme, bytes)
+	}
 }
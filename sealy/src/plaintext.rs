@@ -4,10 +4,14 @@ use std::fmt::Debug;
 use std::ptr::null_mut;
 use std::sync::atomic::{AtomicPtr, Ordering};
 
-use crate::{bindgen, serialization::CompressionType, Context, FromBytes, ToBytes};
+use crate::{
+	bindgen,
+	serialization::{load_checked, CompressionType},
+	Context, EncryptionParameters, FromBytes, ToBytes,
+};
 use crate::{error::*, try_seal, MemoryPool};
 
-use serde::ser::Error;
+use serde::ser::Error as _;
 use serde::{Serialize, Serializer};
 
 /// Class to store a plaintext encoded items. The data encoded for the
@@ -88,12 +92,12 @@ impl Plaintext {
 	/// * `hex_str`: The formatted polynomial string specifying the plaintext
 	///              polynomial.
 	///
-	/// # Panics
-	/// Panics if `hex_str` contains a null character anywhere but the end of the string.
+	/// Fails with [`Error::InvalidArgument`] if `hex_str` contains a null
+	/// character.
 	pub fn from_hex_string(hex_str: &str) -> Result<Self> {
 		let mut handle: *mut c_void = null_mut();
 
-		let hex_string = CString::new(hex_str).unwrap();
+		let hex_string = CString::new(hex_str).map_err(|_| Error::InvalidArgument)?;
 
 		try_seal!(unsafe {
 			bindgen::Plaintext_Create4(hex_string.as_ptr() as *mut u8, null_mut(), &mut handle)
@@ -108,77 +112,142 @@ impl Plaintext {
 	/// from lowest to highest degree, with the first value being the constant
 	/// coefficient.
 	///
-	/// # Panics
-	/// Panics if index is greater than len().
+	/// Fails with [`Error::InvalidArgument`] if index is not less than len().
 	pub fn get_coefficient(
 		&self,
 		index: usize,
-	) -> u64 {
+	) -> Result<u64> {
 		let mut coeff: u64 = 0;
 
-		if index > self.len() {
-			panic!("Index {} out of bounds {}", index, self.len());
+		if index >= self.len()? {
+			return Err(Error::InvalidArgument);
 		}
 
 		try_seal!(unsafe {
 			bindgen::Plaintext_CoeffAt(self.get_handle(), index as u64, &mut coeff)
-		})
-		.expect("Fatal error in Plaintext::index().");
+		})?;
 
-		coeff
+		Ok(coeff)
 	}
 
 	/// Sets the coefficient at the given location. Coefficients are ordered
 	/// from lowest to highest degree, with the first value being the constant
 	/// coefficient.
 	///
-	/// # Panics
-	/// Panics if index is greater than len().
+	/// Fails with [`Error::InvalidArgument`] if index is not less than len().
 	pub fn set_coefficient(
 		&mut self,
 		index: usize,
 		value: u64,
-	) {
-		if index > self.len() {
-			panic!("Index {} out of bounds {}", index, self.len());
+	) -> Result<()> {
+		if index >= self.len()? {
+			return Err(Error::InvalidArgument);
 		}
 
 		try_seal!(unsafe { bindgen::Plaintext_SetCoeffAt(self.get_handle(), index as u64, value) })
-			.expect("Fatal error in Plaintext::index().");
 	}
 
 	/// Sets the number of coefficients this plaintext can hold.
 	pub fn resize(
 		&mut self,
 		count: usize,
-	) {
+	) -> Result<()> {
 		try_seal!(unsafe { bindgen::Plaintext_Resize(self.get_handle(), count as u64) })
-			.expect("Fatal error in Plaintext::resize().");
 	}
 
 	/// Returns the number of coefficients this plaintext can hold.
-	pub fn len(&self) -> usize {
+	pub fn len(&self) -> Result<usize> {
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe { bindgen::Plaintext_CoeffCount(self.get_handle(), &mut size) })
-			.expect("Fatal error in Plaintext::index().");
+		try_seal!(unsafe { bindgen::Plaintext_CoeffCount(self.get_handle(), &mut size) })?;
 
-		size as usize
+		Ok(size as usize)
 	}
 
 	/// Returns `true` if the plaintext is empty.
-	pub fn is_empty(&self) -> bool {
-		self.len() == 0
+	pub fn is_empty(&self) -> Result<bool> {
+		Ok(self.len()? == 0)
 	}
 
 	/// Returns whether the plaintext is in NTT form.
-	pub fn is_ntt_form(&self) -> bool {
+	pub fn is_ntt_form(&self) -> Result<bool> {
+		let mut result = false;
+
+		try_seal!(unsafe { bindgen::Plaintext_IsNTTForm(self.get_handle(), &mut result) })?;
+
+		Ok(result)
+	}
+
+	/// Returns the parms_id of the encryption parameters this plaintext is
+	/// valid for. Plaintexts not in NTT form have an all-zero parms_id.
+	pub fn parms_id(&self) -> Result<Vec<u64>> {
+		let mut parms_id: Vec<u64> = vec![0; EncryptionParameters::block_size() as usize];
+
+		try_seal!(unsafe {
+			bindgen::Plaintext_GetParmsId(self.get_handle(), parms_id.as_mut_ptr())
+		})?;
+
+		Ok(parms_id)
+	}
+
+	/// Returns the scale of the plaintext. Only meaningful for CKKS; BFV
+	/// plaintexts have a scale of 1.
+	pub fn scale(&self) -> Result<f64> {
+		let mut scale = 0.0;
+
+		try_seal!(unsafe { bindgen::Plaintext_Scale(self.get_handle(), &mut scale) })?;
+
+		Ok(scale)
+	}
+
+	/// Checks whether the plaintext data and metadata are valid for the given
+	/// context.
+	pub fn is_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
 		let mut result = false;
 
-		try_seal!(unsafe { bindgen::Plaintext_IsNTTForm(self.get_handle(), &mut result) })
-			.expect("Fatal error in Plaintext::is_ntt_form().");
+		try_seal!(unsafe {
+			bindgen::ValCheck_Plaintext_IsValidFor(
+				self.get_handle(),
+				context.get_handle(),
+				&mut result,
+			)
+		})?;
+
+		Ok(result)
+	}
+
+	/// Returns all the coefficients, from lowest to highest degree.
+	fn coefficients(&self) -> Result<Vec<u64>> {
+		(0..self.len()?).map(|i| self.get_coefficient(i)).collect()
+	}
+
+	/// Checks whether the plaintext metadata matches the given context without
+	/// looking at the coefficients. NTT form plaintexts must belong to a data
+	/// level of the context; other plaintexts must fit in its polynomial degree.
+	pub fn is_metadata_valid_for(
+		&self,
+		context: &Context,
+	) -> Result<bool> {
+		let len = self.len()? as u64;
+
+		if !self.is_ntt_form()? {
+			let first = context.get_chain_level(&context.get_first_parms_id()?)?;
+
+			return Ok(first
+				.map(|level| len <= level.poly_modulus_degree)
+				.unwrap_or(false));
+		}
+
+		let level = match context.get_chain_level(&self.parms_id()?)? {
+			Some(level) => level,
+			None => return Ok(false),
+		};
 
-		result
+		Ok(level.chain_index <= context.get_first_chain_index()?
+			&& len == level.poly_modulus_degree * level.coeff_modulus_size)
 	}
 }
 
@@ -217,17 +286,10 @@ impl PartialEq for Plaintext {
 		&self,
 		other: &Self,
 	) -> bool {
-		if self.len() == other.len() {
-			for i in 0..self.len() {
-				if self.get_coefficient(i) != other.get_coefficient(i) {
-					return false;
-				}
-			}
-
-			true
-		} else {
-			false
-		}
+		matches!(
+			(self.coefficients(), other.coefficients()),
+			(Ok(a), Ok(b)) if a == b
+		)
 	}
 }
 
@@ -236,8 +298,7 @@ impl Hash for Plaintext {
 		&self,
 		state: &mut H,
 	) {
-		for i in 0..self.len() {
-			let c = self.get_coefficient(i);
+		for c in self.coefficients().unwrap_or_default() {
 			state.write_u64(c);
 		}
 	}
@@ -280,18 +341,30 @@ impl FromBytes for Plaintext {
 		context: &Context,
 		data: &[u8],
 	) -> Result<Self> {
-		let mut bytes_read = 0;
+		// SAFETY: the object is checked for the context before it is returned.
+		let plaintext = unsafe { Self::from_bytes_unchecked(context, data)? };
+
+		if !plaintext.is_valid_for(context)? {
+			return Err(Error::InvalidForContext);
+		}
 
+		Ok(plaintext)
+	}
+
+	unsafe fn from_bytes_unchecked(
+		context: &Context,
+		data: &[u8],
+	) -> Result<Self> {
 		let plaintext = Plaintext::new()?;
 
-		try_seal!(unsafe {
-			// While the interface marks data as mut, SEAL doesn't actually modify it, so we're okay.
-			bindgen::Plaintext_Load(
+		// While the interface marks data as mut, SEAL doesn't actually modify it, so we're okay.
+		load_checked(data, |data, len, bytes_read| unsafe {
+			bindgen::Plaintext_UnsafeLoad(
 				plaintext.get_handle(),
 				context.get_handle(),
-				data.as_ptr() as *mut u8,
-				data.len() as u64,
-				&mut bytes_read,
+				data,
+				len,
+				bytes_read,
 			)
 		})?;
 
@@ -301,16 +374,28 @@ impl FromBytes for Plaintext {
 
 impl ToBytes for Plaintext {
 	fn as_bytes(&self) -> Result<Vec<u8>> {
+		self.as_bytes_with(CompressionType::default())
+	}
+
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
 		let mut num_bytes: i64 = 0;
 
 		try_seal!(unsafe {
-			bindgen::Plaintext_SaveSize(
-				self.get_handle(),
-				CompressionType::ZStd as u8,
-				&mut num_bytes,
-			)
+			bindgen::Plaintext_SaveSize(self.get_handle(), compression as u8, &mut num_bytes)
 		})?;
 
+		Ok(num_bytes as u64)
+	}
+
+	fn as_bytes_with(
+		&self,
+		compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		let num_bytes = self.save_size(compression)?;
+
 		let mut data: Vec<u8> = Vec::with_capacity(num_bytes as usize);
 		let mut bytes_written: i64 = 0;
 
@@ -320,8 +405,8 @@ impl ToBytes for Plaintext {
 			bindgen::Plaintext_Save(
 				self.get_handle(),
 				data_ptr,
-				num_bytes as u64,
-				CompressionType::ZStd as u8,
+				num_bytes,
+				compression as u8,
 				&mut bytes_written,
 			)
 		})?;
@@ -334,8 +419,9 @@ impl ToBytes for Plaintext {
 
 impl Drop for Plaintext {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::Plaintext_Destroy(self.get_handle()) })
-			.expect("Internal error in Plaintext::drop.");
+		unsafe {
+			bindgen::Plaintext_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -354,8 +440,20 @@ mod tests {
 	fn plaintext_coefficients_in_increasing_order() {
 		let plaintext = Plaintext::from_hex_string("1234x^2 + 4321").unwrap();
 
-		assert_eq!(plaintext.get_coefficient(0), 0x4321);
-		assert_eq!(plaintext.get_coefficient(1), 0);
-		assert_eq!(plaintext.get_coefficient(2), 0x1234);
+		assert_eq!(plaintext.get_coefficient(0).unwrap(), 0x4321);
+		assert_eq!(plaintext.get_coefficient(1).unwrap(), 0);
+		assert_eq!(plaintext.get_coefficient(2).unwrap(), 0x1234);
+	}
+
+	#[test]
+	fn out_of_bounds_coefficients_are_errors() {
+		let mut plaintext = Plaintext::from_hex_string("1234x^2 + 4321").unwrap();
+
+		assert_eq!(plaintext.get_coefficient(3), Err(Error::InvalidArgument));
+		assert_eq!(plaintext.set_coefficient(3, 1), Err(Error::InvalidArgument));
+		assert_eq!(
+			Plaintext::from_hex_string("12\u{0}34").unwrap_err(),
+			Error::InvalidArgument
+		);
 	}
 }
@@ -107,44 +107,40 @@ impl PolynomialArray {
 	/// Has the array data been loaded? When an array is created, it initially
 	/// has no data. Once data is loaded this is true. Additionally data can only
 	/// be loaded once.
-	pub fn is_reserved(&self) -> bool {
+	pub fn is_reserved(&self) -> Result<bool> {
 		let mut is_reserved: bool = false;
 
 		try_seal!(unsafe {
 			bindgen::PolynomialArray_IsReserved(self.get_handle(), &mut is_reserved)
-		})
-		.expect("Fatal error in PolynomialArray::is_reserved()");
+		})?;
 
-		is_reserved
+		Ok(is_reserved)
 	}
 
 	/// Is the array in RNS form (true).
-	pub fn is_rns(&self) -> bool {
+	pub fn is_rns(&self) -> Result<bool> {
 		let mut is_rns: bool = false;
 
-		try_seal!(unsafe { bindgen::PolynomialArray_IsRns(self.get_handle(), &mut is_rns) })
-			.expect("Fatal error in PolynomialArray::is_rns()");
+		try_seal!(unsafe { bindgen::PolynomialArray_IsRns(self.get_handle(), &mut is_rns) })?;
 
-		is_rns
+		Ok(is_rns)
 	}
 
 	/// Is the array in RNS form (true).
-	pub fn is_multiprecision(&self) -> bool {
-		!self.is_rns()
+	pub fn is_multiprecision(&self) -> Result<bool> {
+		Ok(!self.is_rns()?)
 	}
 
 	/// Converts the polynomial array into the RNS format regardless of its
 	/// current format.
-	pub fn to_rns(&self) {
+	pub fn to_rns(&self) -> Result<()> {
 		try_seal!(unsafe { bindgen::PolynomialArray_ToRns(self.get_handle()) })
-			.expect("Fatal error in PolynomialArray::to_rns()");
 	}
 
 	/// Converts the polynomial array into the multiprecision format regardless
 	/// of its current format.
-	pub fn to_multiprecision(&self) {
+	pub fn to_multiprecision(&self) -> Result<()> {
 		try_seal!(unsafe { bindgen::PolynomialArray_ToMultiprecision(self.get_handle()) })
-			.expect("Fatal error in PolynomialArray::to_multiprecision()");
 	}
 
 	/// Read out the raw data inside the vector. This function is not meant to be
@@ -176,16 +172,16 @@ impl PolynomialArray {
 	pub fn as_multiprecision_u64s(&self) -> Result<Vec<u64>> {
 		// We want to leave the array in the format it was before, so we check
 		// if we need to perform a conversion and temporarily do so if required.
-		let perform_conversion = self.is_rns();
+		let perform_conversion = self.is_rns()?;
 
 		if perform_conversion {
-			self.to_multiprecision();
+			self.to_multiprecision()?;
 		}
 
 		let result = self.as_u64s();
 
 		if perform_conversion {
-			self.to_rns();
+			self.to_rns()?;
 		}
 
 		result
@@ -199,66 +195,62 @@ impl PolynomialArray {
 	pub fn as_rns_u64s(&self) -> Result<Vec<u64>> {
 		// We want to leave the array in the format it was before, so we check
 		// if we need to perform a conversion and temporarily do so if required.
-		let perform_conversion = self.is_multiprecision();
+		let perform_conversion = self.is_multiprecision()?;
 
 		if perform_conversion {
-			self.to_rns();
+			self.to_rns()?;
 		}
 
 		let result = self.as_u64s();
 
 		if perform_conversion {
-			self.to_multiprecision();
+			self.to_multiprecision()?;
 		}
 
 		result
 	}
 
 	/// Returns the number of polynomials stored in the `PolynomialArray`.
-	pub fn num_polynomials(&self) -> u64 {
+	pub fn num_polynomials(&self) -> Result<u64> {
 		let mut size: u64 = 0;
 
-		try_seal!(unsafe { bindgen::PolynomialArray_PolySize(self.get_handle(), &mut size) })
-			.expect("Fatal error in PolynomialArray::num_polynomials");
+		try_seal!(unsafe { bindgen::PolynomialArray_PolySize(self.get_handle(), &mut size) })?;
 
-		size
+		Ok(size)
 	}
 
 	/// Returns the number of coefficients in each polynomial in the `PolynomialArray`.
-	pub fn poly_modulus_degree(&self) -> u64 {
+	pub fn poly_modulus_degree(&self) -> Result<u64> {
 		let mut size: u64 = 0;
 
 		try_seal!(unsafe {
 			bindgen::PolynomialArray_PolyModulusDegree(self.get_handle(), &mut size)
-		})
-		.expect("Fatal error in PolynomialArray::poly_modulus_degree");
+		})?;
 
-		size
+		Ok(size)
 	}
 
 	/// Returns how many moduli are in the coefficient modulus set.
-	pub fn coeff_modulus_size(&self) -> u64 {
+	pub fn coeff_modulus_size(&self) -> Result<u64> {
 		let mut size: u64 = 0;
 
 		try_seal!(unsafe {
 			bindgen::PolynomialArray_CoeffModulusSize(self.get_handle(), &mut size)
-		})
-		.expect("Fatal error in PolynomialArray::coeff_modulus_size");
+		})?;
 
-		size
+		Ok(size)
 	}
 
 	/// Reduces the polynomial array by dropping the last modulus in the modulus
 	/// set.
 	pub fn drop_modulus(&self) -> Result<Self> {
-		if self.coeff_modulus_size() == 1 {
+		if self.coeff_modulus_size()? == 1 {
 			return Err(Error::ModulusChainTooSmall);
 		}
 
 		let mut handle: *mut c_void = null_mut();
 
-		try_seal!(unsafe { bindgen::PolynomialArray_Drop(self.get_handle(), &mut handle) })
-			.expect("Fatal error in PolynomialArray::coeff_modulus_size");
+		try_seal!(unsafe { bindgen::PolynomialArray_Drop(self.get_handle(), &mut handle) })?;
 
 		Ok(Self {
 			handle: AtomicPtr::new(handle),
@@ -290,8 +282,9 @@ impl PartialEq for PolynomialArray {
 
 impl Drop for PolynomialArray {
 	fn drop(&mut self) {
-		try_seal!(unsafe { bindgen::PolynomialArray_Destroy(self.get_handle()) })
-			.expect("Internal error in PolynomialArray::drop()");
+		unsafe {
+			bindgen::PolynomialArray_Destroy(self.get_handle());
+		}
 	}
 }
 
@@ -339,13 +332,13 @@ mod tests {
 		let ciphertext = Ciphertext::new().unwrap();
 		let poly_array = PolynomialArray::new_from_ciphertext(&ctx, &ciphertext).unwrap();
 
-		assert!(poly_array.is_reserved())
+		assert!(poly_array.is_reserved().unwrap())
 	}
 
 	#[test]
 	fn polynomial_array_initially_not_reserved() {
 		let poly_array = PolynomialArray::new().unwrap();
-		assert!(!poly_array.is_reserved());
+		assert!(!poly_array.is_reserved().unwrap());
 	}
 
 	fn generate_ciphertext_example() -> (
@@ -379,8 +372,8 @@ mod tests {
 
 		let plaintext = encoder.encode_u64(&data).unwrap();
 
-		let public_key = gen.create_public_key();
-		let secret_key = gen.secret_key();
+		let public_key = gen.create_public_key().unwrap();
+		let secret_key = gen.secret_key().unwrap();
 
 		let encryptor =
 			Encryptor::with_public_and_secret_key(&ctx, &public_key, &secret_key).unwrap();
@@ -410,60 +403,60 @@ mod tests {
 		let u_encoded = u.as_u64s().unwrap();
 		let e_encoded = e.as_u64s().unwrap();
 
-		assert!(poly_array_public_key.is_reserved());
-		assert!(poly_array_ciphertext.is_reserved());
-		assert!(u.is_reserved());
-		assert!(e.is_reserved());
+		assert!(poly_array_public_key.is_reserved().unwrap());
+		assert!(poly_array_ciphertext.is_reserved().unwrap());
+		assert!(u.is_reserved().unwrap());
+		assert!(e.is_reserved().unwrap());
 
 		// Ciphertext size checks
 		assert_eq!(
-			poly_array_ciphertext.num_polynomials(),
-			ciphertext.num_polynomials()
+			poly_array_ciphertext.num_polynomials().unwrap(),
+			ciphertext.num_polynomials().unwrap()
 		);
-		assert_eq!(poly_array_ciphertext.poly_modulus_degree(), 8192);
+		assert_eq!(poly_array_ciphertext.poly_modulus_degree().unwrap(), 8192);
 		assert_eq!(
-			poly_array_ciphertext.coeff_modulus_size(),
-			ciphertext.coeff_modulus_size()
+			poly_array_ciphertext.coeff_modulus_size().unwrap(),
+			ciphertext.coeff_modulus_size().unwrap()
 		);
 
 		assert_eq!(
 			poly_array_ciphertext_encoded.len() as u64,
-			poly_array_ciphertext.num_polynomials()
-				* poly_array_ciphertext.poly_modulus_degree()
-				* poly_array_ciphertext.coeff_modulus_size()
+			poly_array_ciphertext.num_polynomials().unwrap()
+				* poly_array_ciphertext.poly_modulus_degree().unwrap()
+				* poly_array_ciphertext.coeff_modulus_size().unwrap()
 		);
 
 		// Public key
-		assert_eq!(poly_array_public_key.num_polynomials(), 2);
-		assert_eq!(poly_array_public_key.poly_modulus_degree(), 8192);
-		assert_eq!(poly_array_public_key.coeff_modulus_size(), 4);
+		assert_eq!(poly_array_public_key.num_polynomials().unwrap(), 2);
+		assert_eq!(poly_array_public_key.poly_modulus_degree().unwrap(), 8192);
+		assert_eq!(poly_array_public_key.coeff_modulus_size().unwrap(), 4);
 
 		assert_eq!(
 			poly_array_public_key_encoded.len() as u64,
-			poly_array_public_key.num_polynomials()
-				* poly_array_public_key.poly_modulus_degree()
-				* poly_array_public_key.coeff_modulus_size()
+			poly_array_public_key.num_polynomials().unwrap()
+				* poly_array_public_key.poly_modulus_degree().unwrap()
+				* poly_array_public_key.coeff_modulus_size().unwrap()
 		);
 
 		// u
-		assert_eq!(u.num_polynomials(), 1);
-		assert_eq!(u.poly_modulus_degree(), 8192);
-		assert_eq!(u.coeff_modulus_size(), 4);
+		assert_eq!(u.num_polynomials().unwrap(), 1);
+		assert_eq!(u.poly_modulus_degree().unwrap(), 8192);
+		assert_eq!(u.coeff_modulus_size().unwrap(), 4);
 		assert_eq!(
 			u_encoded.len() as u64,
-			poly_array_public_key.poly_modulus_degree()
-				* poly_array_ciphertext.coeff_modulus_size()
+			poly_array_public_key.poly_modulus_degree().unwrap()
+				* poly_array_ciphertext.coeff_modulus_size().unwrap()
 		);
 
 		// e
-		assert_eq!(e.num_polynomials(), 2);
-		assert_eq!(e.poly_modulus_degree(), 8192);
-		assert_eq!(e.coeff_modulus_size(), 4);
+		assert_eq!(e.num_polynomials().unwrap(), 2);
+		assert_eq!(e.poly_modulus_degree().unwrap(), 8192);
+		assert_eq!(e.coeff_modulus_size().unwrap(), 4);
 		assert_eq!(
 			e_encoded.len() as u64,
-			ciphertext.num_polynomials()
-				* poly_array_public_key.poly_modulus_degree()
-				* ciphertext.coeff_modulus_size()
+			ciphertext.num_polynomials().unwrap()
+				* poly_array_public_key.poly_modulus_degree().unwrap()
+				* ciphertext.coeff_modulus_size().unwrap()
 		);
 
 		// r
@@ -477,8 +470,8 @@ mod tests {
 
 		let poly_array_encoded_original = poly_array.as_u64s().unwrap();
 
-		poly_array.to_multiprecision();
-		poly_array.to_rns();
+		poly_array.to_multiprecision().unwrap();
+		poly_array.to_rns().unwrap();
 
 		let poly_array_encoded_round_trip = poly_array.as_u64s().unwrap();
 
@@ -520,9 +513,9 @@ mod tests {
 		let poly_array = PolynomialArray::new_from_public_key(&ctx, &public_key).unwrap();
 		let poly_array_lower = poly_array.drop_modulus().unwrap();
 
-		assert_eq!(poly_array_lower.num_polynomials(), 2);
-		assert_eq!(poly_array_lower.poly_modulus_degree(), 8192);
-		assert_eq!(poly_array_lower.coeff_modulus_size(), 3);
+		assert_eq!(poly_array_lower.num_polynomials().unwrap(), 2);
+		assert_eq!(poly_array_lower.poly_modulus_degree().unwrap(), 8192);
+		assert_eq!(poly_array_lower.coeff_modulus_size().unwrap(), 3);
 
 		let poly_array_lower_rns = poly_array_lower.as_rns_u64s().unwrap();
 		let poly_array_rns = poly_array
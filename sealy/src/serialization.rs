@@ -1,19 +1,238 @@
-use crate::Result;
+use std::fmt;
+use std::io::{Read, Write};
+use std::marker::PhantomData;
+use std::os::raw::c_long;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess, Visitor};
+
+use crate::error::MalformedInput;
+use crate::{try_seal, Context, Error, Result};
 
 /// Represents the type of compression used in the serialization.
-#[allow(unused)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///
+/// Ciphertexts and keys are mostly uniformly random, so compression pays off
+/// mainly when the coefficient moduli leave unused high bits in every 64-bit
+/// word. Use [`CompressionType::None`] when CPU time matters more than size.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 #[repr(u8)]
 pub enum CompressionType {
+	/// No compression. The fastest mode to save and load.
 	None = 0,
+	/// Zlib (deflate) compression.
 	ZLib = 1,
+	/// Zstandard compression. This is the default.
+	#[default]
 	ZStd = 2,
 }
 
+/// The fixed-size header SEAL writes in front of every serialized object.
+///
+/// Layout (little endian): magic `u16`, header size `u8`, major version `u8`,
+/// minor version `u8`, compression mode `u8`, reserved `u16` and the total
+/// size of the object including this header as `u64`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct SealHeader {
+	pub(crate) magic: u16,
+	pub(crate) header_size: u8,
+	pub(crate) version_major: u8,
+	pub(crate) version_minor: u8,
+	pub(crate) compression: u8,
+	pub(crate) size: u64,
+}
+
+impl SealHeader {
+	/// The magic number SEAL stamps on serialized objects.
+	pub(crate) const MAGIC: u16 = 0xA15E;
+
+	/// The size of the header in bytes.
+	pub(crate) const SIZE: usize = 16;
+
+	/// Parses and validates the header at the start of the given bytes.
+	///
+	/// Only the header itself is checked; use [`SealHeader::validate`] when the
+	/// whole object is at hand.
+	pub(crate) fn parse(bytes: &[u8]) -> Result<Self> {
+		if bytes.len() < Self::SIZE {
+			return Err(MalformedInput::Truncated {
+				expected: Self::SIZE as u64,
+				actual: bytes.len() as u64,
+			}
+			.into());
+		}
+
+		let header = Self {
+			magic: u16::from_le_bytes([bytes[0], bytes[1]]),
+			header_size: bytes[2],
+			version_major: bytes[3],
+			version_minor: bytes[4],
+			compression: bytes[5],
+			size: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+		};
+
+		if header.magic != Self::MAGIC {
+			return Err(MalformedInput::InvalidMagic(header.magic).into());
+		}
+
+		if header.header_size as usize != Self::SIZE {
+			return Err(MalformedInput::InvalidHeaderSize(header.header_size).into());
+		}
+
+		// SEAL 3.4 introduced this header; SEAL 4.x is what we link against.
+		if !matches!(header.version_major, 3 | 4) {
+			return Err(MalformedInput::UnsupportedVersion {
+				major: header.version_major,
+				minor: header.version_minor,
+			}
+			.into());
+		}
+
+		if header.compression > CompressionType::ZStd as u8 {
+			return Err(MalformedInput::UnsupportedCompression(header.compression).into());
+		}
+
+		if header.size < Self::SIZE as u64 {
+			return Err(MalformedInput::Truncated {
+				expected: Self::SIZE as u64,
+				actual: header.size,
+			}
+			.into());
+		}
+
+		let limit = max_load_size();
+
+		if header.size > limit {
+			return Err(MalformedInput::SizeLimitExceeded {
+				declared: header.size,
+				limit,
+			}
+			.into());
+		}
+
+		Ok(header)
+	}
+
+	/// Parses the header and checks that it describes exactly the given bytes.
+	pub(crate) fn validate(bytes: &[u8]) -> Result<Self> {
+		let header = Self::parse(bytes)?;
+
+		if header.size != bytes.len() as u64 {
+			return Err(MalformedInput::SizeMismatch {
+				declared: header.size,
+				actual: bytes.len() as u64,
+			}
+			.into());
+		}
+
+		Ok(header)
+	}
+}
+
+/// The default for [`max_load_size`]: 4 GiB.
+pub const DEFAULT_MAX_LOAD_SIZE: u64 = 4 << 30;
+
+static MAX_LOAD_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_LOAD_SIZE);
+
+/// Sets the largest object, in bytes, the loaders accept.
+///
+/// Headers declaring a larger size are rejected before any data is handed to
+/// SEAL. The limit applies process-wide.
+pub fn set_max_load_size(limit: u64) {
+	MAX_LOAD_SIZE.store(limit, Ordering::Relaxed);
+}
+
+/// Returns the largest object, in bytes, the loaders accept.
+pub fn max_load_size() -> u64 {
+	MAX_LOAD_SIZE.load(Ordering::Relaxed)
+}
+
+/// Validates `bytes` and loads them with the given SEAL load function.
+///
+/// The header is checked before SEAL sees the buffer, errors SEAL raises on
+/// the payload are reported as [`MalformedInput::Corrupt`] and the load must
+/// consume the whole buffer.
+pub(crate) fn load_checked<F>(
+	bytes: &[u8],
+	load: F,
+) -> Result<()>
+where
+	F: FnOnce(*mut u8, u64, &mut i64) -> c_long,
+{
+	SealHeader::validate(bytes)?;
+
+	let mut bytes_read: i64 = 0;
+
+	match try_seal!(load(
+		bytes.as_ptr() as *mut u8,
+		bytes.len() as u64,
+		&mut bytes_read
+	)) {
+		Ok(()) => {}
+		Err(Error::OutOfMemory) => return Err(Error::OutOfMemory),
+		Err(_) => return Err(MalformedInput::Corrupt.into()),
+	}
+
+	if bytes_read != bytes.len() as i64 {
+		return Err(MalformedInput::TrailingBytes {
+			read: bytes_read.max(0) as u64,
+			len: bytes.len() as u64,
+		}
+		.into());
+	}
+
+	Ok(())
+}
+
 /// A trait for converting objects into byte arrays.
+///
+/// Only [`ToBytes::as_bytes`] is required. Types that can choose how they
+/// are compressed also implement [`ToBytes::as_bytes_with`] and
+/// [`ToBytes::save_size`].
 pub trait ToBytes {
-	/// Returns the object as a byte array.
+	/// Returns the object as a byte array, compressed with
+	/// [`CompressionType::ZStd`] where the type supports compression.
 	fn as_bytes(&self) -> Result<Vec<u8>>;
+
+	/// Returns an upper bound on the number of bytes the object occupies once
+	/// serialized with the given compression mode.
+	///
+	/// By default this is the size of [`ToBytes::as_bytes_with`]'s output,
+	/// which serializes the object once.
+	fn save_size(
+		&self,
+		compression: CompressionType,
+	) -> Result<u64> {
+		Ok(self.as_bytes_with(compression)?.len() as u64)
+	}
+
+	/// Returns the object as a byte array serialized with the given
+	/// compression mode.
+	///
+	/// By default the mode is ignored and the output of [`ToBytes::as_bytes`]
+	/// is returned.
+	fn as_bytes_with(
+		&self,
+		_compression: CompressionType,
+	) -> Result<Vec<u8>> {
+		self.as_bytes()
+	}
+
+	/// Writes the serialized object to the given writer.
+	///
+	/// SEAL only serializes into contiguous buffers, so the object is
+	/// serialized once in memory and then written out; no further copies are
+	/// made.
+	fn save_to<W: Write>(
+		&self,
+		writer: &mut W,
+	) -> Result<()>
+	where
+		Self: Sized,
+	{
+		writer.write_all(&self.as_bytes()?)?;
+
+		Ok(())
+	}
 }
 
 /// A trait for converting data from a byte slice under a given SEAL context.
@@ -22,10 +241,181 @@ pub trait FromBytes {
 	type State;
 	/// Deserialize an object from the given bytes using the given
 	/// state.
+	///
+	/// The compression mode is recorded in the serialized header, so bytes
+	/// produced by any [`ToBytes::as_bytes_with`] mode load the same way.
+	///
+	/// The SEAL header is validated before any data is handed to SEAL and the
+	/// object must span exactly the given bytes; malformed input fails with
+	/// [`Error::MalformedInput`]. Objects bound to a context are checked to be
+	/// valid for it once loaded, failing with [`Error::InvalidForContext`]
+	/// otherwise.
 	fn from_bytes(
 		state: &Self::State,
 		bytes: &[u8],
 	) -> Result<Self>
 	where
 		Self: Sized;
+
+	/// Deserialize an object from the given bytes, skipping the check that it
+	/// is valid for the given state. Only use this for trusted data, where the
+	/// cost of validation matters. The header and size checks still apply.
+	///
+	/// Every implementor states explicitly what it skips; objects with no
+	/// validity check beyond loading load the same way as with
+	/// [`FromBytes::from_bytes`].
+	///
+	/// # Safety
+	/// SEAL trusts the data of the object it loads this way, so data that is
+	/// not valid for the state makes later operations on the object read and
+	/// write out of bounds. The bytes must come from an object that was valid
+	/// for the state, such as one serialized by this process, or the object
+	/// must pass its `is_valid_for` check before any other use.
+	unsafe fn from_bytes_unchecked(
+		state: &Self::State,
+		bytes: &[u8],
+	) -> Result<Self>
+	where
+		Self: Sized;
+
+	/// Reads exactly one serialized object from the given reader.
+	///
+	/// The object's size is taken from its header, so the reader is left
+	/// positioned right after the object and several objects can be read
+	/// back to back from the same stream.
+	fn load_from<R: Read>(
+		state: &Self::State,
+		reader: &mut R,
+	) -> Result<Self>
+	where
+		Self: Sized,
+	{
+		let mut bytes = vec![0u8; SealHeader::SIZE];
+		reader.read_exact(&mut bytes)?;
+
+		let header = SealHeader::parse(&bytes)?;
+		let remaining = header.size - SealHeader::SIZE as u64;
+
+		// Grow the buffer as data arrives rather than trusting the declared size
+		// up front.
+		reader.take(remaining).read_to_end(&mut bytes)?;
+
+		if (bytes.len() as u64) < header.size {
+			return Err(MalformedInput::Truncated {
+				expected: header.size,
+				actual: bytes.len() as u64,
+			}
+			.into());
+		}
+
+		Self::from_bytes(state, &bytes)
+	}
+}
+
+/// A [`DeserializeSeed`] that deserializes objects which need a [`Context`]
+/// to be loaded, such as ciphertexts and keys.
+///
+/// SEAL objects serialize with serde as byte strings holding their
+/// [`ToBytes`] representation, so they can be embedded in any serde format.
+///
+/// ```ignore
+/// let mut deserializer = serde_json::Deserializer::from_str(&json);
+/// let ciphertext: Ciphertext = ContextSeed::new(&ctx).deserialize(&mut deserializer)?;
+/// ```
+pub struct ContextSeed<'a, T> {
+	context: &'a Context,
+	_marker: PhantomData<T>,
+}
+
+impl<'a, T> ContextSeed<'a, T> {
+	/// Creates a seed that loads objects under the given context.
+	pub fn new(context: &'a Context) -> Self {
+		Self {
+			context,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Returns the context objects are loaded under.
+	pub fn context(&self) -> &'a Context {
+		self.context
+	}
+}
+
+impl<T> Clone for ContextSeed<'_, T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T> Copy for ContextSeed<'_, T> {}
+
+impl<'de, T> DeserializeSeed<'de> for ContextSeed<'_, T>
+where
+	T: FromBytes<State = Context>,
+{
+	type Value = T;
+
+	fn deserialize<D>(
+		self,
+		deserializer: D,
+	) -> std::result::Result<Self::Value, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let bytes = deserializer.deserialize_bytes(BytesVisitor)?;
+
+		T::from_bytes(self.context, &bytes).map_err(serde::de::Error::custom)
+	}
+}
+
+/// Collects a serde byte string, accepting the sequence of integers that
+/// formats without a native bytes type (such as JSON) produce.
+pub(crate) struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+	type Value = Vec<u8>;
+
+	fn expecting(
+		&self,
+		formatter: &mut fmt::Formatter,
+	) -> fmt::Result {
+		formatter.write_str("a byte array")
+	}
+
+	fn visit_bytes<E>(
+		self,
+		v: &[u8],
+	) -> std::result::Result<Self::Value, E>
+	where
+		E: serde::de::Error,
+	{
+		Ok(v.to_vec())
+	}
+
+	fn visit_byte_buf<E>(
+		self,
+		v: Vec<u8>,
+	) -> std::result::Result<Self::Value, E>
+	where
+		E: serde::de::Error,
+	{
+		Ok(v)
+	}
+
+	fn visit_seq<A>(
+		self,
+		mut seq: A,
+	) -> std::result::Result<Self::Value, A::Error>
+	where
+		A: SeqAccess<'de>,
+	{
+		let mut bytes = Vec::new();
+
+		while let Some(byte) = seq.next_element::<u8>()? {
+			bytes.push(byte);
+		}
+
+		Ok(bytes)
+	}
 }
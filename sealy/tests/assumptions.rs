@@ -54,11 +54,11 @@ fn multiply_ciphertext_increases_terms() {
 		let p = encoder.encode_i64(&data).unwrap();
 		let c = encryptor.encrypt(&p).unwrap();
 
-		assert_eq!(c.num_polynomials(), 2);
+		assert_eq!(c.num_polynomials().unwrap(), 2);
 
 		let c_2 = eval.multiply(&c, &c).unwrap();
 
-		assert_eq!(c_2.num_polynomials(), 3);
+		assert_eq!(c_2.num_polynomials().unwrap(), 3);
 	})
 }
 
@@ -78,11 +78,11 @@ fn multiply_plaintext_does_not_increase_polynomials() {
 		let p = encoder.encode_i64(&data).unwrap();
 		let c = encryptor.encrypt(&p).unwrap();
 
-		assert_eq!(c.num_polynomials(), 2);
+		assert_eq!(c.num_polynomials().unwrap(), 2);
 
 		let c_2 = eval.multiply_plain(&c, &p).unwrap();
 
-		assert_eq!(c_2.num_polynomials(), 2);
+		assert_eq!(c_2.num_polynomials().unwrap(), 2);
 	})
 }
 
@@ -169,8 +169,8 @@ fn relinearization_consumes_no_noise_budget() {
 
 	let encoder = BFVEncoder::new(&ctx).unwrap();
 
-	let public_key = gen.create_public_key();
-	let private_key = gen.secret_key();
+	let public_key = gen.create_public_key().unwrap();
+	let private_key = gen.secret_key().unwrap();
 	let relin_key = gen.create_relinearization_keys().unwrap();
 
 	let encryptor = Encryptor::with_public_and_secret_key(&ctx, &public_key, &private_key).unwrap();
@@ -227,8 +227,8 @@ fn addition_noise_less_equal_operands() {
 
 	let encoder = BFVEncoder::new(&ctx).unwrap();
 
-	let public_key = gen.create_public_key();
-	let private_key = gen.secret_key();
+	let public_key = gen.create_public_key().unwrap();
+	let private_key = gen.secret_key().unwrap();
 
 	let encryptor = Encryptor::with_public_and_secret_key(&ctx, &public_key, &private_key).unwrap();
 	let decryptor = Decryptor::new(&ctx, &private_key).unwrap();
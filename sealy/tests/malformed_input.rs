@@ -0,0 +1,200 @@
+use sealy::*;
+
+fn mk_ctx() -> Context {
+	let params = BFVEncryptionParametersBuilder::new()
+		.set_poly_modulus_degree(DegreeType::D8192)
+		.set_coefficient_modulus(
+			CoefficientModulusFactory::bfv(DegreeType::D8192, SecurityLevel::TC128).unwrap(),
+		)
+		.set_plain_modulus(PlainModulusFactory::batching(DegreeType::D8192, 20).unwrap())
+		.build()
+		.unwrap();
+
+	Context::new(&params, false, SecurityLevel::TC128).unwrap()
+}
+
+fn with_header_byte(
+	bytes: &[u8],
+	index: usize,
+	value: u8,
+) -> Vec<u8> {
+	let mut bytes = bytes.to_vec();
+	bytes[index] = value;
+	bytes
+}
+
+fn with_declared_size(
+	bytes: &[u8],
+	size: u64,
+) -> Vec<u8> {
+	let mut bytes = bytes.to_vec();
+	bytes[8..16].copy_from_slice(&size.to_le_bytes());
+	bytes
+}
+
+/// Derives a corpus of malformed inputs from a valid serialized object,
+/// paired with the error each one is expected to produce. `None` means any
+/// error is acceptable.
+fn corpus(valid: &[u8]) -> Vec<(&'static str, Vec<u8>, Option<MalformedInput>)> {
+	let len = valid.len() as u64;
+
+	let mut trailing = valid.to_vec();
+	trailing.extend_from_slice(b"garbage");
+
+	let mut corrupt = valid.to_vec();
+	for byte in &mut corrupt[32..64] {
+		*byte ^= 0xFF;
+	}
+
+	vec![
+		(
+			"empty",
+			vec![],
+			Some(MalformedInput::Truncated {
+				expected: 16,
+				actual: 0,
+			}),
+		),
+		(
+			"partial header",
+			valid[..7].to_vec(),
+			Some(MalformedInput::Truncated {
+				expected: 16,
+				actual: 7,
+			}),
+		),
+		(
+			"bad magic",
+			with_header_byte(valid, 0, 0x00),
+			Some(MalformedInput::InvalidMagic(0xA100)),
+		),
+		(
+			"bad header size",
+			with_header_byte(valid, 2, 0xFF),
+			Some(MalformedInput::InvalidHeaderSize(0xFF)),
+		),
+		(
+			"bad version",
+			with_header_byte(valid, 3, 0x63),
+			Some(MalformedInput::UnsupportedVersion {
+				major: 0x63,
+				minor: valid[4],
+			}),
+		),
+		(
+			"bad compression",
+			with_header_byte(valid, 5, 0x07),
+			Some(MalformedInput::UnsupportedCompression(0x07)),
+		),
+		(
+			"declared size below header",
+			with_declared_size(valid, 3),
+			Some(MalformedInput::Truncated {
+				expected: 16,
+				actual: 3,
+			}),
+		),
+		(
+			"declared size over limit",
+			with_declared_size(valid, u64::MAX),
+			Some(MalformedInput::SizeLimitExceeded {
+				declared: u64::MAX,
+				limit: DEFAULT_MAX_LOAD_SIZE,
+			}),
+		),
+		(
+			"declared size too short",
+			with_declared_size(valid, len - 1),
+			Some(MalformedInput::SizeMismatch {
+				declared: len - 1,
+				actual: len,
+			}),
+		),
+		(
+			"truncated payload",
+			valid[..valid.len() / 2].to_vec(),
+			Some(MalformedInput::SizeMismatch {
+				declared: len,
+				actual: len / 2,
+			}),
+		),
+		(
+			"trailing garbage",
+			trailing,
+			Some(MalformedInput::SizeMismatch {
+				declared: len,
+				actual: len + 7,
+			}),
+		),
+		("corrupt payload", corrupt, None),
+	]
+}
+
+fn assert_rejected<T: FromBytes<State = Context>>(
+	ctx: &Context,
+	valid: &[u8],
+) {
+	assert!(T::from_bytes(ctx, valid).is_ok());
+
+	for (name, bytes, expected) in corpus(valid) {
+		let result = T::from_bytes(ctx, &bytes);
+
+		match (result, expected) {
+			(Ok(_), _) => panic!("{name}: malformed input was accepted"),
+			(Err(Error::MalformedInput(err)), Some(expected)) => {
+				assert_eq!(*err, expected, "{name}")
+			}
+			(Err(_), None) => {}
+			(Err(err), Some(_)) => panic!("{name}: unexpected error {err:?}"),
+		}
+	}
+}
+
+#[test]
+fn loaders_reject_malformed_input() {
+	let ctx = mk_ctx();
+	let gen = KeyGenerator::new(&ctx).unwrap();
+	let encoder = BFVEncoder::new(&ctx).unwrap();
+
+	let public_key = gen.create_public_key().unwrap();
+	let secret_key = gen.secret_key().unwrap();
+	let relin_keys = gen.create_relinearization_keys().unwrap();
+
+	let encryptor = Encryptor::with_public_key(&ctx, &public_key).unwrap();
+	let plaintext = encoder.encode_u64(&[1, 2, 3]).unwrap();
+	let ciphertext = encryptor.encrypt(&plaintext).unwrap();
+
+	assert_rejected::<Ciphertext>(&ctx, &ciphertext.as_bytes().unwrap());
+	assert_rejected::<Plaintext>(&ctx, &plaintext.as_bytes().unwrap());
+	assert_rejected::<PublicKey>(&ctx, &public_key.as_bytes().unwrap());
+	assert_rejected::<SecretKey>(&ctx, &secret_key.as_bytes().unwrap());
+	assert_rejected::<RelinearizationKey>(&ctx, &relin_keys.as_bytes().unwrap());
+}
+
+#[test]
+fn uncompressed_input_is_rejected_too() {
+	let ctx = mk_ctx();
+	let gen = KeyGenerator::new(&ctx).unwrap();
+	let public_key = gen.create_public_key().unwrap();
+
+	let bytes = public_key.as_bytes_with(CompressionType::None).unwrap();
+
+	assert_rejected::<PublicKey>(&ctx, &bytes);
+}
+
+#[test]
+fn streams_reject_malformed_input() {
+	let ctx = mk_ctx();
+	let gen = KeyGenerator::new(&ctx).unwrap();
+	let bytes = gen.create_public_key().unwrap().as_bytes().unwrap();
+
+	let mut huge = bytes.clone();
+	huge[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+	assert!(PublicKey::load_from(&ctx, &mut huge.as_slice()).is_err());
+
+	let mut short = &bytes[..bytes.len() - 1];
+	assert!(matches!(
+		PublicKey::load_from(&ctx, &mut short),
+		Err(Error::MalformedInput(_))
+	));
+}
@@ -0,0 +1,48 @@
+//! Switching the memory manager profile affects every thread in the
+//! process, so this lives in a binary of its own, where no other test
+//! allocates concurrently.
+
+use sealy::*;
+
+/// Switches back to the global profile, even if the test fails.
+struct RestoreGlobal;
+
+impl Drop for RestoreGlobal {
+	fn drop(&mut self) {
+		MemoryManager::switch_profile(&MemoryProfile::Global).unwrap();
+	}
+}
+
+#[test]
+fn can_switch_profiles() {
+	let _restore = RestoreGlobal;
+
+	let memory_pool = MemoryPool::new().unwrap();
+
+	MemoryManager::switch_profile(&MemoryProfile::Fixed(memory_pool.clone())).unwrap();
+	let current = MemoryManager::get_pool().unwrap();
+
+	assert!(current.is_initialized().unwrap());
+	assert_eq!(
+		current.pool_count().unwrap(),
+		memory_pool.pool_count().unwrap()
+	);
+	assert!(matches!(MemoryManager::profile(), MemoryProfile::Fixed(_)));
+
+	MemoryManager::switch_profile(&MemoryProfile::New).unwrap();
+	assert!(matches!(MemoryManager::profile(), MemoryProfile::New));
+
+	let switched = MemoryManager::switch_profile(&MemoryProfile::ThreadLocal);
+
+	if cfg!(feature = "parallel") {
+		assert_eq!(switched, Err(Error::InvalidOperation));
+		assert!(matches!(MemoryManager::profile(), MemoryProfile::New));
+	} else {
+		switched.unwrap();
+
+		assert_eq!(
+			MemoryManager::get_pool().unwrap_err(),
+			Error::InvalidOperation
+		);
+	}
+}
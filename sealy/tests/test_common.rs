@@ -21,8 +21,8 @@ pub fn run_bfv_test<F>(
 
 	let encoder = BFVEncoder::new(&ctx).unwrap();
 
-	let public_key = gen.create_public_key();
-	let private_key = gen.secret_key();
+	let public_key = gen.create_public_key().unwrap();
+	let private_key = gen.secret_key().unwrap();
 
 	let encryptor = Encryptor::with_public_and_secret_key(&ctx, &public_key, &private_key).unwrap();
 	let decryptor = Decryptor::new(&ctx, &private_key).unwrap();